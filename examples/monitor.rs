@@ -0,0 +1,27 @@
+//! Example: Terminal Monitor
+//!
+//! Streams a watchlist and open positions in the terminal.
+//! Run with `cargo run --example monitor --features tui [watchlist.json]`.
+
+use openalgo::tui::Monitor;
+use openalgo::{OpenAlgo, Watchlist};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = OpenAlgo::from_env()?;
+
+    // Load a saved watchlist, or fall back to a few NSE large caps
+    let watchlist = match std::env::args().nth(1) {
+        Some(path) => Watchlist::load(path)?,
+        None => {
+            let mut watchlist = Watchlist::new("Watchlist");
+            for symbol in ["RELIANCE", "TCS", "HDFCBANK", "INFY", "ICICIBANK"] {
+                watchlist.add("NSE", symbol);
+            }
+            watchlist
+        }
+    };
+
+    Monitor::new(watchlist).run(&client).await?;
+    Ok(())
+}
@@ -2,13 +2,12 @@
 //!
 //! This example demonstrates how to place different types of orders using the OpenAlgo SDK.
 
-use openalgo::{OpenAlgo, BasketOrderItem, OptionsLeg};
+use openalgo::{OpenAlgo, BasketOrderItem};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize the client - simple!
-    let api_key = std::env::var("OPENALGO_API_KEY").unwrap_or_else(|_| "your_api_key".to_string());
-    let client = OpenAlgo::new(&api_key);
+    // Initialize the client from OPENALGO_API_KEY (and OPENALGO_HOST etc.)
+    let client = OpenAlgo::from_env()?;
 
     // Example 1: Place a simple market order
     println!("=== Place Market Order ===");
@@ -67,8 +66,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Example 5: Place basket orders
     println!("\n=== Basket Order ===");
     let orders = vec![
-        BasketOrderItem::new("RELIANCE", "NSE", "BUY", "1", "MARKET", "MIS"),
-        BasketOrderItem::new("TCS", "NSE", "BUY", "1", "MARKET", "MIS"),
+        BasketOrderItem::new("RELIANCE", "NSE", "BUY", 1, "MARKET", "MIS"),
+        BasketOrderItem::new("TCS", "NSE", "BUY", 1, "MARKET", "MIS"),
     ];
     let result = client.basket_order("Strategy1", orders).await?;
     println!("Basket Order Result: {:?}", result);
@@ -80,8 +79,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "RELIANCE",
         "BUY",
         "NSE",
-        "100",
-        "25",
+        100,
+        25,
         "MARKET",
         "MIS",
     ).await?;
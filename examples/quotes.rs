@@ -6,9 +6,8 @@ use openalgo::OpenAlgo;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize the client - simple!
-    let api_key = std::env::var("OPENALGO_API_KEY").unwrap_or_else(|_| "your_api_key".to_string());
-    let client = OpenAlgo::new(&api_key);
+    // Initialize the client from OPENALGO_API_KEY (and OPENALGO_HOST etc.)
+    let client = OpenAlgo::from_env()?;
 
     // Example 1: Get quotes for a symbol
     println!("=== Quotes ===");
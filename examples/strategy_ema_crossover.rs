@@ -0,0 +1,181 @@
+//! Reference strategy: EMA Crossover with an Hourly Trend Filter
+//!
+//! Goes long when the 9-period EMA of 5-minute closes crosses above the
+//! 21-period EMA, and short on the opposite cross, but only in the direction
+//! of the hourly trend (1h close against its 20-period EMA). Crosses against
+//! the trend flatten the position.
+//!
+//! The EMAs are warmed up from history, so the first live bar already trades.
+//! The strategy publishes `Signal`s on an `EventBus`; a separate executor
+//! turns them into journaled orders, and a `LossCircuit` on the same bus
+//! stops both at the day's loss limit.
+//!
+//! Run with `cargo run --example strategy_ema_crossover --features examples-strategies [SYMBOL] [QUANTITY]`.
+
+use openalgo::bus::{BusEvent, EventBus, RiskEvent, Signal};
+use openalgo::candles::MultiTimeframe;
+use openalgo::circuit::{CircuitEvent, LossCircuit};
+use openalgo::journal::OrderJournal;
+use openalgo::price::to_f64;
+use openalgo::websocket::WsSubscriber;
+use openalgo::{OpenAlgo, WsInstrument};
+
+const STRATEGY: &str = "EmaCross";
+const EXCHANGE: &str = "NSE";
+const MAX_DAY_LOSS: f64 = 5_000.0;
+
+/// Exponential moving average
+struct Ema {
+    alpha: f64,
+    value: Option<f64>,
+}
+
+impl Ema {
+    fn new(period: usize) -> Self {
+        Self {
+            alpha: 2.0 / (period as f64 + 1.0),
+            value: None,
+        }
+    }
+
+    fn update(&mut self, close: f64) -> f64 {
+        let value = self.value.map_or(close, |ema| ema + self.alpha * (close - ema));
+        self.value = Some(value);
+        value
+    }
+}
+
+/// Fast and slow EMAs of the 5m closes and the EMA of the 1h closes
+struct Indicators {
+    fast: Ema,
+    slow: Ema,
+    trend: Ema,
+    /// Whether the fast EMA was above the slow one at the last close
+    fast_above: Option<bool>,
+    trend_up: Option<bool>,
+}
+
+impl Indicators {
+    /// Update on a 5m close, returning +1 or -1 on a cross up or down
+    fn on_bar(&mut self, close: f64) -> i64 {
+        let above = self.fast.update(close) > self.slow.update(close);
+        let cross = match self.fast_above {
+            Some(false) if above => 1,
+            Some(true) if !above => -1,
+            _ => 0,
+        };
+        self.fast_above = Some(above);
+        cross
+    }
+
+    fn on_hour(&mut self, close: f64) {
+        self.trend_up = Some(close > self.trend.update(close));
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = OpenAlgo::from_env()?;
+    let symbol = std::env::args().nth(1).unwrap_or_else(|| "RELIANCE".to_string());
+    let quantity: i64 = std::env::args().nth(2).and_then(|q| q.parse().ok()).unwrap_or(10);
+
+    // Warm up the indicators so the first live bar can trade
+    let mut indicators = Indicators {
+        fast: Ema::new(9),
+        slow: Ema::new(21),
+        trend: Ema::new(20),
+        fast_above: None,
+        trend_up: None,
+    };
+    for bar in client.warm_up(&symbol, EXCHANGE, "5m", 200).await?.candles() {
+        indicators.on_bar(to_f64(bar.close));
+    }
+    for bar in client.warm_up(&symbol, EXCHANGE, "1h", 60).await?.candles() {
+        indicators.on_hour(to_f64(bar.close));
+    }
+    println!("{} warmed up: fast above slow {:?}, hourly trend up {:?}", symbol, indicators.fast_above, indicators.trend_up);
+
+    let bus = EventBus::new();
+    let mut executor_events = bus.subscribe();
+    let mut strategy_events = bus.subscribe();
+
+    let (cmd_tx, ticks) = client.websocket().connect().await?;
+    WsSubscriber::new(cmd_tx)
+        .subscribe_quote(vec![WsInstrument::new(EXCHANGE, &symbol)])
+        .await?;
+    let _bars = bus.forward_timeframes(ticks, MultiTimeframe::new(&["5m", "1h"]).expect("1h is a multiple of 5m"));
+    let (circuit, _circuit_handle) = LossCircuit::new(MAX_DAY_LOSS).strategy(STRATEGY).start(&client);
+    let _risk = bus.forward(circuit);
+
+    // Strategy: bars in, target positions out as signals
+    let strategy_bus = bus.clone();
+    let strategy_symbol = symbol.clone();
+    tokio::spawn(async move {
+        while let Ok(event) = strategy_events.recv().await {
+            let BusEvent::Bars(bars) = event else { continue };
+            if bars.symbol != strategy_symbol {
+                continue;
+            }
+            if bars.is_closed("1h") {
+                if let Some(hour) = bars.bar("1h") {
+                    indicators.on_hour(to_f64(hour.close));
+                }
+            }
+            let Some(bar) = bars.bar("5m") else { continue };
+            let cross = indicators.on_bar(to_f64(bar.close));
+            let target = match (cross, indicators.trend_up) {
+                (1, Some(true)) => quantity,
+                (-1, Some(false)) => -quantity,
+                (0, _) => continue,
+                _ => 0,
+            };
+            let signal = Signal::new(STRATEGY, &strategy_symbol, EXCHANGE, if cross > 0 { "BUY" } else { "SELL" }, target)
+                .tag("signal", if cross > 0 { "ema-cross-up" } else { "ema-cross-down" });
+            strategy_bus.publish(signal);
+        }
+    });
+
+    // Executor: move the position to each signal's target
+    let journal = OrderJournal::new();
+    let mut position = 0;
+    while let Ok(event) = executor_events.recv().await {
+        match event {
+            BusEvent::Signal(signal) if signal.strategy == STRATEGY => {
+                let delta = signal.quantity - position;
+                if delta == 0 {
+                    continue;
+                }
+                let tags: Vec<(&str, &str)> = signal.tags.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+                let response = journal
+                    .place_tagged_order(
+                        &client,
+                        STRATEGY,
+                        &signal.symbol,
+                        if delta > 0 { "BUY" } else { "SELL" },
+                        &signal.exchange,
+                        "MARKET",
+                        "MIS",
+                        &delta.abs().to_string(),
+                        &tags,
+                    )
+                    .await;
+                match response {
+                    Ok(response) => {
+                        position = signal.quantity;
+                        println!("{} -> position {}: order {:?}", signal.symbol, position, response.orderid);
+                    }
+                    Err(e) => eprintln!("Order for {} failed: {}", signal.symbol, e),
+                }
+            }
+            BusEvent::Risk(RiskEvent::Circuit(CircuitEvent::Tripped { pnl, .. })) => {
+                eprintln!("Loss limit hit at a day P&L of {:.0}; positions closed", pnl.total);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    journal.refresh(&client).await;
+    journal.save("ema-crossover-journal.jsonl")?;
+    Ok(())
+}
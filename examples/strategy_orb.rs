@@ -0,0 +1,132 @@
+//! Reference strategy: Opening Range Breakout
+//!
+//! Takes the high and low of the first 15 minutes (09:15 - 09:30 IST) as the
+//! opening range, buys a break above it and sells a break below it, with the
+//! other side of the range as the stop. One trade per day; the position is
+//! squared off at 15:10. A `LossCircuit` halts the strategy at the day's loss
+//! limit.
+//!
+//! Started after 09:30, the range is taken from warm-up history. Ticks,
+//! candles and risk events meet on one `EventBus`; orders are tagged in the
+//! journal, which is saved on exit.
+//!
+//! Run with `cargo run --example strategy_orb --features examples-strategies [SYMBOL] [QUANTITY]`.
+
+use openalgo::bus::{BusEvent, EventBus, RiskEvent};
+use openalgo::calendar::{ist_date, ist_day_and_secs};
+use openalgo::circuit::{CircuitEvent, LossCircuit};
+use openalgo::journal::OrderJournal;
+use openalgo::price::{self, Price};
+use openalgo::types::HistoryCandle;
+use openalgo::websocket::WsSubscriber;
+use openalgo::{OpenAlgo, WsConfig, WsData, WsInstrument};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const STRATEGY: &str = "ORB";
+const EXCHANGE: &str = "NSE";
+const RANGE_INTERVAL: &str = "15m";
+/// Seconds after IST midnight
+const RANGE_START: i64 = 9 * 3_600 + 15 * 60;
+const SQUARE_OFF: i64 = 15 * 3_600 + 10 * 60;
+const MAX_DAY_LOSS: f64 = 5_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Flat,
+    Long,
+    Short,
+    Done,
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Today's opening range bar, if it has completed
+fn opening_range(candles: &[HistoryCandle], now: i64) -> Option<(Price, Price)> {
+    let today = ist_date(now);
+    candles
+        .iter()
+        .find(|c| ist_date(c.timestamp) == today && ist_day_and_secs(c.timestamp).1 == RANGE_START)
+        .map(|c| (c.high, c.low))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = OpenAlgo::from_env()?;
+    let symbol = std::env::args().nth(1).unwrap_or_else(|| "SBIN".to_string());
+    let quantity = std::env::args().nth(2).unwrap_or_else(|| "10".to_string());
+    let journal = OrderJournal::new();
+
+    let mut range = opening_range(client.warm_up(&symbol, EXCHANGE, RANGE_INTERVAL, 30).await?.candles(), now_secs());
+    match range {
+        Some((high, low)) => println!("{} opening range from history: {} - {}", symbol, low, high),
+        None => println!("{} waiting for the opening range", symbol),
+    }
+
+    // Market data, built candles and risk events all arrive on the bus
+    let bus = EventBus::new();
+    let mut events = bus.subscribe();
+    let ws = client.websocket().with_config(WsConfig::default().candles(&[RANGE_INTERVAL]));
+    let (cmd_tx, ticks) = ws.connect().await?;
+    WsSubscriber::new(cmd_tx)
+        .subscribe_ltp(vec![WsInstrument::new(EXCHANGE, &symbol)])
+        .await?;
+    let _market = bus.forward_ws(ticks);
+    let (circuit, _circuit_handle) = LossCircuit::new(MAX_DAY_LOSS).strategy(STRATEGY).start(&client);
+    let _risk = bus.forward(circuit);
+
+    let mut side = Side::Flat;
+    while let Ok(event) = events.recv().await {
+        match event {
+            BusEvent::Candle(c) if range.is_none() && c.symbol == symbol && c.interval == RANGE_INTERVAL => {
+                range = opening_range(std::slice::from_ref(&c.candle), c.candle.timestamp);
+                if let Some((high, low)) = range {
+                    println!("{} opening range: {} - {}", symbol, low, high);
+                }
+            }
+            BusEvent::Tick(WsData::Ltp(tick)) if tick.symbol.as_deref() == Some(symbol.as_str()) => {
+                let (Some(ltp), Some((high, low))) = (tick.ltp, range) else { continue };
+                let secs = ist_day_and_secs(now_secs()).1;
+                let (target, signal) = match side {
+                    Side::Flat if secs < SQUARE_OFF && ltp > high => (Side::Long, "breakout-long"),
+                    Side::Flat if secs < SQUARE_OFF && ltp < low => (Side::Short, "breakout-short"),
+                    Side::Long if ltp < low || secs >= SQUARE_OFF => (Side::Done, "exit"),
+                    Side::Short if ltp > high || secs >= SQUARE_OFF => (Side::Done, "exit"),
+                    _ => continue,
+                };
+                let action = match (side, target) {
+                    (Side::Flat, Side::Long) | (Side::Short, _) => "BUY",
+                    _ => "SELL",
+                };
+                let response = journal
+                    .place_tagged_order(
+                        &client,
+                        STRATEGY,
+                        &symbol,
+                        action,
+                        EXCHANGE,
+                        "MARKET",
+                        "MIS",
+                        &quantity,
+                        &[("signal", signal), ("ltp", &price::to_f64(ltp).to_string())],
+                    )
+                    .await?;
+                println!("{} {} at ~{}: order {:?}", signal, symbol, ltp, response.orderid);
+                side = target;
+                if side == Side::Done {
+                    break;
+                }
+            }
+            BusEvent::Risk(RiskEvent::Circuit(CircuitEvent::Tripped { pnl, .. })) => {
+                eprintln!("Loss limit hit at a day P&L of {:.0}; positions closed", pnl.total);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    journal.refresh(&client).await;
+    journal.save("orb-journal.jsonl")?;
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! Reference strategy: Short Straddle with Per-Leg Stop-Loss
+//!
+//! At 09:20 IST sells the at-the-money call and put of the nearest NIFTY
+//! expiry. Each leg is bought back when its premium rises 30% above the
+//! entry price; whatever is left is closed at 15:15. A `LossCircuit` closes
+//! everything at the day's loss limit.
+//!
+//! Leg prices and risk events arrive on one `EventBus`. Entries and exits
+//! are recorded in the order journal with their leg and reason as tags.
+//!
+//! Run with `cargo run --example strategy_short_straddle --features examples-strategies [LOTS]`.
+
+use openalgo::bus::{BusEvent, EventBus, RiskEvent};
+use openalgo::calendar::ist_day_and_secs;
+use openalgo::circuit::{CircuitEvent, LossCircuit};
+use openalgo::journal::{JournalEntry, OrderJournal};
+use openalgo::price::to_f64;
+use openalgo::websocket::WsSubscriber;
+use openalgo::{OpenAlgo, OptionsLeg, WsData, WsInstrument};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const STRATEGY: &str = "ShortStraddle";
+const UNDERLYING: &str = "NIFTY";
+const UNDERLYING_EXCHANGE: &str = "NSE_INDEX";
+const OPTION_EXCHANGE: &str = "NFO";
+const LOT_SIZE: i64 = 75;
+/// Seconds after IST midnight
+const ENTRY: i64 = 9 * 3_600 + 20 * 60;
+const SQUARE_OFF: i64 = 15 * 3_600 + 15 * 60;
+/// A leg is stopped out when its premium rises this much above entry
+const STOP_LOSS: f64 = 0.30;
+const MAX_DAY_LOSS: f64 = 10_000.0;
+
+/// One sold option
+struct Leg {
+    symbol: String,
+    entry: f64,
+    open: bool,
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64)
+}
+
+fn now_millis() -> i64 {
+    now_secs() * 1_000
+}
+
+/// Average fill price of an order, waiting for it to complete
+async fn fill_price(client: &OpenAlgo, orderid: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    for _ in 0..20 {
+        let status = client.order_status(orderid, STRATEGY).await?;
+        if let Some(data) = status.data {
+            if data.order_status.as_deref() == Some("complete") {
+                return Ok(data.average_price.map(to_f64).unwrap_or_default());
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    Err(format!("order {} did not fill", orderid).into())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = OpenAlgo::from_env()?;
+    let lots: i64 = std::env::args().nth(1).and_then(|l| l.parse().ok()).unwrap_or(1);
+    let quantity = (lots * LOT_SIZE).to_string();
+    let journal = OrderJournal::new();
+
+    let wait = ENTRY - ist_day_and_secs(now_secs()).1;
+    if wait > 0 {
+        println!("Waiting {} minutes for the 09:20 entry", wait / 60);
+        tokio::time::sleep(Duration::from_secs(wait as u64)).await;
+    }
+
+    let expiries = client.expiry(UNDERLYING, OPTION_EXCHANGE, "OPT").await?;
+    let expiry = expiries.data.unwrap_or_default().into_iter().next().ok_or("no expiry found")?;
+    let response = client
+        .options_multi_order(
+            STRATEGY,
+            UNDERLYING,
+            UNDERLYING_EXCHANGE,
+            &expiry.replace('-', ""),
+            vec![
+                OptionsLeg::new("ATM", "CE", "SELL", &quantity),
+                OptionsLeg::new("ATM", "PE", "SELL", &quantity),
+            ],
+        )
+        .await?;
+
+    let mut legs = Vec::new();
+    for result in response.results.unwrap_or_default() {
+        let (Some(orderid), Some(symbol)) = (result.orderid, result.symbol) else {
+            eprintln!("Leg {} was not placed: {}", result.leg, result.status);
+            continue;
+        };
+        let entry = fill_price(&client, &orderid).await?;
+        journal.record(JournalEntry {
+            orderid,
+            strategy: STRATEGY.to_string(),
+            symbol: symbol.clone(),
+            exchange: OPTION_EXCHANGE.to_string(),
+            action: "SELL".to_string(),
+            pricetype: "MARKET".to_string(),
+            product: "MIS".to_string(),
+            quantity: lots * LOT_SIZE,
+            placed_at: now_millis(),
+            tags: [("leg", symbol.as_str()), ("reason", "entry")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..JournalEntry::default()
+        });
+        println!("Sold {} at {:.2}, stop {:.2}", symbol, entry, entry * (1.0 + STOP_LOSS));
+        legs.push(Leg { symbol, entry, open: true });
+    }
+    if legs.is_empty() {
+        return Err("no leg was placed".into());
+    }
+
+    let bus = EventBus::new();
+    let mut events = bus.subscribe();
+    let (cmd_tx, ticks) = client.websocket().connect().await?;
+    WsSubscriber::new(cmd_tx)
+        .subscribe_ltp(legs.iter().map(|leg| WsInstrument::new(OPTION_EXCHANGE, &leg.symbol)).collect())
+        .await?;
+    let _market = bus.forward_ws(ticks);
+    let (circuit, _circuit_handle) = LossCircuit::new(MAX_DAY_LOSS).strategy(STRATEGY).start(&client);
+    let _risk = bus.forward(circuit);
+
+    let mut clock = tokio::time::interval(Duration::from_secs(15));
+    loop {
+        tokio::select! {
+            _ = clock.tick() => {
+                if ist_day_and_secs(now_secs()).1 >= SQUARE_OFF {
+                    client.close_position(STRATEGY).await?;
+                    println!("Squared off at 15:15");
+                    break;
+                }
+            }
+            event = events.recv() => match event {
+                Ok(BusEvent::Tick(WsData::Ltp(tick))) => {
+                    let Some(ltp) = tick.ltp.map(to_f64) else { continue };
+                    let Some(leg) = legs.iter_mut().find(|leg| leg.open && tick.symbol.as_deref() == Some(leg.symbol.as_str())) else {
+                        continue;
+                    };
+                    if ltp < leg.entry * (1.0 + STOP_LOSS) {
+                        continue;
+                    }
+                    let response = journal
+                        .place_tagged_order(
+                            &client,
+                            STRATEGY,
+                            &leg.symbol,
+                            "BUY",
+                            OPTION_EXCHANGE,
+                            "MARKET",
+                            "MIS",
+                            &quantity,
+                            &[("leg", leg.symbol.as_str()), ("reason", "stop-loss")],
+                        )
+                        .await?;
+                    leg.open = false;
+                    println!("Stopped out of {} at ~{:.2}: order {:?}", leg.symbol, ltp, response.orderid);
+                    if legs.iter().all(|leg| !leg.open) {
+                        break;
+                    }
+                }
+                Ok(BusEvent::Risk(RiskEvent::Circuit(CircuitEvent::Tripped { pnl, .. }))) => {
+                    eprintln!("Loss limit hit at a day P&L of {:.0}; positions closed", pnl.total);
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Event bus: {}", e),
+            },
+        }
+    }
+
+    journal.refresh(&client).await;
+    journal.save("short-straddle-journal.jsonl")?;
+    Ok(())
+}
@@ -1,10 +1,13 @@
 //! Account API module for OpenAlgo.
 
 use crate::client::{OpenAlgoClient, OpenAlgoError};
+use crate::holdings;
 use crate::types::*;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 /// Account API client
+#[derive(Clone)]
 pub struct AccountAPI {
     client: Arc<OpenAlgoClient>,
 }
@@ -18,7 +21,7 @@ impl AccountAPI {
     /// Get account funds
     pub async fn funds(&self) -> Result<FundsResponse, OpenAlgoError> {
         let request = FundsRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
         };
 
         self.client.post("funds", &request).await
@@ -27,7 +30,7 @@ impl AccountAPI {
     /// Get orderbook
     pub async fn orderbook(&self) -> Result<OrderbookResponse, OpenAlgoError> {
         let request = OrderbookRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
         };
 
         self.client.post("orderbook", &request).await
@@ -36,7 +39,7 @@ impl AccountAPI {
     /// Get tradebook
     pub async fn tradebook(&self) -> Result<TradebookResponse, OpenAlgoError> {
         let request = TradebookRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
         };
 
         self.client.post("tradebook", &request).await
@@ -45,7 +48,7 @@ impl AccountAPI {
     /// Get positionbook
     pub async fn positionbook(&self) -> Result<PositionbookResponse, OpenAlgoError> {
         let request = PositionbookRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
         };
 
         self.client.post("positionbook", &request).await
@@ -54,12 +57,30 @@ impl AccountAPI {
     /// Get holdings
     pub async fn holdings(&self) -> Result<HoldingsResponse, OpenAlgoError> {
         let request = HoldingsRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
         };
 
         self.client.post("holdings", &request).await
     }
 
+    /// Quantity that can still be sold as delivery, per symbol
+    ///
+    /// Fetches holdings and the positionbook and subtracts today's CNC sells.
+    pub async fn sellable_quantities(&self) -> Result<BTreeMap<String, i64>, OpenAlgoError> {
+        let holdings = self.holdings().await?;
+        let positions = self.positionbook().await?;
+        Ok(holdings::sellable_quantities(
+            &holdings.data.and_then(|d| d.holdings).unwrap_or_default(),
+            &positions.data.unwrap_or_default(),
+        ))
+    }
+
+    /// Fail with `ValidationError::InsufficientHoldings` if a CNC sell exceeds the shares held
+    pub async fn check_cnc_sell(&self, symbol: &str, quantity: i64) -> Result<(), OpenAlgoError> {
+        let sellable = self.sellable_quantities().await?;
+        Ok(holdings::check_cnc_sell(&sellable, symbol, quantity)?)
+    }
+
     /// Get margin requirement
     ///
     /// # Arguments
@@ -70,7 +91,7 @@ impl AccountAPI {
         positions: Vec<MarginPosition>,
     ) -> Result<MarginResponse, OpenAlgoError> {
         let request = MarginRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             positions,
         };
 
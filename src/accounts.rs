@@ -0,0 +1,139 @@
+//! Multi-account support for OpenAlgo.
+//!
+//! Holds several configured clients under account labels so one process can
+//! trade on behalf of multiple OpenAlgo accounts.
+
+use crate::client::OpenAlgoError;
+use crate::types::*;
+use crate::OpenAlgo;
+use futures_util::future::join_all;
+use std::collections::BTreeMap;
+
+/// Clients for several OpenAlgo accounts, keyed by label
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example() -> Result<(), openalgo::client::OpenAlgoError> {
+/// use openalgo::{AccountManager, OpenAlgo};
+///
+/// let main = OpenAlgo::new("main-account-key");
+/// let family = main.with_api_key("family-account-key");
+/// let accounts = AccountManager::new()
+///     .with_account("main", main)
+///     .with_account("family", family)
+///     .with_account("hni", OpenAlgo::with_host("hni-key", "https://hni.example.com"));
+///
+/// // Route one order by label
+/// accounts.place_order("family", "Strategy1", "RELIANCE", "BUY", "NSE", "MARKET", "MIS", "1").await?;
+///
+/// // Or place the same order on every account
+/// for (label, result) in accounts.place_order_all("Strategy1", "TCS", "BUY", "NSE", "MARKET", "MIS", "1").await {
+///     println!("{}: {:?}", label, result.map(|r| r.orderid));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct AccountManager {
+    accounts: BTreeMap<String, OpenAlgo>,
+}
+
+impl AccountManager {
+    /// Create an empty manager
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an account, replacing any existing one with the same label
+    pub fn with_account(mut self, label: &str, client: OpenAlgo) -> Self {
+        self.insert(label, client);
+        self
+    }
+
+    /// Add an account, returning the client it replaced
+    pub fn insert(&mut self, label: &str, client: OpenAlgo) -> Option<OpenAlgo> {
+        self.accounts.insert(label.to_string(), client)
+    }
+
+    /// Remove an account
+    pub fn remove(&mut self, label: &str) -> Option<OpenAlgo> {
+        self.accounts.remove(label)
+    }
+
+    /// Client for an account, if configured
+    pub fn get(&self, label: &str) -> Option<&OpenAlgo> {
+        self.accounts.get(label)
+    }
+
+    /// Client for an account, or an error naming the unknown label
+    pub fn account(&self, label: &str) -> Result<&OpenAlgo, OpenAlgoError> {
+        self.get(label)
+            .ok_or_else(|| OpenAlgoError::ApiError(format!("Unknown account: {}", label).into()))
+    }
+
+    /// Configured account labels in sorted order
+    pub fn labels(&self) -> Vec<&str> {
+        self.accounts.keys().map(String::as_str).collect()
+    }
+
+    /// Number of accounts
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Whether no accounts are configured
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Place a market order on one account
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_order(
+        &self,
+        label: &str,
+        strategy: &str,
+        symbol: &str,
+        action: &str,
+        exchange: &str,
+        pricetype: &str,
+        product: &str,
+        quantity: &str,
+    ) -> Result<OrderResponse, OpenAlgoError> {
+        self.account(label)?
+            .place_order(strategy, symbol, action, exchange, pricetype, product, quantity)
+            .await
+    }
+
+    /// Place the same order on every account concurrently
+    ///
+    /// Returns one result per account label; a failure on one account does
+    /// not stop the others.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_order_all(
+        &self,
+        strategy: &str,
+        symbol: &str,
+        action: &str,
+        exchange: &str,
+        pricetype: &str,
+        product: &str,
+        quantity: &str,
+    ) -> Vec<(String, Result<OrderResponse, OpenAlgoError>)> {
+        let orders = self.accounts.iter().map(|(label, client)| async move {
+            let result = client
+                .place_order(strategy, symbol, action, exchange, pricetype, product, quantity)
+                .await;
+            (label.clone(), result)
+        });
+        join_all(orders).await
+    }
+
+    /// Positions for every account
+    pub async fn positionbooks(&self) -> Vec<(String, Result<PositionbookResponse, OpenAlgoError>)> {
+        let books = self
+            .accounts
+            .iter()
+            .map(|(label, client)| async move { (label.clone(), client.positionbook().await) });
+        join_all(books).await
+    }
+}
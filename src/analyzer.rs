@@ -5,6 +5,7 @@ use crate::types::*;
 use std::sync::Arc;
 
 /// Analyzer API client
+#[derive(Clone)]
 pub struct AnalyzerAPI {
     client: Arc<OpenAlgoClient>,
 }
@@ -18,7 +19,7 @@ impl AnalyzerAPI {
     /// Get analyzer status
     pub async fn status(&self) -> Result<AnalyzerStatusResponse, OpenAlgoError> {
         let request = AnalyzerStatusRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
         };
 
         self.client.post("analyzer", &request).await
@@ -34,7 +35,7 @@ impl AnalyzerAPI {
         mode: bool,
     ) -> Result<AnalyzerToggleResponse, OpenAlgoError> {
         let request = AnalyzerToggleRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             mode,
         };
 
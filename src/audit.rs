@@ -0,0 +1,106 @@
+//! Audit log of order-mutating API calls.
+//!
+//! `AuditLog` appends one JSON line per order placement, modification,
+//! cancellation or position close: the time in IST, endpoint, request body
+//! with the API key removed, and the response or error. The file is only ever
+//! appended to. Built with the `audit` feature and installed with
+//! `OpenAlgoBuilder::audit_log`.
+//!
+//! ```text
+//! {"endpoint":"placeorder","ok":true,"request":{"action":"BUY",...},"response":{"orderid":"2403","status":"success"},"timestamp":"2025-01-06T10:15:02.114+05:30"}
+//! ```
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::audit::AuditLog;
+//! use openalgo::OpenAlgo;
+//!
+//! # fn example() -> std::io::Result<()> {
+//! let client = OpenAlgo::builder("your-api-key")
+//!     .audit_log(AuditLog::open("orders-audit.jsonl")?)
+//!     .build();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::calendar;
+use crate::client::OpenAlgoError;
+use crate::middleware::RequestParts;
+use crate::retry;
+use serde_json::{json, Value};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Append-only JSONL file of order-mutating calls
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self { path, file: Mutex::new(file) })
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether calls to an endpoint are recorded
+    pub fn records(endpoint: &str) -> bool {
+        retry::mutates_orders(endpoint)
+    }
+
+    /// Append an entry for a call and its outcome
+    ///
+    /// Write failures are logged as warnings rather than failing the call,
+    /// which has already reached the server.
+    pub fn record(&self, request: &RequestParts, result: &Result<String, OpenAlgoError>) {
+        let line = entry(request, result).to_string();
+        let mut file = self.file.lock().unwrap();
+        if let Err(e) = writeln!(file, "{}", line).and_then(|_| file.flush()) {
+            tracing::warn!(error = %e, path = %self.path.display(), "failed to write audit log");
+        }
+    }
+}
+
+fn entry(request: &RequestParts, result: &Result<String, OpenAlgoError>) -> Value {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default();
+    let timestamp = calendar::ist_datetime(millis)
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, false))
+        .unwrap_or_default();
+    let mut body = request
+        .body
+        .as_deref()
+        .map(|b| serde_json::from_str(b).unwrap_or_else(|_| Value::String(b.to_string())))
+        .unwrap_or(Value::Null);
+    if let Some(fields) = body.as_object_mut() {
+        fields.remove("apikey");
+    }
+
+    let mut entry = json!({
+        "timestamp": timestamp,
+        "endpoint": request.endpoint,
+        "request": body,
+        "ok": result.is_ok(),
+    });
+    if let Some(id) = request.header_value(crate::client::REQUEST_ID_HEADER) {
+        entry["request_id"] = id.into();
+    }
+    match result {
+        Ok(text) => entry["response"] = serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.clone())),
+        Err(e) => entry["error"] = e.to_string().into(),
+    }
+    entry
+}
@@ -0,0 +1,286 @@
+//! openalgo command-line client
+//!
+//! Quick checks against an OpenAlgo server without writing a program.
+//! Built with the `cli` feature:
+//!
+//! ```text
+//! cargo install openalgo --features cli
+//! export OPENALGO_API_KEY=your_api_key
+//! openalgo quotes RELIANCE NSE
+//! openalgo place RELIANCE BUY NSE --quantity 1 --json
+//! openalgo new-strategy momentum-bot
+//! ```
+
+use clap::{Parser, Subcommand};
+use openalgo::scaffold::Scaffold;
+use openalgo::OpenAlgo;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "openalgo", version, about = "Command-line client for the OpenAlgo API")]
+struct Cli {
+    /// API key (defaults to OPENALGO_API_KEY or the config file)
+    #[arg(long, global = true)]
+    api_key: Option<String>,
+
+    /// API host (defaults to OPENALGO_HOST, the config file or http://127.0.0.1:5000)
+    #[arg(long, global = true)]
+    host: Option<String>,
+
+    /// Config file with `api_key = ...` and `host = ...` lines (defaults to ~/.openalgo)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Print raw JSON instead of tables
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Quote for a symbol
+    Quotes { symbol: String, exchange: String },
+    /// Market depth for a symbol
+    Depth { symbol: String, exchange: String },
+    /// Historical candles
+    History {
+        symbol: String,
+        exchange: String,
+        #[arg(long, default_value = "5m")]
+        interval: String,
+        #[arg(long, requires = "end")]
+        start: Option<String>,
+        #[arg(long, requires = "start")]
+        end: Option<String>,
+    },
+    /// Place an order (LIMIT when --price is given, otherwise MARKET)
+    Place {
+        symbol: String,
+        action: String,
+        exchange: String,
+        #[arg(long, default_value = "1")]
+        quantity: String,
+        #[arg(long, default_value = "MIS")]
+        product: String,
+        #[arg(long)]
+        price: Option<String>,
+        #[arg(long, default_value = "cli")]
+        strategy: String,
+    },
+    /// Cancel an order
+    Cancel {
+        orderid: String,
+        #[arg(long, default_value = "cli")]
+        strategy: String,
+    },
+    /// Open positions
+    Positions,
+    /// Funds and margin
+    Funds,
+    /// Show analyzer mode, or switch it with `on` / `off`
+    Analyzer { mode: Option<String> },
+    /// Create a strategy crate wired to config loading, risk limits and the live feed
+    NewStrategy {
+        name: String,
+        /// Directory to create (defaults to ./<name>)
+        #[arg(long)]
+        path: Option<PathBuf>,
+        /// Depend on a local SDK checkout instead of crates.io
+        #[arg(long)]
+        sdk_path: Option<PathBuf>,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    if let Command::NewStrategy { name, path, sdk_path } = &cli.command {
+        return new_strategy(name, path.as_ref(), sdk_path.as_ref());
+    }
+    let config = read_config(cli.config.as_ref())?;
+    let api_key = cli
+        .api_key
+        .or_else(|| std::env::var("OPENALGO_API_KEY").ok())
+        .or_else(|| config.api_key.clone())
+        .ok_or("no API key: pass --api-key, set OPENALGO_API_KEY or add api_key to the config file")?;
+    let host = cli
+        .host
+        .or_else(|| std::env::var("OPENALGO_HOST").ok())
+        .or(config.host)
+        .unwrap_or_else(|| "http://127.0.0.1:5000".to_string());
+    let client = OpenAlgo::with_host(&api_key, &host);
+
+    let response = match cli.command {
+        Command::Quotes { symbol, exchange } => {
+            let quotes = client.quotes(&symbol, &exchange).await?;
+            if !cli.json {
+                println!("{}", quotes);
+                return Ok(());
+            }
+            serde_json::to_value(quotes)?
+        }
+        Command::Depth { symbol, exchange } => serde_json::to_value(client.depth(&symbol, &exchange).await?)?,
+        Command::History { symbol, exchange, interval, start, end } => match (start, end) {
+            (Some(start), Some(end)) => client.history_range(&symbol, &exchange, &interval, &start, &end).await?,
+            _ => client.history(&symbol, &exchange, &interval).await?,
+        },
+        Command::Place { symbol, action, exchange, quantity, product, price, strategy } => {
+            let action = action.to_uppercase();
+            let response = match price {
+                Some(price) => {
+                    client
+                        .place_limit_order(&strategy, &symbol, &action, &exchange, &product, &quantity, &price)
+                        .await?
+                }
+                None => {
+                    client
+                        .place_order(&strategy, &symbol, &action, &exchange, "MARKET", &product, &quantity)
+                        .await?
+                }
+            };
+            serde_json::to_value(response)?
+        }
+        Command::Cancel { orderid, strategy } => serde_json::to_value(client.cancel_order(&orderid, &strategy).await?)?,
+        Command::Positions => {
+            let positions = client.positionbook().await?;
+            if !cli.json {
+                println!("{}", positions);
+                return Ok(());
+            }
+            serde_json::to_value(positions)?
+        }
+        Command::Funds => serde_json::to_value(client.funds().await?)?,
+        Command::Analyzer { mode } => match mode.as_deref() {
+            None => serde_json::to_value(client.analyzer_status().await?)?,
+            Some("on") => serde_json::to_value(client.analyzer_toggle(true).await?)?,
+            Some("off") => serde_json::to_value(client.analyzer_toggle(false).await?)?,
+            Some(other) => return Err(format!("analyzer mode must be `on` or `off`, got `{}`", other).into()),
+        },
+        Command::NewStrategy { .. } => unreachable!("handled before connecting"),
+    };
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&response)?);
+    } else {
+        print_table(&response);
+    }
+    Ok(())
+}
+
+fn new_strategy(name: &str, path: Option<&PathBuf>, sdk_path: Option<&PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let mut scaffold = Scaffold::new(name)?;
+    if let Some(sdk_path) = sdk_path {
+        scaffold = scaffold.sdk_path(std::fs::canonicalize(sdk_path)?);
+    }
+    let dir = path.cloned().unwrap_or_else(|| PathBuf::from(name));
+    for file in scaffold.write(&dir)? {
+        println!("created {}", file.display());
+    }
+    println!("\nNext: cd {}, copy .env.example to .env, edit openalgo.toml and run `cargo run`", dir.display());
+    Ok(())
+}
+
+#[derive(Default)]
+struct Config {
+    api_key: Option<String>,
+    host: Option<String>,
+}
+
+/// Read `key = value` lines from the config file, if present
+fn read_config(path: Option<&PathBuf>) -> Result<Config, Box<dyn std::error::Error>> {
+    let path = match path {
+        Some(path) => path.clone(),
+        None => match std::env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(".openalgo"),
+            None => return Ok(Config::default()),
+        },
+    };
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(format!("reading {}: {}", path.display(), e).into()),
+    };
+
+    let mut config = Config::default();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let value = value.trim().trim_matches('"').to_string();
+        match key.trim() {
+            "api_key" => config.api_key = Some(value),
+            "host" => config.host = Some(value),
+            _ => {}
+        }
+    }
+    Ok(config)
+}
+
+/// Print the `data` of a response as a table, or the whole response when it has none
+fn print_table(response: &Value) {
+    if let Some(message) = response.get("message").and_then(Value::as_str) {
+        println!("{}", message);
+    }
+    let data = response.get("data").unwrap_or(response);
+    match data {
+        Value::Array(rows) if rows.iter().all(Value::is_object) && !rows.is_empty() => print_rows(rows),
+        Value::Object(map) => {
+            let width = map.keys().map(String::len).max().unwrap_or(0);
+            for (key, value) in map {
+                println!("{:width$}  {}", key, cell(value), width = width);
+            }
+        }
+        Value::Array(rows) if rows.is_empty() => println!("(none)"),
+        other => println!("{}", cell(other)),
+    }
+}
+
+fn print_rows(rows: &[Value]) {
+    let columns: Vec<String> = rows
+        .iter()
+        .filter_map(Value::as_object)
+        .flat_map(|row| row.keys().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| columns.iter().map(|c| row.get(c).map(cell).unwrap_or_default()).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| cells.iter().map(|r| r[i].len()).chain([c.len()]).max().unwrap_or(0))
+        .collect();
+
+    let line = |values: &[String]| {
+        values
+            .iter()
+            .zip(&widths)
+            .map(|(v, w)| format!("{:w$}", v, w = *w))
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+    println!("{}", line(&columns));
+    for row in &cells {
+        println!("{}", line(row));
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
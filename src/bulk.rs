@@ -0,0 +1,114 @@
+//! Bounded-parallelism helpers for bulk REST calls.
+//!
+//! `run` calls an async function for every item with at most `concurrency`
+//! calls in flight and returns each item with its own result, in input order.
+//! A failed item does not stop the others. `OpenAlgo::bulk_quotes`,
+//! `bulk_order_status` and `bulk_symbols` cover the common cases.
+//!
+//! The client has no rate limiter of its own, and `concurrency` only bounds
+//! how many calls are in flight: fast responses can still add up to more
+//! requests per second than the server allows. `run_rate_limited` also
+//! spaces out the start of each call.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::{bulk, OpenAlgo};
+//!
+//! # async fn example(client: OpenAlgo) {
+//! let symbols = ["RELIANCE", "TCS", "INFY", "HDFCBANK"];
+//! let ltps = bulk::run(symbols, 2, |symbol| {
+//!     let client = client.clone();
+//!     async move { client.ltp(symbol, "NSE").await }
+//! })
+//! .await;
+//! for (symbol, ltp) in ltps {
+//!     println!("{}: {:?}", symbol, ltp);
+//! }
+//! # }
+//! ```
+
+use crate::client::OpenAlgoError;
+use futures_util::stream::{self, StreamExt};
+use std::future::Future;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+/// Concurrency used when zero is passed
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Run `call` for every item with at most `concurrency` calls in flight
+///
+/// Results keep the input order. A `concurrency` of zero uses
+/// [`DEFAULT_CONCURRENCY`].
+///
+/// # Example
+/// ```rust
+/// use openalgo::bulk;
+/// use openalgo::client::OpenAlgoError;
+///
+/// let results = tokio_test::block_on(bulk::run([1, 2, 3], 2, |n| async move {
+///     if n == 2 { Err(OpenAlgoError::ApiError("bad item".into())) } else { Ok(n * 10) }
+/// }));
+/// assert_eq!(results[0].1.as_ref().ok(), Some(&10));
+/// assert!(results[1].1.is_err());
+/// assert_eq!(results[2].0, 3);
+/// ```
+pub async fn run<I, T, F, Fut, R>(items: I, concurrency: usize, call: F) -> Vec<(T, Result<R, OpenAlgoError>)>
+where
+    I: IntoIterator<Item = T>,
+    T: Clone,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Result<R, OpenAlgoError>>,
+{
+    let concurrency = if concurrency == 0 { DEFAULT_CONCURRENCY } else { concurrency };
+    stream::iter(items)
+        .map(|item| {
+            let call = call(item.clone());
+            async move { (item, call.await) }
+        })
+        .buffered(concurrency)
+        .collect()
+        .await
+}
+
+/// Like `run`, but starting at most `per_second` calls each second
+///
+/// A `per_second` of zero is treated as one.
+///
+/// # Example
+/// ```rust
+/// use openalgo::bulk;
+/// use std::time::{Duration, Instant};
+///
+/// let started = Instant::now();
+/// let results = tokio_test::block_on(bulk::run_rate_limited(1..=5, 5, 50, |n| async move { Ok(n) }));
+/// assert_eq!(results.len(), 5);
+/// // Five calls at 50 per second: the last starts 80 ms after the first
+/// assert!(started.elapsed() >= Duration::from_millis(80));
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn run_rate_limited<I, T, F, Fut, R>(
+    items: I,
+    concurrency: usize,
+    per_second: u32,
+    call: F,
+) -> Vec<(T, Result<R, OpenAlgoError>)>
+where
+    I: IntoIterator<Item = T>,
+    T: Clone,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Result<R, OpenAlgoError>>,
+{
+    let mut ticker = tokio::time::interval(Duration::from_secs(1) / per_second.max(1));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let ticker = tokio::sync::Mutex::new(ticker);
+    run(items, concurrency, |item| {
+        let ticker = &ticker;
+        let call = &call;
+        async move {
+            ticker.lock().await.tick().await;
+            call(item).await
+        }
+    })
+    .await
+}
@@ -0,0 +1,326 @@
+//! Typed event bus connecting data, strategy, risk and execution.
+//!
+//! An `EventBus` carries one `BusEvent` enum (ticks, candles, signals, order
+//! events and risk events) from any number of publishers to any number of
+//! subscribers. Components publish what they produce and subscribe to what
+//! they need, so a candle builder, a strategy, a risk check, an alerter and
+//! the order journal can be wired together without a channel between every
+//! pair. Every subscriber gets every event, in publish order.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::bus::{BusEvent, EventBus, Signal};
+//!
+//! # async fn example(ws: openalgo::websocket::OpenAlgoWebSocket) -> Result<(), openalgo::client::OpenAlgoError> {
+//! let bus = EventBus::new();
+//! let (_commands, ticks) = ws.connect().await?;
+//! let _ticks = bus.forward_ws(ticks);
+//!
+//! // Strategy: turn candles into signals
+//! let strategy_bus = bus.clone();
+//! let mut events = bus.subscribe();
+//! tokio::spawn(async move {
+//!     while let Ok(event) = events.recv().await {
+//!         if let BusEvent::Candle(c) = event {
+//!             if c.candle.close > c.candle.open {
+//!                 strategy_bus.publish(Signal::new("Momentum", &c.symbol, &c.exchange, "BUY", 10).tag("interval", &c.interval));
+//!             }
+//!         }
+//!     }
+//! });
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::candles::{MultiTimeframe, TimeframeBars};
+use crate::circuit::CircuitEvent;
+use crate::dashboard::OrderEvent;
+use crate::types::HistoryCandle;
+use crate::websocket::WsData;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Events buffered per subscriber before the slowest one starts missing them
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// A completed candle of one instrument and interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandleEvent {
+    pub symbol: String,
+    pub exchange: String,
+    /// Interval string such as "1m" or "15m"
+    pub interval: String,
+    pub candle: HistoryCandle,
+}
+
+/// A strategy's decision to trade, before any order is sent
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Signal {
+    pub strategy: String,
+    pub symbol: String,
+    pub exchange: String,
+    /// "BUY" or "SELL"
+    pub action: String,
+    pub quantity: i64,
+    /// Limit price; `None` for a market order
+    pub price: Option<f64>,
+    /// Attribution tags, such as the signal name, for `OrderJournal::place_tagged_order`
+    pub tags: BTreeMap<String, String>,
+}
+
+impl Signal {
+    /// Market signal without tags
+    pub fn new(strategy: &str, symbol: &str, exchange: &str, action: &str, quantity: i64) -> Self {
+        Self {
+            strategy: strategy.to_string(),
+            symbol: symbol.to_string(),
+            exchange: exchange.to_string(),
+            action: action.to_ascii_uppercase(),
+            quantity,
+            ..Self::default()
+        }
+    }
+
+    /// Limit price
+    pub fn price(mut self, price: f64) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// Add a tag
+    pub fn tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.insert(key.to_string(), value.to_string());
+        self
+    }
+}
+
+/// Something the risk side decided or observed
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskEvent {
+    /// From a running `LossCircuit`
+    Circuit(CircuitEvent),
+    /// A signal a risk check refused to trade
+    Rejected { signal: Signal, reason: String },
+}
+
+/// What travels on an `EventBus`
+#[derive(Debug, Clone, PartialEq)]
+pub enum BusEvent {
+    /// LTP, quote or depth message from the WebSocket
+    Tick(WsData),
+    Candle(CandleEvent),
+    /// Aligned bars of several intervals, from `EventBus::forward_timeframes`
+    Bars(TimeframeBars),
+    Signal(Signal),
+    Order(OrderEvent),
+    Risk(RiskEvent),
+}
+
+impl BusEvent {
+    /// Bus event for a WebSocket message; `None` for messages that are not market data
+    pub fn from_ws(data: WsData) -> Option<Self> {
+        match data {
+            WsData::Ltp(_) | WsData::Quote(_) | WsData::Depth(_) => Some(Self::Tick(data)),
+            WsData::Candle {
+                exchange,
+                symbol,
+                interval,
+                candle,
+            } => Some(Self::Candle(CandleEvent {
+                symbol,
+                exchange,
+                interval,
+                candle,
+            })),
+            _ => None,
+        }
+    }
+}
+
+impl From<CandleEvent> for BusEvent {
+    fn from(candle: CandleEvent) -> Self {
+        Self::Candle(candle)
+    }
+}
+
+impl From<TimeframeBars> for BusEvent {
+    fn from(bars: TimeframeBars) -> Self {
+        Self::Bars(bars)
+    }
+}
+
+impl From<Signal> for BusEvent {
+    fn from(signal: Signal) -> Self {
+        Self::Signal(signal)
+    }
+}
+
+impl From<OrderEvent> for BusEvent {
+    fn from(order: OrderEvent) -> Self {
+        Self::Order(order)
+    }
+}
+
+impl From<RiskEvent> for BusEvent {
+    fn from(risk: RiskEvent) -> Self {
+        Self::Risk(risk)
+    }
+}
+
+impl From<CircuitEvent> for BusEvent {
+    fn from(event: CircuitEvent) -> Self {
+        Self::Risk(RiskEvent::Circuit(event))
+    }
+}
+
+/// Publish/subscribe bus of `BusEvent`s
+///
+/// Clones publish into the same bus.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<BusEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl EventBus {
+    /// A bus buffering 1024 events per subscriber
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A bus buffering `capacity` events per subscriber
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            tx: broadcast::channel(capacity.max(1)).0,
+        }
+    }
+
+    /// Receive every event published from now on
+    ///
+    /// A subscriber that falls more than the capacity behind gets
+    /// `RecvError::Lagged` and skips ahead.
+    pub fn subscribe(&self) -> broadcast::Receiver<BusEvent> {
+        self.tx.subscribe()
+    }
+
+    /// Number of live subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    /// Publish an event, returning how many subscribers will see it
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::bus::{BusEvent, EventBus, RiskEvent, Signal};
+    ///
+    /// let bus = EventBus::new();
+    /// let mut risk = bus.subscribe();
+    /// let signal = Signal::new("Momentum", "SBIN", "NSE", "buy", 10).tag("signal", "breakout");
+    /// assert_eq!(bus.publish(signal.clone()), 1);
+    /// bus.publish(RiskEvent::Rejected { signal, reason: "max exposure".into() });
+    ///
+    /// assert!(matches!(risk.try_recv().unwrap(), BusEvent::Signal(s) if s.action == "BUY"));
+    /// assert!(matches!(risk.try_recv().unwrap(), BusEvent::Risk(RiskEvent::Rejected { .. })));
+    /// ```
+    pub fn publish(&self, event: impl Into<BusEvent>) -> usize {
+        // No subscribers is not an error: the event is simply not seen
+        self.tx.send(event.into()).unwrap_or(0)
+    }
+
+    /// Publish the market data of a WebSocket stream until it ends
+    ///
+    /// Ticks become `BusEvent::Tick` and built candles `BusEvent::Candle`;
+    /// other messages are dropped.
+    pub fn forward_ws(&self, mut data: mpsc::Receiver<WsData>) -> JoinHandle<()> {
+        let bus = self.clone();
+        tokio::spawn(async move {
+            while let Some(data) = data.recv().await {
+                if let Some(event) = BusEvent::from_ws(data) {
+                    bus.publish(event);
+                }
+            }
+        })
+    }
+
+    /// Build aligned bars from a WebSocket stream and publish them until it ends
+    ///
+    /// Publishes a `BusEvent::Bars` at every close of the base interval.
+    /// Bars are also closed by the clock, a second after their interval
+    /// ends, so a quiet instrument still delivers its closes.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use openalgo::bus::{BusEvent, EventBus};
+    /// use openalgo::candles::MultiTimeframe;
+    ///
+    /// # async fn example(ws: openalgo::websocket::OpenAlgoWebSocket) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let bus = EventBus::new();
+    /// let mut events = bus.subscribe();
+    /// let (_commands, ticks) = ws.connect().await?;
+    /// let _bars = bus.forward_timeframes(ticks, MultiTimeframe::new(&["5m", "1h"]).unwrap());
+    ///
+    /// while let Ok(event) = events.recv().await {
+    ///     let BusEvent::Bars(bars) = event else { continue };
+    ///     // Trade 5m closes in the direction of the last completed hour
+    ///     let (Some(bar), Some(hour)) = (bars.bar("5m"), bars.bar("1h")) else { continue };
+    ///     if hour.close > hour.open && bar.close > bar.open {
+    ///         println!("{} long setup at {}", bars.symbol, bars.close_time);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn forward_timeframes(&self, mut data: mpsc::Receiver<WsData>, mut timeframes: MultiTimeframe) -> JoinHandle<()> {
+        let bus = self.clone();
+        tokio::spawn(async move {
+            let mut flush = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                tokio::select! {
+                    _ = flush.tick() => {
+                        // Allow a second for late ticks before closing a bar
+                        for bars in timeframes.flush(unix_secs() - 1) {
+                            bus.publish(bars);
+                        }
+                    }
+                    data = data.recv() => match data {
+                        Some(data) => {
+                            if let Some(bars) = timeframes.on_tick(&data, unix_secs()) {
+                                bus.publish(bars);
+                            }
+                        }
+                        None => break,
+                    },
+                }
+            }
+        })
+    }
+
+    /// Publish everything received on a channel until it closes
+    ///
+    /// Used to put the events of a `LossCircuit`, or any other component
+    /// reporting on an `mpsc` channel, on the bus.
+    pub fn forward<T: Into<BusEvent> + Send + 'static>(&self, mut events: mpsc::Receiver<T>) -> JoinHandle<()> {
+        let bus = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                bus.publish(event);
+            }
+        })
+    }
+}
+
+fn unix_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
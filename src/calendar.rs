@@ -0,0 +1,226 @@
+//! Market calendar for OpenAlgo.
+//!
+//! Trading sessions and holidays in IST, used to tell real data gaps apart
+//! from nights, weekends and exchange holidays.
+
+use crate::types::HolidaysResponse;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
+use std::collections::HashSet;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// IST offset from UTC in seconds (+05:30)
+pub const IST_OFFSET_SECS: i64 = 19_800;
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Trading calendar for an exchange
+#[derive(Debug, Clone)]
+pub struct MarketCalendar {
+    /// Session open as seconds after IST midnight
+    pub open_secs: i64,
+    /// Session close as seconds after IST midnight
+    pub close_secs: i64,
+    /// Whether Saturday and Sunday are non-trading days
+    pub skip_weekends: bool,
+    holidays: HashSet<i64>,
+}
+
+impl Default for MarketCalendar {
+    fn default() -> Self {
+        Self::nse()
+    }
+}
+
+impl MarketCalendar {
+    /// NSE/BSE equity and F&O session (09:15 - 15:30 IST, Monday to Friday)
+    pub fn nse() -> Self {
+        Self::new(9 * 3_600 + 15 * 60, 15 * 3_600 + 30 * 60)
+    }
+
+    /// MCX commodity session (09:00 - 23:30 IST, Monday to Friday)
+    pub fn mcx() -> Self {
+        Self::new(9 * 3_600, 23 * 3_600 + 30 * 60)
+    }
+
+    /// Custom weekday session given as seconds after IST midnight
+    pub fn new(open_secs: i64, close_secs: i64) -> Self {
+        Self {
+            open_secs,
+            close_secs,
+            skip_weekends: true,
+            holidays: HashSet::new(),
+        }
+    }
+
+    /// Add a holiday in YYYY-MM-DD format, ignoring invalid dates
+    pub fn add_holiday(&mut self, date: &str) {
+        if let Some(day) = parse_date(date) {
+            self.holidays.insert(day);
+        }
+    }
+
+    /// Add the holidays from a `holidays()` response on which `exchange` is closed
+    pub fn with_holidays(mut self, holidays: &HolidaysResponse, exchange: &str) -> Self {
+        for holiday in holidays.data.iter().flatten() {
+            if holiday.closed_exchanges.iter().any(|e| e == exchange) {
+                self.add_holiday(&holiday.date);
+            }
+        }
+        self
+    }
+
+    /// Whether the IST calendar day (days since epoch) is a trading day
+    pub fn is_trading_day(&self, day: i64) -> bool {
+        if self.skip_weekends {
+            // 1970-01-01 was a Thursday
+            let weekday = (day + 3).rem_euclid(7);
+            if weekday >= 5 {
+                return false;
+            }
+        }
+        !self.holidays.contains(&day)
+    }
+
+    /// Whether a Unix timestamp falls within a trading session
+    pub fn is_trading_time(&self, timestamp: i64) -> bool {
+        let (day, secs) = ist_day_and_secs(timestamp);
+        self.is_trading_day(day) && secs >= self.open_secs && secs < self.close_secs
+    }
+
+    /// Whether a date and time, in any time zone, falls within a trading session
+    pub fn is_trading_at<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        self.is_trading_time(time.timestamp())
+    }
+
+    /// Whether the session is open now, by the local clock
+    pub fn is_market_open(&self) -> bool {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+        self.is_trading_time(now)
+    }
+}
+
+/// The IST time zone (UTC+05:30)
+pub fn ist() -> FixedOffset {
+    FixedOffset::east_opt(IST_OFFSET_SECS as i32).expect("IST offset is in range")
+}
+
+/// Unix seconds for a timestamp given in seconds or milliseconds
+pub fn epoch_secs(timestamp: i64) -> i64 {
+    if timestamp > 100_000_000_000 {
+        timestamp / 1000
+    } else {
+        timestamp
+    }
+}
+
+/// IST date and time of a Unix timestamp in seconds or milliseconds
+///
+/// # Example
+/// ```rust
+/// use openalgo::calendar::ist_datetime;
+/// let time = ist_datetime(1733111100).unwrap();
+/// assert_eq!(time.to_rfc3339(), "2024-12-02T09:15:00+05:30");
+/// assert_eq!(ist_datetime(1733111100_000), Some(time));
+/// ```
+pub fn ist_datetime(timestamp: i64) -> Option<DateTime<FixedOffset>> {
+    let millis = if timestamp > 100_000_000_000 { timestamp } else { timestamp.checked_mul(1000)? };
+    DateTime::from_timestamp_millis(millis).map(|t| t.with_timezone(&ist()))
+}
+
+/// Broker timestamp formats, read as IST
+const IST_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%d-%m-%Y %H:%M:%S",
+    "%d-%b-%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+    "%H:%M:%S %d-%m-%Y",
+    "%H:%M:%S %d-%b-%Y",
+];
+
+/// Parse a timestamp string from a response into IST
+///
+/// Accepts Unix seconds or milliseconds, RFC 3339, and the date-time formats
+/// brokers use, which are read as IST when they carry no offset.
+///
+/// # Example
+/// ```rust
+/// use openalgo::calendar::parse_timestamp;
+/// let time = parse_timestamp("28-Nov-2024 09:59:10").unwrap();
+/// assert_eq!(time.timestamp(), 1732768150);
+/// assert_eq!(parse_timestamp("1732768150"), Some(time));
+/// ```
+pub fn parse_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
+    let value = value.trim();
+    if let Ok(epoch) = value.parse::<i64>() {
+        return ist_datetime(epoch);
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&ist()));
+    }
+    IST_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(|naive| ist().from_local_datetime(&naive).single())
+}
+
+/// Split a Unix timestamp into (IST days since epoch, seconds after IST midnight)
+pub fn ist_day_and_secs(timestamp: i64) -> (i64, i64) {
+    let local = timestamp + IST_OFFSET_SECS;
+    (local.div_euclid(SECS_PER_DAY), local.rem_euclid(SECS_PER_DAY))
+}
+
+/// Parse a YYYY-MM-DD date into days since 1970-01-01
+pub fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.trim().splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.get(..2)?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+/// Format days since 1970-01-01 as YYYY-MM-DD
+///
+/// # Example
+/// ```rust
+/// use openalgo::calendar::{format_date, parse_date};
+/// assert_eq!(format_date(parse_date("2024-12-26").unwrap()), "2024-12-26");
+/// ```
+pub fn format_date(day: i64) -> String {
+    let (year, month, day) = civil_from_days(day);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// IST calendar date (YYYY-MM-DD) of a Unix timestamp
+pub fn ist_date(timestamp: i64) -> String {
+    format_date(ist_day_and_secs(timestamp).0)
+}
+
+/// Number of days since 1970-01-01 for a proleptic Gregorian date
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Proleptic Gregorian (year, month, day) for days since 1970-01-01
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
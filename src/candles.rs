@@ -0,0 +1,726 @@
+//! Candle utilities for OpenAlgo.
+//!
+//! Data-quality validation for historical candles, and building candles from
+//! live ticks, for one interval or several aligned intervals at once.
+
+use crate::calendar::{epoch_secs, ist_day_and_secs, MarketCalendar, IST_OFFSET_SECS};
+use chrono::{DateTime, TimeZone};
+use crate::interval::Interval;
+use crate::price::Price;
+use crate::types::HistoryCandle;
+use crate::websocket::WsData;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Length of an OpenAlgo interval string in seconds ("1m", "5m", "1h", "D")
+///
+/// Returns `None` for weekly/monthly or unrecognised intervals.
+pub fn interval_seconds(interval: &str) -> Option<i64> {
+    let interval: Interval = interval.parse().ok()?;
+    interval.is_fixed().then(|| interval.secs() as i64)
+}
+
+/// A data-quality problem found in a candle series
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CandleIssue {
+    /// A timestamp seen earlier in the series
+    Duplicate { index: usize, timestamp: i64 },
+    /// A timestamp earlier than the previous candle
+    NonMonotonic { index: usize, previous: i64, timestamp: i64 },
+    /// Missing candles during trading time between two candles
+    Gap { after: i64, before: i64, missing: usize },
+    /// A candle with zero traded volume
+    ZeroVolume { index: usize, timestamp: i64 },
+}
+
+/// Result of validating a candle series
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CandleValidationReport {
+    pub candle_count: usize,
+    pub issues: Vec<CandleIssue>,
+}
+
+impl CandleValidationReport {
+    /// Whether no issues were found
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Gap issues as (after, before, missing)
+    pub fn gaps(&self) -> Vec<(i64, i64, usize)> {
+        self.issues
+            .iter()
+            .filter_map(|issue| match issue {
+                CandleIssue::Gap { after, before, missing } => Some((*after, *before, *missing)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Total number of missing candles across all gaps
+    pub fn missing_candles(&self) -> usize {
+        self.gaps().iter().map(|(_, _, missing)| missing).sum()
+    }
+
+    /// Number of duplicate timestamps
+    pub fn duplicate_count(&self) -> usize {
+        self.count(|i| matches!(i, CandleIssue::Duplicate { .. }))
+    }
+
+    /// Number of out-of-order timestamps
+    pub fn non_monotonic_count(&self) -> usize {
+        self.count(|i| matches!(i, CandleIssue::NonMonotonic { .. }))
+    }
+
+    /// Number of zero-volume candles
+    pub fn zero_volume_count(&self) -> usize {
+        self.count(|i| matches!(i, CandleIssue::ZeroVolume { .. }))
+    }
+
+    fn count(&self, predicate: impl Fn(&CandleIssue) -> bool) -> usize {
+        self.issues.iter().filter(|i| predicate(i)).count()
+    }
+}
+
+/// Validate a candle series for gaps, duplicates, ordering and zero volume
+///
+/// Gaps are only counted for bars that fall inside the calendar's trading
+/// sessions, so nights, weekends and holidays are not reported. Index
+/// candles carry no volume and will report every candle as `ZeroVolume`.
+///
+/// # Example
+/// ```rust
+/// use openalgo::calendar::MarketCalendar;
+/// use openalgo::candles::validate_candles;
+/// use openalgo::HistoryCandle;
+///
+/// // 2024-12-02 09:15, 09:20 and 09:30 IST (09:25 is missing)
+/// let candles: Vec<HistoryCandle> = [1733111100, 1733111400, 1733112000]
+///     .iter()
+///     .map(|ts| serde_json::from_value(serde_json::json!({
+///         "timestamp": ts, "open": 1.0, "high": 1.0, "low": 1.0, "close": 1.0, "volume": 10
+///     })).unwrap())
+///     .collect();
+///
+/// let report = validate_candles(&candles, "5m", &MarketCalendar::nse());
+/// assert_eq!(report.missing_candles(), 1);
+/// ```
+pub fn validate_candles(
+    candles: &[HistoryCandle],
+    interval: &str,
+    calendar: &MarketCalendar,
+) -> CandleValidationReport {
+    let step = interval_seconds(interval);
+    let mut issues = Vec::new();
+    let mut seen = HashSet::with_capacity(candles.len());
+    let mut previous: Option<i64> = None;
+
+    for (index, candle) in candles.iter().enumerate() {
+        let timestamp = candle.timestamp;
+
+        if !seen.insert(timestamp) {
+            issues.push(CandleIssue::Duplicate { index, timestamp });
+        } else if let Some(prev) = previous {
+            if timestamp < prev {
+                issues.push(CandleIssue::NonMonotonic { index, previous: prev, timestamp });
+            } else if let Some(step) = step {
+                let missing = missing_bars(prev, timestamp, step, calendar);
+                if missing > 0 {
+                    issues.push(CandleIssue::Gap { after: prev, before: timestamp, missing });
+                }
+            }
+        }
+
+        if candle.volume == 0 {
+            issues.push(CandleIssue::ZeroVolume { index, timestamp });
+        }
+
+        previous = Some(previous.map_or(timestamp, |p| p.max(timestamp)));
+    }
+
+    CandleValidationReport {
+        candle_count: candles.len(),
+        issues,
+    }
+}
+
+/// Number of expected bars strictly between two bar timestamps
+fn missing_bars(after: i64, before: i64, step: i64, calendar: &MarketCalendar) -> usize {
+    if before - after <= step {
+        return 0;
+    }
+
+    if step >= SECS_PER_DAY {
+        let (first, _) = ist_day_and_secs(after);
+        let (last, _) = ist_day_and_secs(before);
+        return (first + 1..last)
+            .filter(|day| calendar.is_trading_day(*day))
+            .count();
+    }
+
+    (1..)
+        .map(|k| after + k * step)
+        .take_while(|t| *t < before)
+        .filter(|t| calendar.is_trading_time(*t))
+        .count()
+}
+
+/// Merge candle series, dropping duplicate timestamps and sorting by time
+///
+/// When a timestamp appears more than once the first occurrence is kept.
+pub fn merge_candles(series: impl IntoIterator<Item = HistoryCandle>) -> Vec<HistoryCandle> {
+    let mut seen = HashSet::new();
+    let mut merged: Vec<HistoryCandle> = series
+        .into_iter()
+        .filter(|c| seen.insert(c.timestamp))
+        .collect();
+    merged.sort_by_key(|c| c.timestamp);
+    merged
+}
+
+/// Write candles as CSV: `timestamp,open,high,low,close,volume,oi`
+///
+/// # Example
+/// ```rust
+/// use openalgo::candles::write_csv;
+/// use openalgo::types::HistoryCandle;
+///
+/// let candle: HistoryCandle = serde_json::from_str(
+///     r#"{"timestamp": 1733111100, "open": 801, "high": 803.5, "low": 800, "close": 802, "volume": 12000}"#,
+/// ).unwrap();
+/// let mut out = Vec::new();
+/// write_csv(&[candle], &mut out).unwrap();
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     "timestamp,open,high,low,close,volume,oi\n1733111100,801,803.5,800,802,12000,\n"
+/// );
+/// ```
+pub fn write_csv<W: std::io::Write>(candles: &[HistoryCandle], writer: W) -> std::io::Result<()> {
+    crate::csv::write_candles(candles, writer)
+}
+
+/// Builds candles of one interval from live ticks of one instrument
+///
+/// Intraday bars are aligned to the session open (09:15 IST by default), so
+/// hourly bars start at 09:15, 10:15 and so on. Bar volume is derived from the
+/// cumulative day volume carried by Quote/Depth ticks; LTP ticks add none.
+#[derive(Debug, Clone)]
+pub struct CandleBuilder {
+    step: i64,
+    /// Seconds after IST midnight that bars are aligned to
+    anchor: i64,
+    current: Option<HistoryCandle>,
+    /// Cumulative volume before the current bar
+    base_volume: Option<i64>,
+    last_volume: Option<i64>,
+    /// Start of the last completed bar; older ticks are ignored
+    last_completed: Option<i64>,
+    /// Volume of a resumed bar from before the first live tick
+    carried_volume: i64,
+}
+
+impl CandleBuilder {
+    /// Builder for bars of `step` seconds
+    pub fn new(step: i64) -> Self {
+        Self {
+            step: step.max(1),
+            anchor: MarketCalendar::nse().open_secs,
+            current: None,
+            base_volume: None,
+            last_volume: None,
+            last_completed: None,
+            carried_volume: 0,
+        }
+    }
+
+    /// Builder for an OpenAlgo interval string such as "1m" or "15m"
+    pub fn for_interval(interval: &str) -> Option<Self> {
+        interval_seconds(interval).map(Self::new)
+    }
+
+    /// Align bars to a different time of day (seconds after IST midnight)
+    pub fn with_anchor(mut self, anchor_secs: i64) -> Self {
+        self.anchor = anchor_secs;
+        self
+    }
+
+    /// Start time of the bar containing `timestamp`
+    pub fn bar_start(&self, timestamp: i64) -> i64 {
+        let offset = IST_OFFSET_SECS - self.anchor;
+        (timestamp + offset).div_euclid(self.step) * self.step - offset
+    }
+
+    /// Bar currently being built
+    pub fn current(&self) -> Option<&HistoryCandle> {
+        self.current.as_ref()
+    }
+
+    /// Add a tick, returning the previous bar if this tick starts a new one
+    ///
+    /// Ticks older than the current bar are ignored.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::candles::CandleBuilder;
+    /// use openalgo::price::from_f64;
+    ///
+    /// let mut builder = CandleBuilder::for_interval("1m").unwrap();
+    /// // 2024-12-02 09:15:05 and 09:15:40 IST, then 09:16:02 closes the first bar
+    /// assert!(builder.update(1733111105, from_f64(100.0), Some(1_000)).is_none());
+    /// assert!(builder.update(1733111140, from_f64(101.5), Some(1_400)).is_none());
+    /// let bar = builder.update(1733111162, from_f64(101.0), Some(1_500)).unwrap();
+    /// assert_eq!(bar.timestamp, 1733111100);
+    /// assert_eq!((bar.open, bar.high, bar.close), (from_f64(100.0), from_f64(101.5), from_f64(101.5)));
+    /// assert_eq!(bar.volume, 400);
+    /// ```
+    pub fn update(&mut self, timestamp: i64, price: Price, cumulative_volume: Option<i64>) -> Option<HistoryCandle> {
+        let start = self.bar_start(timestamp);
+        if self.last_completed.is_some_and(|last| start <= last) {
+            return None;
+        }
+
+        let mut completed = None;
+        match &self.current {
+            Some(bar) if start < bar.timestamp => return None,
+            Some(bar) if start > bar.timestamp => {
+                self.last_completed = Some(bar.timestamp);
+                completed = self.current.take();
+                self.base_volume = self.last_volume;
+                self.carried_volume = 0;
+            }
+            _ => {}
+        }
+
+        if let Some(volume) = cumulative_volume {
+            self.base_volume.get_or_insert(volume);
+            self.last_volume = Some(volume);
+        }
+        let volume = match (self.last_volume, self.base_volume) {
+            (Some(last), Some(base)) => (last - base).max(0),
+            _ => 0,
+        };
+
+        let bar = self.current.get_or_insert_with(|| HistoryCandle {
+            timestamp: start,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: 0,
+            oi: None,
+            extra: HashMap::new(),
+        });
+        bar.high = bar.high.max(price);
+        bar.low = bar.low.min(price);
+        bar.close = price;
+        bar.volume = volume + self.carried_volume;
+
+        completed
+    }
+
+    /// Continue a bar that is still forming, such as the last bar of a history request
+    ///
+    /// Live ticks then extend it instead of starting a partial bar, and ticks
+    /// before it are ignored. Its volume is kept and live volume is added on top.
+    pub fn resume(&mut self, mut bar: HistoryCandle) {
+        bar.timestamp = self.bar_start(bar.timestamp);
+        self.last_completed = Some(bar.timestamp - self.step);
+        self.carried_volume = bar.volume;
+        self.base_volume = None;
+        self.last_volume = None;
+        self.current = Some(bar);
+    }
+
+    /// Add a tick stamped with a date and time in any time zone
+    pub fn update_at<Tz: TimeZone>(
+        &mut self,
+        time: &DateTime<Tz>,
+        price: Price,
+        cumulative_volume: Option<i64>,
+    ) -> Option<HistoryCandle> {
+        self.update(time.timestamp(), price, cumulative_volume)
+    }
+
+    /// Close the current bar if its interval ended before `now`
+    pub fn flush(&mut self, now: i64) -> Option<HistoryCandle> {
+        let ended = self
+            .current
+            .as_ref()
+            .is_some_and(|bar| now >= bar.timestamp + self.step);
+        if ended {
+            self.base_volume = self.last_volume;
+            self.carried_volume = 0;
+            self.last_completed = self.current.as_ref().map(|bar| bar.timestamp);
+            self.current.take()
+        } else {
+            None
+        }
+    }
+}
+
+/// Candle series of one instrument that starts from history and continues live
+///
+/// The history supplies the completed bars an indicator needs to warm up. A
+/// history bar still forming at `now` is not treated as complete: live ticks
+/// extend it, so the seam between history and live bars has no partial,
+/// duplicate or missing bar.
+#[derive(Debug, Clone)]
+pub struct LiveCandles {
+    interval: String,
+    candles: Vec<HistoryCandle>,
+    builder: CandleBuilder,
+}
+
+impl LiveCandles {
+    /// Series of an intraday interval from history fetched at `now` (Unix seconds)
+    ///
+    /// Returns `None` for daily and longer or unrecognised intervals. A
+    /// history that ends more than one bar before `now` during the session is
+    /// logged, since live ticks cannot fill the missing bars.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::candles::LiveCandles;
+    /// use openalgo::price::from_f64;
+    /// use openalgo::types::HistoryCandle;
+    ///
+    /// let bar = |timestamp: i64, close: f64| HistoryCandle {
+    ///     timestamp,
+    ///     open: from_f64(close),
+    ///     high: from_f64(close),
+    ///     low: from_f64(close),
+    ///     close: from_f64(close),
+    ///     volume: 100,
+    ///     oi: None,
+    ///     extra: Default::default(),
+    /// };
+    /// // 2024-12-02: 5m bars at 09:15 and 09:20 IST, fetched at 09:22
+    /// let (open, now) = (1_733_111_100, 1_733_111_100 + 420);
+    /// let mut series = LiveCandles::new("5m", vec![bar(open, 800.0), bar(open + 300, 801.0)], now).unwrap();
+    /// // The 09:20 bar is still forming and continues from live ticks
+    /// assert_eq!(series.candles().len(), 1);
+    /// assert!(series.update(open + 200, from_f64(999.0), None).is_none()); // in a completed bar: ignored
+    /// assert!(series.update(now + 10, from_f64(803.0), None).is_none());
+    /// let closed = series.update(open + 600, from_f64(802.0), None).unwrap();
+    /// assert_eq!((closed.timestamp, closed.open, closed.high), (open + 300, from_f64(801.0), from_f64(803.0)));
+    /// assert_eq!(closed.volume, 100);
+    /// assert_eq!(series.candles().len(), 2);
+    /// ```
+    pub fn new(interval: &str, history: Vec<HistoryCandle>, now: i64) -> Option<Self> {
+        let step = interval_seconds(interval).filter(|step| *step < SECS_PER_DAY)?;
+        let mut builder = CandleBuilder::new(step);
+        let mut candles: Vec<HistoryCandle> = merge_candles(history)
+            .into_iter()
+            .filter(|c| c.timestamp <= now)
+            .collect();
+        if candles.last().is_some_and(|c| builder.bar_start(c.timestamp) + step > now) {
+            builder.resume(candles.pop()?);
+        }
+
+        let next = builder.current().map_or(builder.bar_start(now), |bar| bar.timestamp);
+        if let Some(last) = candles.last() {
+            let calendar = MarketCalendar::nse();
+            let missing = (builder.bar_start(last.timestamp) + step..next)
+                .step_by(step as usize)
+                .filter(|start| calendar.is_trading_time(*start))
+                .count();
+            if missing > 0 {
+                log::warn!("{} history ends {} bar(s) before the live bars start", interval, missing);
+            }
+        }
+
+        Some(Self {
+            interval: interval.to_string(),
+            candles,
+            builder,
+        })
+    }
+
+    /// Interval string of the series
+    pub fn interval(&self) -> &str {
+        &self.interval
+    }
+
+    /// Completed bars, oldest first
+    pub fn candles(&self) -> &[HistoryCandle] {
+        &self.candles
+    }
+
+    /// Bar currently being built
+    pub fn current(&self) -> Option<&HistoryCandle> {
+        self.builder.current()
+    }
+
+    /// Keep only the latest `count` completed bars
+    pub fn keep_last(&mut self, count: usize) {
+        let excess = self.candles.len().saturating_sub(count);
+        self.candles.drain(..excess);
+    }
+
+    /// Add a live tick, returning the bar it completed
+    pub fn update(&mut self, timestamp: i64, price: Price, cumulative_volume: Option<i64>) -> Option<HistoryCandle> {
+        let completed = self.builder.update(timestamp, price, cumulative_volume)?;
+        self.candles.push(completed.clone());
+        Some(completed)
+    }
+
+    /// Close the current bar if its interval ended before `now`
+    pub fn flush(&mut self, now: i64) -> Option<HistoryCandle> {
+        let completed = self.builder.flush(now)?;
+        self.candles.push(completed.clone());
+        Some(completed)
+    }
+}
+
+/// Builds candles for every instrument seen in a WebSocket stream
+#[derive(Debug, Clone, Default)]
+pub struct CandleAggregator {
+    /// Interval strings and their lengths in seconds
+    intervals: Vec<(String, i64)>,
+    builders: HashMap<(String, String, usize), CandleBuilder>,
+}
+
+impl CandleAggregator {
+    /// Aggregator for the given intervals; unrecognised intervals are skipped
+    pub fn new(intervals: &[String]) -> Self {
+        Self {
+            intervals: intervals
+                .iter()
+                .filter_map(|i| interval_seconds(i).map(|secs| (i.clone(), secs)))
+                .collect(),
+            builders: HashMap::new(),
+        }
+    }
+
+    /// Whether any interval is configured
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Feed a market data event, returning completed `WsData::Candle` events
+    ///
+    /// Ticks without a timestamp are stamped with `now`.
+    pub fn on_tick(&mut self, data: &WsData, now: i64) -> Vec<WsData> {
+        let (price, volume, timestamp) = match data {
+            WsData::Ltp(d) => (d.ltp, None, d.timestamp),
+            WsData::Quote(d) => (d.ltp, d.volume, d.timestamp),
+            WsData::Depth(d) => (d.ltp, d.volume, d.timestamp),
+            _ => return Vec::new(),
+        };
+        let (Some(price), Some(symbol)) = (price, data.symbol()) else {
+            return Vec::new();
+        };
+        let exchange = data.exchange().unwrap_or_default();
+        let timestamp = timestamp.map(epoch_secs).unwrap_or(now);
+
+        let mut events = Vec::new();
+        for (index, (interval, step)) in self.intervals.iter().enumerate() {
+            let builder = self
+                .builders
+                .entry((exchange.to_string(), symbol.to_string(), index))
+                .or_insert_with(|| CandleBuilder::new(*step));
+            if let Some(candle) = builder.update(timestamp, price, volume) {
+                events.push(WsData::Candle {
+                    exchange: exchange.to_string(),
+                    symbol: symbol.to_string(),
+                    interval: interval.clone(),
+                    candle,
+                });
+            }
+        }
+        events
+    }
+
+    /// Close bars whose interval ended before `now`
+    pub fn flush(&mut self, now: i64) -> Vec<WsData> {
+        let mut events = Vec::new();
+        for ((exchange, symbol, index), builder) in self.builders.iter_mut() {
+            if let Some(candle) = builder.flush(now) {
+                events.push(WsData::Candle {
+                    exchange: exchange.clone(),
+                    symbol: symbol.clone(),
+                    interval: self.intervals[*index].0.clone(),
+                    candle,
+                });
+            }
+        }
+        events
+    }
+}
+
+/// Completed bars of several intervals for one instrument, at a close of the shortest interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeframeBars {
+    pub symbol: String,
+    pub exchange: String,
+    /// Unix seconds the base bar closed
+    pub close_time: i64,
+    /// Latest completed bar of each interval; intervals without one yet are missing
+    pub bars: BTreeMap<String, HistoryCandle>,
+    /// Intervals whose bar completed with this update, shortest first
+    pub closed: Vec<String>,
+}
+
+impl TimeframeBars {
+    /// Latest completed bar of an interval
+    pub fn bar(&self, interval: &str) -> Option<&HistoryCandle> {
+        self.bars.get(interval)
+    }
+
+    /// Whether the bar of an interval completed with this update
+    pub fn is_closed(&self, interval: &str) -> bool {
+        self.closed.iter().any(|i| i == interval)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TimeframeState {
+    builders: Vec<CandleBuilder>,
+    bars: BTreeMap<String, HistoryCandle>,
+}
+
+/// Builds bars of several intervals from one tick stream, aligned on bar closes
+///
+/// Every interval must be a whole multiple of the shortest (the base), and
+/// all bars are anchored to the session open, so a higher-timeframe bar
+/// always closes together with a base bar. Each update carries only
+/// completed bars: a 1h filter read at a 5m close never sees the hour still
+/// forming.
+#[derive(Debug, Clone)]
+pub struct MultiTimeframe {
+    /// Interval strings and lengths in seconds, shortest first
+    intervals: Vec<(String, i64)>,
+    instruments: HashMap<(String, String), TimeframeState>,
+}
+
+impl MultiTimeframe {
+    /// Builder for the given intervals
+    ///
+    /// Returns `None` if an interval is unrecognised or not a whole multiple
+    /// of the shortest one.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::candles::MultiTimeframe;
+    /// use openalgo::price::from_f64;
+    ///
+    /// assert!(MultiTimeframe::new(&["5m", "7m"]).is_none());
+    ///
+    /// let mut bars = MultiTimeframe::new(&["15m", "5m"]).unwrap();
+    /// assert_eq!(bars.base(), "5m");
+    /// // 2024-12-02 09:15 IST, then one tick every 5 minutes
+    /// let start = 1_733_111_100;
+    /// let mut updates = Vec::new();
+    /// for i in 0..4 {
+    ///     updates.extend(bars.update("SBIN", "NSE", start + i * 300, from_f64(800.0 + i as f64), None));
+    /// }
+    /// assert_eq!(updates.len(), 3);
+    /// // 09:20 and 09:25: no 15m bar has completed yet
+    /// assert!(updates[0].bar("15m").is_none() && !updates[1].is_closed("15m"));
+    /// // 09:30 closes the third 5m bar and the 09:15 15m bar together
+    /// assert_eq!(updates[2].close_time, start + 900);
+    /// assert_eq!(updates[2].closed, ["5m", "15m"]);
+    /// assert_eq!(updates[2].bar("15m").unwrap().close, from_f64(802.0));
+    /// ```
+    pub fn new(intervals: &[&str]) -> Option<Self> {
+        let mut parsed = intervals
+            .iter()
+            .map(|i| interval_seconds(i).map(|secs| (i.to_string(), secs)))
+            .collect::<Option<Vec<_>>>()?;
+        parsed.sort_by_key(|(_, secs)| *secs);
+        parsed.dedup_by_key(|(_, secs)| *secs);
+        let base = parsed.first()?.1;
+        if parsed.iter().any(|(_, secs)| secs % base != 0) {
+            return None;
+        }
+        Some(Self {
+            intervals: parsed,
+            instruments: HashMap::new(),
+        })
+    }
+
+    /// Shortest interval, whose closes drive the updates
+    pub fn base(&self) -> &str {
+        &self.intervals[0].0
+    }
+
+    /// Add a tick of an instrument, returning the bars if it closed a base bar
+    pub fn update(
+        &mut self,
+        symbol: &str,
+        exchange: &str,
+        timestamp: i64,
+        price: Price,
+        cumulative_volume: Option<i64>,
+    ) -> Option<TimeframeBars> {
+        let intervals = &self.intervals;
+        let state = self
+            .instruments
+            .entry((exchange.to_string(), symbol.to_string()))
+            .or_insert_with(|| TimeframeState {
+                builders: intervals.iter().map(|(_, step)| CandleBuilder::new(*step)).collect(),
+                bars: BTreeMap::new(),
+            });
+        let completed: Vec<Option<HistoryCandle>> = state
+            .builders
+            .iter_mut()
+            .map(|builder| builder.update(timestamp, price, cumulative_volume))
+            .collect();
+        Self::record(intervals, state, symbol, exchange, completed)
+    }
+
+    /// Add a WebSocket LTP, quote or depth tick; ticks without a timestamp are stamped with `now`
+    pub fn on_tick(&mut self, data: &WsData, now: i64) -> Option<TimeframeBars> {
+        let (price, volume, timestamp) = match data {
+            WsData::Ltp(d) => (d.ltp, None, d.timestamp),
+            WsData::Quote(d) => (d.ltp, d.volume, d.timestamp),
+            WsData::Depth(d) => (d.ltp, d.volume, d.timestamp),
+            _ => return None,
+        };
+        let (Some(price), Some(symbol)) = (price, data.symbol()) else {
+            return None;
+        };
+        let exchange = data.exchange().unwrap_or_default();
+        let timestamp = timestamp.map(epoch_secs).unwrap_or(now);
+        self.update(symbol, exchange, timestamp, price, volume)
+    }
+
+    /// Close bars whose interval ended before `now`, for every instrument
+    pub fn flush(&mut self, now: i64) -> Vec<TimeframeBars> {
+        let mut updates = Vec::new();
+        for ((exchange, symbol), state) in self.instruments.iter_mut() {
+            let completed = state.builders.iter_mut().map(|builder| builder.flush(now)).collect();
+            updates.extend(Self::record(&self.intervals, state, symbol, exchange, completed));
+        }
+        updates
+    }
+
+    fn record(
+        intervals: &[(String, i64)],
+        state: &mut TimeframeState,
+        symbol: &str,
+        exchange: &str,
+        completed: Vec<Option<HistoryCandle>>,
+    ) -> Option<TimeframeBars> {
+        let mut closed = Vec::new();
+        for ((interval, _), bar) in intervals.iter().zip(completed) {
+            if let Some(bar) = bar {
+                state.bars.insert(interval.clone(), bar);
+                closed.push(interval.clone());
+            }
+        }
+        let (base, step) = &intervals[0];
+        if closed.first() != Some(base) {
+            return None;
+        }
+        Some(TimeframeBars {
+            symbol: symbol.to_string(),
+            exchange: exchange.to_string(),
+            close_time: state.bars[base].timestamp + step,
+            bars: state.bars.clone(),
+            closed,
+        })
+    }
+}
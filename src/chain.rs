@@ -0,0 +1,496 @@
+//! Option chain streaming for OpenAlgo.
+//!
+//! Resolves the symbols of an option chain and subscribes them on the
+//! WebSocket in batches, yielding one stream keyed by strike and option type.
+//! Chains larger than the broker's per-connection limit are sharded across a
+//! `WsPool`. `ChainMonitor` instead polls the REST option chain and emits the
+//! per-strike LTP, OI and IV changes between consecutive snapshots.
+
+use crate::client::OpenAlgoError;
+use crate::data::DataAPI;
+use crate::futures::derivatives_exchange;
+use crate::options::OptionType;
+use crate::pool::WsPool;
+use crate::price::{self, Price};
+use crate::types::*;
+use crate::volatility::build_iv_slice;
+use crate::websocket::{OpenAlgoWebSocket, WsData, WsMode};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Default number of instruments sent per subscribe message
+const DEFAULT_BATCH_SIZE: usize = 50;
+
+/// Option chain subscription settings
+#[derive(Debug, Clone)]
+pub struct ChainSubscription {
+    pub underlying: String,
+    pub exchange: String,
+    pub expiry_date: String,
+    /// Strikes on each side of ATM, or the full chain when `None`
+    pub strikes_around_atm: Option<i32>,
+    pub mode: WsMode,
+    /// Instruments per subscribe message
+    pub batch_size: usize,
+    /// Maximum symbols allowed on one connection, if the broker enforces one
+    pub max_symbols_per_connection: Option<usize>,
+}
+
+impl ChainSubscription {
+    /// Subscribe the full chain in LTP mode
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::chain::ChainSubscription;
+    /// use openalgo::websocket::WsMode;
+    /// let sub = ChainSubscription::new("NIFTY", "NSE_INDEX", "26DEC24")
+    ///     .strikes_around_atm(10)
+    ///     .mode(WsMode::Quote);
+    /// ```
+    pub fn new(underlying: &str, exchange: &str, expiry_date: &str) -> Self {
+        Self {
+            underlying: underlying.to_string(),
+            exchange: exchange.to_string(),
+            expiry_date: expiry_date.to_string(),
+            strikes_around_atm: None,
+            mode: WsMode::Ltp,
+            batch_size: DEFAULT_BATCH_SIZE,
+            max_symbols_per_connection: None,
+        }
+    }
+
+    /// Only subscribe `count` strikes on each side of ATM
+    pub fn strikes_around_atm(mut self, count: i32) -> Self {
+        self.strikes_around_atm = Some(count);
+        self
+    }
+
+    /// Subscription mode
+    pub fn mode(mut self, mode: WsMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Instruments per subscribe message
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Per-connection symbol limit of the broker; larger chains use several connections
+    pub fn max_symbols_per_connection(mut self, max_symbols: usize) -> Self {
+        self.max_symbols_per_connection = Some(max_symbols);
+        self
+    }
+}
+
+/// Strike and option type a chain symbol belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChainKey {
+    pub strike: Price,
+    pub option_type: OptionType,
+}
+
+/// Event from an option chain stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChainEvent {
+    /// Market data for a chain option
+    Tick { key: ChainKey, data: WsData },
+    /// Connection status or error from the WebSocket
+    Status(WsData),
+}
+
+/// Merged live stream of an option chain
+pub struct OptionChainStream {
+    pool: WsPool,
+    events: mpsc::Receiver<ChainEvent>,
+    symbols: HashMap<String, ChainKey>,
+    instruments: Vec<WsInstrument>,
+    mode: WsMode,
+}
+
+impl OptionChainStream {
+    /// Receive the next chain event
+    pub async fn recv(&mut self) -> Option<ChainEvent> {
+        self.events.recv().await
+    }
+
+    /// Subscribed symbols and their chain keys
+    pub fn symbols(&self) -> &HashMap<String, ChainKey> {
+        &self.symbols
+    }
+
+    /// Connection pool carrying the chain subscriptions
+    pub fn pool(&self) -> &WsPool {
+        &self.pool
+    }
+
+    /// Unsubscribe the chain and disconnect
+    pub async fn close(&self) -> Result<(), OpenAlgoError> {
+        self.pool
+            .unsubscribe(self.mode, self.instruments.clone())
+            .await?;
+        self.pool.disconnect().await
+    }
+}
+
+/// Resolve an option chain into (symbol, key) pairs
+pub fn chain_symbols(chain: &OptionChainResponse) -> Vec<(String, ChainKey)> {
+    let mut symbols = Vec::new();
+    for strike in chain.chain.iter().flatten() {
+        for (data, option_type) in [(&strike.ce, OptionType::CE), (&strike.pe, OptionType::PE)] {
+            if let Some(symbol) = data.as_ref().and_then(|d| d.symbol.clone()) {
+                symbols.push((
+                    symbol,
+                    ChainKey {
+                        strike: strike.strike,
+                        option_type,
+                    },
+                ));
+            }
+        }
+    }
+    symbols
+}
+
+/// Resolve, connect and subscribe an option chain on the WebSocket
+pub async fn subscribe_option_chain(
+    ws: &OpenAlgoWebSocket,
+    data: &DataAPI,
+    subscription: &ChainSubscription,
+) -> Result<OptionChainStream, OpenAlgoError> {
+    let chain = match subscription.strikes_around_atm {
+        Some(count) => {
+            data.option_chain_strikes(
+                &subscription.underlying,
+                &subscription.exchange,
+                &subscription.expiry_date,
+                count,
+            )
+            .await?
+        }
+        None => {
+            data.option_chain(
+                &subscription.underlying,
+                &subscription.exchange,
+                &subscription.expiry_date,
+            )
+            .await?
+        }
+    };
+    if chain.chain.is_none() {
+        return Err(OpenAlgoError::ApiError(
+            chain
+                .message
+                .unwrap_or_else(|| "Option chain has no data".to_string())
+                .into(),
+        ));
+    }
+
+    let symbols: HashMap<String, ChainKey> = chain_symbols(&chain).into_iter().collect();
+
+    let options_exchange = derivatives_exchange(&subscription.exchange);
+    let instruments: Vec<WsInstrument> = symbols
+        .keys()
+        .map(|symbol| WsInstrument::new(options_exchange, symbol))
+        .collect();
+
+    let max_symbols = subscription.max_symbols_per_connection.unwrap_or(usize::MAX);
+    let (pool, mut data_rx) = WsPool::connect(ws.clone(), max_symbols).await?;
+    for batch in instruments.chunks(subscription.batch_size.max(1)) {
+        pool.subscribe(subscription.mode, batch.to_vec()).await?;
+    }
+
+    let (event_tx, event_rx) = mpsc::channel::<ChainEvent>(256);
+    let keys = symbols.clone();
+    tokio::spawn(async move {
+        while let Some(data) = data_rx.recv().await {
+            let event = match data.symbol().and_then(|s| keys.get(s)) {
+                Some(key) => ChainEvent::Tick { key: *key, data },
+                None if data.symbol().is_some() => continue,
+                None => ChainEvent::Status(data),
+            };
+            if event_tx.send(event).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(OptionChainStream {
+        pool,
+        events: event_rx,
+        symbols,
+        instruments,
+        mode: subscription.mode,
+    })
+}
+
+/// Prices, open interest and IV of one chain option at a refresh
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrikeSnapshot {
+    pub key: ChainKey,
+    pub symbol: Option<String>,
+    pub ltp: Option<f64>,
+    pub oi: Option<i64>,
+    /// Implied volatility as a fraction (0.15 = 15%), when an interest rate is set
+    pub iv: Option<f64>,
+}
+
+/// One refresh of an option chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainSnapshot {
+    pub underlying_ltp: Option<f64>,
+    pub atm_strike: Option<Price>,
+    pub options: Vec<StrikeSnapshot>,
+}
+
+/// Change of one chain option between two snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChainChange {
+    Ltp { key: ChainKey, previous: f64, current: f64 },
+    OpenInterest { key: ChainKey, previous: i64, current: i64 },
+    Iv { key: ChainKey, previous: f64, current: f64 },
+    /// Option that was not in the previous snapshot, e.g. after ATM moved
+    Added(StrikeSnapshot),
+    /// Option that is no longer in the chain
+    Removed(ChainKey),
+}
+
+/// Smallest changes reported by `ChainSnapshot::diff`; the default reports any change
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChangeThresholds {
+    pub ltp: f64,
+    pub oi: i64,
+    pub iv: f64,
+}
+
+impl ChainSnapshot {
+    /// Snapshot an option chain response, computing IV when `interest_rate` (in percent) is given
+    pub fn from_chain(chain: &OptionChainResponse, expiry_date: &str, interest_rate: Option<f64>) -> Self {
+        let slice = interest_rate.and_then(|rate| build_iv_slice(chain, expiry_date, rate).ok());
+        let mut options = Vec::new();
+        for strike in chain.chain.iter().flatten() {
+            let point = slice
+                .as_ref()
+                .and_then(|s| s.points.iter().find(|p| (p.strike - price::to_f64(strike.strike)).abs() < 1e-9));
+            for (data, option_type) in [(&strike.ce, OptionType::CE), (&strike.pe, OptionType::PE)] {
+                let Some(data) = data else { continue };
+                options.push(StrikeSnapshot {
+                    key: ChainKey { strike: strike.strike, option_type },
+                    symbol: data.symbol.clone(),
+                    ltp: data.ltp.map(price::to_f64),
+                    oi: data.oi,
+                    iv: point.and_then(|p| match option_type {
+                        OptionType::CE => p.call_iv,
+                        OptionType::PE => p.put_iv,
+                    }),
+                });
+            }
+        }
+        Self {
+            underlying_ltp: chain.underlying_ltp.map(price::to_f64),
+            atm_strike: chain.atm_strike,
+            options,
+        }
+    }
+
+    /// Snapshot of one option
+    pub fn option(&self, key: &ChainKey) -> Option<&StrikeSnapshot> {
+        self.options.iter().find(|o| o.key == *key)
+    }
+
+    /// Changes from this snapshot to `next` that reach the thresholds
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::chain::{ChainChange, ChainSnapshot, ChangeThresholds};
+    /// use openalgo::OptionChainResponse;
+    ///
+    /// let chain = |ltp: f64, oi: i64| -> OptionChainResponse {
+    ///     serde_json::from_value(serde_json::json!({
+    ///         "status": "success",
+    ///         "underlying_ltp": 24000.0,
+    ///         "chain": [{"strike": 24000, "ce": {"symbol": "NIFTY26DEC2424000CE", "ltp": ltp, "oi": oi}}]
+    ///     }))
+    ///     .unwrap()
+    /// };
+    /// let before = ChainSnapshot::from_chain(&chain(120.0, 50_000), "26DEC24", None);
+    /// let after = ChainSnapshot::from_chain(&chain(121.0, 75_000), "26DEC24", None);
+    ///
+    /// let thresholds = ChangeThresholds { ltp: 5.0, oi: 10_000, ..Default::default() };
+    /// let changes = before.diff(&after, &thresholds);
+    /// assert_eq!(changes.len(), 1);
+    /// assert!(matches!(changes[0], ChainChange::OpenInterest { previous: 50_000, current: 75_000, .. }));
+    /// ```
+    pub fn diff(&self, next: &ChainSnapshot, thresholds: &ChangeThresholds) -> Vec<ChainChange> {
+        let mut changes = Vec::new();
+        for current in &next.options {
+            let key = current.key;
+            let Some(previous) = self.option(&key) else {
+                changes.push(ChainChange::Added(current.clone()));
+                continue;
+            };
+            if let (Some(p), Some(c)) = (previous.ltp, current.ltp) {
+                if p != c && (c - p).abs() >= thresholds.ltp {
+                    changes.push(ChainChange::Ltp { key, previous: p, current: c });
+                }
+            }
+            if let (Some(p), Some(c)) = (previous.oi, current.oi) {
+                if p != c && (c - p).abs() >= thresholds.oi {
+                    changes.push(ChainChange::OpenInterest { key, previous: p, current: c });
+                }
+            }
+            if let (Some(p), Some(c)) = (previous.iv, current.iv) {
+                if p != c && (c - p).abs() >= thresholds.iv {
+                    changes.push(ChainChange::Iv { key, previous: p, current: c });
+                }
+            }
+        }
+        for previous in &self.options {
+            if next.option(&previous.key).is_none() {
+                changes.push(ChainChange::Removed(previous.key));
+            }
+        }
+        changes
+    }
+}
+
+/// Update sent by a `ChainMonitor` after each refresh
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainUpdate {
+    pub snapshot: ChainSnapshot,
+    /// Changes since the previous update; empty on the first
+    pub changes: Vec<ChainChange>,
+}
+
+/// Option chain polling settings
+#[derive(Debug, Clone)]
+pub struct ChainMonitor {
+    pub underlying: String,
+    pub exchange: String,
+    pub expiry_date: String,
+    /// Strikes on each side of ATM, or the full chain when `None`
+    pub strikes_around_atm: Option<i32>,
+    pub interval: Duration,
+    /// Interest rate in percent used to compute IV; IV is not tracked when `None`
+    pub interest_rate: Option<f64>,
+    pub thresholds: ChangeThresholds,
+}
+
+impl ChainMonitor {
+    /// Poll the full chain every 5 seconds, reporting every LTP and OI change
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::chain::{ChainMonitor, ChangeThresholds};
+    /// use std::time::Duration;
+    ///
+    /// let monitor = ChainMonitor::new("NIFTY", "NSE_INDEX", "26DEC24")
+    ///     .strikes_around_atm(10)
+    ///     .interval(Duration::from_secs(30))
+    ///     .interest_rate(6.5)
+    ///     .thresholds(ChangeThresholds { oi: 100_000, ..Default::default() });
+    /// ```
+    pub fn new(underlying: &str, exchange: &str, expiry_date: &str) -> Self {
+        Self {
+            underlying: underlying.to_string(),
+            exchange: exchange.to_string(),
+            expiry_date: expiry_date.to_string(),
+            strikes_around_atm: None,
+            interval: Duration::from_secs(5),
+            interest_rate: None,
+            thresholds: ChangeThresholds::default(),
+        }
+    }
+
+    /// Only poll `count` strikes on each side of ATM
+    pub fn strikes_around_atm(mut self, count: i32) -> Self {
+        self.strikes_around_atm = Some(count);
+        self
+    }
+
+    /// Time between refreshes
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Track IV, computed locally at this interest rate (in percent)
+    pub fn interest_rate(mut self, interest_rate: f64) -> Self {
+        self.interest_rate = Some(interest_rate);
+        self
+    }
+
+    /// Smallest changes to report
+    pub fn thresholds(mut self, thresholds: ChangeThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Start polling in the background
+    ///
+    /// An update is sent after the first refresh and after every later
+    /// refresh with at least one change. Changes are measured against the
+    /// last snapshot sent, so small moves add up until they reach the
+    /// thresholds. Failed refreshes are logged and skipped. Dropping the
+    /// receiver stops the monitor.
+    pub fn start(&self, data: &DataAPI) -> (mpsc::Receiver<ChainUpdate>, JoinHandle<()>) {
+        let (update_tx, update_rx) = mpsc::channel::<ChainUpdate>(32);
+        let data = data.clone();
+        let monitor = self.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(monitor.interval);
+            let mut previous: Option<ChainSnapshot> = None;
+            loop {
+                ticker.tick().await;
+                if update_tx.is_closed() {
+                    break;
+                }
+
+                let chain = match monitor.strikes_around_atm {
+                    Some(count) => {
+                        data.option_chain_strikes(&monitor.underlying, &monitor.exchange, &monitor.expiry_date, count)
+                            .await
+                    }
+                    None => data.option_chain(&monitor.underlying, &monitor.exchange, &monitor.expiry_date).await,
+                };
+                let chain = match chain {
+                    Ok(chain) if chain.chain.is_some() => chain,
+                    Ok(chain) => {
+                        log::warn!(
+                            "Chain monitor for {} got no data: {}",
+                            monitor.underlying,
+                            chain.message.unwrap_or_default()
+                        );
+                        continue;
+                    }
+                    Err(e) => {
+                        log::warn!("Chain monitor for {} failed: {}", monitor.underlying, e);
+                        continue;
+                    }
+                };
+
+                let snapshot = ChainSnapshot::from_chain(&chain, &monitor.expiry_date, monitor.interest_rate);
+                let changes = match &previous {
+                    Some(previous) => {
+                        let changes = previous.diff(&snapshot, &monitor.thresholds);
+                        if changes.is_empty() {
+                            continue;
+                        }
+                        changes
+                    }
+                    None => Vec::new(),
+                };
+                previous = Some(snapshot.clone());
+                if update_tx.send(ChainUpdate { snapshot, changes }).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        (update_rx, handle)
+    }
+}
@@ -0,0 +1,419 @@
+//! Failure injection for resilience testing.
+//!
+//! `Chaos` describes the faults to inject and how often: latency spikes,
+//! 5xx responses, connection resets and malformed JSON bodies. Any
+//! `Transport` can be wrapped with `Chaos::wrap`, including the one behind
+//! `MockOpenAlgo` (see `MockOpenAlgo::chaos_client`). The REST requests then
+//! fail the way a flaky broker adapter does, so retries, circuit breakers
+//! and strategy error handling can be tested before going live.
+//!
+//! `ChaosProxy` does the same for the WebSocket feed. It is a TCP proxy
+//! between the client and a `ws://` server that randomly cuts connections.
+//!
+//! Faults are drawn from a seeded generator, so a failing run can be
+//! replayed with `Chaos::seed`. Enabled by the `test-utils` feature.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::chaos::Chaos;
+//! use openalgo::mock::MockOpenAlgo;
+//! use std::time::Duration;
+//!
+//! let mock = MockOpenAlgo::new();
+//! mock.set_price("SBIN", 801.5);
+//! let chaos = Chaos::new()
+//!     .seed(7)
+//!     .server_errors(0.3)
+//!     .malformed_json(0.1)
+//!     .latency(0.2, Duration::from_millis(5));
+//! let client = mock.chaos_client(&chaos);
+//!
+//! tokio_test::block_on(async {
+//!     let mut ok = 0;
+//!     for _ in 0..50 {
+//!         if client.ltp("SBIN", "NSE").await.is_ok() {
+//!             ok += 1;
+//!         }
+//!     }
+//!     let stats = chaos.stats();
+//!     assert_eq!(stats.requests, 50);
+//!     assert_eq!(ok, 50 - stats.server_errors - stats.malformed);
+//! });
+//! ```
+
+use crate::client::{ApiErrorDetails, OpenAlgoError};
+use crate::middleware::RequestParts;
+use crate::transport::{Transport, TransportFuture};
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Faults to inject and their probabilities (0.0 to 1.0 per request)
+///
+/// Clones share the random generator and the counters.
+#[derive(Clone)]
+pub struct Chaos {
+    latency: (f64, Duration),
+    server_errors: f64,
+    statuses: Vec<u16>,
+    connection_errors: f64,
+    malformed_json: f64,
+    ws_drops: f64,
+    endpoints: Option<Vec<String>>,
+    rng: Arc<Mutex<SplitMix>>,
+    stats: Arc<Counters>,
+}
+
+impl std::fmt::Debug for Chaos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Chaos")
+            .field("latency", &self.latency)
+            .field("server_errors", &self.server_errors)
+            .field("statuses", &self.statuses)
+            .field("connection_errors", &self.connection_errors)
+            .field("malformed_json", &self.malformed_json)
+            .field("ws_drops", &self.ws_drops)
+            .field("endpoints", &self.endpoints)
+            .finish()
+    }
+}
+
+impl Default for Chaos {
+    fn default() -> Self {
+        Self {
+            latency: (0.0, Duration::ZERO),
+            server_errors: 0.0,
+            statuses: vec![500, 502, 503, 504],
+            connection_errors: 0.0,
+            malformed_json: 0.0,
+            ws_drops: 0.0,
+            endpoints: None,
+            rng: Arc::new(Mutex::new(SplitMix::from_entropy())),
+            stats: Arc::new(Counters::default()),
+        }
+    }
+}
+
+impl Chaos {
+    /// No faults until some are enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw faults from a fixed seed to make a run reproducible
+    pub fn seed(self, seed: u64) -> Self {
+        *self.rng.lock().unwrap() = SplitMix(seed);
+        self
+    }
+
+    /// Delay a request by `spike` with `probability`
+    pub fn latency(mut self, probability: f64, spike: Duration) -> Self {
+        self.latency = (probability, spike);
+        self
+    }
+
+    /// Answer with a 5xx error with `probability`, without reaching the server
+    pub fn server_errors(mut self, probability: f64) -> Self {
+        self.server_errors = probability;
+        self
+    }
+
+    /// Status codes used for server errors, 500 and 502-504 by default
+    pub fn statuses(mut self, statuses: &[u16]) -> Self {
+        if !statuses.is_empty() {
+            self.statuses = statuses.to_vec();
+        }
+        self
+    }
+
+    /// Fail with a connection reset with `probability`, without reaching the server
+    pub fn connection_errors(mut self, probability: f64) -> Self {
+        self.connection_errors = probability;
+        self
+    }
+
+    /// Truncate the response body with `probability` so it is not valid JSON
+    ///
+    /// The request does reach the server, so an order may be placed even
+    /// though the client sees an error.
+    pub fn malformed_json(mut self, probability: f64) -> Self {
+        self.malformed_json = probability;
+        self
+    }
+
+    /// Cut a `ChaosProxy` connection with `probability` per server message
+    pub fn ws_drops(mut self, probability: f64) -> Self {
+        self.ws_drops = probability;
+        self
+    }
+
+    /// Only inject REST faults into these endpoints, e.g. `["placeorder"]`
+    pub fn endpoints(mut self, endpoints: &[&str]) -> Self {
+        self.endpoints = Some(endpoints.iter().map(|e| e.to_string()).collect());
+        self
+    }
+
+    /// Wrap a transport so its requests are subject to these faults
+    pub fn wrap(&self, inner: Arc<dyn Transport>) -> ChaosTransport {
+        ChaosTransport {
+            inner,
+            chaos: self.clone(),
+        }
+    }
+
+    /// Faults injected so far
+    pub fn stats(&self) -> ChaosStats {
+        self.stats.snapshot()
+    }
+
+    fn roll(&self, probability: f64) -> bool {
+        probability > 0.0 && self.rng.lock().unwrap().next_f64() < probability
+    }
+
+    fn status(&self) -> u16 {
+        let index = self.rng.lock().unwrap().next_u64() as usize % self.statuses.len();
+        self.statuses[index]
+    }
+
+    fn applies_to(&self, url: &str) -> bool {
+        let endpoint = endpoint_of(url);
+        self.endpoints
+            .as_ref()
+            .is_none_or(|endpoints| endpoints.iter().any(|e| *e == endpoint))
+    }
+
+    /// Run one request through the configured faults
+    async fn inject<'a>(&self, url: &str, request: TransportFuture<'a>) -> Result<String, OpenAlgoError> {
+        if !self.applies_to(url) {
+            return request.await;
+        }
+        self.stats.requests.fetch_add(1, Ordering::Relaxed);
+        if self.roll(self.latency.0) {
+            self.stats.delayed.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(self.latency.1).await;
+        }
+        if self.roll(self.connection_errors) {
+            self.stats.connection_errors.fetch_add(1, Ordering::Relaxed);
+            return Err(io::Error::new(io::ErrorKind::ConnectionReset, "chaos: connection reset").into());
+        }
+        if self.roll(self.server_errors) {
+            self.stats.server_errors.fetch_add(1, Ordering::Relaxed);
+            let status = self.status();
+            let body = format!(r#"{{"status":"error","message":"chaos: injected HTTP {}"}}"#, status);
+            return Err(OpenAlgoError::ApiError(Box::new(ApiErrorDetails::from_body(Some(status), &body))));
+        }
+        let body = request.await?;
+        if self.roll(self.malformed_json) {
+            self.stats.malformed.fetch_add(1, Ordering::Relaxed);
+            return Ok(truncate(&body));
+        }
+        Ok(body)
+    }
+}
+
+/// Fault counts of a `Chaos`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChaosStats {
+    /// REST requests the faults applied to
+    pub requests: u64,
+    pub delayed: u64,
+    pub server_errors: u64,
+    pub connection_errors: u64,
+    pub malformed: u64,
+    /// WebSocket connections cut by a `ChaosProxy`
+    pub ws_drops: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    requests: AtomicU64,
+    delayed: AtomicU64,
+    server_errors: AtomicU64,
+    connection_errors: AtomicU64,
+    malformed: AtomicU64,
+    ws_drops: AtomicU64,
+}
+
+impl Counters {
+    fn snapshot(&self) -> ChaosStats {
+        ChaosStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            delayed: self.delayed.load(Ordering::Relaxed),
+            server_errors: self.server_errors.load(Ordering::Relaxed),
+            connection_errors: self.connection_errors.load(Ordering::Relaxed),
+            malformed: self.malformed.load(Ordering::Relaxed),
+            ws_drops: self.ws_drops.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Transport injecting the faults of a `Chaos` into another transport
+pub struct ChaosTransport {
+    inner: Arc<dyn Transport>,
+    chaos: Chaos,
+}
+
+impl Transport for ChaosTransport {
+    fn post_json<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+        Box::pin(self.chaos.inject(url, self.inner.post_json(url, body)))
+    }
+
+    fn get_json<'a>(&'a self, url: &'a str, query: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        Box::pin(self.chaos.inject(url, self.inner.get_json(url, query)))
+    }
+
+    fn send<'a>(&'a self, request: &'a RequestParts) -> TransportFuture<'a> {
+        Box::pin(self.chaos.inject(&request.url, self.inner.send(request)))
+    }
+}
+
+/// Endpoint path after `/api/<version>/`
+fn endpoint_of(url: &str) -> &str {
+    let path = url.split("/api/").nth(1).unwrap_or(url);
+    let path = path.split_once('/').map_or(path, |(_, rest)| rest);
+    path.split(['?', '#']).next().unwrap_or(path).trim_end_matches('/')
+}
+
+/// First half of a body, cut on a character boundary
+fn truncate(body: &str) -> String {
+    let mut end = body.len() / 2;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    body[..end].to_string()
+}
+
+/// SplitMix64, enough for fault rolls without a `rand` dependency
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn from_entropy() -> Self {
+        use std::hash::BuildHasher;
+        Self(std::collections::hash_map::RandomState::new().hash_one(0u64))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// TCP proxy in front of a `ws://` server that randomly cuts connections
+///
+/// Point the client's `ws_url` at `ChaosProxy::url`. Each chunk the server
+/// sends (usually one WebSocket message) cuts the connection with the
+/// `Chaos::ws_drops` probability, and `drop_connections` cuts them all.
+///
+/// # Example
+/// ```rust,no_run
+/// use openalgo::chaos::{Chaos, ChaosProxy};
+/// use openalgo::OpenAlgo;
+///
+/// # async fn example() -> std::io::Result<()> {
+/// let chaos = Chaos::new().ws_drops(0.01);
+/// let proxy = ChaosProxy::start("ws://127.0.0.1:8765", &chaos).await?;
+/// let client = OpenAlgo::builder("your-api-key").ws_url(&proxy.url()).build();
+/// // ... run the strategy with a ReconnectPolicy and check it recovers ...
+/// println!("connections cut: {}", chaos.stats().ws_drops);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ChaosProxy {
+    url: String,
+    cut: watch::Sender<u64>,
+    task: JoinHandle<()>,
+}
+
+impl std::fmt::Debug for ChaosProxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChaosProxy").field("url", &self.url).finish()
+    }
+}
+
+impl ChaosProxy {
+    /// Listen on a free loopback port and forward to `upstream`
+    pub async fn start(upstream: &str, chaos: &Chaos) -> io::Result<Self> {
+        let upstream = url::Url::parse(upstream).map_err(io::Error::other)?;
+        if upstream.scheme() != "ws" {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "ChaosProxy only forwards ws:// URLs"));
+        }
+        let host = upstream.host_str().unwrap_or("127.0.0.1").to_string();
+        let port = upstream.port_or_known_default().unwrap_or(80);
+        let path = upstream[url::Position::BeforePath..].to_string();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}{}", listener.local_addr()?, path);
+        let (cut, _) = watch::channel(0);
+        let task = tokio::spawn(accept_loop(listener, (host, port), chaos.clone(), cut.clone()));
+        Ok(Self { url, cut, task })
+    }
+
+    /// URL for the client's `ws_url`
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Cut every open connection now
+    pub fn drop_connections(&self) {
+        self.cut.send_modify(|n| *n += 1);
+    }
+}
+
+impl Drop for ChaosProxy {
+    fn drop(&mut self) {
+        self.drop_connections();
+        self.task.abort();
+    }
+}
+
+async fn accept_loop(listener: TcpListener, upstream: (String, u16), chaos: Chaos, cut: watch::Sender<u64>) {
+    while let Ok((client, _)) = listener.accept().await {
+        let server = match TcpStream::connect((upstream.0.as_str(), upstream.1)).await {
+            Ok(server) => server,
+            Err(e) => {
+                log::warn!("chaos proxy: cannot reach {}:{}: {}", upstream.0, upstream.1, e);
+                continue;
+            }
+        };
+        tokio::spawn(forward(client, server, chaos.clone(), cut.subscribe()));
+    }
+}
+
+/// Copy bytes both ways until either side closes or the connection is cut
+async fn forward(client: TcpStream, server: TcpStream, chaos: Chaos, mut cut: watch::Receiver<u64>) {
+    let (mut client_read, mut client_write) = client.into_split();
+    let (mut server_read, mut server_write) = server.into_split();
+    let upstream = tokio::io::copy(&mut client_read, &mut server_write);
+    let downstream = async {
+        let mut buf = vec![0u8; 16 * 1024];
+        loop {
+            let n = server_read.read(&mut buf).await?;
+            if n == 0 {
+                return Ok::<_, io::Error>(());
+            }
+            if chaos.roll(chaos.ws_drops) {
+                chaos.stats.ws_drops.fetch_add(1, Ordering::Relaxed);
+                return Ok(());
+            }
+            client_write.write_all(&buf[..n]).await?;
+        }
+    };
+    tokio::select! {
+        _ = upstream => {}
+        _ = downstream => {}
+        _ = cut.changed() => {}
+    }
+}
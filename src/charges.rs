@@ -0,0 +1,314 @@
+//! Trading charges estimator for Indian exchanges.
+//!
+//! `ChargeSchedule` holds the rates of each segment: brokerage, securities
+//! transaction tax (STT), exchange transaction charges, SEBI turnover fees,
+//! stamp duty and GST. `estimate` prices one executed order from its side
+//! and turnover; `estimate_trade` and `ChargesSummary` do the same for
+//! tradebook rows. The default schedule is a typical discount broker on NSE
+//! (flat ₹20 or 0.03% brokerage, no brokerage on delivery) with statutory
+//! rates as of October 2024. Rates change, and brokers differ, so set your
+//! own with `ChargeSchedule::rates` when it matters.
+//!
+//! Commodity and currency trades are priced with the futures or options
+//! rates.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::charges::{ChargeSchedule, Segment};
+//!
+//! let schedule = ChargeSchedule::default();
+//! // Intraday buy and sell of 100 SBIN at 800 and 805
+//! let buy = schedule.estimate(Segment::EquityIntraday, "BUY", 80_000.0);
+//! let sell = schedule.estimate(Segment::EquityIntraday, "SELL", 80_500.0);
+//! assert_eq!(buy.brokerage, 20.0);
+//! assert!(buy.stt == 0.0 && sell.stt > 0.0);
+//! println!("round trip costs {:.2}", buy.total() + sell.total());
+//! ```
+
+use crate::price;
+use crate::symbols::{parse_derivative_symbol, DerivativeKind};
+use crate::types::TradebookTrade;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Kind of trade, each with its own rates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Segment {
+    /// Cash equity held overnight (CNC)
+    EquityDelivery,
+    /// Cash equity squared off the same day (MIS)
+    EquityIntraday,
+    Futures,
+    /// Options; turnover is the premium
+    Options,
+}
+
+impl Segment {
+    /// Segment of a trade from its exchange, symbol and product
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::charges::Segment;
+    ///
+    /// assert_eq!(Segment::classify("NSE", "SBIN", "CNC"), Segment::EquityDelivery);
+    /// assert_eq!(Segment::classify("NSE", "SBIN", "MIS"), Segment::EquityIntraday);
+    /// assert_eq!(Segment::classify("NFO", "NIFTY26DEC24FUT", "NRML"), Segment::Futures);
+    /// assert_eq!(Segment::classify("NFO", "NIFTY26DEC2424000CE", "MIS"), Segment::Options);
+    /// ```
+    pub fn classify(exchange: &str, symbol: &str, product: &str) -> Self {
+        match exchange.to_ascii_uppercase().as_str() {
+            "NSE" | "BSE" if product.eq_ignore_ascii_case("CNC") => Segment::EquityDelivery,
+            "NSE" | "BSE" => Segment::EquityIntraday,
+            _ => match parse_derivative_symbol(symbol).map(|s| s.kind) {
+                Some(DerivativeKind::Option { .. }) => Segment::Options,
+                _ => Segment::Futures,
+            },
+        }
+    }
+
+    /// Display name ("Equity delivery", "Futures", ...)
+    pub fn label(self) -> &'static str {
+        match self {
+            Segment::EquityDelivery => "Equity delivery",
+            Segment::EquityIntraday => "Equity intraday",
+            Segment::Futures => "Futures",
+            Segment::Options => "Options",
+        }
+    }
+}
+
+/// Rates of one segment; percentages are fractions of turnover (0.001 = 0.1%)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChargeRates {
+    /// Brokerage per executed order, in rupees
+    pub brokerage_flat: f64,
+    /// Brokerage as a fraction of turnover; when both are set the lower applies
+    pub brokerage_rate: f64,
+    /// STT on buys
+    pub stt_buy: f64,
+    /// STT on sells
+    pub stt_sell: f64,
+    /// Exchange transaction charges
+    pub exchange_rate: f64,
+    /// SEBI turnover fees
+    pub sebi_rate: f64,
+    /// Stamp duty on buys
+    pub stamp_buy: f64,
+    /// GST on brokerage, exchange charges and SEBI fees
+    pub gst_rate: f64,
+}
+
+const SEBI_RATE: f64 = 10.0 / 1e7;
+const GST_RATE: f64 = 0.18;
+
+impl ChargeRates {
+    /// Rates of `segment` in the default schedule
+    pub fn default_for(segment: Segment) -> Self {
+        let base = Self {
+            brokerage_flat: 20.0,
+            brokerage_rate: 0.0003,
+            stt_buy: 0.0,
+            stt_sell: 0.0,
+            exchange_rate: 0.0,
+            sebi_rate: SEBI_RATE,
+            stamp_buy: 0.0,
+            gst_rate: GST_RATE,
+        };
+        match segment {
+            Segment::EquityDelivery => Self {
+                brokerage_flat: 0.0,
+                brokerage_rate: 0.0,
+                stt_buy: 0.001,
+                stt_sell: 0.001,
+                exchange_rate: 0.0000297,
+                stamp_buy: 0.00015,
+                ..base
+            },
+            Segment::EquityIntraday => Self {
+                stt_sell: 0.00025,
+                exchange_rate: 0.0000297,
+                stamp_buy: 0.00003,
+                ..base
+            },
+            Segment::Futures => Self {
+                stt_sell: 0.0002,
+                exchange_rate: 0.0000173,
+                stamp_buy: 0.00002,
+                ..base
+            },
+            Segment::Options => Self {
+                brokerage_rate: 0.0,
+                stt_sell: 0.001,
+                exchange_rate: 0.0003503,
+                stamp_buy: 0.00003,
+                ..base
+            },
+        }
+    }
+
+    fn brokerage(&self, turnover: f64) -> f64 {
+        let by_rate = self.brokerage_rate * turnover;
+        match (self.brokerage_flat > 0.0, self.brokerage_rate > 0.0) {
+            (true, true) => self.brokerage_flat.min(by_rate),
+            (true, false) => self.brokerage_flat,
+            (false, _) => by_rate,
+        }
+    }
+}
+
+/// Charges of one order or a set of orders, in rupees
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Charges {
+    pub brokerage: f64,
+    pub stt: f64,
+    pub exchange: f64,
+    pub sebi: f64,
+    pub stamp: f64,
+    pub gst: f64,
+}
+
+impl Charges {
+    /// Sum of all components
+    pub fn total(&self) -> f64 {
+        self.brokerage + self.stt + self.exchange + self.sebi + self.stamp + self.gst
+    }
+
+    /// Add another set of charges to this one
+    pub fn add(&mut self, other: &Charges) {
+        self.brokerage += other.brokerage;
+        self.stt += other.stt;
+        self.exchange += other.exchange;
+        self.sebi += other.sebi;
+        self.stamp += other.stamp;
+        self.gst += other.gst;
+    }
+
+    /// Components as `(name, amount)` pairs, in a fixed order
+    pub fn components(&self) -> [(&'static str, f64); 6] {
+        [
+            ("Brokerage", self.brokerage),
+            ("STT", self.stt),
+            ("Exchange charges", self.exchange),
+            ("SEBI fees", self.sebi),
+            ("Stamp duty", self.stamp),
+            ("GST", self.gst),
+        ]
+    }
+}
+
+/// Rates of every segment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChargeSchedule {
+    rates: BTreeMap<Segment, ChargeRates>,
+}
+
+impl Default for ChargeSchedule {
+    fn default() -> Self {
+        let rates = [Segment::EquityDelivery, Segment::EquityIntraday, Segment::Futures, Segment::Options]
+            .into_iter()
+            .map(|s| (s, ChargeRates::default_for(s)))
+            .collect();
+        Self { rates }
+    }
+}
+
+impl ChargeSchedule {
+    /// Replace the rates of one segment
+    pub fn rates(mut self, segment: Segment, rates: ChargeRates) -> Self {
+        self.rates.insert(segment, rates);
+        self
+    }
+
+    /// Rates used for `segment`
+    pub fn rates_for(&self, segment: Segment) -> ChargeRates {
+        self.rates.get(&segment).copied().unwrap_or_else(|| ChargeRates::default_for(segment))
+    }
+
+    /// Charges of one executed order with `turnover` rupees traded
+    ///
+    /// `action` is "BUY" or "SELL"; anything else is treated as a buy.
+    pub fn estimate(&self, segment: Segment, action: &str, turnover: f64) -> Charges {
+        let rates = self.rates_for(segment);
+        let turnover = turnover.abs();
+        let sell = action.eq_ignore_ascii_case("SELL");
+        let brokerage = rates.brokerage(turnover);
+        let exchange = rates.exchange_rate * turnover;
+        let sebi = rates.sebi_rate * turnover;
+        Charges {
+            brokerage,
+            stt: turnover * if sell { rates.stt_sell } else { rates.stt_buy },
+            exchange,
+            sebi,
+            stamp: if sell { 0.0 } else { rates.stamp_buy * turnover },
+            gst: rates.gst_rate * (brokerage + exchange + sebi),
+        }
+    }
+
+    /// Segment and charges of a tradebook row, `None` without a turnover
+    ///
+    /// The turnover is `trade_value`, or quantity times average price.
+    pub fn estimate_trade(&self, trade: &TradebookTrade) -> Option<(Segment, Charges)> {
+        let turnover = trade_turnover(trade)?;
+        let segment = Segment::classify(
+            trade.exchange.as_deref().unwrap_or_default(),
+            trade.symbol.as_deref().unwrap_or_default(),
+            trade.product.as_deref().unwrap_or_default(),
+        );
+        let action = trade.action.as_deref().unwrap_or_default();
+        Some((segment, self.estimate(segment, action, turnover)))
+    }
+}
+
+/// Turnover of a tradebook row: `trade_value`, or quantity times average price
+pub fn trade_turnover(trade: &TradebookTrade) -> Option<f64> {
+    trade.trade_value.filter(|v| *v != 0.0).or_else(|| {
+        let quantity = trade.quantity?;
+        let average = price::to_f64(trade.average_price?);
+        Some(quantity * average)
+    })
+}
+
+/// Charges of a day's trades, in total and per segment
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChargesSummary {
+    pub total: Charges,
+    pub by_segment: BTreeMap<Segment, Charges>,
+    /// Turnover of the trades priced
+    pub turnover: f64,
+    /// Trades priced; rows without a turnover are skipped
+    pub trades: usize,
+}
+
+impl ChargesSummary {
+    /// Price every tradebook row with `schedule`
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::charges::{ChargeSchedule, ChargesSummary, Segment};
+    /// use openalgo::TradebookResponse;
+    ///
+    /// let book: TradebookResponse = serde_json::from_str(r#"{"status": "success", "data": [
+    ///     {"orderid": "1", "symbol": "SBIN", "exchange": "NSE", "action": "BUY", "product": "MIS", "quantity": 100, "average_price": 800},
+    ///     {"orderid": "2", "symbol": "SBIN", "exchange": "NSE", "action": "SELL", "product": "MIS", "quantity": 100, "average_price": 805}
+    /// ]}"#).unwrap();
+    /// let summary = ChargesSummary::from_trades(&ChargeSchedule::default(), book.data.as_deref().unwrap());
+    /// assert_eq!(summary.trades, 2);
+    /// assert_eq!(summary.turnover, 160_500.0);
+    /// assert_eq!(summary.total.brokerage, 40.0);
+    /// assert!(summary.by_segment.contains_key(&Segment::EquityIntraday));
+    /// ```
+    pub fn from_trades(schedule: &ChargeSchedule, trades: &[TradebookTrade]) -> Self {
+        let mut summary = Self::default();
+        for trade in trades {
+            let (Some(turnover), Some((segment, charges))) = (trade_turnover(trade), schedule.estimate_trade(trade)) else {
+                continue;
+            };
+            summary.total.add(&charges);
+            summary.by_segment.entry(segment).or_default().add(&charges);
+            summary.turnover += turnover.abs();
+            summary.trades += 1;
+        }
+        summary
+    }
+}
@@ -0,0 +1,399 @@
+//! Max-daily-loss circuit for OpenAlgo.
+//!
+//! A trading halt blocks new and modified orders on a client, and on every
+//! copy of it, until the end of the IST trading day; cancellations and
+//! position closes still go through. With `OpenAlgoBuilder::trading_halt_file`
+//! the halt is saved to disk, so a restarted strategy stays halted.
+//!
+//! `LossCircuit` polls the day's P&L (tradebook plus mark-to-market at the
+//! positionbook LTP) and, once the loss limit is breached, halts trading,
+//! triggers the kill switch (cancel all orders, close all positions) and
+//! sends a Telegram notification. A position without an LTP is marked at its
+//! last known price, and a circuit that cannot read the P&L for several polls
+//! in a row trips as well.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::circuit::{CircuitEvent, LossCircuit};
+//! use openalgo::OpenAlgo;
+//!
+//! # async fn example() -> Result<(), openalgo::client::OpenAlgoError> {
+//! let client = OpenAlgo::builder("your-api-key").trading_halt_file("halt.json").build();
+//! let (mut events, _handle) = LossCircuit::new(25_000.0)
+//!     .strategy("Momentum")
+//!     .notify_telegram("trader")
+//!     .start(&client);
+//! while let Some(event) = events.recv().await {
+//!     if let CircuitEvent::Tripped { pnl, .. } = event {
+//!         eprintln!("Trading halted at a day P&L of {:.0}", pnl.total);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::calendar::{format_date, ist_day_and_secs};
+use crate::client::OpenAlgoError;
+use crate::price;
+use crate::retry;
+use crate::types::TradebookTrade;
+use crate::OpenAlgo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::sync::mpsc;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::task::JoinHandle;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Trading halted for the rest of an IST trading day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradingHalt {
+    /// IST date (YYYY-MM-DD) the halt applies to
+    pub date: String,
+    pub reason: String,
+}
+
+impl TradingHalt {
+    /// Halt for the rest of today (IST)
+    pub fn today(reason: &str) -> Self {
+        Self { date: today(), reason: reason.to_string() }
+    }
+
+    /// Whether the halt still applies
+    pub fn is_active(&self) -> bool {
+        self.date == today()
+    }
+}
+
+fn today() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    format_date(ist_day_and_secs(now).0)
+}
+
+/// Trading halt shared by a client and its copies, optionally saved to a file
+#[derive(Debug, Default)]
+pub(crate) struct HaltState {
+    halt: Mutex<Option<TradingHalt>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    path: Option<PathBuf>,
+}
+
+impl HaltState {
+    /// State saved to `path`, starting from the halt already in it
+    ///
+    /// A missing file means no halt. A file that cannot be read or parsed
+    /// fails closed: trading is halted for today and the error is logged.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn with_file(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let halt = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str::<TradingHalt>(&text).map(Some).map_err(std::io::Error::other),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        };
+        let halt = halt.unwrap_or_else(|e| {
+            tracing::error!(error = %e, path = %path.display(), "unreadable trading halt file; halting trading");
+            Some(TradingHalt::today(&format!("trading halt file {} is unreadable: {}", path.display(), e)))
+        });
+        Self { halt: Mutex::new(halt), path: Some(path) }
+    }
+
+    /// The halt in force, if any
+    pub(crate) fn active(&self) -> Option<TradingHalt> {
+        self.halt.lock().unwrap().clone().filter(TradingHalt::is_active)
+    }
+
+    pub(crate) fn set(&self, halt: Option<TradingHalt>) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = &self.path {
+            let written = match &halt {
+                Some(halt) => serde_json::to_string(halt)
+                    .map_err(std::io::Error::other)
+                    .and_then(|text| std::fs::write(path, text)),
+                None => std::fs::remove_file(path).or_else(|e| match e.kind() {
+                    std::io::ErrorKind::NotFound => Ok(()),
+                    _ => Err(e),
+                }),
+            };
+            if let Err(e) = written {
+                tracing::warn!(error = %e, path = %path.display(), "failed to save trading halt");
+            }
+        }
+        *self.halt.lock().unwrap() = halt;
+    }
+
+    /// Error for a request refused by the halt, if it is
+    pub(crate) fn check(&self, endpoint: &str) -> Result<(), OpenAlgoError> {
+        if !retry::enters_orders(endpoint) {
+            return Ok(());
+        }
+        match self.active() {
+            Some(halt) => Err(OpenAlgoError::ApiError(
+                format!("Trading is halted until the next trading day: {}", halt.reason).into(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Realized and mark-to-market P&L of the day's trades
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DayPnl {
+    pub realized: f64,
+    pub unrealized: f64,
+    pub total: f64,
+    /// Symbols with an open quantity but no LTP, not even a last known one; their unrealized P&L is left out
+    pub unpriced: Vec<String>,
+}
+
+/// Day P&L from the tradebook, marking open quantity to `ltps` keyed by (symbol, exchange)
+///
+/// Buys and sells of each symbol are matched at their average prices;
+/// the unmatched quantity is marked to the LTP.
+///
+/// # Example
+/// ```rust
+/// use openalgo::circuit::day_pnl;
+/// use openalgo::TradebookTrade;
+/// use std::collections::HashMap;
+///
+/// let trades: Vec<TradebookTrade> = serde_json::from_str(r#"[
+///     {"action": "BUY", "symbol": "SBIN", "exchange": "NSE", "quantity": 100, "average_price": 800.0},
+///     {"action": "SELL", "symbol": "SBIN", "exchange": "NSE", "quantity": 60, "average_price": 790.0}
+/// ]"#).unwrap();
+/// let ltps = HashMap::from([(("SBIN".to_string(), "NSE".to_string()), 780.0)]);
+///
+/// let pnl = day_pnl(&trades, &ltps);
+/// assert_eq!(pnl.realized, -600.0);
+/// assert_eq!(pnl.unrealized, -800.0);
+/// assert_eq!(pnl.total, -1400.0);
+/// ```
+pub fn day_pnl(trades: &[TradebookTrade], ltps: &HashMap<(String, String), f64>) -> DayPnl {
+    #[derive(Default)]
+    struct Legs {
+        buy_qty: f64,
+        buy_value: f64,
+        sell_qty: f64,
+        sell_value: f64,
+    }
+
+    let mut legs: BTreeMap<(String, String), Legs> = BTreeMap::new();
+    for trade in trades {
+        let (Some(symbol), Some(exchange), Some(quantity), Some(price)) =
+            (&trade.symbol, &trade.exchange, trade.quantity, trade.average_price)
+        else {
+            continue;
+        };
+        let value = quantity * price::to_f64(price);
+        let entry = legs.entry((symbol.clone(), exchange.clone())).or_default();
+        match trade.action.as_deref().map(str::to_ascii_uppercase).as_deref() {
+            Some("BUY") => {
+                entry.buy_qty += quantity;
+                entry.buy_value += value;
+            }
+            Some("SELL") => {
+                entry.sell_qty += quantity;
+                entry.sell_value += value;
+            }
+            _ => {}
+        }
+    }
+
+    let mut pnl = DayPnl::default();
+    for (key, l) in legs {
+        let avg_buy = if l.buy_qty > 0.0 { l.buy_value / l.buy_qty } else { 0.0 };
+        let avg_sell = if l.sell_qty > 0.0 { l.sell_value / l.sell_qty } else { 0.0 };
+        let matched = l.buy_qty.min(l.sell_qty);
+        pnl.realized += matched * (avg_sell - avg_buy);
+
+        let open = l.buy_qty - l.sell_qty;
+        if open.abs() < 1e-9 {
+            continue;
+        }
+        match ltps.get(&key) {
+            Some(ltp) if open > 0.0 => pnl.unrealized += open * (ltp - avg_buy),
+            Some(ltp) => pnl.unrealized += -open * (avg_sell - ltp),
+            None => pnl.unpriced.push(key.0),
+        }
+    }
+    pnl.total = pnl.realized + pnl.unrealized;
+    pnl
+}
+
+/// Event sent by a running `LossCircuit`
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CircuitEvent {
+    /// Day P&L at a poll
+    Pnl(DayPnl),
+    /// A P&L poll failed; `failures` counts consecutive failures
+    PollFailed { failures: u32, error: String },
+    /// The loss limit was breached and trading halted; `errors` lists kill switch or notification failures
+    Tripped { pnl: DayPnl, errors: Vec<String> },
+}
+
+/// Halts trading when the day's loss reaches a limit
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct LossCircuit {
+    /// Loss in rupees, as a positive number, that trips the circuit
+    pub max_loss: f64,
+    pub interval: Duration,
+    /// Strategy name sent with the cancel-all and close-position calls
+    pub strategy: String,
+    /// Telegram username notified when the circuit trips
+    pub telegram_username: Option<String>,
+    /// Consecutive failed polls that trip the circuit, since the loss can no longer be seen
+    pub max_failed_polls: u32,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl LossCircuit {
+    /// Trip at a day loss of `max_loss`, polling every 10 seconds, or after 3 failed polls in a row
+    pub fn new(max_loss: f64) -> Self {
+        Self {
+            max_loss: max_loss.abs(),
+            interval: Duration::from_secs(10),
+            strategy: "openalgo".to_string(),
+            telegram_username: None,
+            max_failed_polls: 3,
+        }
+    }
+
+    /// Time between P&L polls
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Strategy name for the kill switch calls
+    pub fn strategy(mut self, strategy: &str) -> Self {
+        self.strategy = strategy.to_string();
+        self
+    }
+
+    /// Notify a Telegram user when the circuit trips
+    pub fn notify_telegram(mut self, username: &str) -> Self {
+        self.telegram_username = Some(username.to_string());
+        self
+    }
+
+    /// Trip after this many consecutive failed polls
+    pub fn max_failed_polls(mut self, polls: u32) -> Self {
+        self.max_failed_polls = polls.max(1);
+        self
+    }
+
+    /// Start polling in the background
+    ///
+    /// After tripping, the monitor sends `CircuitEvent::Tripped` and stops.
+    /// If trading is already halted it stops without polling. Each failed
+    /// poll sends `CircuitEvent::PollFailed`; after `max_failed_polls` in a
+    /// row the circuit trips with the last P&L it read.
+    ///
+    /// Events are published without waiting: when the receiver is dropped or
+    /// its buffer is full they are discarded, and the monitor keeps polling
+    /// and tripping regardless. It runs until trading is halted; abort the
+    /// returned handle to stop it earlier.
+    pub fn start(&self, client: &OpenAlgo) -> (mpsc::Receiver<CircuitEvent>, JoinHandle<()>) {
+        let (event_tx, event_rx) = mpsc::channel::<CircuitEvent>(32);
+        let client = client.clone();
+        let circuit = self.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(circuit.interval);
+            let mut ltps = HashMap::new();
+            let mut last = DayPnl::default();
+            let mut failures = 0;
+            loop {
+                ticker.tick().await;
+                if client.trading_halt().is_some() {
+                    break;
+                }
+
+                let (pnl, reason) = match poll_pnl(&client, &mut ltps).await {
+                    Ok(pnl) if pnl.total > -circuit.max_loss => {
+                        failures = 0;
+                        last = pnl.clone();
+                        let _ = event_tx.try_send(CircuitEvent::Pnl(pnl));
+                        continue;
+                    }
+                    Ok(pnl) => {
+                        let reason = format!("day loss {:.2} breached the limit of {:.2}", -pnl.total, circuit.max_loss);
+                        (pnl, reason)
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        log::warn!("Loss circuit P&L poll failed ({} in a row): {}", failures, e);
+                        let error = e.to_string();
+                        if failures < circuit.max_failed_polls {
+                            let _ = event_tx.try_send(CircuitEvent::PollFailed { failures, error });
+                            continue;
+                        }
+                        (last.clone(), format!("day P&L could not be read for {} polls: {}", failures, error))
+                    }
+                };
+
+                client.halt_trading(&reason);
+                let mut errors = Vec::new();
+                if let Err(e) = client.kill_switch(&circuit.strategy).await {
+                    errors.push(format!("kill switch: {}", e));
+                }
+                if let Some(username) = &circuit.telegram_username {
+                    let message = format!("Trading halted: {}", reason);
+                    if let Err(e) = client.telegram(username, &message).await {
+                        errors.push(format!("telegram: {}", e));
+                    }
+                }
+                let _ = event_tx.try_send(CircuitEvent::Tripped { pnl, errors });
+                break;
+            }
+        });
+
+        (event_rx, handle)
+    }
+}
+
+/// Day P&L of an account from its tradebook, marked at positionbook LTPs
+///
+/// Open symbols the positionbook has no LTP for are priced with a quote.
+pub async fn current_pnl(client: &OpenAlgo) -> Result<DayPnl, OpenAlgoError> {
+    poll_pnl(client, &mut HashMap::new()).await
+}
+
+/// Day P&L, updating `ltps` with the latest prices and marking symbols without one at the price kept there
+async fn poll_pnl(client: &OpenAlgo, ltps: &mut HashMap<(String, String), f64>) -> Result<DayPnl, OpenAlgoError> {
+    let trades = client.tradebook().await?.data.unwrap_or_default();
+    let positions = client.positionbook().await?.data.unwrap_or_default();
+    ltps.extend(positions.into_iter().filter_map(|p| {
+        let ltp: f64 = p.ltp.as_deref()?.trim().parse().ok()?;
+        Some(((p.symbol?, p.exchange?), ltp))
+    }));
+    let pnl = day_pnl(&trades, ltps);
+    if pnl.unpriced.is_empty() {
+        return Ok(pnl);
+    }
+
+    let unpriced: BTreeSet<(String, String)> = trades
+        .iter()
+        .filter_map(|t| Some((t.symbol.clone()?, t.exchange.clone()?)))
+        .filter(|key| pnl.unpriced.contains(&key.0) && !ltps.contains_key(key))
+        .collect();
+    for (symbol, exchange) in unpriced {
+        match client.quotes(&symbol, &exchange).await {
+            Ok(quote) => {
+                if let Some(ltp) = quote.data.and_then(|q| q.ltp) {
+                    ltps.insert((symbol, exchange), price::to_f64(ltp));
+                }
+            }
+            Err(e) => log::warn!("Loss circuit could not price {}: {}", symbol, e),
+        }
+    }
+    Ok(day_pnl(&trades, ltps))
+}
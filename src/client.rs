@@ -1,11 +1,32 @@
 //! HTTP client for OpenAlgo API.
 
+use crate::circuit::HaltState;
+use crate::de;
+use crate::interval::Interval;
+use crate::latency::{LatencyStats, LatencyTracker};
+use crate::middleware::{Method, Middleware, RequestParts};
+use crate::positions::PositionCache;
+use crate::quantity::QuantityError;
+use crate::retry::{RetryClass, RetryPolicy};
+use crate::secret::{ApiKey, SharedApiKey};
+use crate::symbols::SymbolCache;
+use crate::transport::{ReqwestTransport, Transport};
+use crate::validation::ValidationError;
 use reqwest::Client;
 use serde::{de::DeserializeOwned, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use web_time::Instant;
 use thiserror::Error;
+use tracing::Instrument;
 
 /// Errors that can occur when using the OpenAlgo API
+///
+/// Errors from REST calls arrive wrapped in `Request`; match on `inner()`
+/// to see what went wrong regardless of the wrapping.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum OpenAlgoError {
     #[error("HTTP request failed: {0}")]
     RequestError(#[from] reqwest::Error),
@@ -14,68 +35,619 @@ pub enum OpenAlgoError {
     JsonError(#[from] serde_json::Error),
 
     #[error("API error: {0}")]
-    ApiError(String),
+    ApiError(Box<ApiErrorDetails>),
 
     #[error("WebSocket error: {0}")]
     WebSocketError(String),
 
     #[error("URL parse error: {0}")]
     UrlError(#[from] url::ParseError),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
+    #[error("Invalid quantity: {0}")]
+    QuantityError(#[from] QuantityError),
+
+    #[error("Invalid order: {0}")]
+    ValidationError(#[from] ValidationError),
+
+    #[error("Interval {interval} is not supported by the server (supported: {})", supported.join(", "))]
+    UnsupportedInterval {
+        interval: String,
+        supported: Vec<String>,
+    },
+
+    /// A REST call failed; `context` names the call and `source` says why
+    #[error("{context}: {source}")]
+    Request {
+        context: Box<ErrorContext>,
+        source: Box<OpenAlgoError>,
+    },
+}
+
+impl OpenAlgoError {
+    /// Details of an API error, if this is one
+    pub fn api_details(&self) -> Option<&ApiErrorDetails> {
+        match self.inner() {
+            OpenAlgoError::ApiError(details) => Some(details),
+            _ => None,
+        }
+    }
+
+    /// The call that failed, for errors returned by REST requests
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            OpenAlgoError::Request { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The underlying error, without request context
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) {
+    /// use openalgo::client::OpenAlgoError;
+    /// if let Err(e) = client.quotes("RELIANCE", "NSE").await {
+    ///     if let Some(context) = e.context() {
+    ///         eprintln!("{} failed after {:?}, request {:?}", context.endpoint, context.elapsed, context.request);
+    ///     }
+    ///     match e.inner() {
+    ///         OpenAlgoError::JsonError(e) => eprintln!("Unexpected response: {}", e),
+    ///         other => eprintln!("{}", other),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn inner(&self) -> &OpenAlgoError {
+        match self {
+            OpenAlgoError::Request { source, .. } => source.inner(),
+            other => other,
+        }
+    }
+}
+
+/// Which REST call an error came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorContext {
+    pub method: Method,
+    /// Endpoint such as "placeorder"
+    pub endpoint: String,
+    /// HTTP status code, when known
+    pub http_status: Option<u16>,
+    /// Time from sending the request to the failure
+    pub elapsed: Duration,
+    /// Request body or query parameters with the API key removed
+    pub request: Option<serde_json::Value>,
+}
+
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.method.as_str(), self.endpoint)?;
+        if let Some(status) = self.http_status {
+            write!(f, " (HTTP {})", status)?;
+        }
+        write!(f, " failed after {} ms", self.elapsed.as_millis())
+    }
+}
+
+/// Error reported by the OpenAlgo API, parsed from the response body
+///
+/// # Example
+/// ```rust
+/// use openalgo::client::ApiErrorDetails;
+///
+/// let body = r#"{"status":"error","message":{"quantity":["Not a valid integer."]}}"#;
+/// let details = ApiErrorDetails::from_body(Some(400), body);
+/// assert_eq!(details.status.as_deref(), Some("error"));
+/// assert_eq!(details.field_errors["quantity"], vec!["Not a valid integer."]);
+/// assert_eq!(details.to_string(), "HTTP 400 - quantity: Not a valid integer.");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiErrorDetails {
+    /// Human-readable description
+    pub message: String,
+    /// HTTP status code of a non-2xx response
+    pub http_status: Option<u16>,
+    /// `status` field of the error body, usually "error"
+    pub status: Option<String>,
+    /// Machine-readable error code, if the server sent one
+    pub code: Option<String>,
+    /// Validation errors by request field
+    pub field_errors: BTreeMap<String, Vec<String>>,
+    /// Response body as received
+    pub body: Option<String>,
+    /// Request ID reported by the server or sent by the client
+    pub request_id: Option<String>,
+}
+
+impl ApiErrorDetails {
+    /// Parse an error response body
+    ///
+    /// Understands the standard `{"status": "error", "message": ...}` shape,
+    /// where `message` may be a string or an object of field errors, plus
+    /// optional `code`/`error_code` and `errors` fields. Other bodies are
+    /// kept as the message.
+    pub fn from_body(http_status: Option<u16>, body: &str) -> Self {
+        let mut details = Self {
+            message: body.to_string(),
+            http_status,
+            body: Some(body.to_string()),
+            ..Self::default()
+        };
+        let Ok(serde_json::Value::Object(json)) = serde_json::from_str::<serde_json::Value>(body) else {
+            return details;
+        };
+
+        details.status = json.get("status").and_then(|v| v.as_str()).map(str::to_string);
+        details.code = ["code", "error_code"]
+            .iter()
+            .find_map(|key| match json.get(*key)? {
+                serde_json::Value::String(code) => Some(code.clone()),
+                serde_json::Value::Number(code) => Some(code.to_string()),
+                _ => None,
+            });
+        for key in ["errors", "message"] {
+            if let Some(serde_json::Value::Object(fields)) = json.get(key) {
+                for (field, errors) in fields {
+                    let errors = match errors {
+                        serde_json::Value::Array(items) => items.iter().map(json_text).collect(),
+                        other => vec![json_text(other)],
+                    };
+                    details.field_errors.entry(field.clone()).or_default().extend(errors);
+                }
+            }
+        }
+
+        match json.get("message").or_else(|| json.get("error")) {
+            Some(serde_json::Value::String(message)) => details.message = message.clone(),
+            _ if !details.field_errors.is_empty() => {
+                details.message = details
+                    .field_errors
+                    .iter()
+                    .map(|(field, errors)| format!("{}: {}", field, errors.join(", ")))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+            }
+            _ => {}
+        }
+        details
+    }
+}
+
+impl From<String> for ApiErrorDetails {
+    fn from(message: String) -> Self {
+        Self { message, ..Self::default() }
+    }
+}
+
+impl From<&str> for ApiErrorDetails {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<String> for Box<ApiErrorDetails> {
+    fn from(message: String) -> Self {
+        Box::new(message.into())
+    }
+}
+
+impl From<&str> for Box<ApiErrorDetails> {
+    fn from(message: &str) -> Self {
+        Box::new(message.into())
+    }
+}
+
+impl std::fmt::Display for ApiErrorDetails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(status) = self.http_status {
+            write!(f, "HTTP {} - ", status)?;
+        }
+        f.write_str(&self.message)?;
+        if let Some(id) = &self.request_id {
+            write!(f, " [request id {}]", id)?;
+        }
+        Ok(())
+    }
+}
+
+fn json_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// `User-Agent` sent with every request
+pub const USER_AGENT: &str = concat!("openalgo-rust/", env!("CARGO_PKG_VERSION"));
+
+/// Header carrying the client-generated request ID
+pub const REQUEST_ID_HEADER: &str = "X-Request-ID";
+
+/// Default port of the OpenAlgo WebSocket server
+pub const DEFAULT_WS_PORT: u16 = 8765;
+
+/// Derive the WebSocket URL from the HTTP host
+///
+/// `http` maps to `ws` and `https` to `wss`; the host is kept and the port replaced.
+///
+/// # Example
+/// ```rust
+/// use openalgo::client::derive_ws_url;
+/// assert_eq!(derive_ws_url("http://127.0.0.1:5000", 8765), "ws://127.0.0.1:8765");
+/// assert_eq!(derive_ws_url("https://algo.example.com", 8765), "wss://algo.example.com:8765");
+/// ```
+pub fn derive_ws_url(host: &str, port: u16) -> String {
+    let (scheme, rest) = match host.split_once("://") {
+        Some(("https", rest)) => ("wss", rest),
+        Some((_, rest)) => ("ws", rest),
+        None => ("ws", host),
+    };
+    let authority = rest.split('/').next().unwrap_or(rest);
+    // Strip any port, keeping bracketed IPv6 addresses intact
+    let hostname = match authority.rfind(':') {
+        Some(i) if !authority[i..].contains(']') => &authority[..i],
+        _ => authority,
+    };
+    format!("{}://{}:{}", scheme, hostname, port)
 }
 
 /// HTTP client for making API requests
+///
+/// The API key is redacted in `Debug` output.
 pub struct OpenAlgoClient {
-    pub api_key: String,
+    api_key: Arc<SharedApiKey>,
     pub host: String,
     pub version: String,
     pub ws_url: String,
     pub http_client: Client,
+    transport: Arc<dyn Transport>,
+    middleware: Middleware,
+    log_bodies: bool,
+    request_ids: bool,
+    validate_intervals: bool,
+    validate_orders: bool,
+    guard_cnc_sells: bool,
+    strict: bool,
+    smart_order_cache: Option<Duration>,
+    intervals: Arc<Mutex<Option<Vec<Interval>>>>,
+    retry: RetryPolicy,
+    latency: Arc<LatencyTracker>,
+    symbols: Arc<SymbolCache>,
+    positions: Arc<PositionCache>,
+    halt: Arc<HaltState>,
+}
+
+impl std::fmt::Debug for OpenAlgoClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenAlgoClient")
+            .field("api_key", &self.api_key.get())
+            .field("host", &self.host)
+            .field("version", &self.version)
+            .field("ws_url", &self.ws_url)
+            .finish_non_exhaustive()
+    }
 }
 
 impl OpenAlgoClient {
     /// Create a new OpenAlgo client
     pub fn new(api_key: &str, host: &str, version: &str, ws_url: &str) -> Self {
+        Self::with_http_client(api_key, host, version, ws_url, Client::new())
+    }
+
+    /// Create a client that sends requests through a pre-configured `reqwest::Client`
+    ///
+    /// Use this for your own proxies, TLS settings, timeouts or connection pools.
+    pub fn with_http_client(
+        api_key: &str,
+        host: &str,
+        version: &str,
+        ws_url: &str,
+        http_client: Client,
+    ) -> Self {
+        let transport = Arc::new(ReqwestTransport::new(http_client.clone()));
+        Self::build(api_key, host, version, ws_url, http_client, transport)
+    }
+
+    /// Create a client that sends requests through a custom transport
+    ///
+    /// `http_client` is left at its default and is not used for requests.
+    pub fn with_transport(
+        api_key: &str,
+        host: &str,
+        version: &str,
+        ws_url: &str,
+        transport: Arc<dyn Transport>,
+    ) -> Self {
+        Self::build(api_key, host, version, ws_url, Client::new(), transport)
+    }
+
+    fn build(
+        api_key: &str,
+        host: &str,
+        version: &str,
+        ws_url: &str,
+        http_client: Client,
+        transport: Arc<dyn Transport>,
+    ) -> Self {
         Self {
-            api_key: api_key.to_string(),
+            api_key: Arc::new(SharedApiKey::new(api_key)),
             host: host.trim_end_matches('/').to_string(),
             version: version.to_string(),
             ws_url: ws_url.to_string(),
-            http_client: Client::new(),
+            http_client,
+            transport,
+            middleware: Middleware::default(),
+            log_bodies: false,
+            request_ids: false,
+            validate_intervals: false,
+            validate_orders: false,
+            guard_cnc_sells: false,
+            strict: false,
+            smart_order_cache: None,
+            intervals: Arc::default(),
+            retry: RetryPolicy::default(),
+            latency: Arc::default(),
+            symbols: Arc::default(),
+            positions: Arc::default(),
+            halt: Arc::default(),
+        }
+    }
+
+    /// The API key requests are currently sent with
+    pub fn api_key(&self) -> ApiKey {
+        self.api_key.get()
+    }
+
+    /// Replace the API key of this client while it is in use
+    ///
+    /// Every sub-API sharing this client, and every WebSocket created from it,
+    /// switches to the new key: requests already built keep the old one, later
+    /// ones use the new one, and live WebSocket connections reconnect and
+    /// resubscribe with it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::OpenAlgoClient;
+    /// let client = OpenAlgoClient::new("old-key", "http://127.0.0.1:5000", "v1", "ws://127.0.0.1:8765");
+    /// client.set_api_key("new-key");
+    /// assert_eq!(client.api_key().expose_secret(), "new-key");
+    /// ```
+    pub fn set_api_key(&self, api_key: &str) {
+        self.api_key.set(api_key);
+    }
+
+    /// Key cell shared with WebSockets created from this client
+    pub(crate) fn shared_api_key(&self) -> Arc<SharedApiKey> {
+        Arc::clone(&self.api_key)
+    }
+
+    /// Copy of this client that authenticates with another API key
+    ///
+    /// Shares the host, HTTP client and transport with the original.
+    pub fn with_api_key(&self, api_key: &str) -> Self {
+        Self {
+            api_key: Arc::new(SharedApiKey::new(api_key)),
+            host: self.host.clone(),
+            version: self.version.clone(),
+            ws_url: self.ws_url.clone(),
+            http_client: self.http_client.clone(),
+            transport: Arc::clone(&self.transport),
+            middleware: self.middleware.clone(),
+            log_bodies: self.log_bodies,
+            request_ids: self.request_ids,
+            validate_intervals: self.validate_intervals,
+            validate_orders: self.validate_orders,
+            guard_cnc_sells: self.guard_cnc_sells,
+            strict: self.strict,
+            smart_order_cache: self.smart_order_cache,
+            intervals: Arc::clone(&self.intervals),
+            retry: self.retry.clone(),
+            latency: Arc::clone(&self.latency),
+            symbols: Arc::clone(&self.symbols),
+            positions: Arc::clone(&self.positions),
+            halt: Arc::clone(&self.halt),
         }
     }
 
+    /// Run a hook before every request, e.g. to add headers or sign it
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::OpenAlgoClient;
+    /// let client = OpenAlgoClient::new("key", "http://127.0.0.1:5000", "v1", "ws://127.0.0.1:8765")
+    ///     .on_request(|req| req.header("X-Desk", "equities"));
+    /// ```
+    pub fn on_request(mut self, hook: impl Fn(&mut RequestParts) + Send + Sync + 'static) -> Self {
+        self.middleware.on_request(hook);
+        self
+    }
+
+    /// Run a hook on every response body or error, e.g. to map server replies to errors
+    pub fn on_response(
+        mut self,
+        hook: impl Fn(&RequestParts, Result<String, OpenAlgoError>) -> Result<String, OpenAlgoError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.middleware.on_response(hook);
+        self
+    }
+
+    /// Replace all request and response hooks
+    pub fn with_middleware(mut self, middleware: Middleware) -> Self {
+        self.middleware = middleware;
+        self
+    }
+
+    /// Send a unique `X-Request-ID` header with every request
+    ///
+    /// The ID is recorded on the request span and appended to API errors,
+    /// unless the server returned its own request ID.
+    pub fn request_ids(mut self, enabled: bool) -> Self {
+        self.request_ids = enabled;
+        self
+    }
+
+    /// Log request and response bodies at debug level, with the API key redacted
+    pub fn log_bodies(mut self, enabled: bool) -> Self {
+        self.log_bodies = enabled;
+        self
+    }
+
+    /// Check history intervals against the server's `intervals()` before sending
+    ///
+    /// The supported intervals are fetched once and cached.
+    pub fn validate_intervals(mut self, enabled: bool) -> Self {
+        self.validate_intervals = enabled;
+        self
+    }
+
+    /// Whether history intervals are checked before sending
+    pub fn validates_intervals(&self) -> bool {
+        self.validate_intervals
+    }
+
+    /// Check order prices against the tick size and quantities against the lot size before sending
+    pub fn validate_orders(mut self, enabled: bool) -> Self {
+        self.validate_orders = enabled;
+        self
+    }
+
+    /// Whether orders are checked against tick and lot size before sending
+    pub fn validates_orders(&self) -> bool {
+        self.validate_orders
+    }
+
+    /// Check CNC sells against holdings before sending
+    pub fn guard_cnc_sells(mut self, enabled: bool) -> Self {
+        self.guard_cnc_sells = enabled;
+        self
+    }
+
+    /// Whether CNC sells are checked against holdings before sending
+    pub fn guards_cnc_sells(&self) -> bool {
+        self.guard_cnc_sells
+    }
+
+    /// Fail responses with fields the SDK does not model instead of keeping them in `extra`
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
+
+    /// Whether responses with unknown fields fail
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Skip smart orders whose target matches a cached position no older than `max_age`
+    pub fn smart_order_position_cache(mut self, max_age: Duration) -> Self {
+        self.smart_order_cache = Some(max_age);
+        self
+    }
+
+    /// Maximum age of a cached position that smart orders rely on, if enabled
+    pub fn smart_order_cache_age(&self) -> Option<Duration> {
+        self.smart_order_cache
+    }
+
+    /// Retry transient failures of safe endpoints
+    ///
+    /// Order placement, modification and cancellation are never retried; see
+    /// [`crate::retry`]. On `wasm32` retries are sent without a delay.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Whether an endpoint is retried under this client's retry policy
+    pub fn retry_class(&self, endpoint: &str) -> RetryClass {
+        self.retry.classify(endpoint)
+    }
+
+    /// Latency percentiles per endpoint over recent requests
+    ///
+    /// Every attempt is timed, including retries and failures.
+    pub fn latency_stats(&self) -> LatencyStats {
+        self.latency.snapshot()
+    }
+
+    /// Clear the latency stats
+    pub fn reset_latency_stats(&self) {
+        self.latency.reset();
+    }
+
+    pub(crate) fn cached_intervals(&self) -> Option<Vec<Interval>> {
+        self.intervals.lock().unwrap().clone()
+    }
+
+    pub(crate) fn cache_intervals(&self, intervals: Vec<Interval>) {
+        *self.intervals.lock().unwrap() = Some(intervals);
+    }
+
+    pub(crate) fn symbol_cache(&self) -> &SymbolCache {
+        &self.symbols
+    }
+
+    pub(crate) fn position_cache(&self) -> &PositionCache {
+        &self.positions
+    }
+
+    pub(crate) fn halt_state(&self) -> &HaltState {
+        &self.halt
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn with_halt_state(mut self, halt: HaltState) -> Self {
+        self.halt = Arc::new(halt);
+        self
+    }
+
     /// Build the full API URL for an endpoint
     pub fn build_url(&self, endpoint: &str) -> String {
         format!("{}/api/{}/{}", self.host, self.version, endpoint)
     }
 
     /// Make a POST request to the API
+    ///
+    /// Runs inside an `openalgo.request` span recording the endpoint, status and duration.
     pub async fn post<T, R>(&self, endpoint: &str, body: &T) -> Result<R, OpenAlgoError>
     where
         T: Serialize,
         R: DeserializeOwned,
     {
-        let url = self.build_url(endpoint);
-
-        let response = self.http_client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(body)
-            .send()
-            .await?;
-
-        let status = response.status();
-        let text = response.text().await?;
+        let body = serde_json::to_string(body)?;
+        self.send(self.request_parts(Method::Post, endpoint, Some(body), Vec::new()))
+            .await
+    }
 
-        if !status.is_success() {
+    /// POST to an endpoint the SDK does not wrap, adding the API key
+    ///
+    /// `body` must serialize to a JSON object; `apikey` is inserted unless
+    /// the body already has one.
+    pub async fn post_custom<T, R>(&self, endpoint: &str, body: &T) -> Result<R, OpenAlgoError>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        let mut body = serde_json::to_value(body)?;
+        let Some(fields) = body.as_object_mut() else {
             return Err(OpenAlgoError::ApiError(format!(
-                "HTTP {} - {}",
-                status, text
-            )));
-        }
-
-        let result: R = serde_json::from_str(&text)?;
-        Ok(result)
+                "Request body for {} must be a JSON object",
+                endpoint
+            ).into()));
+        };
+        fields
+            .entry("apikey")
+            .or_insert_with(|| self.api_key().expose_secret().into());
+        self.post(endpoint, &body).await
     }
 
     /// Make a GET request to the API
@@ -83,26 +655,197 @@ impl OpenAlgoClient {
     where
         R: DeserializeOwned,
     {
-        let url = self.build_url(endpoint);
+        let query = query_params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self.send(self.request_parts(Method::Get, endpoint, None, query))
+            .await
+    }
 
-        let response = self.http_client
-            .get(&url)
-            .header("Content-Type", "application/json")
-            .query(query_params)
-            .send()
-            .await?;
+    fn request_parts(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<String>,
+        query: Vec<(String, String)>,
+    ) -> RequestParts {
+        let mut headers = vec![("User-Agent".to_string(), USER_AGENT.to_string())];
+        if self.request_ids {
+            headers.push((REQUEST_ID_HEADER.to_string(), new_request_id()));
+        }
+        RequestParts {
+            method,
+            endpoint: endpoint.to_string(),
+            url: self.build_url(endpoint),
+            headers,
+            body,
+            query,
+        }
+    }
+
+    /// Run middleware around the transport and deserialize the response
+    async fn send<R: DeserializeOwned>(&self, mut parts: RequestParts) -> Result<R, OpenAlgoError> {
+        let span = request_span(parts.method.as_str(), &parts.endpoint);
+        let request_id = parts.header_value(REQUEST_ID_HEADER).map(str::to_string);
+        if let Some(id) = &request_id {
+            span.record("request_id", id.as_str());
+        }
+        async {
+            if let Err(e) = self.halt.check(&parts.endpoint) {
+                return Err(self.with_context(e, &parts, Instant::now()));
+            }
+            self.middleware.before(&mut parts);
+            if self.log_bodies {
+                if let Some(body) = &parts.body {
+                    tracing::debug!(body = %self.redact(body), "request");
+                }
+            }
+            let started = Instant::now();
+            let mut attempt = 0;
+            let text = loop {
+                let attempt_started = Instant::now();
+                let text = self.middleware.after(&parts, self.transport.send(&parts).await);
+                self.latency.record(&parts.endpoint, attempt_started.elapsed(), text.is_ok());
+                match &text {
+                    Err(e) if self.retry.should_retry(&parts.endpoint, e, attempt) => {
+                        let delay = self.retry.delay(attempt);
+                        tracing::debug!(error = %e, attempt = attempt + 1, delay_ms = delay.as_millis() as u64, "retrying request");
+                        sleep(delay).await;
+                        attempt += 1;
+                    }
+                    _ => break text,
+                }
+            };
+            let text = match (text, &request_id) {
+                (Err(OpenAlgoError::ApiError(mut details)), Some(id)) if details.request_id.is_none() => {
+                    details.request_id = Some(id.clone());
+                    Err(OpenAlgoError::ApiError(details))
+                }
+                (text, _) => text,
+            };
+            self.parse_response(text, started)
+                .map_err(|e| self.with_context(e, &parts, started))
+        }
+        .instrument(span)
+        .await
+    }
 
-        let status = response.status();
-        let text = response.text().await?;
+    /// Wrap an error with the endpoint, status, timing and sanitized request
+    fn with_context(&self, error: OpenAlgoError, parts: &RequestParts, started: Instant) -> OpenAlgoError {
+        let http_status = match &error {
+            OpenAlgoError::ApiError(details) => details.http_status,
+            OpenAlgoError::RequestError(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        };
+        let request = match &parts.body {
+            Some(body) => serde_json::from_str(body)
+                .map(sanitize)
+                .unwrap_or_else(|_| serde_json::Value::String(self.redact(body))),
+            None => sanitize(serde_json::Value::Object(
+                parts
+                    .query
+                    .iter()
+                    .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+                    .collect(),
+            )),
+        };
+        OpenAlgoError::Request {
+            context: Box::new(ErrorContext {
+                method: parts.method,
+                endpoint: parts.endpoint.clone(),
+                http_status,
+                elapsed: started.elapsed(),
+                request: Some(request),
+            }),
+            source: Box::new(error),
+        }
+    }
 
-        if !status.is_success() {
-            return Err(OpenAlgoError::ApiError(format!(
-                "HTTP {} - {}",
-                status, text
-            )));
+    /// Record the outcome on the current request span and deserialize the body
+    fn parse_response<R: DeserializeOwned>(
+        &self,
+        text: Result<String, OpenAlgoError>,
+        started: Instant,
+    ) -> Result<R, OpenAlgoError> {
+        let span = tracing::Span::current();
+        span.record("duration_ms", started.elapsed().as_millis() as u64);
+        let text = match text {
+            Ok(text) => text,
+            Err(e) => {
+                span.record("status", "failed");
+                tracing::warn!(error = %e, "request failed");
+                return Err(e);
+            }
+        };
+        if self.log_bodies {
+            tracing::debug!(body = %self.redact(&text), "response");
+        }
+        if !span.is_disabled() {
+            let status = serde_json::from_str::<serde_json::Value>(&text)
+                .ok()
+                .and_then(|v| v.get("status")?.as_str().map(str::to_string));
+            span.record("status", status.as_deref().unwrap_or("unknown"));
         }
 
-        let result: R = serde_json::from_str(&text)?;
+        let result: R = if self.strict {
+            de::from_str_strict(&text)?
+        } else {
+            serde_json::from_str(&text)?
+        };
         Ok(result)
     }
+
+    /// Replace the API key in logged text
+    fn redact(&self, text: &str) -> String {
+        let api_key = self.api_key();
+        if api_key.is_empty() {
+            text.to_string()
+        } else {
+            text.replace(api_key.expose_secret(), "[REDACTED]")
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(delay: Duration) {
+    tokio::time::sleep(delay).await;
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(_delay: Duration) {}
+
+/// Drop the API key from a request payload
+fn sanitize(mut request: serde_json::Value) -> serde_json::Value {
+    if let Some(fields) = request.as_object_mut() {
+        fields.remove("apikey");
+    }
+    request
+}
+
+fn request_span(method: &'static str, endpoint: &str) -> tracing::Span {
+    tracing::debug_span!(
+        "openalgo.request",
+        method,
+        endpoint,
+        status = tracing::field::Empty,
+        request_id = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
+    )
+}
+
+/// Unique-enough ID for correlating a request with server logs
+fn new_request_id() -> String {
+    use std::collections::hash_map::RandomState;
+    use std::hash::BuildHasher;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let random = RandomState::new().hash_one((nanos, seq));
+    format!("{:016x}{:08x}", random, seq as u32)
 }
@@ -0,0 +1,132 @@
+//! Clock synchronization for OpenAlgo.
+//!
+//! Compares server timestamps on ticks against the local clock to detect
+//! skew, and can shift event timestamps onto the local clock.
+
+use crate::websocket::WsData;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Number of recent samples used for the estimate
+const WINDOW: usize = 256;
+
+/// Estimated offset between the local clock and server timestamps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClockSkewReport {
+    pub samples: usize,
+    /// Smallest local-minus-server offset in the window (least network delay)
+    pub min_offset_ms: Option<i64>,
+    pub median_offset_ms: Option<i64>,
+}
+
+impl ClockSkewReport {
+    /// Best estimate of the skew; positive when the local clock is ahead
+    pub fn skew_ms(&self) -> Option<i64> {
+        self.min_offset_ms
+    }
+
+    /// Whether the skew exceeds `threshold_ms` in either direction
+    pub fn is_skewed(&self, threshold_ms: i64) -> bool {
+        self.skew_ms().is_some_and(|skew| skew.abs() > threshold_ms)
+    }
+}
+
+/// Tracks clock skew from server timestamps
+///
+/// Each sample is `local receive time - server timestamp`, which is the clock
+/// offset plus network delay; the minimum over recent samples approximates
+/// the offset alone.
+#[derive(Debug, Clone, Default)]
+pub struct ClockSync {
+    offsets: VecDeque<i64>,
+}
+
+impl ClockSync {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a server timestamp (seconds or milliseconds) received at `local_ms`
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::clock::ClockSync;
+    ///
+    /// let mut clock = ClockSync::new();
+    /// clock.record(1_733_111_100_000, 1_733_111_102_050);
+    /// clock.record(1_733_111_101_000, 1_733_111_103_010);
+    /// let report = clock.report();
+    /// assert_eq!(report.skew_ms(), Some(2_010));
+    /// assert!(report.is_skewed(1_000));
+    /// ```
+    pub fn record(&mut self, server_timestamp: i64, local_ms: i64) {
+        if self.offsets.len() == WINDOW {
+            self.offsets.pop_front();
+        }
+        self.offsets.push_back(local_ms - timestamp_millis(server_timestamp));
+    }
+
+    /// Record the timestamp of a market data event against the current time
+    pub fn record_event(&mut self, data: &WsData) {
+        if let Some(timestamp) = event_timestamp(data) {
+            self.record(timestamp, now_millis());
+        }
+    }
+
+    /// Current skew estimate
+    pub fn report(&self) -> ClockSkewReport {
+        let mut sorted: Vec<i64> = self.offsets.iter().copied().collect();
+        sorted.sort_unstable();
+        ClockSkewReport {
+            samples: sorted.len(),
+            min_offset_ms: sorted.first().copied(),
+            median_offset_ms: sorted.get(sorted.len() / 2).copied(),
+        }
+    }
+
+    /// Shift an event's timestamp onto the local clock using the current estimate
+    pub fn adjust(&self, data: &mut WsData) {
+        let Some(skew_ms) = self.report().skew_ms() else {
+            return;
+        };
+        let timestamp = match data {
+            WsData::Ltp(d) => &mut d.timestamp,
+            WsData::Quote(d) => &mut d.timestamp,
+            WsData::Depth(d) => &mut d.timestamp,
+            _ => return,
+        };
+        if let Some(ts) = timestamp {
+            *ts = if is_millis(*ts) { *ts + skew_ms } else { *ts + skew_ms / 1000 };
+        }
+    }
+}
+
+fn event_timestamp(data: &WsData) -> Option<i64> {
+    match data {
+        WsData::Ltp(d) => d.timestamp,
+        WsData::Quote(d) => d.timestamp,
+        WsData::Depth(d) => d.timestamp,
+        _ => None,
+    }
+}
+
+fn is_millis(timestamp: i64) -> bool {
+    timestamp > 100_000_000_000
+}
+
+fn timestamp_millis(timestamp: i64) -> i64 {
+    if is_millis(timestamp) {
+        timestamp
+    } else {
+        timestamp * 1000
+    }
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
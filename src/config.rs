@@ -0,0 +1,233 @@
+//! TOML configuration files for OpenAlgo.
+//!
+//! Keeps deployment settings out of code: connection settings, the default
+//! strategy, exchange and product, risk limits and watchlists. Built with the
+//! `config` feature. The API key may be left out of the file, in which case
+//! `OPENALGO_API_KEY` is used.
+//!
+//! ```toml
+//! host = "https://algo.example.com"
+//! request_ids = true
+//! max_retries = 2
+//!
+//! [defaults]
+//! strategy = "Momentum"
+//! exchange = "NSE"
+//! product = "MIS"
+//!
+//! [risk]
+//! max_order_quantity = 1800
+//! max_order_value = 500000.0
+//! allowed_exchanges = ["NSE", "NFO"]
+//!
+//! [[watchlists]]
+//! name = "banks"
+//! instruments = [
+//!     { exchange = "NSE", symbol = "HDFCBANK" },
+//!     { exchange = "NSE", symbol = "ICICIBANK" },
+//! ]
+//! ```
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::config::OpenAlgoConfig;
+//!
+//! # async fn example() -> Result<(), openalgo::client::OpenAlgoError> {
+//! let config = OpenAlgoConfig::load("openalgo.toml")?;
+//! let client = config.client()?;
+//! config.risk.check_order("NSE", 10, 2500.0)?;
+//! client
+//!     .place_order(config.strategy(), "RELIANCE", "BUY", "NSE", "MARKET", config.product(), "10")
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::OpenAlgoError;
+use crate::retry::RetryPolicy;
+use crate::watchlist::Watchlist;
+use crate::{OpenAlgo, OpenAlgoBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Client settings, defaults, risk limits and watchlists from a TOML file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenAlgoConfig {
+    /// API key; `OPENALGO_API_KEY` is used when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ws_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ws_port: Option<u16>,
+    pub request_ids: bool,
+    pub log_bodies: bool,
+    pub validate_intervals: bool,
+    /// Check order prices and quantities against tick and lot size before sending
+    pub validate_orders: bool,
+    /// Check CNC sells against holdings before sending
+    pub guard_cnc_sells: bool,
+    /// Retries of safe endpoints after transient failures; orders are never retried
+    pub max_retries: u32,
+    pub defaults: OrderDefaults,
+    pub risk: RiskLimits,
+    pub watchlists: Vec<Watchlist>,
+}
+
+/// Default order fields
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrderDefaults {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exchange: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
+}
+
+/// Per-order limits checked before an order is sent; unset limits are not checked
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_order_quantity: Option<i64>,
+    /// Largest quantity times price of a single order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_order_value: Option<f64>,
+    /// Exchanges orders may go to; empty allows all
+    pub allowed_exchanges: Vec<String>,
+}
+
+impl RiskLimits {
+    /// Check an order against the limits; pass a price of zero for market orders to skip the value check
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::config::RiskLimits;
+    ///
+    /// let risk = RiskLimits { max_order_quantity: Some(100), allowed_exchanges: vec!["NSE".into()], ..Default::default() };
+    /// assert!(risk.check_order("NSE", 50, 2500.0).is_ok());
+    /// assert!(risk.check_order("NSE", 150, 2500.0).is_err());
+    /// assert!(risk.check_order("BSE", 50, 2500.0).is_err());
+    /// ```
+    pub fn check_order(&self, exchange: &str, quantity: i64, price: f64) -> Result<(), OpenAlgoError> {
+        if !self.allowed_exchanges.is_empty() && !self.allowed_exchanges.iter().any(|e| e.eq_ignore_ascii_case(exchange)) {
+            return Err(OpenAlgoError::ApiError(format!("Exchange {} is not allowed by the risk limits", exchange).into()));
+        }
+        if let Some(max) = self.max_order_quantity.filter(|max| quantity > *max) {
+            return Err(OpenAlgoError::ApiError(format!("Quantity {} is above the limit of {}", quantity, max).into()));
+        }
+        let value = quantity as f64 * price;
+        if let Some(max) = self.max_order_value.filter(|max| value > *max) {
+            return Err(OpenAlgoError::ApiError(format!("Order value {:.2} is above the limit of {:.2}", value, max).into()));
+        }
+        Ok(())
+    }
+}
+
+impl OpenAlgoConfig {
+    /// Read a config file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, OpenAlgoError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        Self::from_toml(&content).map_err(|e| match e {
+            OpenAlgoError::ConfigError(msg) => OpenAlgoError::ConfigError(format!("{}: {}", path.display(), msg)),
+            other => other,
+        })
+    }
+
+    /// Parse a config from TOML text
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::config::OpenAlgoConfig;
+    ///
+    /// let config = OpenAlgoConfig::from_toml(r#"
+    ///     api_key = "your-api-key"
+    ///     [defaults]
+    ///     strategy = "Momentum"
+    ///     [[watchlists]]
+    ///     name = "banks"
+    ///     instruments = [{ exchange = "NSE", symbol = "HDFCBANK" }]
+    /// "#).unwrap();
+    /// assert_eq!(config.strategy(), "Momentum");
+    /// assert_eq!(config.product(), "MIS");
+    /// assert_eq!(config.watchlist("banks").unwrap().instruments.len(), 1);
+    /// ```
+    pub fn from_toml(content: &str) -> Result<Self, OpenAlgoError> {
+        toml::from_str(content).map_err(|e| OpenAlgoError::ConfigError(e.to_string()))
+    }
+
+    /// Write the config as TOML
+    pub fn to_toml(&self) -> Result<String, OpenAlgoError> {
+        toml::to_string_pretty(self).map_err(|e| OpenAlgoError::ConfigError(e.to_string()))
+    }
+
+    /// Save the config to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), OpenAlgoError> {
+        std::fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    /// A client builder with the connection settings applied
+    pub fn builder(&self) -> Result<OpenAlgoBuilder, OpenAlgoError> {
+        let api_key = match &self.api_key {
+            Some(key) => key.clone(),
+            None => std::env::var("OPENALGO_API_KEY")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .ok_or_else(|| OpenAlgoError::ConfigError("no api_key in the config file and OPENALGO_API_KEY is not set".to_string()))?,
+        };
+        let mut builder = OpenAlgoBuilder::new(&api_key)
+            .request_ids(self.request_ids)
+            .log_bodies(self.log_bodies)
+            .validate_intervals(self.validate_intervals)
+            .validate_orders(self.validate_orders)
+            .guard_cnc_sells(self.guard_cnc_sells)
+            .retry(RetryPolicy::new(self.max_retries));
+        if let Some(host) = &self.host {
+            builder = builder.host(host);
+        }
+        if let Some(version) = &self.version {
+            builder = builder.version(version);
+        }
+        if let Some(port) = self.ws_port {
+            builder = builder.ws_port(port);
+        }
+        if let Some(ws_url) = &self.ws_url {
+            builder = builder.ws_url(ws_url);
+        }
+        Ok(builder)
+    }
+
+    /// A client with the connection settings applied
+    pub fn client(&self) -> Result<OpenAlgo, OpenAlgoError> {
+        self.builder()?.try_build()
+    }
+
+    /// Default strategy name, "openalgo" when unset
+    pub fn strategy(&self) -> &str {
+        self.defaults.strategy.as_deref().unwrap_or("openalgo")
+    }
+
+    /// Default exchange, "NSE" when unset
+    pub fn exchange(&self) -> &str {
+        self.defaults.exchange.as_deref().unwrap_or("NSE")
+    }
+
+    /// Default product, "MIS" when unset
+    pub fn product(&self) -> &str {
+        self.defaults.product.as_deref().unwrap_or("MIS")
+    }
+
+    /// Watchlist by name
+    pub fn watchlist(&self, name: &str) -> Option<&Watchlist> {
+        self.watchlists.iter().find(|w| w.name == name)
+    }
+}
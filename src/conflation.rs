@@ -0,0 +1,157 @@
+//! Conflated market data streams for OpenAlgo.
+//!
+//! Emits at most one update per instrument and mode per interval, keeping the
+//! latest value, so slow consumers are not flooded during busy markets.
+
+use crate::websocket::WsData;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Counters for a conflated stream
+#[derive(Debug, Default)]
+pub struct ConflationStats {
+    received: AtomicU64,
+    emitted: AtomicU64,
+    conflated: AtomicU64,
+}
+
+/// Point-in-time copy of conflation counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConflationSnapshot {
+    /// Market data updates received from the source
+    pub received: u64,
+    /// Market data updates delivered to the consumer
+    pub emitted: u64,
+    /// Updates replaced by a newer value before delivery
+    pub conflated: u64,
+}
+
+impl ConflationStats {
+    /// Snapshot the counters
+    pub fn snapshot(&self) -> ConflationSnapshot {
+        ConflationSnapshot {
+            received: self.received.load(Ordering::Relaxed),
+            emitted: self.emitted.load(Ordering::Relaxed),
+            conflated: self.conflated.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Conflation key: (mode, exchange, symbol)
+type ConflationKey = (u8, String, String);
+
+fn conflation_key(data: &WsData) -> Option<ConflationKey> {
+    let mode = match data {
+        WsData::Ltp(_) => 1,
+        WsData::Quote(_) => 2,
+        WsData::Depth(_) => 3,
+        _ => return None,
+    };
+    Some((
+        mode,
+        data.exchange().unwrap_or_default().to_string(),
+        data.symbol()?.to_string(),
+    ))
+}
+
+/// Conflate a WebSocket data stream
+///
+/// The first update for an instrument is delivered immediately; further
+/// updates within `interval` are held and only the latest is delivered when
+/// the interval elapses. Connection events and errors pass through unchanged.
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+/// use openalgo::conflation::conflate;
+/// use std::time::Duration;
+///
+/// let (cmd_tx, data_rx) = client.websocket().connect().await?;
+/// let (mut data_rx, stats) = conflate(data_rx, Duration::from_millis(250));
+/// while let Some(data) = data_rx.recv().await {
+///     println!("{:?} (conflated so far: {})", data, stats.snapshot().conflated);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn conflate(
+    mut source: mpsc::Receiver<WsData>,
+    interval: Duration,
+) -> (mpsc::Receiver<WsData>, Arc<ConflationStats>) {
+    let (data_tx, data_rx) = mpsc::channel::<WsData>(128);
+    let stats = Arc::new(ConflationStats::default());
+    let task_stats = Arc::clone(&stats);
+
+    tokio::spawn(async move {
+        let stats = task_stats;
+        let mut last_emit: HashMap<ConflationKey, Instant> = HashMap::new();
+        let mut pending: HashMap<ConflationKey, WsData> = HashMap::new();
+        let mut flush = tokio::time::interval((interval / 4).max(Duration::from_millis(1)));
+
+        loop {
+            tokio::select! {
+                msg = source.recv() => {
+                    let Some(data) = msg else { break };
+                    let Some(key) = conflation_key(&data) else {
+                        if data_tx.send(data).await.is_err() {
+                            return;
+                        }
+                        continue;
+                    };
+
+                    stats.received.fetch_add(1, Ordering::Relaxed);
+                    let now = Instant::now();
+                    let due = last_emit
+                        .get(&key)
+                        .is_none_or(|last| now.duration_since(*last) >= interval);
+
+                    if due && !pending.contains_key(&key) {
+                        last_emit.insert(key, now);
+                        stats.emitted.fetch_add(1, Ordering::Relaxed);
+                        if data_tx.send(data).await.is_err() {
+                            return;
+                        }
+                    } else if pending.insert(key, data).is_some() {
+                        stats.conflated.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                _ = flush.tick() => {
+                    let now = Instant::now();
+                    let ready: Vec<ConflationKey> = pending
+                        .keys()
+                        .filter(|key| {
+                            last_emit
+                                .get(*key)
+                                .is_none_or(|last| now.duration_since(*last) >= interval)
+                        })
+                        .cloned()
+                        .collect();
+
+                    for key in ready {
+                        if let Some(data) = pending.remove(&key) {
+                            last_emit.insert(key, now);
+                            stats.emitted.fetch_add(1, Ordering::Relaxed);
+                            if data_tx.send(data).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Deliver whatever is still held once the source closes
+        for (_, data) in pending.drain() {
+            stats.emitted.fetch_add(1, Ordering::Relaxed);
+            if data_tx.send(data).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    (data_rx, stats)
+}
@@ -0,0 +1,540 @@
+//! Embedded control server for running strategies.
+//!
+//! A `ControlServer` gives operators of a headless strategy host an HTTP and
+//! WebSocket endpoint for every strategy registered with it: status, P&L
+//! and positions, plus pause, resume and stop controls. Strategies publish
+//! their numbers through the `StrategyControl` handle returned by
+//! `register`, and check it (or `wait_while_paused`) before trading.
+//! Enabled by the `control` feature.
+//!
+//! | Request | Response |
+//! |---|---|
+//! | `GET /strategies` | Every strategy |
+//! | `GET /strategies/{name}` | One strategy |
+//! | `POST /strategies/{name}/pause` | Pause it |
+//! | `POST /strategies/{name}/resume` | Resume it; `409` once stopped |
+//! | `POST /strategies/{name}/stop` | Stop it for good |
+//! | `GET /ws` | WebSocket pushing every strategy on each change |
+//! | `GET /dashboard` | WebSocket relaying the attached [`DashboardFeed`] |
+//!
+//! With `auth_token` set, every request needs an
+//! `Authorization: Bearer <token>` header. Requests carrying an `Origin`
+//! header are refused with `403` unless the origin was allowed with
+//! `allow_origin`, so a web page open in the operator's browser cannot
+//! pause or stop strategies. Bind to `127.0.0.1` unless the port is
+//! otherwise protected, and keep clear of the OpenAlgo WebSocket port
+//! ([`DEFAULT_WS_PORT`](crate::client::DEFAULT_WS_PORT), 8765) on a host that also runs OpenAlgo.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::control::{ControlServer, StrategyStatus};
+//!
+//! # async fn example() -> std::io::Result<()> {
+//! let server = ControlServer::new().auth_token("change-me");
+//! let momentum = server.register("Momentum");
+//! let (address, _handle) = server.start("127.0.0.1:8787").await?;
+//! println!("control server on {}", address);
+//!
+//! loop {
+//!     if momentum.wait_while_paused().await == StrategyStatus::Stopped {
+//!         break;
+//!     }
+//!     // ... trade, then publish the numbers
+//!     momentum.set_pnl(1_250.0);
+//!     # break;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::dashboard::{DashboardFeed, DashboardMessage};
+use crate::netting::VirtualPosition;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, Notify};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Largest request head accepted, in bytes
+const MAX_HEAD: usize = 16 * 1024;
+
+/// Longest a client may take to send its request head
+const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run state of a strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StrategyStatus {
+    Running,
+    Paused,
+    /// Terminal: a stopped strategy cannot be resumed
+    Stopped,
+}
+
+/// What the control server reports for a strategy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyInfo {
+    pub name: String,
+    pub status: StrategyStatus,
+    pub pnl: f64,
+    pub positions: Vec<VirtualPosition>,
+    /// Unix seconds of the last change
+    pub updated_at: i64,
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// A strategy's handle on its control state
+///
+/// Clones share the same state.
+///
+/// # Example
+/// ```rust
+/// use openalgo::control::{ControlServer, StrategyStatus};
+///
+/// let server = ControlServer::new();
+/// let strategy = server.register("Momentum");
+/// assert!(strategy.pause());
+/// assert!(!strategy.pause());
+/// assert_eq!(server.strategies()[0].status, StrategyStatus::Paused);
+/// assert!(strategy.stop());
+/// assert!(!strategy.resume());
+/// ```
+#[derive(Debug, Clone)]
+pub struct StrategyControl {
+    info: Arc<Mutex<StrategyInfo>>,
+    changed: Arc<Notify>,
+    server_changed: Arc<Notify>,
+}
+
+impl StrategyControl {
+    fn update(&self, f: impl FnOnce(&mut StrategyInfo)) {
+        {
+            let mut info = self.info.lock().unwrap();
+            f(&mut info);
+            info.updated_at = now_secs();
+        }
+        self.changed.notify_waiters();
+        self.server_changed.notify_waiters();
+    }
+
+    /// Snapshot of the strategy's state
+    pub fn info(&self) -> StrategyInfo {
+        self.info.lock().unwrap().clone()
+    }
+
+    /// Current run state
+    pub fn status(&self) -> StrategyStatus {
+        self.info.lock().unwrap().status
+    }
+
+    /// Whether the strategy should trade
+    pub fn is_running(&self) -> bool {
+        self.status() == StrategyStatus::Running
+    }
+
+    /// Whether the strategy has been stopped
+    pub fn is_stopped(&self) -> bool {
+        self.status() == StrategyStatus::Stopped
+    }
+
+    /// Publish the strategy's P&L
+    pub fn set_pnl(&self, pnl: f64) {
+        self.update(|info| info.pnl = pnl);
+    }
+
+    /// Publish the strategy's positions
+    pub fn set_positions(&self, positions: Vec<VirtualPosition>) {
+        self.update(|info| info.positions = positions);
+    }
+
+    /// Pause a running strategy; false if it is not running
+    pub fn pause(&self) -> bool {
+        self.transition(StrategyStatus::Running, StrategyStatus::Paused)
+    }
+
+    /// Resume a paused strategy; false if it is not paused
+    pub fn resume(&self) -> bool {
+        self.transition(StrategyStatus::Paused, StrategyStatus::Running)
+    }
+
+    /// Stop the strategy; false if it was already stopped
+    pub fn stop(&self) -> bool {
+        if self.is_stopped() {
+            return false;
+        }
+        self.update(|info| info.status = StrategyStatus::Stopped);
+        true
+    }
+
+    fn transition(&self, from: StrategyStatus, to: StrategyStatus) -> bool {
+        if self.status() != from {
+            return false;
+        }
+        self.update(|info| info.status = to);
+        true
+    }
+
+    /// Wait until the strategy is not paused, returning `Running` or `Stopped`
+    pub async fn wait_while_paused(&self) -> StrategyStatus {
+        loop {
+            let changed = self.changed.notified();
+            let status = self.status();
+            if status != StrategyStatus::Paused {
+                return status;
+            }
+            changed.await;
+        }
+    }
+}
+
+/// HTTP and WebSocket control endpoint for registered strategies
+#[derive(Clone, Default)]
+pub struct ControlServer {
+    strategies: Arc<Mutex<BTreeMap<String, StrategyControl>>>,
+    changed: Arc<Notify>,
+    token: Option<String>,
+    origins: Vec<String>,
+    dashboard: Option<DashboardFeed>,
+}
+
+impl fmt::Debug for ControlServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ControlServer")
+            .field("strategies", &self.strategies.lock().unwrap().keys().collect::<Vec<_>>())
+            .field("token", &self.token.as_ref().map(|_| "***"))
+            .field("origins", &self.origins)
+            .finish()
+    }
+}
+
+impl ControlServer {
+    /// A server with no strategies and no authentication
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require `Authorization: Bearer <token>` on every request
+    pub fn auth_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Accept browser requests from `origin`, e.g. `http://localhost:3000` for a dashboard frontend
+    ///
+    /// Requests with any other `Origin` header are refused.
+    pub fn allow_origin(mut self, origin: &str) -> Self {
+        self.origins.push(origin.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Relay a dashboard feed to WebSocket clients of `/dashboard`, one JSON message per frame
+    pub fn dashboard(mut self, feed: &DashboardFeed) -> Self {
+        self.dashboard = Some(feed.clone());
+        self
+    }
+
+    /// Register a running strategy, or get the handle of one already registered
+    pub fn register(&self, name: &str) -> StrategyControl {
+        let control = self
+            .strategies
+            .lock()
+            .unwrap()
+            .entry(name.to_string())
+            .or_insert_with(|| StrategyControl {
+                info: Arc::new(Mutex::new(StrategyInfo {
+                    name: name.to_string(),
+                    status: StrategyStatus::Running,
+                    pnl: 0.0,
+                    positions: Vec::new(),
+                    updated_at: now_secs(),
+                })),
+                changed: Arc::default(),
+                server_changed: self.changed.clone(),
+            })
+            .clone();
+        self.changed.notify_waiters();
+        control
+    }
+
+    /// Handle of a registered strategy
+    pub fn strategy(&self, name: &str) -> Option<StrategyControl> {
+        self.strategies.lock().unwrap().get(name).cloned()
+    }
+
+    /// Every registered strategy, by name
+    pub fn strategies(&self) -> Vec<StrategyInfo> {
+        self.strategies.lock().unwrap().values().map(StrategyControl::info).collect()
+    }
+
+    /// Bind to `address` and serve in the background
+    ///
+    /// Returns the bound address (useful with port 0) and the accept loop's
+    /// handle; abort it to stop serving.
+    pub async fn start(&self, address: &str) -> io::Result<(SocketAddr, JoinHandle<()>)> {
+        let listener = TcpListener::bind(address).await?;
+        let local = listener.local_addr()?;
+        if self.token.is_none() && !local.ip().is_loopback() {
+            log::warn!("control: serving on {} without an auth token", local);
+        }
+        let server = self.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let server = server.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = server.handle(stream).await {
+                                log::debug!("control: connection from {} failed: {}", peer, e);
+                            }
+                        });
+                    }
+                    Err(e) => log::warn!("control: accept failed: {}", e),
+                }
+            }
+        });
+        Ok((local, handle))
+    }
+
+    async fn handle(&self, mut stream: TcpStream) -> io::Result<()> {
+        let Ok(head) = tokio::time::timeout(HEAD_TIMEOUT, read_head(&mut stream)).await else { return Ok(()) };
+        let Some(request) = head? else { return Ok(()) };
+
+        if let Some(origin) = request.header("origin") {
+            if !self.origins.iter().any(|allowed| allowed.as_str() == origin.trim_end_matches('/')) {
+                return respond(&mut stream, 403, &serde_json::json!({"error": "origin not allowed"})).await;
+            }
+        }
+        if let Some(token) = &self.token {
+            let expected = format!("Bearer {}", token);
+            let given = request.header("authorization").unwrap_or_default();
+            if !constant_time_eq(given.as_bytes(), expected.as_bytes()) {
+                return respond(&mut stream, 401, &serde_json::json!({"error": "unauthorized"})).await;
+            }
+        }
+
+        let segments: Vec<String> = request.path.split('/').filter(|s| !s.is_empty()).map(percent_decode).collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+        let upgrade = request.header("upgrade").is_some_and(|u| u.eq_ignore_ascii_case("websocket"));
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["ws"]) if upgrade => {
+                let Some(socket) = accept_websocket(stream, &request).await? else { return Ok(()) };
+                self.push_updates(socket).await;
+                Ok(())
+            }
+            ("GET", ["dashboard"]) if upgrade => {
+                let Some(feed) = &self.dashboard else { return not_found(&mut stream).await };
+                let messages = feed.subscribe();
+                let Some(socket) = accept_websocket(stream, &request).await? else { return Ok(()) };
+                relay_dashboard(socket, messages).await;
+                Ok(())
+            }
+            ("GET", ["strategies"]) => respond(&mut stream, 200, &self.strategies()).await,
+            ("GET", ["strategies", name]) => match self.strategy(name) {
+                Some(control) => respond(&mut stream, 200, &control.info()).await,
+                None => not_found(&mut stream).await,
+            },
+            ("POST", ["strategies", name, action]) => {
+                let Some(control) = self.strategy(name) else { return not_found(&mut stream).await };
+                let done = match *action {
+                    "pause" => control.pause(),
+                    "resume" => control.resume(),
+                    "stop" => control.stop(),
+                    _ => return not_found(&mut stream).await,
+                };
+                let status = if done || control.status() == target(action) { 200 } else { 409 };
+                respond(&mut stream, status, &control.info()).await
+            }
+            (_, ["ws"] | ["dashboard"] | ["strategies"] | ["strategies", _] | ["strategies", _, _]) => {
+                respond(&mut stream, 405, &serde_json::json!({"error": "method not allowed"})).await
+            }
+            _ => not_found(&mut stream).await,
+        }
+    }
+
+    /// Send every strategy now and after each change, until the client goes away
+    async fn push_updates(&self, socket: WebSocketStream<TcpStream>) {
+        let (mut sink, mut incoming) = socket.split();
+        loop {
+            let changed = self.changed.notified();
+            let Ok(text) = serde_json::to_string(&self.strategies()) else { return };
+            if sink.send(Message::Text(text)).await.is_err() {
+                return;
+            }
+            tokio::select! {
+                _ = changed => {}
+                // Heartbeat, so dead clients are noticed
+                _ = tokio::time::sleep(Duration::from_secs(30)) => {}
+                message = incoming.next() => match message {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                    _ => {}
+                },
+            }
+        }
+    }
+}
+
+/// Complete a WebSocket upgrade; answers `400` and returns `None` without a key
+async fn accept_websocket(mut stream: TcpStream, request: &RequestHead) -> io::Result<Option<WebSocketStream<TcpStream>>> {
+    let Some(key) = request.header("sec-websocket-key") else {
+        respond(&mut stream, 400, &serde_json::json!({"error": "missing Sec-WebSocket-Key"})).await?;
+        return Ok(None);
+    };
+    let head = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+    stream.write_all(head.as_bytes()).await?;
+    Ok(Some(WebSocketStream::from_raw_socket(stream, Role::Server, None).await))
+}
+
+/// Forward dashboard messages until the client goes away; lagging clients skip ahead
+async fn relay_dashboard(socket: WebSocketStream<TcpStream>, mut messages: broadcast::Receiver<DashboardMessage>) {
+    let (mut sink, mut incoming) = socket.split();
+    loop {
+        tokio::select! {
+            message = messages.recv() => match message {
+                Ok(message) => {
+                    if sink.send(Message::Text(message.to_json())).await.is_err() {
+                        return;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("control: dashboard client skipped {} messages", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            message = incoming.next() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                _ => {}
+            },
+        }
+    }
+}
+
+/// Status a control action leads to
+fn target(action: &str) -> StrategyStatus {
+    match action {
+        "pause" => StrategyStatus::Paused,
+        "stop" => StrategyStatus::Stopped,
+        _ => StrategyStatus::Running,
+    }
+}
+
+struct RequestHead {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Read the request line and headers; `None` if the client sent nothing usable
+async fn read_head(stream: &mut TcpStream) -> io::Result<Option<RequestHead>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 || buffer.len() + n > MAX_HEAD {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    }
+    let text = String::from_utf8_lossy(&buffer);
+    let mut lines = text.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Ok(None);
+    };
+    let headers = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+    Ok(Some(RequestHead {
+        method: method.to_ascii_uppercase(),
+        path: target.split('?').next().unwrap_or_default().to_string(),
+        headers,
+    }))
+}
+
+async fn respond<T: Serialize + ?Sized>(stream: &mut TcpStream, status: u16, body: &T) -> io::Result<()> {
+    let body = serde_json::to_string(body)?;
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        _ => "",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn not_found(stream: &mut TcpStream) -> io::Result<()> {
+    respond(stream, 404, &serde_json::json!({"error": "not found"})).await
+}
+
+/// Compare without returning early, so response timing does not reveal how much of a token matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Decode `%XX` escapes in a path segment
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
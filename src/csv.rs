@@ -0,0 +1,287 @@
+//! CSV export for books and market data.
+//!
+//! `to_csv(writer)` on the orderbook, tradebook, positionbook, holdings,
+//! option chain and history responses writes a header line and one row per
+//! item. Columns are fixed and always in the same order, missing values are
+//! empty cells, and fields the server adds beyond the typed ones are left out,
+//! so daily exports line up from one day to the next.
+
+use crate::types::*;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+/// Quote a CSV cell containing a comma, quote or newline
+pub(crate) fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn text(value: &Option<String>) -> String {
+    value.as_deref().map(field).unwrap_or_default()
+}
+
+fn number<T: Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn write_row<W: Write>(writer: &mut W, cells: &[String]) -> io::Result<()> {
+    writeln!(writer, "{}", cells.join(","))
+}
+
+fn write_header<W: Write>(writer: &mut W, columns: &[&str]) -> io::Result<()> {
+    writeln!(writer, "{}", columns.join(","))
+}
+
+const ORDER_COLUMNS: [&str; 11] = [
+    "orderid",
+    "symbol",
+    "exchange",
+    "action",
+    "quantity",
+    "price",
+    "trigger_price",
+    "pricetype",
+    "product",
+    "order_status",
+    "timestamp",
+];
+
+impl OrderbookResponse {
+    /// Write the orders as CSV
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::OrderbookResponse;
+    ///
+    /// let book: OrderbookResponse = serde_json::from_str(r#"{"status": "success", "data": {"orders": [
+    ///     {"orderid": "2403", "symbol": "RELIANCE", "exchange": "NSE", "action": "BUY",
+    ///      "quantity": "1", "price": 2500, "pricetype": "LIMIT", "product": "MIS", "order_status": "open"}
+    /// ]}}"#).unwrap();
+    /// let mut out = Vec::new();
+    /// book.to_csv(&mut out).unwrap();
+    /// let csv = String::from_utf8(out).unwrap();
+    /// let mut lines = csv.lines();
+    /// assert_eq!(lines.next(), Some("orderid,symbol,exchange,action,quantity,price,trigger_price,pricetype,product,order_status,timestamp"));
+    /// assert_eq!(lines.next(), Some("2403,RELIANCE,NSE,BUY,1,2500,,LIMIT,MIS,open,"));
+    /// ```
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_header(&mut writer, &ORDER_COLUMNS)?;
+        let orders = self.data.as_ref().and_then(|d| d.orders.as_deref()).unwrap_or_default();
+        for o in orders {
+            write_row(
+                &mut writer,
+                &[
+                    text(&o.orderid),
+                    text(&o.symbol),
+                    text(&o.exchange),
+                    text(&o.action),
+                    text(&o.quantity),
+                    number(o.price),
+                    number(o.trigger_price),
+                    text(&o.pricetype),
+                    text(&o.product),
+                    text(&o.order_status),
+                    text(&o.timestamp),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl TradebookResponse {
+    /// Write the trades as CSV
+    ///
+    /// Columns: `orderid,symbol,exchange,action,quantity,average_price,trade_value,product,timestamp`.
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_header(
+            &mut writer,
+            &[
+                "orderid",
+                "symbol",
+                "exchange",
+                "action",
+                "quantity",
+                "average_price",
+                "trade_value",
+                "product",
+                "timestamp",
+            ],
+        )?;
+        for t in self.data.as_deref().unwrap_or_default() {
+            write_row(
+                &mut writer,
+                &[
+                    text(&t.orderid),
+                    text(&t.symbol),
+                    text(&t.exchange),
+                    text(&t.action),
+                    number(t.quantity),
+                    number(t.average_price),
+                    number(t.trade_value),
+                    text(&t.product),
+                    text(&t.timestamp),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl PositionbookResponse {
+    /// Write the positions as CSV
+    ///
+    /// Columns: `symbol,exchange,product,quantity,average_price,ltp,pnl`.
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_header(
+            &mut writer,
+            &["symbol", "exchange", "product", "quantity", "average_price", "ltp", "pnl"],
+        )?;
+        for p in self.data.as_deref().unwrap_or_default() {
+            write_row(
+                &mut writer,
+                &[
+                    text(&p.symbol),
+                    text(&p.exchange),
+                    text(&p.product),
+                    text(&p.quantity),
+                    text(&p.average_price),
+                    text(&p.ltp),
+                    text(&p.pnl),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl HoldingsResponse {
+    /// Write the holdings as CSV
+    ///
+    /// Columns: `symbol,exchange,product,quantity,pnl,pnlpercent`. The
+    /// statistics are not included.
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_header(&mut writer, &["symbol", "exchange", "product", "quantity", "pnl", "pnlpercent"])?;
+        let holdings = self.data.as_ref().and_then(|d| d.holdings.as_deref()).unwrap_or_default();
+        for h in holdings {
+            write_row(
+                &mut writer,
+                &[
+                    text(&h.symbol),
+                    text(&h.exchange),
+                    text(&h.product),
+                    number(h.quantity),
+                    number(h.pnl),
+                    number(h.pnlpercent),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+const LEG_COLUMNS: [&str; 12] = [
+    "symbol",
+    "ltp",
+    "bid",
+    "ask",
+    "open",
+    "high",
+    "low",
+    "prev_close",
+    "volume",
+    "oi",
+    "lotsize",
+    "tick_size",
+];
+
+fn leg_cells(leg: Option<&OptionStrikeData>) -> Vec<String> {
+    match leg {
+        Some(l) => vec![
+            text(&l.symbol),
+            number(l.ltp),
+            number(l.bid),
+            number(l.ask),
+            number(l.open),
+            number(l.high),
+            number(l.low),
+            number(l.prev_close),
+            number(l.volume),
+            number(l.oi),
+            number(l.lotsize),
+            number(l.tick_size),
+        ],
+        None => vec![String::new(); LEG_COLUMNS.len()],
+    }
+}
+
+impl OptionChainResponse {
+    /// Write the chain as CSV, one row per strike
+    ///
+    /// Columns are `strike`, `label` (the call's label, such as `ATM`), then
+    /// the call's fields prefixed `ce_` and the put's prefixed `pe_`: `symbol,
+    /// ltp, bid, ask, open, high, low, prev_close, volume, oi, lotsize,
+    /// tick_size`. A missing leg leaves its cells empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::OptionChainResponse;
+    ///
+    /// let chain: OptionChainResponse = serde_json::from_str(r#"{"status": "success", "chain": [
+    ///     {"strike": 24000, "ce": {"symbol": "NIFTY26DEC2424000CE", "label": "ATM", "ltp": 120.5}, "pe": null}
+    /// ]}"#).unwrap();
+    /// let mut out = Vec::new();
+    /// chain.to_csv(&mut out).unwrap();
+    /// let csv = String::from_utf8(out).unwrap();
+    /// let mut lines = csv.lines();
+    /// assert!(lines.next().unwrap().starts_with("strike,label,ce_symbol,ce_ltp,"));
+    /// assert!(lines.next().unwrap().starts_with("24000,ATM,NIFTY26DEC2424000CE,120.5,"));
+    /// ```
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut header = vec!["strike".to_string(), "label".to_string()];
+        for side in ["ce", "pe"] {
+            header.extend(LEG_COLUMNS.iter().map(|c| format!("{}_{}", side, c)));
+        }
+        write_row(&mut writer, &header)?;
+        for s in self.chain.as_deref().unwrap_or_default() {
+            let label = s.ce.as_ref().and_then(|c| c.label.clone()).or_else(|| s.pe.as_ref().and_then(|p| p.label.clone()));
+            let mut cells = vec![s.strike.to_string(), text(&label)];
+            cells.extend(leg_cells(s.ce.as_ref()));
+            cells.extend(leg_cells(s.pe.as_ref()));
+            write_row(&mut writer, &cells)?;
+        }
+        Ok(())
+    }
+}
+
+impl HistoryResponse {
+    /// Write the candles as CSV
+    ///
+    /// Columns: `timestamp,open,high,low,close,volume,oi`. See
+    /// `candles::write_csv` for a candle slice.
+    pub fn to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_candles(self.data.as_deref().unwrap_or_default(), writer)
+    }
+}
+
+pub(crate) fn write_candles<W: Write>(candles: &[HistoryCandle], mut writer: W) -> io::Result<()> {
+    write_header(&mut writer, &["timestamp", "open", "high", "low", "close", "volume", "oi"])?;
+    for c in candles {
+        write_row(
+            &mut writer,
+            &[
+                c.timestamp.to_string(),
+                c.open.to_string(),
+                c.high.to_string(),
+                c.low.to_string(),
+                c.close.to_string(),
+                c.volume.to_string(),
+                number(c.oi),
+            ],
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,352 @@
+//! Single live data feed for dashboards.
+//!
+//! A `DashboardFeed` merges what a trading frontend shows into one stream of
+//! serializable `DashboardMessage`s: account snapshots (funds and positions),
+//! order events (new orders and status changes, found by diffing the
+//! orderbook), ticks from the WebSocket, and strategy statistics published by
+//! the strategies themselves. Every subscriber gets every message, in order,
+//! with a sequence number. With the `control` feature,
+//! `ControlServer::dashboard` also broadcasts the feed over WebSocket.
+//!
+//! ```text
+//! {"seq":42,"timestamp":1733111102114,"type":"order","data":{"orderid":"2403","symbol":"SBIN",...,"status":"complete","previous_status":"open"}}
+//! ```
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::dashboard::{DashboardFeed, StrategyStats};
+//!
+//! # async fn example(client: openalgo::OpenAlgo, ws: openalgo::websocket::OpenAlgoWebSocket) -> Result<(), openalgo::client::OpenAlgoError> {
+//! let feed = DashboardFeed::new();
+//! let mut messages = feed.subscribe();
+//! let _polling = feed.start(&client);
+//! let (_commands, ticks) = ws.connect().await?;
+//! let _ticks = feed.forward_ticks(ticks);
+//!
+//! feed.publish_strategy(StrategyStats::new("Momentum", 1_250.0));
+//! while let Ok(message) = messages.recv().await {
+//!     println!("{}", message.to_json());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::price;
+use crate::types::{FundsData, OrderbookOrder, PositionbookPosition};
+use crate::websocket::WsData;
+use crate::OpenAlgo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Messages buffered per subscriber before the slowest one starts missing them
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// Funds and open positions at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountSnapshot {
+    /// `None` when the funds request failed
+    pub funds: Option<FundsData>,
+    pub positions: Vec<PositionbookPosition>,
+}
+
+/// A new order, or an order whose status changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderEvent {
+    pub orderid: String,
+    pub symbol: Option<String>,
+    pub exchange: Option<String>,
+    pub action: Option<String>,
+    pub quantity: Option<String>,
+    pub status: Option<String>,
+    /// Status at the previous poll; `None` for an order seen for the first time
+    pub previous_status: Option<String>,
+}
+
+/// Last traded price of an instrument
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickEvent {
+    pub symbol: String,
+    pub exchange: String,
+    pub ltp: f64,
+    /// Exchange timestamp, when the tick carried one
+    pub timestamp: Option<i64>,
+}
+
+/// Figures a strategy reports about itself
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StrategyStats {
+    pub name: String,
+    pub pnl: f64,
+    /// Any other figures, such as trade count or exposure
+    pub metrics: BTreeMap<String, f64>,
+}
+
+impl StrategyStats {
+    /// Stats with a P&L and no other figures
+    pub fn new(name: &str, pnl: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            pnl,
+            metrics: BTreeMap::new(),
+        }
+    }
+
+    /// Add a named figure
+    pub fn metric(mut self, name: &str, value: f64) -> Self {
+        self.metrics.insert(name.to_string(), value);
+        self
+    }
+}
+
+/// What a dashboard message carries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum DashboardEvent {
+    Account(AccountSnapshot),
+    Order(OrderEvent),
+    Tick(TickEvent),
+    Strategy(StrategyStats),
+}
+
+/// One message of the dashboard feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DashboardMessage {
+    /// Position in the feed, from 1
+    pub seq: u64,
+    /// Unix milliseconds the message was published
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: DashboardEvent,
+}
+
+impl DashboardMessage {
+    /// The message as one line of JSON
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::dashboard::{DashboardEvent, DashboardMessage, StrategyStats};
+    ///
+    /// let message = DashboardMessage {
+    ///     seq: 7,
+    ///     timestamp: 0,
+    ///     event: DashboardEvent::Strategy(StrategyStats::new("Momentum", 1250.0)),
+    /// };
+    /// assert_eq!(
+    ///     message.to_json(),
+    ///     r#"{"seq":7,"timestamp":0,"type":"strategy","data":{"name":"Momentum","pnl":1250.0,"metrics":{}}}"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Order events between two orderbook polls
+///
+/// `previous` maps order IDs to the status seen at the last poll and is
+/// updated in place. Orders not in `previous` are reported as new.
+///
+/// # Example
+/// ```rust
+/// use openalgo::dashboard::order_events;
+/// use openalgo::OrderbookOrder;
+/// use std::collections::HashMap;
+///
+/// let order = |status: &str| -> OrderbookOrder {
+///     serde_json::from_value(serde_json::json!({"orderid": "2403", "symbol": "SBIN", "order_status": status})).unwrap()
+/// };
+/// let mut seen = HashMap::new();
+/// assert_eq!(order_events(&mut seen, &[order("open")])[0].previous_status, None);
+/// assert!(order_events(&mut seen, &[order("open")]).is_empty());
+/// let events = order_events(&mut seen, &[order("complete")]);
+/// assert_eq!(events[0].previous_status.as_deref(), Some("open"));
+/// ```
+pub fn order_events(previous: &mut HashMap<String, Option<String>>, orders: &[OrderbookOrder]) -> Vec<OrderEvent> {
+    let mut events = Vec::new();
+    for order in orders {
+        let Some(orderid) = &order.orderid else { continue };
+        let before = previous.insert(orderid.clone(), order.order_status.clone());
+        if before.as_ref() == Some(&order.order_status) {
+            continue;
+        }
+        events.push(OrderEvent {
+            orderid: orderid.clone(),
+            symbol: order.symbol.clone(),
+            exchange: order.exchange.clone(),
+            action: order.action.clone(),
+            quantity: order.quantity.clone(),
+            status: order.order_status.clone(),
+            previous_status: before.flatten(),
+        });
+    }
+    events
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Broadcast stream of account, order, tick and strategy messages
+///
+/// Clones publish into the same feed.
+#[derive(Debug, Clone)]
+pub struct DashboardFeed {
+    tx: broadcast::Sender<DashboardMessage>,
+    seq: Arc<AtomicU64>,
+    account_interval: Duration,
+    order_interval: Duration,
+}
+
+impl Default for DashboardFeed {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl DashboardFeed {
+    /// A feed buffering 1024 messages per subscriber, polling the account
+    /// every 5 seconds and the orderbook every 2 seconds
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A feed buffering `capacity` messages per subscriber
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            tx: broadcast::channel(capacity.max(1)).0,
+            seq: Arc::default(),
+            account_interval: Duration::from_secs(5),
+            order_interval: Duration::from_secs(2),
+        }
+    }
+
+    /// How often `start` polls funds and positions
+    pub fn account_interval(mut self, interval: Duration) -> Self {
+        self.account_interval = interval;
+        self
+    }
+
+    /// How often `start` polls the orderbook for order events
+    pub fn order_interval(mut self, interval: Duration) -> Self {
+        self.order_interval = interval;
+        self
+    }
+
+    /// Receive every message published from now on
+    ///
+    /// A subscriber that falls more than the capacity behind gets
+    /// `RecvError::Lagged` and skips ahead.
+    pub fn subscribe(&self) -> broadcast::Receiver<DashboardMessage> {
+        self.tx.subscribe()
+    }
+
+    /// Publish an event and return its sequence number
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::dashboard::{DashboardEvent, DashboardFeed, StrategyStats};
+    ///
+    /// let feed = DashboardFeed::new();
+    /// let mut messages = feed.subscribe();
+    /// feed.publish_strategy(StrategyStats::new("Momentum", 1250.0).metric("trades", 12.0));
+    /// let message = messages.try_recv().unwrap();
+    /// assert_eq!(message.seq, 1);
+    /// assert!(matches!(message.event, DashboardEvent::Strategy(s) if s.metrics["trades"] == 12.0));
+    /// ```
+    pub fn publish(&self, event: DashboardEvent) -> u64 {
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed) + 1;
+        // No subscribers is not an error: the message is simply not seen
+        let _ = self.tx.send(DashboardMessage {
+            seq,
+            timestamp: now_millis(),
+            event,
+        });
+        seq
+    }
+
+    /// Publish a strategy's figures
+    pub fn publish_strategy(&self, stats: StrategyStats) -> u64 {
+        self.publish(DashboardEvent::Strategy(stats))
+    }
+
+    /// Publish the LTP of a WebSocket LTP, quote or depth message; other messages are ignored
+    pub fn publish_tick(&self, data: &WsData) -> Option<u64> {
+        let (symbol, exchange, ltp, timestamp) = match data {
+            WsData::Ltp(d) => (&d.symbol, &d.exchange, d.ltp, d.timestamp),
+            WsData::Quote(d) => (&d.symbol, &d.exchange, d.ltp, d.timestamp),
+            WsData::Depth(d) => (&d.symbol, &d.exchange, d.ltp, d.timestamp),
+            _ => return None,
+        };
+        Some(self.publish(DashboardEvent::Tick(TickEvent {
+            symbol: symbol.clone().unwrap_or_default(),
+            exchange: exchange.clone().unwrap_or_default(),
+            ltp: price::to_f64(ltp?),
+            timestamp,
+        })))
+    }
+
+    /// Publish the ticks of a WebSocket data stream until it ends
+    pub fn forward_ticks(&self, mut data: mpsc::Receiver<WsData>) -> JoinHandle<()> {
+        let feed = self.clone();
+        tokio::spawn(async move {
+            while let Some(message) = data.recv().await {
+                feed.publish_tick(&message);
+            }
+        })
+    }
+
+    /// Poll the account and orderbook in the background and publish them
+    ///
+    /// Orders already in the book at the first poll are published as new.
+    /// Failed polls are logged and retried at the next interval. Abort the
+    /// handle to stop polling.
+    pub fn start(&self, client: &OpenAlgo) -> JoinHandle<()> {
+        let feed = self.clone();
+        let client = client.clone();
+        tokio::spawn(async move {
+            let mut account_timer = tokio::time::interval(feed.account_interval);
+            let mut order_timer = tokio::time::interval(feed.order_interval);
+            let mut seen = HashMap::new();
+            loop {
+                tokio::select! {
+                    _ = account_timer.tick() => {
+                        let funds = match client.funds().await {
+                            Ok(response) => response.data,
+                            Err(e) => {
+                                log::warn!("dashboard: funds failed: {}", e);
+                                None
+                            }
+                        };
+                        match client.positionbook().await {
+                            Ok(response) => {
+                                feed.publish(DashboardEvent::Account(AccountSnapshot {
+                                    funds,
+                                    positions: response.data.unwrap_or_default(),
+                                }));
+                            }
+                            Err(e) => log::warn!("dashboard: positionbook failed: {}", e),
+                        }
+                    }
+                    _ = order_timer.tick() => match client.orderbook().await {
+                        Ok(response) => {
+                            let orders = response.data.and_then(|d| d.orders).unwrap_or_default();
+                            for event in order_events(&mut seen, &orders) {
+                                feed.publish(DashboardEvent::Order(event));
+                            }
+                        }
+                        Err(e) => log::warn!("dashboard: orderbook failed: {}", e),
+                    },
+                }
+            }
+        })
+    }
+}
@@ -1,10 +1,20 @@
 //! Data API module for OpenAlgo.
 
+use crate::bulk;
+use crate::calendar::{ist_date, MarketCalendar};
+use crate::candles::{interval_seconds, merge_candles, CandleValidationReport, LiveCandles};
 use crate::client::{OpenAlgoClient, OpenAlgoError};
+use crate::interval::Interval;
+use crate::options::{OptionType, ResolvedOption, StrikeResolver};
+use crate::price::Price;
+use crate::symbols::{resolve_index, SymbolMap};
 use crate::types::*;
 use std::sync::Arc;
+use std::time::Duration;
+use web_time::{SystemTime, UNIX_EPOCH};
 
 /// Data API client
+#[derive(Clone)]
 pub struct DataAPI {
     client: Arc<OpenAlgoClient>,
 }
@@ -22,7 +32,7 @@ impl DataAPI {
         exchange: &str,
     ) -> Result<QuotesResponse, OpenAlgoError> {
         let request = QuotesRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             symbol: symbol.to_string(),
             exchange: exchange.to_string(),
         };
@@ -30,6 +40,72 @@ impl DataAPI {
         self.client.post("quotes", &request).await
     }
 
+    /// Get a quote with a GET request and query parameters
+    ///
+    /// Same response as `quotes`, but cacheable by HTTP proxies and easy to
+    /// reproduce with curl. The API key travels in the URL, so avoid it where
+    /// URLs are logged.
+    pub async fn quotes_get(&self, symbol: &str, exchange: &str) -> Result<QuotesResponse, OpenAlgoError> {
+        let apikey = self.client.api_key();
+        let apikey = apikey.expose_secret();
+        self.client
+            .get("quotes", &[("apikey", apikey), ("symbol", symbol), ("exchange", exchange)])
+            .await
+    }
+
+    /// Get the last traded price for a symbol
+    pub async fn ltp(&self, symbol: &str, exchange: &str) -> Result<Price, OpenAlgoError> {
+        let quote = self.quote_data(symbol, exchange).await?;
+        quote
+            .ltp
+            .ok_or_else(|| missing_field("LTP", symbol, exchange))
+    }
+
+    /// Get the bid/ask spread for a symbol
+    pub async fn bid_ask_spread(&self, symbol: &str, exchange: &str) -> Result<Price, OpenAlgoError> {
+        let quote = self.quote_data(symbol, exchange).await?;
+        quote
+            .bid_ask_spread()
+            .ok_or_else(|| missing_field("bid/ask", symbol, exchange))
+    }
+
+    /// Get the percentage change from previous close for a symbol
+    pub async fn change_percent(&self, symbol: &str, exchange: &str) -> Result<f64, OpenAlgoError> {
+        let quote = self.quote_data(symbol, exchange).await?;
+        quote
+            .change_percent()
+            .ok_or_else(|| missing_field("LTP/previous close", symbol, exchange))
+    }
+
+    /// Fetch quotes and unwrap the data, turning API failures into errors
+    async fn quote_data(&self, symbol: &str, exchange: &str) -> Result<QuotesData, OpenAlgoError> {
+        let response = self.quotes(symbol, exchange).await?;
+        response.data.ok_or_else(|| {
+            OpenAlgoError::ApiError(
+                response
+                    .message
+                    .unwrap_or_else(|| format!("No quote data for {}:{}", exchange, symbol))
+                    .into(),
+            )
+        })
+    }
+
+    /// Get the spot quote of an index by common name (e.g. "NIFTY", "Bank Nifty", "SENSEX")
+    pub async fn index_quote(&self, name: &str) -> Result<QuotesResponse, OpenAlgoError> {
+        let (symbol, exchange) = resolve_index(name)
+            .ok_or_else(|| OpenAlgoError::ApiError(format!("Unknown index: {}", name).into()))?;
+
+        self.quotes(symbol, exchange).await
+    }
+
+    /// Get the spot LTP of an index by common name
+    pub async fn index_ltp(&self, name: &str) -> Result<Price, OpenAlgoError> {
+        let (symbol, exchange) = resolve_index(name)
+            .ok_or_else(|| OpenAlgoError::ApiError(format!("Unknown index: {}", name).into()))?;
+
+        self.ltp(symbol, exchange).await
+    }
+
     /// Get quotes for multiple symbols (simplified API with tuples)
     pub async fn multi_quotes(
         &self,
@@ -41,7 +117,7 @@ impl DataAPI {
             .collect();
 
         let request = MultiQuotesRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             symbols: symbols_vec,
         };
 
@@ -55,7 +131,7 @@ impl DataAPI {
         exchange: &str,
     ) -> Result<DepthResponse, OpenAlgoError> {
         let request = DepthRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             symbol: symbol.to_string(),
             exchange: exchange.to_string(),
         };
@@ -70,8 +146,9 @@ impl DataAPI {
         exchange: &str,
         interval: &str,
     ) -> Result<serde_json::Value, OpenAlgoError> {
+        self.check_interval_if_enabled(interval).await?;
         let request = HistoryRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             symbol: symbol.to_string(),
             exchange: exchange.to_string(),
             interval: interval.to_string(),
@@ -91,8 +168,9 @@ impl DataAPI {
         start_date: &str,
         end_date: &str,
     ) -> Result<serde_json::Value, OpenAlgoError> {
+        self.check_interval_if_enabled(interval).await?;
         let request = HistoryRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             symbol: symbol.to_string(),
             exchange: exchange.to_string(),
             interval: interval.to_string(),
@@ -103,15 +181,142 @@ impl DataAPI {
         self.client.post("history", &request).await
     }
 
+    /// Get historical candles as typed data (including OI for derivatives)
+    ///
+    /// # Arguments
+    ///
+    /// * `start_date` / `end_date` - Optional date range (YYYY-MM-DD format)
+    pub async fn history_candles(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        interval: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> Result<HistoryResponse, OpenAlgoError> {
+        self.check_interval_if_enabled(interval).await?;
+        let request = HistoryRequest {
+            apikey: self.client.api_key(),
+            symbol: symbol.to_string(),
+            exchange: exchange.to_string(),
+            interval: interval.to_string(),
+            start_date: start_date.map(|d| d.to_string()),
+            end_date: end_date.map(|d| d.to_string()),
+        };
+
+        self.client.post("history", &request).await
+    }
+
+    /// Refetch the gap ranges found by `validate_candles` and merge them in
+    ///
+    /// Returns the merged, de-duplicated series sorted by timestamp.
+    pub async fn refetch_gaps(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        interval: &str,
+        candles: Vec<HistoryCandle>,
+        report: &CandleValidationReport,
+    ) -> Result<Vec<HistoryCandle>, OpenAlgoError> {
+        let mut series = candles;
+        for (after, before, _) in report.gaps() {
+            let start_date = ist_date(after);
+            let end_date = ist_date(before);
+            let response = self
+                .history_candles(symbol, exchange, interval, Some(&start_date), Some(&end_date))
+                .await?;
+            series.extend(response.data.unwrap_or_default());
+        }
+
+        Ok(merge_candles(series))
+    }
+
+    /// Fetch at least `bars` recent completed candles to warm up a live series
+    ///
+    /// Enough trading days are requested to cover `bars` bars of the NSE
+    /// session, plus margin for weekends and holidays. The returned series
+    /// keeps the latest `bars` completed candles and continues from live
+    /// ticks; a bar still forming is extended rather than duplicated.
+    pub async fn warm_up(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        interval: &str,
+        bars: usize,
+    ) -> Result<LiveCandles, OpenAlgoError> {
+        let step = interval_seconds(interval)
+            .filter(|step| *step < 86_400)
+            .ok_or_else(|| OpenAlgoError::ConfigError(format!("warm-up needs an intraday interval, not {}", interval)))?;
+        let calendar = MarketCalendar::nse();
+        let bars_per_day = ((calendar.close_secs - calendar.open_secs) / step).max(1) as usize;
+        let trading_days = bars.div_ceil(bars_per_day) + 1;
+        let calendar_days = (trading_days * 7 / 5 + 4) as i64;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+        let start_date = ist_date(now - calendar_days * 86_400);
+        let end_date = ist_date(now);
+        let response = self
+            .history_candles(symbol, exchange, interval, Some(&start_date), Some(&end_date))
+            .await?;
+        let mut series = LiveCandles::new(interval, response.data.unwrap_or_default(), now)
+            .expect("interval was checked to be intraday");
+        series.keep_last(bars);
+        Ok(series)
+    }
+
     /// Get available intervals
     pub async fn intervals(&self) -> Result<IntervalsResponse, OpenAlgoError> {
         let request = IntervalsRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
         };
 
         self.client.post("intervals", &request).await
     }
 
+    /// Get the supported intervals with a GET request
+    pub async fn intervals_get(&self) -> Result<IntervalsResponse, OpenAlgoError> {
+        let apikey = self.client.api_key();
+        self.client.get("intervals", &[("apikey", apikey.expose_secret())]).await
+    }
+
+    /// Intervals the server supports, shortest first, fetched once and cached
+    pub async fn supported_intervals(&self) -> Result<Vec<Interval>, OpenAlgoError> {
+        if let Some(intervals) = self.client.cached_intervals() {
+            return Ok(intervals);
+        }
+        let response = self.intervals().await?;
+        let data = response.data.ok_or_else(|| {
+            OpenAlgoError::ApiError(
+                response
+                    .message
+                    .unwrap_or_else(|| "Intervals response has no data".to_string())
+                    .into(),
+            )
+        })?;
+        let intervals = data.intervals();
+        self.client.cache_intervals(intervals.clone());
+        Ok(intervals)
+    }
+
+    /// Parse an interval and check that the server supports it
+    pub async fn check_interval(&self, interval: &str) -> Result<Interval, OpenAlgoError> {
+        let supported = self.supported_intervals().await?;
+        match interval.parse::<Interval>() {
+            Ok(parsed) if supported.contains(&parsed) => Ok(parsed),
+            _ => Err(OpenAlgoError::UnsupportedInterval {
+                interval: interval.to_string(),
+                supported: supported.iter().map(Interval::to_string).collect(),
+            }),
+        }
+    }
+
+    async fn check_interval_if_enabled(&self, interval: &str) -> Result<(), OpenAlgoError> {
+        if self.client.validates_intervals() {
+            self.check_interval(interval).await?;
+        }
+        Ok(())
+    }
+
     /// Get option chain
     pub async fn option_chain(
         &self,
@@ -120,7 +325,7 @@ impl DataAPI {
         expiry_date: &str,
     ) -> Result<OptionChainResponse, OpenAlgoError> {
         let request = OptionChainRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             underlying: underlying.to_string(),
             exchange: exchange.to_string(),
             expiry_date: expiry_date.to_string(),
@@ -139,7 +344,7 @@ impl DataAPI {
         strike_count: i32,
     ) -> Result<OptionChainResponse, OpenAlgoError> {
         let request = OptionChainRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             underlying: underlying.to_string(),
             exchange: exchange.to_string(),
             expiry_date: expiry_date.to_string(),
@@ -156,7 +361,7 @@ impl DataAPI {
         exchange: &str,
     ) -> Result<SymbolResponse, OpenAlgoError> {
         let request = SymbolRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             symbol: symbol.to_string(),
             exchange: exchange.to_string(),
         };
@@ -164,6 +369,104 @@ impl DataAPI {
         self.client.post("symbol", &request).await
     }
 
+    /// Symbol metadata, served from the in-memory cache when fresh
+    ///
+    /// Lot size, tick size and token rarely change, so repeated lookups by
+    /// execution and validation code skip the round-trip. Entries expire
+    /// after `DEFAULT_SYMBOL_TTL` unless changed with `set_symbol_cache_ttl`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::transport::{Transport, TransportFuture};
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// /// Counts symbol lookups
+    /// struct Counting(AtomicU32);
+    ///
+    /// impl Transport for Counting {
+    ///     fn post_json<'a>(&'a self, _url: &'a str, _body: String) -> TransportFuture<'a> {
+    ///         self.0.fetch_add(1, Ordering::SeqCst);
+    ///         Box::pin(async { Ok(r#"{"status":"success","data":{"symbol":"NIFTY30DEC25FUT","lotsize":75}}"#.to_string()) })
+    ///     }
+    /// }
+    ///
+    /// let transport = Arc::new(Counting(AtomicU32::new(0)));
+    /// let client = openalgo::OpenAlgo::builder("test-key").transport(transport.clone()).build();
+    /// tokio_test::block_on(async {
+    ///     client.data.symbol_cached("NIFTY30DEC25FUT", "NFO").await.unwrap();
+    ///     client.data.symbol_cached("NIFTY30DEC25FUT", "NFO").await.unwrap();
+    ///     assert_eq!(transport.0.load(Ordering::SeqCst), 1);
+    ///
+    ///     client.data.invalidate_symbol("NIFTY30DEC25FUT", "NFO");
+    ///     client.data.symbol_cached("NIFTY30DEC25FUT", "NFO").await.unwrap();
+    ///     assert_eq!(transport.0.load(Ordering::SeqCst), 2);
+    /// });
+    /// ```
+    pub async fn symbol_cached(&self, symbol: &str, exchange: &str) -> Result<SymbolData, OpenAlgoError> {
+        let cache = self.client.symbol_cache();
+        if let Some(data) = cache.get(symbol, exchange) {
+            return Ok(data);
+        }
+        let response = self.symbol(symbol, exchange).await?;
+        let data = response.data.ok_or_else(|| {
+            OpenAlgoError::ApiError(
+                response
+                    .message
+                    .unwrap_or_else(|| format!("Unknown symbol: {} ({})", symbol, exchange))
+                    .into(),
+            )
+        })?;
+        cache.insert(symbol, exchange, data.clone());
+        Ok(data)
+    }
+
+    /// Fetch and cache metadata for many instruments, e.g. a watchlist, at most `concurrency` at a time
+    ///
+    /// Returns the instruments that could not be loaded.
+    pub async fn preload_symbols(
+        &self,
+        instruments: &[WsInstrument],
+        concurrency: usize,
+    ) -> Vec<(WsInstrument, OpenAlgoError)> {
+        bulk::run(instruments.iter().cloned(), concurrency, |i| async move {
+            self.symbol_cached(&i.symbol, &i.exchange).await
+        })
+        .await
+        .into_iter()
+        .filter_map(|(instrument, result)| result.err().map(|e| (instrument, e)))
+        .collect()
+    }
+
+    /// Child order quantities for `total_qty`, each within the symbol's freeze quantity
+    ///
+    /// Uses the cached lot size and freeze quantity of the symbol; quantities
+    /// that are not whole lots fail with `OpenAlgoError::QuantityError`.
+    pub async fn split_by_freeze_qty(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        total_qty: i64,
+    ) -> Result<Vec<i64>, OpenAlgoError> {
+        let info = self.symbol_cached(symbol, exchange).await?;
+        Ok(info.split_quantity(total_qty)?)
+    }
+
+    /// Drop the cached metadata of one symbol
+    pub fn invalidate_symbol(&self, symbol: &str, exchange: &str) {
+        self.client.symbol_cache().invalidate(symbol, exchange);
+    }
+
+    /// Drop all cached symbol metadata
+    pub fn clear_symbol_cache(&self) {
+        self.client.symbol_cache().clear();
+    }
+
+    /// Set how long symbol metadata is cached
+    pub fn set_symbol_cache_ttl(&self, ttl: Duration) {
+        self.client.symbol_cache().set_ttl(ttl);
+    }
+
     /// Search symbols
     pub async fn search(
         &self,
@@ -171,14 +474,56 @@ impl DataAPI {
         exchange: &str,
     ) -> Result<SearchResponse, OpenAlgoError> {
         let request = SearchRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
+            query: query.to_string(),
+            exchange: exchange.to_string(),
+            filters: SearchFilters::default(),
+        };
+
+        self.client.post("search", &request).await
+    }
+
+    /// Search symbols with instrument type, expiry and paging filters
+    ///
+    /// Filters that are not set are left out of the request.
+    pub async fn search_filtered(
+        &self,
+        query: &str,
+        exchange: &str,
+        filters: &SearchFilters,
+    ) -> Result<SearchResponse, OpenAlgoError> {
+        let request = SearchRequest {
+            apikey: self.client.api_key(),
             query: query.to_string(),
             exchange: exchange.to_string(),
+            filters: filters.clone(),
         };
 
         self.client.post("search", &request).await
     }
 
+    /// One page of search results
+    ///
+    /// Use `next_offset()` on the page to request the next one.
+    pub async fn search_page(
+        &self,
+        query: &str,
+        exchange: &str,
+        filters: &SearchFilters,
+    ) -> Result<SearchResultPage, OpenAlgoError> {
+        let response = self.search_filtered(query, exchange, filters).await?;
+        Ok(SearchResultPage::from_response(response, filters))
+    }
+
+    /// Search for symbols with a GET request and query parameters
+    pub async fn search_get(&self, query: &str, exchange: &str) -> Result<SearchResponse, OpenAlgoError> {
+        let apikey = self.client.api_key();
+        let apikey = apikey.expose_secret();
+        self.client
+            .get("search", &[("apikey", apikey), ("query", query), ("exchange", exchange)])
+            .await
+    }
+
     /// Get option symbol
     pub async fn option_symbol(
         &self,
@@ -189,7 +534,7 @@ impl DataAPI {
         option_type: &str,
     ) -> Result<OptionSymbolResponse, OpenAlgoError> {
         let request = OptionSymbolRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             underlying: underlying.to_string(),
             exchange: exchange.to_string(),
             expiry_date: expiry_date.to_string(),
@@ -200,6 +545,62 @@ impl DataAPI {
         self.client.post("optionsymbol", &request).await
     }
 
+    /// Fetch the option chain of an expiry once and build a local strike resolver
+    pub async fn strike_resolver(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_date: &str,
+    ) -> Result<StrikeResolver, OpenAlgoError> {
+        let chain = self.option_chain(underlying, exchange, expiry_date).await?;
+        StrikeResolver::from_chain(underlying, exchange, expiry_date, &chain)
+    }
+
+    /// Resolve an ATM/offset option locally, calling `optionsymbol` only when the resolver cannot
+    ///
+    /// # Arguments
+    ///
+    /// * `resolver` - Resolver for the underlying and expiry
+    /// * `offset` - Strikes from ATM; positive is OTM, negative is ITM
+    /// * `option_type` - Call or put
+    pub async fn resolve_option(
+        &self,
+        resolver: &StrikeResolver,
+        offset: i32,
+        option_type: OptionType,
+    ) -> Result<ResolvedOption, OpenAlgoError> {
+        if let Some(resolved) = resolver.resolve(offset, option_type) {
+            return Ok(resolved);
+        }
+
+        let response = self
+            .option_symbol(
+                &resolver.underlying,
+                &resolver.exchange,
+                &resolver.expiry_date,
+                &offset.to_string(),
+                option_type.as_str(),
+            )
+            .await?;
+        let symbol = response.symbol.ok_or_else(|| {
+            OpenAlgoError::ApiError(
+                response
+                    .message
+                    .unwrap_or_else(|| format!("No {} option at offset {}", option_type.as_str(), offset))
+                    .into(),
+            )
+        })?;
+        Ok(ResolvedOption {
+            symbol,
+            exchange: response
+                .exchange
+                .unwrap_or_else(|| crate::futures::derivatives_exchange(&resolver.exchange).to_string()),
+            strike: None,
+            lotsize: response.lotsize,
+            tick_size: response.tick_size,
+        })
+    }
+
     /// Get synthetic future price
     pub async fn synthetic_future(
         &self,
@@ -208,7 +609,7 @@ impl DataAPI {
         expiry_date: &str,
     ) -> Result<SyntheticFutureResponse, OpenAlgoError> {
         let request = SyntheticFutureRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             underlying: underlying.to_string(),
             exchange: exchange.to_string(),
             expiry_date: expiry_date.to_string(),
@@ -227,7 +628,7 @@ impl DataAPI {
         underlying_exchange: &str,
     ) -> Result<OptionGreeksResponse, OpenAlgoError> {
         let request = OptionGreeksRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             symbol: symbol.to_string(),
             exchange: exchange.to_string(),
             interest_rate,
@@ -246,7 +647,7 @@ impl DataAPI {
         instrumenttype: &str,
     ) -> Result<ExpiryResponse, OpenAlgoError> {
         let request = ExpiryRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             symbol: symbol.to_string(),
             exchange: exchange.to_string(),
             instrumenttype: instrumenttype.to_string(),
@@ -261,10 +662,32 @@ impl DataAPI {
         exchange: &str,
     ) -> Result<InstrumentsResponse, OpenAlgoError> {
         let request = InstrumentsRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             exchange: exchange.to_string(),
         };
 
         self.client.post("instruments", &request).await
     }
+
+    /// Fetch the instruments of each exchange and build a broker symbol map
+    pub async fn symbol_map(&self, exchanges: &[&str]) -> Result<SymbolMap, OpenAlgoError> {
+        let mut map = SymbolMap::default();
+        for exchange in exchanges {
+            let response = self.instruments(exchange).await?;
+            let instruments = response.data.ok_or_else(|| {
+                OpenAlgoError::ApiError(
+                    response
+                        .message
+                        .unwrap_or_else(|| format!("No instruments for {}", exchange))
+                        .into(),
+                )
+            })?;
+            map.extend(&instruments);
+        }
+        Ok(map)
+    }
+}
+
+fn missing_field(field: &str, symbol: &str, exchange: &str) -> OpenAlgoError {
+    OpenAlgoError::ApiError(format!("No {} in quote for {}:{}", field, exchange, symbol).into())
 }
@@ -0,0 +1,230 @@
+//! Lenient deserializers for OpenAlgo responses.
+//!
+//! Broker adapters behind OpenAlgo disagree on whether quantities, prices and
+//! P&L are JSON numbers or strings. These helpers accept either, so one
+//! adapter's quirk does not fail the whole response. They also work on your
+//! own types, e.g. for `post_custom` responses.
+//!
+//! # Example
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Fill {
+//!     #[serde(deserialize_with = "openalgo::de::number")]
+//!     quantity: i64,
+//!     #[serde(default, deserialize_with = "openalgo::de::option_number")]
+//!     price: Option<f64>,
+//!     #[serde(default, deserialize_with = "openalgo::de::option_string")]
+//!     orderid: Option<String>,
+//! }
+//!
+//! let fill: Fill = serde_json::from_str(r#"{"quantity": "50", "price": 101.5, "orderid": 2403}"#).unwrap();
+//! assert_eq!(fill.quantity, 50);
+//! assert_eq!(fill.price, Some(101.5));
+//! assert_eq!(fill.orderid.as_deref(), Some("2403"));
+//!
+//! let fill: Fill = serde_json::from_str(r#"{"quantity": 50.0, "price": ""}"#).unwrap();
+//! assert_eq!(fill.price, None);
+//! ```
+
+use serde::de::{Deserialize, DeserializeOwned, Deserializer, Error};
+use serde_json::Value;
+use std::cell::Cell;
+use std::collections::HashMap;
+
+thread_local! {
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Number types the lenient deserializers can produce
+pub trait Numeric: Sized {
+    fn from_i64(value: i64) -> Option<Self>;
+    fn from_f64(value: f64) -> Option<Self>;
+
+    /// Parse a decimal string without separators
+    fn from_str(value: &str) -> Option<Self> {
+        match value.parse::<i64>() {
+            Ok(i) => Self::from_i64(i),
+            Err(_) => value.parse::<f64>().ok().and_then(Self::from_f64),
+        }
+    }
+}
+
+impl Numeric for f64 {
+    fn from_i64(value: i64) -> Option<Self> {
+        Some(value as f64)
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(value)
+    }
+}
+
+macro_rules! integer_numeric {
+    ($($t:ty),*) => {$(
+        impl Numeric for $t {
+            fn from_i64(value: i64) -> Option<Self> {
+                <$t>::try_from(value).ok()
+            }
+
+            fn from_f64(value: f64) -> Option<Self> {
+                if value.fract() == 0.0 && value >= <$t>::MIN as f64 && value <= <$t>::MAX as f64 {
+                    Some(value as $t)
+                } else {
+                    None
+                }
+            }
+        }
+    )*};
+}
+
+integer_numeric!(i32, i64, u32, u64);
+
+#[cfg(feature = "decimal")]
+impl Numeric for rust_decimal::Decimal {
+    fn from_i64(value: i64) -> Option<Self> {
+        Some(value.into())
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        rust_decimal::prelude::FromPrimitive::from_f64(value)
+    }
+
+    // Parsed from the text so 0.05 stays exactly 0.05
+    fn from_str(value: &str) -> Option<Self> {
+        value
+            .parse()
+            .ok()
+            .or_else(|| Self::from_scientific(value).ok())
+    }
+}
+
+/// A number given as a JSON number or a numeric string
+pub fn number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Numeric,
+{
+    option_number(deserializer)?.ok_or_else(|| D::Error::custom("expected a number, found null or an empty string"))
+}
+
+/// An optional number; `null` and empty strings become `None`
+pub fn option_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Numeric,
+{
+    let value = Value::deserialize(deserializer)?;
+    let parsed = match &value {
+        Value::Null => return Ok(None),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => T::from_i64(i),
+            None => T::from_str(&n.to_string()),
+        },
+        Value::String(s) => {
+            let s = s.trim().replace(',', "");
+            if s.is_empty() {
+                return Ok(None);
+            }
+            T::from_str(&s)
+        }
+        _ => None,
+    };
+    parsed
+        .map(Some)
+        .ok_or_else(|| D::Error::custom(format!("invalid number: {}", value)))
+}
+
+/// A string given as a JSON string, number or boolean
+pub fn string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    option_string(deserializer)?.ok_or_else(|| D::Error::custom("expected a string, found null"))
+}
+
+/// An optional string given as a JSON string, number or boolean
+pub fn option_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        Value::String(s) => Ok(Some(s)),
+        Value::Number(n) => Ok(Some(n.to_string())),
+        Value::Bool(b) => Ok(Some(b.to_string())),
+        other => Err(D::Error::custom(format!("expected a string, found {}", other))),
+    }
+}
+
+/// Unix seconds given as a JSON number, a numeric string or a date-time string
+///
+/// Date-time strings are anything `calendar::parse_timestamp` accepts, such
+/// as RFC 3339 (`2025-04-01T09:15:00+05:30`) or IST local time.
+///
+/// # Example
+/// ```rust
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Bar {
+///     #[serde(deserialize_with = "openalgo::de::timestamp")]
+///     timestamp: i64,
+/// }
+///
+/// let bar: Bar = serde_json::from_str(r#"{"timestamp": "2025-04-01T09:15:00+05:30"}"#).unwrap();
+/// assert_eq!(bar.timestamp, 1743479100);
+/// let bar: Bar = serde_json::from_str(r#"{"timestamp": 1743479100}"#).unwrap();
+/// assert_eq!(bar.timestamp, 1743479100);
+/// ```
+pub fn timestamp<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(s) => crate::calendar::parse_timestamp(&s)
+            .map(|time| time.timestamp())
+            .ok_or_else(|| D::Error::custom(format!("invalid timestamp: {:?}", s))),
+        other => number(other).map_err(D::Error::custom),
+    }
+}
+
+/// Fields a response type does not model, collected into its `extra` map
+///
+/// Inside `from_str_strict` any such field is an error instead.
+pub fn extra<'de, D>(deserializer: D) -> Result<HashMap<String, Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let extra = HashMap::<String, Value>::deserialize(deserializer)?;
+    if STRICT.with(Cell::get) && !extra.is_empty() {
+        let mut fields: Vec<&str> = extra.keys().map(String::as_str).collect();
+        fields.sort_unstable();
+        return Err(D::Error::custom(format!("unknown field(s): {}", fields.join(", "))));
+    }
+    Ok(extra)
+}
+
+/// Deserialize a response, rejecting fields the SDK types do not model
+///
+/// Used by clients built with `strict(true)` and by contract tests; plain
+/// `serde_json::from_str` keeps unknown fields in `extra`.
+///
+/// # Example
+/// ```rust
+/// use openalgo::types::OrderResponse;
+///
+/// let body = r#"{"status": "success", "orderid": "2403", "client_id": "AB1"}"#;
+/// let response: OrderResponse = serde_json::from_str(body).unwrap();
+/// assert!(response.extra.contains_key("client_id"));
+///
+/// let error = openalgo::de::from_str_strict::<OrderResponse>(body).unwrap_err();
+/// assert!(error.to_string().contains("client_id"));
+/// ```
+pub fn from_str_strict<T: DeserializeOwned>(text: &str) -> serde_json::Result<T> {
+    let previous = STRICT.with(|strict| strict.replace(true));
+    let result = serde_json::from_str(text);
+    STRICT.with(|strict| strict.set(previous));
+    result
+}
@@ -0,0 +1,141 @@
+//! Market depth analytics for OpenAlgo.
+//!
+//! Order-book imbalance, microprice and cumulative depth for REST depth
+//! snapshots and live WebSocket depth, plus a tracker holding the latest
+//! live book per instrument.
+
+use crate::price::{self, Price};
+use crate::types::*;
+use crate::websocket::WsData;
+use std::collections::HashMap;
+
+/// Microstructure metrics computed from bid/ask depth levels
+///
+/// Empty levels (zero price or quantity) are ignored.
+pub trait DepthMetrics {
+    /// Bid levels, best first
+    fn bid_levels(&self) -> &[DepthLevel];
+
+    /// Ask levels, best first
+    fn ask_levels(&self) -> &[DepthLevel];
+
+    /// Total bid quantity over the best `levels` levels
+    fn cumulative_bid_quantity(&self, levels: usize) -> i64 {
+        active_levels(self.bid_levels()).take(levels).map(|l| l.quantity).sum()
+    }
+
+    /// Total ask quantity over the best `levels` levels
+    fn cumulative_ask_quantity(&self, levels: usize) -> i64 {
+        active_levels(self.ask_levels()).take(levels).map(|l| l.quantity).sum()
+    }
+
+    /// Order-book imbalance over the best `levels` levels, in [-1, 1]
+    ///
+    /// Positive values mean more resting bid quantity than ask quantity.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::{DepthData, DepthMetrics};
+    /// let depth: DepthData = serde_json::from_str(r#"{
+    ///     "bids": [{"price": 100.0, "quantity": 300}],
+    ///     "asks": [{"price": 100.5, "quantity": 100}]
+    /// }"#).unwrap();
+    /// assert_eq!(depth.imbalance(5), Some(0.5));
+    /// assert_eq!(depth.microprice(), Some(100.375));
+    /// ```
+    fn imbalance(&self, levels: usize) -> Option<f64> {
+        let bid = self.cumulative_bid_quantity(levels) as f64;
+        let ask = self.cumulative_ask_quantity(levels) as f64;
+        if bid + ask <= 0.0 {
+            return None;
+        }
+        Some((bid - ask) / (bid + ask))
+    }
+
+    /// Best bid and best ask midpoint
+    fn mid_price(&self) -> Option<f64> {
+        let bid = active_levels(self.bid_levels()).next()?;
+        let ask = active_levels(self.ask_levels()).next()?;
+        Some((price::to_f64(bid.price) + price::to_f64(ask.price)) / 2.0)
+    }
+
+    /// Quantity-weighted microprice from the top of book
+    fn microprice(&self) -> Option<f64> {
+        let bid = active_levels(self.bid_levels()).next()?;
+        let ask = active_levels(self.ask_levels()).next()?;
+        let total = (bid.quantity + ask.quantity) as f64;
+        let (bid_price, ask_price) = (price::to_f64(bid.price), price::to_f64(ask.price));
+        Some((bid_price * ask.quantity as f64 + ask_price * bid.quantity as f64) / total)
+    }
+}
+
+fn active_levels(levels: &[DepthLevel]) -> impl Iterator<Item = &DepthLevel> {
+    levels.iter().filter(|l| l.price > Price::default() && l.quantity > 0)
+}
+
+impl DepthMetrics for DepthData {
+    fn bid_levels(&self) -> &[DepthLevel] {
+        self.bids.as_deref().unwrap_or(&[])
+    }
+
+    fn ask_levels(&self) -> &[DepthLevel] {
+        self.asks.as_deref().unwrap_or(&[])
+    }
+}
+
+impl DepthMetrics for WsDepthData {
+    fn bid_levels(&self) -> &[DepthLevel] {
+        self.bids.as_deref().unwrap_or(&[])
+    }
+
+    fn ask_levels(&self) -> &[DepthLevel] {
+        self.asks.as_deref().unwrap_or(&[])
+    }
+}
+
+/// Latest live depth per instrument, fed from the WebSocket stream
+#[derive(Debug, Clone, Default)]
+pub struct OrderBookTracker {
+    books: HashMap<(String, String), WsDepthData>,
+}
+
+impl OrderBookTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a WebSocket event, returning true if a book was updated
+    pub fn update(&mut self, data: &WsData) -> bool {
+        match data {
+            WsData::Depth(depth) => self.update_depth(depth.clone()),
+            _ => false,
+        }
+    }
+
+    /// Store a depth snapshot, returning true if it identified an instrument
+    pub fn update_depth(&mut self, depth: WsDepthData) -> bool {
+        match (depth.exchange.clone(), depth.symbol.clone()) {
+            (Some(exchange), Some(symbol)) => {
+                self.books.insert((exchange, symbol), depth);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Latest book for an instrument
+    pub fn book(&self, exchange: &str, symbol: &str) -> Option<&WsDepthData> {
+        self.books.get(&(exchange.to_string(), symbol.to_string()))
+    }
+
+    /// Number of instruments with a book
+    pub fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    /// Whether no books have been received
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+}
@@ -0,0 +1,295 @@
+//! Human-readable output for key responses.
+//!
+//! `Display` on the orderbook, positionbook, holdings, option chain and
+//! quotes responses prints an aligned table (or the server message when there
+//! is no data); `to_table()` returns just the table.
+
+use crate::types::*;
+use std::fmt;
+
+/// Plain-text table with left-aligned text and right-aligned numbers
+struct Table {
+    headers: Vec<&'static str>,
+    numeric: Vec<bool>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Columns as `(header, numeric)` pairs
+    fn new(columns: &[(&'static str, bool)]) -> Self {
+        Self {
+            headers: columns.iter().map(|(h, _)| *h).collect(),
+            numeric: columns.iter().map(|(_, n)| *n).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    fn render(&self) -> String {
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .map(|r| r[i].chars().count())
+                    .chain([self.headers[i].len()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |cells: Vec<&str>| {
+            cells
+                .iter()
+                .enumerate()
+                .map(|(i, c)| match self.numeric[i] {
+                    true => format!("{:>w$}", c, w = widths[i]),
+                    false => format!("{:w$}", c, w = widths[i]),
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut out = line(self.headers.clone());
+        for row in &self.rows {
+            out.push('\n');
+            out.push_str(&line(row.iter().map(String::as_str).collect()));
+        }
+        out
+    }
+}
+
+fn text(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "-".to_string())
+}
+
+fn price<T: fmt::Display>(value: Option<T>) -> String {
+    value.map(|v| format!("{:.2}", v)).unwrap_or_else(|| "-".to_string())
+}
+
+fn count<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+/// The table, or the server message (or `empty`) when there are no rows
+fn write_table(f: &mut fmt::Formatter<'_>, table: Option<String>, message: &Option<String>, empty: &str) -> fmt::Result {
+    match (table, message) {
+        (Some(table), _) => f.write_str(&table),
+        (None, Some(message)) => f.write_str(message),
+        (None, None) => f.write_str(empty),
+    }
+}
+
+impl OrderbookResponse {
+    /// Orders as a text table, `None` when there are none
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::OrderbookResponse;
+    ///
+    /// let book: OrderbookResponse = serde_json::from_str(r#"{"status": "success", "data": {"orders": [
+    ///     {"orderid": "2403", "symbol": "RELIANCE", "exchange": "NSE", "action": "BUY",
+    ///      "quantity": "1", "price": 2500, "pricetype": "LIMIT", "product": "MIS", "order_status": "open"}
+    /// ]}}"#).unwrap();
+    /// let table = book.to_table().unwrap();
+    /// assert!(table.lines().nth(1).unwrap().starts_with("2403"));
+    /// println!("{}", book);
+    /// ```
+    pub fn to_table(&self) -> Option<String> {
+        let orders = self.data.as_ref()?.orders.as_ref().filter(|o| !o.is_empty())?;
+        let mut table = Table::new(&[
+            ("ORDER ID", false),
+            ("SYMBOL", false),
+            ("EXCHANGE", false),
+            ("ACTION", false),
+            ("QTY", true),
+            ("PRICE", true),
+            ("TRIGGER", true),
+            ("TYPE", false),
+            ("PRODUCT", false),
+            ("STATUS", false),
+            ("TIME", false),
+        ]);
+        for o in orders {
+            table.row(vec![
+                text(&o.orderid),
+                text(&o.symbol),
+                text(&o.exchange),
+                text(&o.action),
+                text(&o.quantity),
+                price(o.price),
+                price(o.trigger_price),
+                text(&o.pricetype),
+                text(&o.product),
+                text(&o.order_status),
+                text(&o.timestamp),
+            ]);
+        }
+        Some(table.render())
+    }
+}
+
+impl fmt::Display for OrderbookResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_table(f, self.to_table(), &self.message, "(no orders)")
+    }
+}
+
+impl PositionbookResponse {
+    /// Positions as a text table, `None` when there are none
+    pub fn to_table(&self) -> Option<String> {
+        let positions = self.data.as_ref().filter(|p| !p.is_empty())?;
+        let mut table = Table::new(&[
+            ("SYMBOL", false),
+            ("EXCHANGE", false),
+            ("PRODUCT", false),
+            ("QTY", true),
+            ("AVG PRICE", true),
+            ("LTP", true),
+            ("P&L", true),
+        ]);
+        for p in positions {
+            table.row(vec![
+                text(&p.symbol),
+                text(&p.exchange),
+                text(&p.product),
+                text(&p.quantity),
+                text(&p.average_price),
+                text(&p.ltp),
+                text(&p.pnl),
+            ]);
+        }
+        Some(table.render())
+    }
+}
+
+impl fmt::Display for PositionbookResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_table(f, self.to_table(), &self.message, "(no positions)")
+    }
+}
+
+impl HoldingsResponse {
+    /// Holdings as a text table with a totals line, `None` when there are none
+    pub fn to_table(&self) -> Option<String> {
+        let data = self.data.as_ref()?;
+        let holdings = data.holdings.as_ref().filter(|h| !h.is_empty())?;
+        let mut table = Table::new(&[
+            ("SYMBOL", false),
+            ("EXCHANGE", false),
+            ("PRODUCT", false),
+            ("QTY", true),
+            ("P&L", true),
+            ("P&L %", true),
+        ]);
+        for h in holdings {
+            table.row(vec![
+                text(&h.symbol),
+                text(&h.exchange),
+                text(&h.product),
+                count(h.quantity),
+                price(h.pnl),
+                price(h.pnlpercent),
+            ]);
+        }
+        let mut out = table.render();
+        if let Some(stats) = &data.statistics {
+            out.push_str(&format!(
+                "\nValue {}  Invested {}  P&L {} ({}%)",
+                price(stats.totalholdingvalue),
+                price(stats.totalinvvalue),
+                price(stats.totalprofitandloss),
+                price(stats.totalpnlpercentage),
+            ));
+        }
+        Some(out)
+    }
+}
+
+impl fmt::Display for HoldingsResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_table(f, self.to_table(), &self.message, "(no holdings)")
+    }
+}
+
+impl OptionChainResponse {
+    /// Calls and puts side by side around the strike, `None` when the chain is empty
+    pub fn to_table(&self) -> Option<String> {
+        let chain = self.chain.as_ref().filter(|c| !c.is_empty())?;
+        let mut table = Table::new(&[
+            ("CE OI", true),
+            ("CE VOL", true),
+            ("CE LTP", true),
+            ("STRIKE", true),
+            ("PE LTP", true),
+            ("PE VOL", true),
+            ("PE OI", true),
+        ]);
+        for s in chain {
+            let (ce, pe) = (s.ce.as_ref(), s.pe.as_ref());
+            let atm = self.atm_strike == Some(s.strike);
+            table.row(vec![
+                count(ce.and_then(|c| c.oi)),
+                count(ce.and_then(|c| c.volume)),
+                price(ce.and_then(|c| c.ltp)),
+                format!("{}{:.2}", if atm { "*" } else { "" }, s.strike),
+                price(pe.and_then(|p| p.ltp)),
+                count(pe.and_then(|p| p.volume)),
+                count(pe.and_then(|p| p.oi)),
+            ]);
+        }
+        let mut out = format!(
+            "{} {} spot {} (* ATM)\n",
+            text(&self.underlying),
+            text(&self.expiry_date),
+            price(self.underlying_ltp),
+        );
+        out.push_str(&table.render());
+        Some(out)
+    }
+}
+
+impl fmt::Display for OptionChainResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_table(f, self.to_table(), &self.message, "(empty option chain)")
+    }
+}
+
+impl QuotesResponse {
+    /// The quote as a one-row text table, `None` without data
+    pub fn to_table(&self) -> Option<String> {
+        let q = self.data.as_ref()?;
+        let mut table = Table::new(&[
+            ("LTP", true),
+            ("BID", true),
+            ("ASK", true),
+            ("OPEN", true),
+            ("HIGH", true),
+            ("LOW", true),
+            ("PREV CLOSE", true),
+            ("VOLUME", true),
+            ("OI", true),
+        ]);
+        table.row(vec![
+            price(q.ltp),
+            price(q.bid),
+            price(q.ask),
+            price(q.open),
+            price(q.high),
+            price(q.low),
+            price(q.prev_close),
+            count(q.volume),
+            count(q.oi),
+        ]);
+        Some(table.render())
+    }
+}
+
+impl fmt::Display for QuotesResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_table(f, self.to_table(), &self.message, "(no quote)")
+    }
+}
@@ -0,0 +1,314 @@
+//! End-of-day account report.
+//!
+//! `EodReport::fetch` collects the day's tradebook, positionbook and funds,
+//! prices every trade with a `ChargeSchedule`, and works out gross and net
+//! P&L. With the `xlsx` feature, `to_xlsx` renders it as a workbook with
+//! Trades, Positions, P&L Summary and Charges sheets, and `save` writes it
+//! to a file.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::charges::ChargeSchedule;
+//! use openalgo::eod::EodReport;
+//!
+//! # async fn example(client: openalgo::OpenAlgo) -> Result<(), Box<dyn std::error::Error>> {
+//! let report = EodReport::fetch(&client, &ChargeSchedule::default()).await?;
+//! println!("net P&L {:.2} after {:.2} charges", report.net_pnl(), report.charges.total.total());
+//! # #[cfg(feature = "xlsx")]
+//! report.save(format!("eod-{}.xlsx", report.date))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::calendar::ist_date;
+use crate::charges::{trade_turnover, ChargeSchedule, Charges, ChargesSummary, Segment};
+use crate::client::OpenAlgoError;
+use crate::types::{FundsData, PositionbookPosition, TradebookTrade};
+use crate::OpenAlgo;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// A trade with its estimated charges
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricedTrade {
+    pub trade: TradebookTrade,
+    pub turnover: Option<f64>,
+    /// Segment and charges, `None` when the trade has no turnover
+    pub charges: Option<(Segment, Charges)>,
+}
+
+/// One day's trades, positions, funds and charges
+#[derive(Debug, Clone, PartialEq)]
+pub struct EodReport {
+    /// Trading date (YYYY-MM-DD, IST)
+    pub date: String,
+    pub trades: Vec<PricedTrade>,
+    pub positions: Vec<PositionbookPosition>,
+    pub funds: Option<FundsData>,
+    pub charges: ChargesSummary,
+}
+
+impl EodReport {
+    /// Build a report from data already fetched
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::charges::ChargeSchedule;
+    /// use openalgo::eod::EodReport;
+    /// use openalgo::{PositionbookResponse, TradebookResponse};
+    ///
+    /// let trades: TradebookResponse = serde_json::from_str(r#"{"status": "success", "data": [
+    ///     {"orderid": "1", "symbol": "SBIN", "exchange": "NSE", "action": "BUY", "product": "MIS", "quantity": 100, "average_price": 800},
+    ///     {"orderid": "2", "symbol": "SBIN", "exchange": "NSE", "action": "SELL", "product": "MIS", "quantity": 100, "average_price": 805}
+    /// ]}"#).unwrap();
+    /// let positions: PositionbookResponse = serde_json::from_str(r#"{"status": "success", "data": [
+    ///     {"symbol": "SBIN", "exchange": "NSE", "product": "MIS", "quantity": "0", "average_price": "0", "ltp": "805", "pnl": "500"}
+    /// ]}"#).unwrap();
+    /// let report = EodReport::new(
+    ///     "2024-12-02",
+    ///     trades.data.unwrap(),
+    ///     positions.data.unwrap(),
+    ///     None,
+    ///     &ChargeSchedule::default(),
+    /// );
+    /// assert_eq!(report.gross_pnl(), 500.0);
+    /// assert!(report.net_pnl() < 500.0 && report.net_pnl() > 400.0);
+    /// ```
+    pub fn new(
+        date: impl Into<String>,
+        trades: Vec<TradebookTrade>,
+        positions: Vec<PositionbookPosition>,
+        funds: Option<FundsData>,
+        schedule: &ChargeSchedule,
+    ) -> Self {
+        let charges = ChargesSummary::from_trades(schedule, &trades);
+        let trades = trades
+            .into_iter()
+            .map(|trade| PricedTrade {
+                turnover: trade_turnover(&trade),
+                charges: schedule.estimate_trade(&trade),
+                trade,
+            })
+            .collect();
+        Self {
+            date: date.into(),
+            trades,
+            positions,
+            funds,
+            charges,
+        }
+    }
+
+    /// Fetch today's tradebook, positionbook and funds and build the report
+    pub async fn fetch(client: &OpenAlgo, schedule: &ChargeSchedule) -> Result<Self, OpenAlgoError> {
+        let (trades, positions, funds) = tokio::join!(client.tradebook(), client.positionbook(), client.funds());
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        Ok(Self::new(
+            ist_date(now),
+            trades?.data.unwrap_or_default(),
+            positions?.data.unwrap_or_default(),
+            funds?.data,
+            schedule,
+        ))
+    }
+
+    /// Sum of the positionbook P&L, before charges
+    pub fn gross_pnl(&self) -> f64 {
+        self.positions.iter().filter_map(|p| number(&p.pnl)).sum()
+    }
+
+    /// Gross P&L less the estimated charges
+    pub fn net_pnl(&self) -> f64 {
+        self.gross_pnl() - self.charges.total.total()
+    }
+
+    /// The report as an xlsx workbook
+    #[cfg(feature = "xlsx")]
+    pub fn to_xlsx(&self) -> std::io::Result<Vec<u8>> {
+        workbook::render(self).and_then(|mut w| w.save_to_buffer()).map_err(std::io::Error::other)
+    }
+
+    /// Write the xlsx workbook to `path`
+    #[cfg(all(feature = "xlsx", not(target_arch = "wasm32")))]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_xlsx()?)
+    }
+}
+
+fn number(value: &Option<String>) -> Option<f64> {
+    value.as_deref()?.trim().parse().ok()
+}
+
+#[cfg(feature = "xlsx")]
+mod workbook {
+    use super::{number, EodReport};
+    use crate::charges::Charges;
+    use crate::price;
+    use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+
+    const MONEY: &str = "#,##0.00";
+
+    struct Formats {
+        header: Format,
+        money: Format,
+        bold_money: Format,
+    }
+
+    pub(super) fn render(report: &EodReport) -> Result<Workbook, XlsxError> {
+        let formats = Formats {
+            header: Format::new().set_bold(),
+            money: Format::new().set_num_format(MONEY),
+            bold_money: Format::new().set_bold().set_num_format(MONEY),
+        };
+        let mut workbook = Workbook::new();
+        trades(workbook.add_worksheet().set_name("Trades")?, report, &formats)?;
+        positions(workbook.add_worksheet().set_name("Positions")?, report, &formats)?;
+        summary(workbook.add_worksheet().set_name("P&L Summary")?, report, &formats)?;
+        charges(workbook.add_worksheet().set_name("Charges")?, report, &formats)?;
+        Ok(workbook)
+    }
+
+    fn header(sheet: &mut Worksheet, columns: &[&str], formats: &Formats) -> Result<(), XlsxError> {
+        for (col, name) in columns.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, *name, &formats.header)?;
+        }
+        sheet.set_freeze_panes(1, 0)?;
+        Ok(())
+    }
+
+    fn text(sheet: &mut Worksheet, row: u32, col: u16, value: &Option<String>) -> Result<(), XlsxError> {
+        if let Some(value) = value {
+            sheet.write_string(row, col, value)?;
+        }
+        Ok(())
+    }
+
+    fn money(sheet: &mut Worksheet, row: u32, col: u16, value: Option<f64>, formats: &Formats) -> Result<(), XlsxError> {
+        if let Some(value) = value {
+            sheet.write_number_with_format(row, col, value, &formats.money)?;
+        }
+        Ok(())
+    }
+
+    /// A number when the text parses as one, else the text
+    fn numeric_text(sheet: &mut Worksheet, row: u32, col: u16, value: &Option<String>, formats: &Formats) -> Result<(), XlsxError> {
+        match number(value) {
+            Some(n) => money(sheet, row, col, Some(n), formats),
+            None => text(sheet, row, col, value),
+        }
+    }
+
+    fn trades(sheet: &mut Worksheet, report: &EodReport, formats: &Formats) -> Result<(), XlsxError> {
+        header(
+            sheet,
+            &[
+                "Time", "Order ID", "Symbol", "Exchange", "Product", "Action", "Quantity", "Average price", "Turnover",
+                "Segment", "Charges",
+            ],
+            formats,
+        )?;
+        for (i, priced) in report.trades.iter().enumerate() {
+            let row = i as u32 + 1;
+            let t = &priced.trade;
+            text(sheet, row, 0, &t.timestamp)?;
+            text(sheet, row, 1, &t.orderid)?;
+            text(sheet, row, 2, &t.symbol)?;
+            text(sheet, row, 3, &t.exchange)?;
+            text(sheet, row, 4, &t.product)?;
+            text(sheet, row, 5, &t.action)?;
+            if let Some(quantity) = t.quantity {
+                sheet.write_number(row, 6, quantity)?;
+            }
+            money(sheet, row, 7, t.average_price.map(price::to_f64), formats)?;
+            money(sheet, row, 8, priced.turnover, formats)?;
+            if let Some((segment, charges)) = &priced.charges {
+                sheet.write_string(row, 9, segment.label())?;
+                money(sheet, row, 10, Some(charges.total()), formats)?;
+            }
+        }
+        sheet.autofit();
+        Ok(())
+    }
+
+    fn positions(sheet: &mut Worksheet, report: &EodReport, formats: &Formats) -> Result<(), XlsxError> {
+        header(
+            sheet,
+            &["Symbol", "Exchange", "Product", "Quantity", "Average price", "LTP", "P&L"],
+            formats,
+        )?;
+        for (i, p) in report.positions.iter().enumerate() {
+            let row = i as u32 + 1;
+            text(sheet, row, 0, &p.symbol)?;
+            text(sheet, row, 1, &p.exchange)?;
+            text(sheet, row, 2, &p.product)?;
+            match number(&p.quantity) {
+                Some(quantity) => sheet.write_number(row, 3, quantity).map(|_| ())?,
+                None => text(sheet, row, 3, &p.quantity)?,
+            }
+            numeric_text(sheet, row, 4, &p.average_price, formats)?;
+            numeric_text(sheet, row, 5, &p.ltp, formats)?;
+            numeric_text(sheet, row, 6, &p.pnl, formats)?;
+        }
+        sheet.autofit();
+        Ok(())
+    }
+
+    fn summary(sheet: &mut Worksheet, report: &EodReport, formats: &Formats) -> Result<(), XlsxError> {
+        header(sheet, &["Item", "Value"], formats)?;
+        sheet.write_string(1, 0, "Date")?;
+        sheet.write_string(1, 1, &report.date)?;
+        sheet.write_string(2, 0, "Trades")?;
+        sheet.write_number(2, 1, report.trades.len() as f64)?;
+        let mut rows: Vec<(&str, Option<f64>)> = vec![
+            ("Turnover", Some(report.charges.turnover)),
+            ("Gross P&L", Some(report.gross_pnl())),
+            ("Estimated charges", Some(report.charges.total.total())),
+        ];
+        if let Some(funds) = &report.funds {
+            rows.push(("Realized M2M", number(&funds.m2mrealized)));
+            rows.push(("Unrealized M2M", number(&funds.m2munrealized)));
+            rows.push(("Available cash", number(&funds.availablecash)));
+            rows.push(("Utilised margin", number(&funds.utiliseddebits)));
+        }
+        let mut row = 3;
+        for (name, value) in rows {
+            sheet.write_string(row, 0, name)?;
+            money(sheet, row, 1, value, formats)?;
+            row += 1;
+        }
+        sheet.write_string_with_format(row, 0, "Net P&L", &formats.header)?;
+        sheet.write_number_with_format(row, 1, report.net_pnl(), &formats.bold_money)?;
+        sheet.autofit();
+        Ok(())
+    }
+
+    fn charges(sheet: &mut Worksheet, report: &EodReport, formats: &Formats) -> Result<(), XlsxError> {
+        let segments: Vec<_> = report.charges.by_segment.iter().collect();
+        let mut columns = vec!["Charge"];
+        columns.extend(segments.iter().map(|(s, _)| s.label()));
+        columns.push("Total");
+        header(sheet, &columns, formats)?;
+
+        let total_col = segments.len() as u16 + 1;
+        let write_line = |sheet: &mut Worksheet, row: u32, pick: &dyn Fn(&Charges) -> f64, format: &Format| -> Result<(), XlsxError> {
+            for (col, (_, charges)) in segments.iter().enumerate() {
+                sheet.write_number_with_format(row, col as u16 + 1, pick(charges), format)?;
+            }
+            sheet.write_number_with_format(row, total_col, pick(&report.charges.total), format)?;
+            Ok(())
+        };
+        let components = Charges::default().components();
+        for (i, (name, _)) in components.iter().enumerate() {
+            let row = i as u32 + 1;
+            sheet.write_string(row, 0, *name)?;
+            write_line(sheet, row, &|c: &Charges| c.components()[i].1, &formats.money)?;
+        }
+        let row = components.len() as u32 + 1;
+        sheet.write_string_with_format(row, 0, "Total", &formats.header)?;
+        write_line(sheet, row, &|c: &Charges| c.total(), &formats.bold_money)?;
+        sheet.autofit();
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+//! Execution quality analytics over the order journal.
+//!
+//! For every strategy and symbol, `ExecutionReport` gives the fill rate,
+//! the slippage of fills against the arrival price (the decision quote's
+//! mid, or its LTP), and the time from placement to fill. Slippage is signed
+//! so that positive is a cost: buying above or selling below arrival.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::execution::ExecutionReport;
+//! use openalgo::journal::{DecisionQuote, JournalEntry};
+//!
+//! let filled = JournalEntry {
+//!     orderid: "1".into(), strategy: "S".into(), symbol: "SBIN".into(), action: "BUY".into(),
+//!     quantity: 10, placed_at: 0, filled_at: Some(400), status: Some("complete".into()),
+//!     average_price: Some(800.8),
+//!     decision: Some(DecisionQuote { bid: Some(799.9), ask: Some(800.1), ..Default::default() }),
+//!     ..Default::default()
+//! };
+//! let rejected = JournalEntry { orderid: "2".into(), status: Some("rejected".into()), ..filled.clone() };
+//! let report = ExecutionReport::from_entries(&[filled, rejected]);
+//! let sbin = &report.rows[0];
+//! assert_eq!(sbin.fill_rate, Some(0.5));
+//! assert!((sbin.mean_slippage_bps.unwrap() - 10.0).abs() < 1e-9);
+//! assert!((sbin.slippage_cost - 8.0).abs() < 1e-9);
+//! assert_eq!(sbin.mean_time_to_fill_ms, Some(400.0));
+//! ```
+
+use crate::journal::JournalEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Slippage of a fill against the arrival price, in basis points
+///
+/// Positive when the fill was worse than arrival: above it for a buy, below
+/// it for a sell.
+///
+/// # Example
+/// ```rust
+/// use openalgo::execution::slippage_bps;
+/// assert!((slippage_bps("BUY", 100.0, 100.1) - 10.0).abs() < 1e-9);
+/// assert!((slippage_bps("SELL", 100.0, 100.1) + 10.0).abs() < 1e-9);
+/// ```
+pub fn slippage_bps(action: &str, arrival: f64, fill: f64) -> f64 {
+    side(action) * (fill - arrival) / arrival * 10_000.0
+}
+
+pub(crate) fn side(action: &str) -> f64 {
+    if action.eq_ignore_ascii_case("SELL") {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+/// Execution statistics of one strategy and symbol
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionStats {
+    pub strategy: String,
+    pub symbol: String,
+    /// Orders journaled
+    pub orders: usize,
+    /// Orders completed
+    pub filled: usize,
+    /// Orders rejected or cancelled
+    pub unfilled: usize,
+    /// Completed orders over finished ones; `None` while none has finished
+    pub fill_rate: Option<f64>,
+    /// Quantity-weighted slippage of fills with an arrival price, in bps
+    pub mean_slippage_bps: Option<f64>,
+    /// Money lost to slippage: quantity times price difference, summed
+    pub slippage_cost: f64,
+    pub mean_time_to_fill_ms: Option<f64>,
+    pub median_time_to_fill_ms: Option<f64>,
+    pub max_time_to_fill_ms: Option<i64>,
+}
+
+impl ExecutionStats {
+    fn from_entries(strategy: &str, symbol: &str, entries: &[&JournalEntry]) -> Self {
+        let filled: Vec<&&JournalEntry> = entries.iter().filter(|e| e.is_filled()).collect();
+        let unfilled = entries.iter().filter(|e| e.is_finished() && !e.is_filled()).count();
+
+        let mut weighted_bps = 0.0;
+        let mut slipped_quantity = 0.0;
+        let mut slippage_cost = 0.0;
+        for entry in &filled {
+            let arrival = entry.decision.as_ref().and_then(|d| d.arrival_price());
+            if let (Some(arrival), Some(fill)) = (arrival, entry.average_price) {
+                let quantity = entry.quantity as f64;
+                weighted_bps += slippage_bps(&entry.action, arrival, fill) * quantity;
+                slipped_quantity += quantity;
+                slippage_cost += side(&entry.action) * (fill - arrival) * quantity;
+            }
+        }
+
+        let mut times: Vec<i64> = filled.iter().filter_map(|e| e.time_to_fill()).collect();
+        times.sort_unstable();
+        let median = match times.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(times[n / 2] as f64),
+            n => Some((times[n / 2 - 1] + times[n / 2]) as f64 / 2.0),
+        };
+
+        Self {
+            strategy: strategy.to_string(),
+            symbol: symbol.to_string(),
+            orders: entries.len(),
+            filled: filled.len(),
+            unfilled,
+            fill_rate: (filled.len() + unfilled > 0).then(|| filled.len() as f64 / (filled.len() + unfilled) as f64),
+            mean_slippage_bps: (slipped_quantity > 0.0).then(|| weighted_bps / slipped_quantity),
+            slippage_cost,
+            mean_time_to_fill_ms: (!times.is_empty()).then(|| times.iter().sum::<i64>() as f64 / times.len() as f64),
+            median_time_to_fill_ms: median,
+            max_time_to_fill_ms: times.last().copied(),
+        }
+    }
+}
+
+/// Execution quality per strategy and symbol, with a total over all orders
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    /// One row per strategy and symbol, sorted by both
+    pub rows: Vec<ExecutionStats>,
+    /// All orders together; strategy and symbol are empty
+    pub total: ExecutionStats,
+}
+
+impl ExecutionReport {
+    /// Report over journal entries
+    pub fn from_entries(entries: &[JournalEntry]) -> Self {
+        let mut groups: BTreeMap<(&str, &str), Vec<&JournalEntry>> = BTreeMap::new();
+        for entry in entries {
+            groups
+                .entry((entry.strategy.as_str(), entry.symbol.as_str()))
+                .or_default()
+                .push(entry);
+        }
+        let all: Vec<&JournalEntry> = entries.iter().collect();
+        Self {
+            rows: groups
+                .iter()
+                .map(|((strategy, symbol), group)| ExecutionStats::from_entries(strategy, symbol, group))
+                .collect(),
+            total: ExecutionStats::from_entries("", "", &all),
+        }
+    }
+
+    /// Statistics of one strategy and symbol
+    pub fn stats(&self, strategy: &str, symbol: &str) -> Option<&ExecutionStats> {
+        self.rows.iter().find(|r| r.strategy == strategy && r.symbol == symbol)
+    }
+
+    /// Rows as CSV with a header line; missing values are empty cells
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::execution::ExecutionReport;
+    /// use openalgo::journal::JournalEntry;
+    ///
+    /// let entry = JournalEntry { strategy: "S".into(), symbol: "SBIN".into(), ..Default::default() };
+    /// let csv = ExecutionReport::from_entries(&[entry]).to_csv();
+    /// let mut lines = csv.lines();
+    /// assert!(lines.next().unwrap().starts_with("strategy,symbol,orders,filled"));
+    /// assert_eq!(lines.next(), Some("S,SBIN,1,0,0,,,0.00,,,"));
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "strategy,symbol,orders,filled,unfilled,fill_rate,mean_slippage_bps,slippage_cost,\
+             mean_time_to_fill_ms,median_time_to_fill_ms,max_time_to_fill_ms\n",
+        );
+        for row in &self.rows {
+            let cells = [
+                crate::csv::field(&row.strategy),
+                crate::csv::field(&row.symbol),
+                row.orders.to_string(),
+                row.filled.to_string(),
+                row.unfilled.to_string(),
+                optional(row.fill_rate, 4),
+                optional(row.mean_slippage_bps, 2),
+                format!("{:.2}", row.slippage_cost),
+                optional(row.mean_time_to_fill_ms, 0),
+                optional(row.median_time_to_fill_ms, 0),
+                row.max_time_to_fill_ms.map(|t| t.to_string()).unwrap_or_default(),
+            ];
+            csv.push_str(&cells.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+fn optional(value: Option<f64>, decimals: usize) -> String {
+    value.map(|v| format!("{:.*}", decimals, v)).unwrap_or_default()
+}
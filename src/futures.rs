@@ -0,0 +1,495 @@
+//! Futures API module for OpenAlgo.
+//!
+//! Compares synthetic futures (from the option chain) against the traded
+//! futures contracts to monitor basis across expiries, lists the futures
+//! chain of an underlying and stitches successive contracts into a
+//! back-adjusted continuous series.
+
+use crate::client::{OpenAlgoClient, OpenAlgoError};
+use crate::data::DataAPI;
+use crate::options::parse_expiry_date;
+use crate::price::{self, Price};
+use crate::types::HistoryCandle;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::sync::mpsc;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::task::JoinHandle;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Basis between the traded future and the synthetic future for one expiry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BasisEntry {
+    pub expiry_date: String,
+    pub future_symbol: String,
+    pub future_price: Option<f64>,
+    pub synthetic_price: Option<f64>,
+    pub underlying_ltp: Option<f64>,
+}
+
+impl BasisEntry {
+    /// Future price minus synthetic future price
+    pub fn basis(&self) -> Option<f64> {
+        Some(self.future_price? - self.synthetic_price?)
+    }
+
+    /// Basis as a percentage of the synthetic future price
+    pub fn basis_percent(&self) -> Option<f64> {
+        let synthetic = self.synthetic_price.filter(|p| *p != 0.0)?;
+        Some(self.basis()? / synthetic * 100.0)
+    }
+}
+
+/// Basis across all listed futures expiries of an underlying
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BasisReport {
+    pub underlying: String,
+    pub exchange: String,
+    pub entries: Vec<BasisEntry>,
+    /// Expiries left out because a quote failed, as `"<expiry>: <error>"`
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+/// Alert raised when the basis of an expiry exceeds the monitor threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BasisAlert {
+    pub underlying: String,
+    pub entry: BasisEntry,
+    pub basis_percent: f64,
+    pub threshold_percent: f64,
+}
+
+/// A listed futures contract
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuturesContract {
+    pub symbol: String,
+    pub exchange: String,
+    /// Expiry in compact form ("26DEC24")
+    pub expiry_date: String,
+    /// Unix timestamp of the expiry close (15:30 IST)
+    pub expiry_timestamp: Option<i64>,
+    pub lotsize: Option<i32>,
+    pub tick_size: Option<Price>,
+}
+
+/// Futures contracts of an underlying, nearest expiry first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuturesChain {
+    pub underlying: String,
+    /// Derivatives exchange of the contracts (e.g. "NFO")
+    pub exchange: String,
+    pub contracts: Vec<FuturesContract>,
+}
+
+impl FuturesChain {
+    /// Contracts not yet expired at a Unix timestamp, nearest expiry first
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::futures::{FuturesChain, FuturesContract};
+    /// use openalgo::options::parse_expiry_date;
+    ///
+    /// let contract = |expiry: &str| FuturesContract {
+    ///     symbol: format!("NIFTY{}FUT", expiry),
+    ///     exchange: "NFO".into(),
+    ///     expiry_date: expiry.into(),
+    ///     expiry_timestamp: parse_expiry_date(expiry),
+    ///     lotsize: Some(75),
+    ///     tick_size: None,
+    /// };
+    /// let chain = FuturesChain {
+    ///     underlying: "NIFTY".into(),
+    ///     exchange: "NFO".into(),
+    ///     contracts: vec![contract("28NOV24"), contract("26DEC24"), contract("30JAN25")],
+    /// };
+    /// // After the November expiry, December is the front month
+    /// let active = chain.active_at(parse_expiry_date("29NOV24").unwrap());
+    /// assert_eq!(active[0].expiry_date, "26DEC24");
+    /// assert_eq!(active[1].expiry_date, "30JAN25");
+    /// ```
+    pub fn active_at(&self, timestamp: i64) -> &[FuturesContract] {
+        let expired = self
+            .contracts
+            .iter()
+            .take_while(|c| c.expiry_timestamp.is_some_and(|expiry| expiry < timestamp))
+            .count();
+        &self.contracts[expired..]
+    }
+
+    /// Nearest unexpired contract
+    pub fn front(&self) -> Option<&FuturesContract> {
+        self.active_at(now_secs()).first()
+    }
+
+    /// Contract after the front month
+    pub fn next(&self) -> Option<&FuturesContract> {
+        self.active_at(now_secs()).get(1)
+    }
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Stitch candles of successive contracts into a back-adjusted continuous series
+///
+/// `segments` holds the candles of each contract, oldest expiry first. The
+/// series rolls to the next contract after the last bar both contracts
+/// traded, and every earlier bar is shifted by the price gap between the two
+/// contracts at that bar, so the series has no jump at the roll. When two
+/// contracts have no bar in common, the gap is measured from the last close
+/// of the older contract to the first open of the newer one. Volume and open
+/// interest are left as traded.
+///
+/// # Example
+/// ```rust
+/// use openalgo::futures::back_adjust;
+/// use openalgo::price::{from_f64, to_f64};
+/// use openalgo::types::HistoryCandle;
+///
+/// let candle = |timestamp: i64, close: f64| HistoryCandle {
+///     timestamp,
+///     open: from_f64(close),
+///     high: from_f64(close),
+///     low: from_f64(close),
+///     close: from_f64(close),
+///     volume: 0,
+///     oi: None,
+///     extra: Default::default(),
+/// };
+/// let november = vec![candle(1, 100.0), candle(2, 102.0), candle(3, 104.0)];
+/// let december = vec![candle(3, 110.0), candle(4, 111.0)];
+///
+/// let series = back_adjust(&[november, december]);
+/// let closes: Vec<_> = series.iter().map(|c| to_f64(c.close)).collect();
+/// assert_eq!(closes, vec![106.0, 108.0, 110.0, 111.0]);
+/// ```
+pub fn back_adjust(segments: &[Vec<HistoryCandle>]) -> Vec<HistoryCandle> {
+    let mut series: Vec<HistoryCandle> = Vec::new();
+    for older in segments.iter().rev() {
+        if older.is_empty() {
+            continue;
+        }
+        if series.is_empty() {
+            series = older.clone();
+            continue;
+        }
+
+        let roll = older
+            .iter()
+            .rev()
+            .find_map(|o| series.iter().find(|n| n.timestamp == o.timestamp).map(|n| (o, n)));
+        let (cutoff, gap) = match roll {
+            Some((o, n)) => (o.timestamp, n.close - o.close),
+            None => {
+                let last = &older[older.len() - 1];
+                let gap = series[0].open - last.close;
+                (series[0].timestamp - 1, gap)
+            }
+        };
+
+        let mut stitched: Vec<HistoryCandle> = older
+            .iter()
+            .filter(|c| c.timestamp <= cutoff)
+            .map(|c| {
+                let mut c = c.clone();
+                c.open += gap;
+                c.high += gap;
+                c.low += gap;
+                c.close += gap;
+                c
+            })
+            .collect();
+        stitched.extend(series.into_iter().filter(|c| c.timestamp > cutoff));
+        series = stitched;
+    }
+    series
+}
+
+/// Map an underlying exchange to its derivatives exchange (NSE -> NFO, BSE -> BFO)
+pub fn derivatives_exchange(exchange: &str) -> &str {
+    match exchange {
+        "NSE" | "NSE_INDEX" => "NFO",
+        "BSE" | "BSE_INDEX" => "BFO",
+        "MCX_INDEX" => "MCX",
+        other => other,
+    }
+}
+
+/// OpenAlgo futures symbol for an underlying and expiry (e.g. "NIFTY26DEC24FUT")
+///
+/// # Example
+/// ```rust
+/// use openalgo::futures::future_symbol;
+/// assert_eq!(future_symbol("NIFTY", "26-DEC-24"), "NIFTY26DEC24FUT");
+/// ```
+pub fn future_symbol(underlying: &str, expiry_date: &str) -> String {
+    format!("{}{}FUT", underlying, compact_expiry(expiry_date))
+}
+
+/// Expiry in the compact form used by symbols and option endpoints ("26DEC24")
+fn compact_expiry(expiry_date: &str) -> String {
+    expiry_date.replace('-', "").to_ascii_uppercase()
+}
+
+/// Futures API client
+#[derive(Clone)]
+pub struct FuturesAPI {
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    client: Arc<OpenAlgoClient>,
+    data: DataAPI,
+}
+
+impl FuturesAPI {
+    /// Create a new Futures API client
+    pub fn new(client: Arc<OpenAlgoClient>) -> Self {
+        Self {
+            data: DataAPI::new(Arc::clone(&client)),
+            client,
+        }
+    }
+
+    /// Get the basis of every listed futures expiry of an underlying
+    ///
+    /// An expiry whose synthetic future or quote cannot be fetched is left
+    /// out and listed in `errors`; only a failed expiry lookup fails the call.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - Underlying symbol (e.g. "NIFTY")
+    /// * `exchange` - Exchange of the underlying (e.g. "NSE_INDEX")
+    pub async fn basis_report(
+        &self,
+        underlying: &str,
+        exchange: &str,
+    ) -> Result<BasisReport, OpenAlgoError> {
+        basis_report(&self.data, underlying, exchange).await
+    }
+
+    /// List the futures contracts of an underlying, nearest expiry first
+    ///
+    /// Expiries come from the expiry endpoint and each contract is resolved
+    /// through the symbol cache for its lot and tick size.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - Underlying symbol (e.g. "NIFTY")
+    /// * `exchange` - Exchange of the underlying (e.g. "NSE_INDEX")
+    pub async fn futures_chain(
+        &self,
+        underlying: &str,
+        exchange: &str,
+    ) -> Result<FuturesChain, OpenAlgoError> {
+        let fo_exchange = derivatives_exchange(exchange);
+        let mut contracts = Vec::new();
+        for expiry_date in futures_expiries(&self.data, underlying, fo_exchange).await? {
+            let symbol = future_symbol(underlying, &expiry_date);
+            let info = self.data.symbol_cached(&symbol, fo_exchange).await?;
+            contracts.push(FuturesContract {
+                symbol: info.symbol.unwrap_or(symbol),
+                exchange: fo_exchange.to_string(),
+                expiry_timestamp: parse_expiry_date(&expiry_date),
+                expiry_date: compact_expiry(&expiry_date),
+                lotsize: info.lotsize,
+                tick_size: info.tick_size,
+            });
+        }
+        contracts.sort_by_key(|c| c.expiry_timestamp.unwrap_or(i64::MAX));
+
+        Ok(FuturesChain {
+            underlying: underlying.to_string(),
+            exchange: fo_exchange.to_string(),
+            contracts,
+        })
+    }
+
+    /// Nearest unexpired futures contract of an underlying
+    pub async fn front_month(
+        &self,
+        underlying: &str,
+        exchange: &str,
+    ) -> Result<FuturesContract, OpenAlgoError> {
+        let chain = self.futures_chain(underlying, exchange).await?;
+        chain.front().cloned().ok_or_else(|| {
+            OpenAlgoError::ApiError(format!("No unexpired futures contract for {}", underlying).into())
+        })
+    }
+
+    /// Futures contract after the front month of an underlying
+    pub async fn next_month(
+        &self,
+        underlying: &str,
+        exchange: &str,
+    ) -> Result<FuturesContract, OpenAlgoError> {
+        let chain = self.futures_chain(underlying, exchange).await?;
+        chain.next().cloned().ok_or_else(|| {
+            OpenAlgoError::ApiError(format!("No next-month futures contract for {}", underlying).into())
+        })
+    }
+
+    /// Back-adjusted continuous series from the history of successive contracts
+    ///
+    /// Fetches the candles of each contract and stitches them with
+    /// [`back_adjust`]. Expired contracts are not listed by the expiry
+    /// endpoint; build their symbols with [`future_symbol`].
+    ///
+    /// # Arguments
+    ///
+    /// * `symbols` - Contract symbols, oldest expiry first
+    /// * `exchange` - Derivatives exchange (e.g. "NFO")
+    /// * `interval` - Candle interval (e.g. "D")
+    /// * `start_date` - Start date (YYYY-MM-DD)
+    /// * `end_date` - End date (YYYY-MM-DD)
+    pub async fn continuous_history(
+        &self,
+        symbols: &[&str],
+        exchange: &str,
+        interval: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> Result<Vec<HistoryCandle>, OpenAlgoError> {
+        let mut segments = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            let history = self
+                .data
+                .history_candles(symbol, exchange, interval, start_date, end_date)
+                .await?;
+            segments.push(history.data.unwrap_or_default());
+        }
+        Ok(back_adjust(&segments))
+    }
+
+    /// Start a background monitor that polls the basis and emits alerts
+    ///
+    /// An alert is sent whenever the absolute basis of an expiry is at least
+    /// `threshold_percent` of the synthetic future price. Dropping the
+    /// receiver stops the monitor.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - Underlying symbol (e.g. "NIFTY")
+    /// * `exchange` - Exchange of the underlying (e.g. "NSE_INDEX")
+    /// * `threshold_percent` - Basis threshold in percent (e.g. 0.5)
+    /// * `interval` - Polling interval
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_basis_monitor(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        threshold_percent: f64,
+        interval: Duration,
+    ) -> (mpsc::Receiver<BasisAlert>, JoinHandle<()>) {
+        let (alert_tx, alert_rx) = mpsc::channel::<BasisAlert>(32);
+        let data = DataAPI::new(Arc::clone(&self.client));
+        let underlying = underlying.to_string();
+        let exchange = exchange.to_string();
+
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if alert_tx.is_closed() {
+                    break;
+                }
+
+                let report = match basis_report(&data, &underlying, &exchange).await {
+                    Ok(report) => report,
+                    Err(e) => {
+                        log::warn!("Basis monitor for {} failed: {}", underlying, e);
+                        continue;
+                    }
+                };
+                for error in &report.errors {
+                    log::warn!("Basis monitor for {} skipped {}", underlying, error);
+                }
+
+                for entry in report.entries {
+                    if let Some(basis_percent) = entry.basis_percent() {
+                        if basis_percent.abs() >= threshold_percent {
+                            let alert = BasisAlert {
+                                underlying: underlying.clone(),
+                                entry,
+                                basis_percent,
+                                threshold_percent,
+                            };
+                            if alert_tx.send(alert).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        (alert_rx, handle)
+    }
+}
+
+async fn basis_report(
+    data: &DataAPI,
+    underlying: &str,
+    exchange: &str,
+) -> Result<BasisReport, OpenAlgoError> {
+    let fo_exchange = derivatives_exchange(exchange);
+    let expiry_dates = futures_expiries(data, underlying, fo_exchange).await?;
+
+    let mut entries = Vec::with_capacity(expiry_dates.len());
+    let mut errors = Vec::new();
+    for expiry_date in expiry_dates {
+        let compact = compact_expiry(&expiry_date);
+        let symbol = future_symbol(underlying, &expiry_date);
+
+        let synthetic = match data.synthetic_future(underlying, exchange, &compact).await {
+            Ok(synthetic) => synthetic,
+            Err(e) => {
+                errors.push(format!("{}: {}", compact, e));
+                continue;
+            }
+        };
+        let quote = match data.quotes(&symbol, fo_exchange).await {
+            Ok(quote) => quote,
+            Err(e) => {
+                errors.push(format!("{}: {}", compact, e));
+                continue;
+            }
+        };
+
+        entries.push(BasisEntry {
+            expiry_date: compact,
+            future_symbol: symbol,
+            future_price: quote.data.and_then(|q| q.ltp).map(price::to_f64),
+            synthetic_price: synthetic.synthetic_future_price.map(price::to_f64),
+            underlying_ltp: synthetic.underlying_ltp.map(price::to_f64),
+        });
+    }
+
+    Ok(BasisReport {
+        underlying: underlying.to_string(),
+        exchange: exchange.to_string(),
+        entries,
+        errors,
+    })
+}
+
+async fn futures_expiries(
+    data: &DataAPI,
+    underlying: &str,
+    fo_exchange: &str,
+) -> Result<Vec<String>, OpenAlgoError> {
+    let expiries = data.expiry(underlying, fo_exchange, "futures").await?;
+    expiries.data.ok_or_else(|| {
+        OpenAlgoError::ApiError(
+            expiries
+                .message
+                .unwrap_or_else(|| format!("No futures expiries for {}", underlying))
+                .into(),
+        )
+    })
+}
@@ -0,0 +1,349 @@
+//! Health monitoring for OpenAlgo.
+//!
+//! Periodically probes the REST API (and optionally WebSocket liveness) and
+//! publishes Healthy/Degraded/Down transitions, so trading logic can pause
+//! while the server or broker link is unreliable.
+//!
+//! `CancelOnDisconnect` builds on the monitor as an opt-in safety net: when
+//! the connection goes down, or a watched strategy task panics, it cancels
+//! the open orders of the configured strategies as soon as OpenAlgo can be
+//! reached again, so orders left behind by a strategy that lost control do
+//! not fill unattended.
+
+use crate::client::OpenAlgoClient;
+use crate::utilities::UtilitiesAPI;
+use crate::websocket::OpenAlgoWebSocket;
+use crate::OpenAlgo;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::{JoinError, JoinHandle};
+
+/// Overall health of the connection to OpenAlgo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HealthState {
+    Healthy,
+    /// Reachable but slow, failing intermittently, or the WebSocket is down
+    Degraded,
+    /// Repeated API failures
+    Down,
+}
+
+/// A change of health state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthEvent {
+    pub state: HealthState,
+    /// State before this change, `None` for the first check
+    pub previous: Option<HealthState>,
+    /// Round-trip time of the probe, if it succeeded
+    pub latency: Option<Duration>,
+    /// Why the state is not healthy
+    pub reason: Option<String>,
+}
+
+/// Health monitor settings
+#[derive(Clone)]
+pub struct HealthConfig {
+    pub interval: Duration,
+    /// Probes slower than this count as degraded
+    pub slow_threshold: Duration,
+    /// Probes taking longer than this count as failed
+    pub timeout: Duration,
+    /// Consecutive failures before the state becomes `Down`
+    pub failures_before_down: u32,
+    websocket: Option<OpenAlgoWebSocket>,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+            slow_threshold: Duration::from_secs(2),
+            timeout: Duration::from_secs(5),
+            failures_before_down: 3,
+            websocket: None,
+        }
+    }
+}
+
+impl HealthConfig {
+    /// How often to probe the API
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(Duration::from_millis(100));
+        self
+    }
+
+    /// Probe latency above which the state is degraded
+    pub fn slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = threshold;
+        self
+    }
+
+    /// Probe timeout
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Consecutive failures before the state becomes `Down`
+    pub fn failures_before_down(mut self, failures: u32) -> Self {
+        self.failures_before_down = failures.max(1);
+        self
+    }
+
+    /// Also report degraded while this WebSocket client has no live socket
+    pub fn websocket(mut self, ws: OpenAlgoWebSocket) -> Self {
+        self.websocket = Some(ws);
+        self
+    }
+}
+
+/// Spawn the monitor task; dropping the receiver stops it
+pub(crate) fn start_health_monitor(
+    client: Arc<OpenAlgoClient>,
+    config: HealthConfig,
+) -> (mpsc::Receiver<HealthEvent>, JoinHandle<()>) {
+    monitor(client, config, None)
+}
+
+/// Spawn the monitor task, also publishing whether each probe reached the API to `reachable`
+fn monitor(
+    client: Arc<OpenAlgoClient>,
+    config: HealthConfig,
+    reachable: Option<watch::Sender<bool>>,
+) -> (mpsc::Receiver<HealthEvent>, JoinHandle<()>) {
+    let (event_tx, event_rx) = mpsc::channel::<HealthEvent>(32);
+    let utilities = UtilitiesAPI::new(client);
+
+    let handle = tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(config.interval);
+        let mut current: Option<HealthState> = None;
+        let mut failures = 0u32;
+
+        loop {
+            ticker.tick().await;
+            if event_tx.is_closed() {
+                break;
+            }
+
+            let probe = match tokio::time::timeout(config.timeout, utilities.ping()).await {
+                Ok(Ok(ping)) if ping.is_ok() => Ok(ping.latency),
+                Ok(Ok(ping)) => Err(ping.response.message.unwrap_or(ping.response.status)),
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(_) => Err(format!("No response within {:?}", config.timeout)),
+            };
+            if let Some(reachable) = &reachable {
+                reachable.send_replace(probe.is_ok());
+            }
+
+            let (state, latency, reason) = match probe {
+                Ok(latency) => {
+                    failures = 0;
+                    if latency > config.slow_threshold {
+                        (HealthState::Degraded, Some(latency), Some(format!("Slow API response: {:?}", latency)))
+                    } else if config.websocket.as_ref().is_some_and(|ws| !ws.is_connected()) {
+                        (HealthState::Degraded, Some(latency), Some("WebSocket disconnected".to_string()))
+                    } else {
+                        (HealthState::Healthy, Some(latency), None)
+                    }
+                }
+                Err(reason) => {
+                    failures += 1;
+                    let state = if failures >= config.failures_before_down {
+                        HealthState::Down
+                    } else {
+                        HealthState::Degraded
+                    };
+                    (state, None, Some(reason))
+                }
+            };
+
+            if current != Some(state) {
+                let event = HealthEvent { state, previous: current, latency, reason };
+                current = Some(state);
+                if event_tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    (event_rx, handle)
+}
+
+/// Event sent by a running `CancelOnDisconnect` watchdog
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchdogEvent {
+    /// Health change seen by the watchdog
+    Health(HealthEvent),
+    /// Cancellation is due and waits for OpenAlgo to be reachable
+    Armed { reason: String },
+    /// `cancel_all_order` was sent for every strategy; `errors` lists the failed ones
+    Cancelled {
+        reason: String,
+        canceled_orders: Vec<String>,
+        errors: Vec<String>,
+    },
+}
+
+/// Cancels a strategy's open orders after a lost connection or a panic
+///
+/// Opt-in protection for unattended strategies. The watchdog runs its own
+/// health monitor; when the state becomes `Down`, or a task passed to
+/// `Watchdog::watch` panics, it arms, and at the next successful probe it
+/// calls `cancel_all_order` for each strategy. Positions are left alone.
+#[derive(Clone)]
+pub struct CancelOnDisconnect {
+    /// Strategies whose orders are cancelled
+    pub strategies: Vec<String>,
+    /// Probes that decide when the connection is down and when it is back
+    pub health: HealthConfig,
+}
+
+impl CancelOnDisconnect {
+    /// Watch over the orders of `strategies`, probing every 10 seconds
+    pub fn new(strategies: &[&str]) -> Self {
+        Self {
+            strategies: strategies.iter().map(|s| s.to_string()).collect(),
+            health: HealthConfig::default(),
+        }
+    }
+
+    /// Health monitor settings; `failures_before_down` sets how long an outage must last
+    pub fn health(mut self, config: HealthConfig) -> Self {
+        self.health = config;
+        self
+    }
+
+    /// Start the watchdog in the background
+    ///
+    /// Each outage or panic cancels once; a later one arms it again.
+    /// Dropping the receiver stops the watchdog.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use openalgo::health::{CancelOnDisconnect, WatchdogEvent};
+    ///
+    /// # async fn run_strategy() {}
+    /// # async fn example(client: openalgo::OpenAlgo) {
+    /// let (mut events, watchdog) = CancelOnDisconnect::new(&["Momentum"]).start(&client);
+    /// watchdog.watch(tokio::spawn(run_strategy()));
+    ///
+    /// while let Some(event) = events.recv().await {
+    ///     if let WatchdogEvent::Cancelled { reason, canceled_orders, .. } = event {
+    ///         eprintln!("Cancelled {} orders after {}", canceled_orders.len(), reason);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn start(&self, client: &OpenAlgo) -> (mpsc::Receiver<WatchdogEvent>, Watchdog) {
+        let (event_tx, event_rx) = mpsc::channel::<WatchdogEvent>(32);
+        let (trigger_tx, mut triggers) = mpsc::unbounded_channel::<String>();
+        let client = client.clone();
+        let strategies = self.strategies.clone();
+        let (reachable_tx, mut reachable) = watch::channel(false);
+        let (mut health, _monitor) = monitor(Arc::clone(&client.client), self.health.clone(), Some(reachable_tx));
+
+        let handle = tokio::spawn(async move {
+            let mut armed: Option<String> = None;
+            let mut triggers_open = true;
+            loop {
+                let disarmed = armed.is_none();
+                tokio::select! {
+                    _ = event_tx.closed() => break,
+                    // Every probe, not just state changes: a slow probe after a failed one stays `Degraded`
+                    changed = reachable.changed() => {
+                        if changed.is_err() {
+                            break;
+                        }
+                    }
+                    event = health.recv() => {
+                        let Some(event) = event else { break };
+                        if event.state == HealthState::Down && disarmed {
+                            armed = Some(format!("connection down: {}", event.reason.clone().unwrap_or_default()));
+                        }
+                        if event_tx.send(WatchdogEvent::Health(event)).await.is_err() {
+                            break;
+                        }
+                    }
+                    // The sender lives in the watchdog; closed once every copy was dropped
+                    reason = triggers.recv(), if triggers_open => {
+                        match reason {
+                            Some(reason) if disarmed => armed = Some(reason),
+                            Some(_) => {}
+                            None => triggers_open = false,
+                        }
+                    }
+                }
+
+                let Some(reason) = armed.clone() else { continue };
+                if disarmed {
+                    log::warn!("Cancel-on-disconnect armed: {}", reason);
+                    if event_tx.send(WatchdogEvent::Armed { reason: reason.clone() }).await.is_err() {
+                        break;
+                    }
+                }
+                if !*reachable.borrow_and_update() {
+                    continue;
+                }
+
+                armed = None;
+                let mut canceled_orders = Vec::new();
+                let mut errors = Vec::new();
+                for strategy in &strategies {
+                    match client.cancel_all_order(strategy).await {
+                        Ok(response) => canceled_orders.extend(response.canceled_orders.unwrap_or_default()),
+                        Err(e) => errors.push(format!("{}: {}", strategy, e)),
+                    }
+                }
+                for error in &errors {
+                    log::warn!("Cancel-on-disconnect failed for {}", error);
+                }
+                let event = WatchdogEvent::Cancelled { reason, canceled_orders, errors };
+                if event_tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        (event_rx, Watchdog { triggers: trigger_tx, handle: Arc::new(handle) })
+    }
+}
+
+/// Handle to a running `CancelOnDisconnect`
+///
+/// Clones refer to the same watchdog.
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    triggers: mpsc::UnboundedSender<String>,
+    handle: Arc<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// Arm the watchdog for `reason`, e.g. after losing the market data feed
+    pub fn trigger(&self, reason: &str) {
+        let _ = self.triggers.send(reason.to_string());
+    }
+
+    /// Arm the watchdog if `task` panics
+    ///
+    /// The returned handle resolves to the task's own result.
+    pub fn watch<T: Send + 'static>(&self, task: JoinHandle<T>) -> JoinHandle<Result<T, JoinError>> {
+        let watchdog = self.clone();
+        tokio::spawn(async move {
+            let result = task.await;
+            if let Err(e) = &result {
+                if e.is_panic() {
+                    watchdog.trigger(&format!("strategy task panicked: {}", e));
+                }
+            }
+            result
+        })
+    }
+
+    /// Whether the watchdog has stopped
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
@@ -0,0 +1,75 @@
+//! Sellable delivery quantities and the CNC sell guard.
+//!
+//! A CNC sell above the quantity held in the demat account is rejected by the
+//! broker or, worse, becomes a short delivery. `sellable_quantities` derives
+//! what can still be sold per symbol from holdings and today's CNC positions;
+//! `OpenAlgoBuilder::guard_cnc_sells` checks every CNC sell against it before
+//! sending.
+//!
+//! Holdings are keyed by symbol alone, since shares held can be sold on
+//! either NSE or BSE.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::holdings::{check_cnc_sell, sellable_quantities};
+//! use openalgo::{HoldingItem, PositionbookPosition};
+//!
+//! let holdings: Vec<HoldingItem> = serde_json::from_str(
+//!     r#"[{"symbol": "TCS", "exchange": "NSE", "product": "CNC", "quantity": 10}]"#,
+//! ).unwrap();
+//! // 4 already sold today
+//! let positions: Vec<PositionbookPosition> = serde_json::from_str(
+//!     r#"[{"symbol": "TCS", "exchange": "BSE", "product": "CNC", "quantity": "-4"}]"#,
+//! ).unwrap();
+//!
+//! let sellable = sellable_quantities(&holdings, &positions);
+//! assert_eq!(sellable["TCS"], 6);
+//! assert!(check_cnc_sell(&sellable, "TCS", 6).is_ok());
+//! assert!(check_cnc_sell(&sellable, "TCS", 7).is_err());
+//! assert!(check_cnc_sell(&sellable, "INFY", 1).is_err());
+//! ```
+
+use crate::types::{HoldingItem, PositionbookPosition};
+use crate::validation::ValidationError;
+use std::collections::BTreeMap;
+
+/// Quantity that can still be sold as delivery, per symbol
+///
+/// Holdings minus today's CNC sells. Shares bought today are not counted, as
+/// they settle into holdings later.
+pub fn sellable_quantities(holdings: &[HoldingItem], positions: &[PositionbookPosition]) -> BTreeMap<String, i64> {
+    let mut sellable = BTreeMap::new();
+    for holding in holdings {
+        let (Some(symbol), Some(quantity)) = (&holding.symbol, holding.quantity) else {
+            continue;
+        };
+        *sellable.entry(symbol.to_ascii_uppercase()).or_insert(0) += i64::from(quantity.max(0));
+    }
+    for position in positions {
+        if !position.product.as_deref().is_some_and(|p| p.eq_ignore_ascii_case("CNC")) {
+            continue;
+        }
+        let Some(symbol) = &position.symbol else { continue };
+        let quantity: i64 = position.quantity.as_deref().and_then(|q| q.trim().parse().ok()).unwrap_or(0);
+        if quantity < 0 {
+            if let Some(held) = sellable.get_mut(&symbol.to_ascii_uppercase()) {
+                *held = (*held + quantity).max(0);
+            }
+        }
+    }
+    sellable
+}
+
+/// Check a CNC sell of `quantity` against the sellable quantities
+pub fn check_cnc_sell(sellable: &BTreeMap<String, i64>, symbol: &str, quantity: i64) -> Result<(), ValidationError> {
+    let held = sellable.get(&symbol.to_ascii_uppercase()).copied().unwrap_or(0);
+    if quantity > held {
+        return Err(ValidationError::InsufficientHoldings { quantity, sellable: held });
+    }
+    Ok(())
+}
+
+/// Whether an order sells delivery shares
+pub(crate) fn is_cnc_sell(action: &str, product: &str) -> bool {
+    action.trim().eq_ignore_ascii_case("SELL") && product.trim().eq_ignore_ascii_case("CNC")
+}
@@ -0,0 +1,202 @@
+//! Candle intervals for OpenAlgo.
+//!
+//! `Interval` parses the interval strings used by `history` ("1m", "5m",
+//! "1h", "D", "W", "M"), orders them by length and converts them to a
+//! `Duration`. `OpenAlgoBuilder::validate_intervals` checks history calls
+//! against the intervals the server reports before sending them.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::interval::Interval;
+//! use std::time::Duration;
+//!
+//! let five: Interval = "5m".parse().unwrap();
+//! assert_eq!(five.duration(), Duration::from_secs(300));
+//! assert!(five < Interval::hours(1) && Interval::hours(1) < Interval::DAY);
+//! assert_eq!(Interval::DAY.to_string(), "D");
+//! ```
+
+use crate::types::IntervalsData;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Unit of an interval
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IntervalUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl IntervalUnit {
+    /// Length in seconds; a month counts as 30 days
+    pub fn secs(&self) -> u64 {
+        match self {
+            IntervalUnit::Second => 1,
+            IntervalUnit::Minute => 60,
+            IntervalUnit::Hour => 3_600,
+            IntervalUnit::Day => 86_400,
+            IntervalUnit::Week => 7 * 86_400,
+            IntervalUnit::Month => 30 * 86_400,
+        }
+    }
+
+    fn suffix(&self) -> &'static str {
+        match self {
+            IntervalUnit::Second => "s",
+            IntervalUnit::Minute => "m",
+            IntervalUnit::Hour => "h",
+            IntervalUnit::Day => "d",
+            IntervalUnit::Week => "w",
+            IntervalUnit::Month => "M",
+        }
+    }
+}
+
+/// A candle interval such as 5 minutes or 1 day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    pub count: u32,
+    pub unit: IntervalUnit,
+}
+
+impl Interval {
+    pub const DAY: Interval = Interval::new(1, IntervalUnit::Day);
+    pub const WEEK: Interval = Interval::new(1, IntervalUnit::Week);
+    pub const MONTH: Interval = Interval::new(1, IntervalUnit::Month);
+
+    /// Interval of `count` units
+    pub const fn new(count: u32, unit: IntervalUnit) -> Self {
+        Self { count, unit }
+    }
+
+    /// Interval of `count` seconds
+    pub const fn seconds(count: u32) -> Self {
+        Self::new(count, IntervalUnit::Second)
+    }
+
+    /// Interval of `count` minutes
+    pub const fn minutes(count: u32) -> Self {
+        Self::new(count, IntervalUnit::Minute)
+    }
+
+    /// Interval of `count` hours
+    pub const fn hours(count: u32) -> Self {
+        Self::new(count, IntervalUnit::Hour)
+    }
+
+    /// Length in seconds; a month counts as 30 days
+    pub fn secs(&self) -> u64 {
+        self.count as u64 * self.unit.secs()
+    }
+
+    /// Length as a `Duration`; a month counts as 30 days
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.secs())
+    }
+
+    /// Whether bars have a fixed length (seconds to days, not weeks or months)
+    pub fn is_fixed(&self) -> bool {
+        self.unit <= IntervalUnit::Day
+    }
+
+    /// `count` times this interval, e.g. the span of 20 bars
+    pub fn times(&self, count: u32) -> Duration {
+        self.duration() * count
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.secs()
+            .cmp(&other.secs())
+            .then(self.unit.cmp(&other.unit))
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.count, self.unit) {
+            (1, IntervalUnit::Day) => f.write_str("D"),
+            (1, IntervalUnit::Week) => f.write_str("W"),
+            (1, IntervalUnit::Month) => f.write_str("M"),
+            (count, unit) => write!(f, "{}{}", count, unit.suffix()),
+        }
+    }
+}
+
+/// Error from parsing an interval string
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid interval: {0:?}")]
+pub struct ParseIntervalError(pub String);
+
+impl FromStr for Interval {
+    type Err = ParseIntervalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        let invalid = || ParseIntervalError(s.to_string());
+        match value {
+            "D" => return Ok(Self::DAY),
+            "W" => return Ok(Self::WEEK),
+            "M" => return Ok(Self::MONTH),
+            _ => {}
+        }
+        let split = value.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let (count, unit) = value.split_at(split);
+        let count: u32 = count.parse().map_err(|_| invalid())?;
+        let unit = match unit {
+            "s" => IntervalUnit::Second,
+            "m" => IntervalUnit::Minute,
+            "h" => IntervalUnit::Hour,
+            "d" | "D" => IntervalUnit::Day,
+            "w" | "W" => IntervalUnit::Week,
+            "M" => IntervalUnit::Month,
+            _ => return Err(invalid()),
+        };
+        if count == 0 {
+            return Err(invalid());
+        }
+        Ok(Self::new(count, unit))
+    }
+}
+
+impl TryFrom<&str> for Interval {
+    type Error = ParseIntervalError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl IntervalsData {
+    /// Every interval the server supports, shortest first
+    ///
+    /// Entries that do not parse are skipped.
+    pub fn intervals(&self) -> Vec<Interval> {
+        let mut intervals: Vec<Interval> = [&self.seconds, &self.minutes, &self.hours, &self.days, &self.weeks, &self.months]
+            .into_iter()
+            .flatten()
+            .filter_map(|i| i.parse().ok())
+            .collect();
+        intervals.sort();
+        intervals.dedup();
+        intervals
+    }
+
+    /// Whether the server supports an interval
+    pub fn supports(&self, interval: &Interval) -> bool {
+        self.intervals().contains(interval)
+    }
+}
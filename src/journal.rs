@@ -0,0 +1,417 @@
+//! Order journal keyed by order ID.
+//!
+//! `OrderJournal` keeps one `JournalEntry` per order placed through it: what
+//! was sent, the quote the decision was made on, and the fill once
+//! `refresh` has seen the order finish. It is the input to the execution
+//! quality analytics in [`crate::execution`], and can be saved to and loaded
+//! from a JSONL file, one entry per line.
+//!
+//! The API's `strategy` field names the strategy but not the signal or leg an
+//! order came from. Tags attached at placement, such as `signal=breakout`,
+//! are kept on the entry so fills can be attributed later.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::journal::OrderJournal;
+//!
+//! # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+//! let journal = OrderJournal::new();
+//! journal.place_order(&client, "MyStrategy", "SBIN", "BUY", "NSE", "MARKET", "MIS", "10").await?;
+//! journal.refresh(&client).await;
+//! let report = journal.report();
+//! println!("{}", report.to_csv());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::calendar;
+use crate::client::OpenAlgoError;
+use crate::execution::ExecutionReport;
+use crate::price;
+use crate::types::{OrderResponse, OrderStatusData, QuotesData};
+use crate::OpenAlgo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Quote seen when the decision to place an order was made
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DecisionQuote {
+    pub ltp: Option<f64>,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    /// Unix milliseconds the quote was taken
+    pub timestamp: i64,
+}
+
+impl DecisionQuote {
+    /// Decision quote from a quotes response taken at `timestamp` (Unix ms)
+    pub fn from_quote(quote: &QuotesData, timestamp: i64) -> Self {
+        Self {
+            ltp: quote.ltp.map(price::to_f64),
+            bid: quote.bid.map(price::to_f64),
+            ask: quote.ask.map(price::to_f64),
+            timestamp,
+        }
+    }
+
+    /// Arrival price: the bid/ask mid when both sides are quoted, else the LTP
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::journal::DecisionQuote;
+    ///
+    /// let quote = DecisionQuote { ltp: Some(100.2), bid: Some(100.0), ask: Some(100.5), timestamp: 0 };
+    /// assert_eq!(quote.arrival_price(), Some(100.25));
+    /// let quote = DecisionQuote { bid: Some(0.0), ..quote };
+    /// assert_eq!(quote.arrival_price(), Some(100.2));
+    /// ```
+    pub fn arrival_price(&self) -> Option<f64> {
+        match (self.bid, self.ask) {
+            (Some(bid), Some(ask)) if bid > 0.0 && ask > 0.0 => Some((bid + ask) / 2.0),
+            _ => self.ltp.filter(|ltp| *ltp > 0.0),
+        }
+    }
+}
+
+/// One journaled order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub orderid: String,
+    pub strategy: String,
+    pub symbol: String,
+    pub exchange: String,
+    /// "BUY" or "SELL"
+    pub action: String,
+    pub pricetype: String,
+    pub product: String,
+    pub quantity: i64,
+    /// Unix milliseconds the order was sent
+    pub placed_at: i64,
+    /// Quote the order was placed on, if one could be fetched
+    pub decision: Option<DecisionQuote>,
+    /// Last order status seen, such as "open" or "complete"
+    pub status: Option<String>,
+    pub average_price: Option<f64>,
+    /// Unix milliseconds the order was seen complete
+    pub filled_at: Option<i64>,
+    /// User tags attached at placement
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+}
+
+impl JournalEntry {
+    /// Whether the order completed
+    pub fn is_filled(&self) -> bool {
+        self.status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("complete"))
+    }
+
+    /// Whether the order completed, was rejected or was cancelled
+    pub fn is_finished(&self) -> bool {
+        self.status.as_deref().is_some_and(|s| {
+            ["complete", "rejected", "cancelled", "canceled"]
+                .iter()
+                .any(|finished| s.eq_ignore_ascii_case(finished))
+        })
+    }
+
+    /// Value of a tag
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags.get(key).map(String::as_str)
+    }
+
+    /// Milliseconds from placement to fill
+    pub fn time_to_fill(&self) -> Option<i64> {
+        self.filled_at.map(|filled| filled - self.placed_at)
+    }
+
+    /// Apply an order status seen at `now` (Unix ms)
+    ///
+    /// The fill time is the broker's order timestamp when it parses, and
+    /// otherwise the time the completed status was first seen.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::journal::JournalEntry;
+    /// use openalgo::OrderStatusData;
+    ///
+    /// let mut entry = JournalEntry { placed_at: 1732768149_000, ..Default::default() };
+    /// let status: OrderStatusData = serde_json::from_str(
+    ///     r#"{"order_status": "complete", "average_price": 801.5, "timestamp": "28-Nov-2024 09:59:10"}"#,
+    /// ).unwrap();
+    /// entry.update(&status, 1732768155_000);
+    /// assert!(entry.is_filled());
+    /// assert_eq!(entry.average_price, Some(801.5));
+    /// assert_eq!(entry.time_to_fill(), Some(1_000));
+    /// ```
+    pub fn update(&mut self, status: &OrderStatusData, now: i64) {
+        self.status = status.order_status.clone();
+        if let Some(average) = status.average_price.map(price::to_f64).filter(|p| *p > 0.0) {
+            self.average_price = Some(average);
+        }
+        if self.is_filled() && self.filled_at.is_none() {
+            let broker_time = status
+                .timestamp
+                .as_deref()
+                .and_then(calendar::parse_timestamp)
+                .map(|t| t.timestamp_millis())
+                .filter(|t| *t >= self.placed_at);
+            self.filled_at = Some(broker_time.unwrap_or(now));
+        }
+    }
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// In-memory journal of orders, keyed by order ID
+///
+/// Clones share the same entries.
+#[derive(Debug, Clone, Default)]
+pub struct OrderJournal {
+    entries: Arc<Mutex<BTreeMap<String, JournalEntry>>>,
+}
+
+impl OrderJournal {
+    /// An empty journal
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace the entry for its order ID
+    pub fn record(&self, entry: JournalEntry) {
+        self.entries.lock().unwrap().insert(entry.orderid.clone(), entry);
+    }
+
+    /// Entry of an order
+    pub fn get(&self, orderid: &str) -> Option<JournalEntry> {
+        self.entries.lock().unwrap().get(orderid).cloned()
+    }
+
+    /// Every entry, in placement order
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        let mut entries: Vec<JournalEntry> = self.entries.lock().unwrap().values().cloned().collect();
+        entries.sort_by_key(|e| e.placed_at);
+        entries
+    }
+
+    /// Tags of an order
+    pub fn tags(&self, orderid: &str) -> Option<BTreeMap<String, String>> {
+        self.entries.lock().unwrap().get(orderid).map(|e| e.tags.clone())
+    }
+
+    /// Set a tag on a journaled order; returns false if the order is not journaled
+    pub fn tag(&self, orderid: &str, key: &str, value: &str) -> bool {
+        match self.entries.lock().unwrap().get_mut(orderid) {
+            Some(entry) => {
+                entry.tags.insert(key.to_string(), value.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Entries whose tag `key` is `value`, in placement order
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::journal::{JournalEntry, OrderJournal};
+    ///
+    /// let journal = OrderJournal::new();
+    /// journal.record(JournalEntry { orderid: "2403".into(), placed_at: 1, ..Default::default() });
+    /// journal.record(JournalEntry { orderid: "2404".into(), placed_at: 2, ..Default::default() });
+    /// journal.tag("2403", "signal", "breakout");
+    /// journal.tag("2404", "signal", "reversal");
+    ///
+    /// let breakouts = journal.find_by_tag("signal", "breakout");
+    /// assert_eq!(breakouts.len(), 1);
+    /// assert_eq!(breakouts[0].orderid, "2403");
+    ///
+    /// // Tags are kept in the JSONL file
+    /// let copy = OrderJournal::from_jsonl(&journal.to_jsonl()).unwrap();
+    /// assert_eq!(copy.get("2404").unwrap().tag("signal"), Some("reversal"));
+    /// ```
+    pub fn find_by_tag(&self, key: &str, value: &str) -> Vec<JournalEntry> {
+        let mut entries: Vec<JournalEntry> = self
+            .entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|e| e.tag(key) == Some(value))
+            .cloned()
+            .collect();
+        entries.sort_by_key(|e| e.placed_at);
+        entries
+    }
+
+    /// Number of journaled orders
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether the journal is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Place an order, journaling it with the quote fetched just before
+    ///
+    /// A failed quote does not stop the order; the entry is then recorded
+    /// without a decision quote. Orders the broker did not assign an ID to
+    /// are not journaled.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_order(
+        &self,
+        client: &OpenAlgo,
+        strategy: &str,
+        symbol: &str,
+        action: &str,
+        exchange: &str,
+        pricetype: &str,
+        product: &str,
+        quantity: &str,
+    ) -> Result<OrderResponse, OpenAlgoError> {
+        self.place_tagged_order(client, strategy, symbol, action, exchange, pricetype, product, quantity, &[])
+            .await
+    }
+
+    /// Place an order like `place_order`, attaching tags to its entry
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use openalgo::journal::OrderJournal;
+    /// # async fn example(client: openalgo::OpenAlgo, journal: OrderJournal) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let tags = [("signal", "orb-15m"), ("leg", "entry")];
+    /// let response = journal
+    ///     .place_tagged_order(&client, "MyStrategy", "SBIN", "BUY", "NSE", "MARKET", "MIS", "10", &tags)
+    ///     .await?;
+    /// if let Some(orderid) = response.orderid {
+    ///     println!("{:?}", journal.tags(&orderid));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_tagged_order(
+        &self,
+        client: &OpenAlgo,
+        strategy: &str,
+        symbol: &str,
+        action: &str,
+        exchange: &str,
+        pricetype: &str,
+        product: &str,
+        quantity: &str,
+        tags: &[(&str, &str)],
+    ) -> Result<OrderResponse, OpenAlgoError> {
+        let decision = match client.quotes(symbol, exchange).await {
+            Ok(response) => response.data.map(|quote| DecisionQuote::from_quote(&quote, now_millis())),
+            Err(e) => {
+                log::warn!("journal: no decision quote for {}: {}", symbol, e);
+                None
+            }
+        };
+        let placed_at = now_millis();
+        let response = client
+            .place_order(strategy, symbol, action, exchange, pricetype, product, quantity)
+            .await?;
+        if let Some(orderid) = &response.orderid {
+            self.record(JournalEntry {
+                orderid: orderid.clone(),
+                strategy: strategy.to_string(),
+                symbol: symbol.to_string(),
+                exchange: exchange.to_string(),
+                action: action.to_ascii_uppercase(),
+                pricetype: pricetype.to_string(),
+                product: product.to_string(),
+                quantity: quantity.trim().parse().unwrap_or(0),
+                placed_at,
+                decision,
+                tags: tags.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+                ..JournalEntry::default()
+            });
+        }
+        Ok(response)
+    }
+
+    /// Fetch the status of every unfinished order and record fills
+    ///
+    /// Returns the number of entries updated; failed lookups are logged and
+    /// retried on the next call.
+    pub async fn refresh(&self, client: &OpenAlgo) -> usize {
+        let pending: Vec<(String, String)> = self
+            .entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|e| !e.is_finished())
+            .map(|e| (e.orderid.clone(), e.strategy.clone()))
+            .collect();
+        let mut updated = 0;
+        for (orderid, strategy) in pending {
+            match client.order_status(&orderid, &strategy).await {
+                Ok(response) => {
+                    if let Some(data) = response.data {
+                        if let Some(entry) = self.entries.lock().unwrap().get_mut(&orderid) {
+                            entry.update(&data, now_millis());
+                            updated += 1;
+                        }
+                    }
+                }
+                Err(e) => log::warn!("journal: status of order {} failed: {}", orderid, e),
+            }
+        }
+        updated
+    }
+
+    /// Execution quality report over every entry
+    pub fn report(&self) -> ExecutionReport {
+        ExecutionReport::from_entries(&self.entries())
+    }
+
+    /// Entries as JSON lines, in placement order
+    pub fn to_jsonl(&self) -> String {
+        self.entries()
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    /// Journal from JSON lines written by `to_jsonl`; blank lines are skipped
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::journal::{JournalEntry, OrderJournal};
+    ///
+    /// let journal = OrderJournal::new();
+    /// journal.record(JournalEntry { orderid: "2403".into(), quantity: 10, ..Default::default() });
+    /// let copy = OrderJournal::from_jsonl(&journal.to_jsonl()).unwrap();
+    /// assert_eq!(copy.get("2403").unwrap().quantity, 10);
+    /// ```
+    pub fn from_jsonl(text: &str) -> Result<Self, serde_json::Error> {
+        let journal = Self::new();
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            journal.record(serde_json::from_str(line)?);
+        }
+        Ok(journal)
+    }
+
+    /// Write the journal to a JSONL file, replacing it
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_jsonl())
+    }
+
+    /// Read a journal saved with `save`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_jsonl(&text).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
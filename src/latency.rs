@@ -0,0 +1,141 @@
+//! REST latency tracking for OpenAlgo.
+//!
+//! Every request attempt is timed per endpoint. `OpenAlgo::latency_stats`
+//! returns p50/p95/p99 over the most recent requests, so a slow host or
+//! broker adapter shows up before orders start timing out.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Requests per endpoint the percentiles are computed over
+pub const LATENCY_WINDOW: usize = 1000;
+
+/// Latency snapshot of one endpoint
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EndpointLatency {
+    pub endpoint: String,
+    /// Requests since the client was created or the stats were reset
+    pub count: u64,
+    /// Requests that failed, including timeouts
+    pub errors: u64,
+    /// Percentiles over the last `LATENCY_WINDOW` requests, in milliseconds
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+}
+
+/// Latency of every endpoint called so far, ordered by endpoint
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub endpoints: Vec<EndpointLatency>,
+}
+
+impl LatencyStats {
+    /// Stats of one endpoint
+    pub fn endpoint(&self, endpoint: &str) -> Option<&EndpointLatency> {
+        self.endpoints.iter().find(|e| e.endpoint == endpoint)
+    }
+
+    /// Render the stats in the Prometheus text exposition format
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::latency::{EndpointLatency, LatencyStats};
+    ///
+    /// let stats = LatencyStats {
+    ///     endpoints: vec![EndpointLatency { endpoint: "quotes".into(), count: 3, p95_ms: 42.0, ..Default::default() }],
+    /// };
+    /// let text = stats.to_prometheus();
+    /// assert!(text.contains("openalgo_request_latency_ms{endpoint=\"quotes\",quantile=\"0.95\"} 42"));
+    /// assert!(text.contains("openalgo_requests_total{endpoint=\"quotes\"} 3"));
+    /// ```
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::from("# TYPE openalgo_request_latency_ms summary\n");
+        for e in &self.endpoints {
+            for (quantile, value) in [("0.5", e.p50_ms), ("0.95", e.p95_ms), ("0.99", e.p99_ms)] {
+                out.push_str(&format!(
+                    "openalgo_request_latency_ms{{endpoint=\"{}\",quantile=\"{}\"}} {}\n",
+                    e.endpoint, quantile, value
+                ));
+            }
+        }
+        out.push_str("# TYPE openalgo_requests_total counter\n");
+        for e in &self.endpoints {
+            out.push_str(&format!("openalgo_requests_total{{endpoint=\"{}\"}} {}\n", e.endpoint, e.count));
+        }
+        out.push_str("# TYPE openalgo_request_errors_total counter\n");
+        for e in &self.endpoints {
+            out.push_str(&format!("openalgo_request_errors_total{{endpoint=\"{}\"}} {}\n", e.endpoint, e.errors));
+        }
+        out
+    }
+}
+
+#[derive(Default)]
+struct Samples {
+    count: u64,
+    errors: u64,
+    /// Most recent durations in milliseconds
+    recent: VecDeque<f64>,
+}
+
+/// Live per-endpoint latency shared by a client and its copies
+#[derive(Default)]
+pub(crate) struct LatencyTracker {
+    endpoints: Mutex<BTreeMap<String, Samples>>,
+}
+
+impl LatencyTracker {
+    pub(crate) fn record(&self, endpoint: &str, elapsed: Duration, ok: bool) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let samples = endpoints.entry(endpoint.to_string()).or_default();
+        samples.count += 1;
+        if !ok {
+            samples.errors += 1;
+        }
+        if samples.recent.len() == LATENCY_WINDOW {
+            samples.recent.pop_front();
+        }
+        samples.recent.push_back(elapsed.as_secs_f64() * 1000.0);
+    }
+
+    pub(crate) fn reset(&self) {
+        self.endpoints.lock().unwrap().clear();
+    }
+
+    pub(crate) fn snapshot(&self) -> LatencyStats {
+        let endpoints = self.endpoints.lock().unwrap();
+        LatencyStats {
+            endpoints: endpoints
+                .iter()
+                .map(|(endpoint, samples)| {
+                    let mut sorted: Vec<f64> = samples.recent.iter().copied().collect();
+                    sorted.sort_by(|a, b| a.total_cmp(b));
+                    EndpointLatency {
+                        endpoint: endpoint.clone(),
+                        count: samples.count,
+                        errors: samples.errors,
+                        p50_ms: percentile(&sorted, 0.50),
+                        p95_ms: percentile(&sorted, 0.95),
+                        p99_ms: percentile(&sorted, 0.99),
+                        max_ms: sorted.last().copied().unwrap_or(0.0),
+                        mean_ms: if sorted.is_empty() { 0.0 } else { sorted.iter().sum::<f64>() / sorted.len() as f64 },
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (q * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
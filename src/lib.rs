@@ -24,6 +24,14 @@
 //! ```
 
 pub mod types;
+pub mod de;
+pub mod price;
+pub mod quantity;
+pub mod validation;
+pub mod holdings;
+pub mod positions;
+mod display;
+mod csv;
 pub mod client;
 pub mod orders;
 pub mod data;
@@ -31,6 +39,79 @@ pub mod account;
 pub mod utilities;
 pub mod analyzer;
 pub mod websocket;
+pub mod options;
+pub mod volatility;
+pub mod futures;
+pub mod depth;
+pub mod symbols;
+pub mod calendar;
+pub mod candles;
+pub mod interval;
+pub mod watchlist;
+pub mod bulk;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod chain;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod conflation;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod metrics;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod proxy;
+pub mod registry;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod reconnect;
+pub mod clock;
+pub mod transport;
+pub mod secret;
+pub mod middleware;
+pub mod retry;
+pub mod latency;
+pub mod risk;
+pub mod circuit;
+pub mod netting;
+pub mod journal;
+pub mod execution;
+pub mod shadow;
+pub mod charges;
+pub mod eod;
+pub mod simulator;
+pub mod optimize;
+pub mod report;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod persistence;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod dashboard;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bus;
+#[cfg(all(feature = "control", not(target_arch = "wasm32")))]
+pub mod control;
+#[cfg(all(feature = "audit", not(target_arch = "wasm32")))]
+pub mod audit;
+#[cfg(not(target_arch = "wasm32"))]
+mod tls;
+pub mod accounts;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod health;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod scheduler;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod supervisor;
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+pub mod mock;
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+pub mod chaos;
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+pub mod vcr;
+#[cfg(all(feature = "test-server", not(target_arch = "wasm32")))]
+pub mod test_server;
+#[cfg(all(feature = "tui", not(target_arch = "wasm32")))]
+pub mod tui;
+#[cfg(all(feature = "cli", not(target_arch = "wasm32")))]
+pub mod scaffold;
 
 pub use types::*;
 pub use client::OpenAlgoClient;
@@ -39,11 +120,448 @@ pub use data::DataAPI;
 pub use account::AccountAPI;
 pub use utilities::UtilitiesAPI;
 pub use analyzer::AnalyzerAPI;
+#[cfg(not(target_arch = "wasm32"))]
+pub use websocket::{BackpressurePolicy, WsConfig};
+#[cfg(any(not(target_arch = "wasm32"), feature = "wasm"))]
 pub use websocket::OpenAlgoWebSocket;
+pub use websocket::{WsCommand, WsData, WsMode, WsSubscriber};
+pub use options::OptionType;
+pub use volatility::VolatilityAPI;
+pub use futures::FuturesAPI;
+pub use depth::{DepthMetrics, OrderBookTracker};
+pub use watchlist::Watchlist;
+#[cfg(not(target_arch = "wasm32"))]
+pub use pool::WsPool;
+pub use accounts::AccountManager;
 
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Builder for an `OpenAlgo` client
+///
+/// # Example
+/// ```rust
+/// use openalgo::OpenAlgo;
+/// let client = OpenAlgo::builder("your-api-key")
+///     .host("http://192.168.1.10:5000")
+///     .ws_port(9000)
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct OpenAlgoBuilder {
+    api_key: secret::ApiKey,
+    host: String,
+    version: String,
+    ws_url: Option<String>,
+    ws_port: u16,
+    transport: Option<Arc<dyn transport::Transport>>,
+    http_client: Option<reqwest::Client>,
+    http: transport::HttpSettings,
+    middleware: middleware::Middleware,
+    log_bodies: bool,
+    request_ids: bool,
+    validate_intervals: bool,
+    validate_orders: bool,
+    guard_cnc_sells: bool,
+    strict: bool,
+    smart_order_cache: Option<Duration>,
+    retry: retry::RetryPolicy,
+    #[cfg(not(target_arch = "wasm32"))]
+    halt_file: Option<std::path::PathBuf>,
+}
+
+impl OpenAlgoBuilder {
+    /// Start with the default local host
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            api_key: secret::ApiKey::new(api_key),
+            host: "http://127.0.0.1:5000".to_string(),
+            version: "v1".to_string(),
+            ws_url: None,
+            ws_port: client::DEFAULT_WS_PORT,
+            transport: None,
+            http_client: None,
+            http: transport::HttpSettings::default(),
+            middleware: middleware::Middleware::default(),
+            log_bodies: false,
+            request_ids: false,
+            validate_intervals: false,
+            validate_orders: false,
+            guard_cnc_sells: false,
+            strict: false,
+            smart_order_cache: None,
+            retry: retry::RetryPolicy::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            halt_file: None,
+        }
+    }
+
+    /// Start from the environment
+    ///
+    /// Reads `OPENALGO_API_KEY` (required), `OPENALGO_HOST`, `OPENALGO_WS_URL`
+    /// and `OPENALGO_VERSION`; unset or empty variables keep the defaults. With
+    /// the `dotenv` feature a `.env` file in the working directory (or a
+    /// parent) is loaded first, without overriding variables already set.
+    pub fn from_env() -> Result<Self, client::OpenAlgoError> {
+        #[cfg(all(feature = "dotenv", not(target_arch = "wasm32")))]
+        let _ = dotenvy::dotenv();
+
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let api_key = var("OPENALGO_API_KEY")
+            .ok_or_else(|| client::OpenAlgoError::ConfigError("OPENALGO_API_KEY is not set".to_string()))?;
+        let mut builder = Self::new(&api_key);
+        if let Some(host) = var("OPENALGO_HOST") {
+            builder = builder.host(&host);
+        }
+        if let Some(ws_url) = var("OPENALGO_WS_URL") {
+            builder = builder.ws_url(&ws_url);
+        }
+        if let Some(version) = var("OPENALGO_VERSION") {
+            builder = builder.version(&version);
+        }
+        Ok(builder)
+    }
+
+    /// API host URL
+    pub fn host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self
+    }
+
+    /// API version
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Explicit WebSocket URL, overriding the one derived from the host
+    pub fn ws_url(mut self, ws_url: &str) -> Self {
+        self.ws_url = Some(ws_url.to_string());
+        self
+    }
+
+    /// Port of the derived WebSocket URL
+    pub fn ws_port(mut self, port: u16) -> Self {
+        self.ws_port = port;
+        self
+    }
+
+    /// Use a pre-configured `reqwest::Client` (proxies, TLS, timeouts, pools)
+    ///
+    /// Ignored when a custom `transport` is set.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::OpenAlgo;
+    /// use std::time::Duration;
+    ///
+    /// let http = reqwest::Client::builder()
+    ///     .proxy(reqwest::Proxy::all("http://proxy.corp.example:3128").unwrap())
+    ///     .timeout(Duration::from_secs(10))
+    ///     .build()
+    ///     .unwrap();
+    /// let client = OpenAlgo::builder("your-api-key").http_client(http).build();
+    /// ```
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Accept gzip-compressed responses (on by default)
+    ///
+    /// Compression cuts download time for large responses such as
+    /// `instruments`, long `history` ranges and full option chains. Needs the
+    /// `compression` feature (enabled by default); browsers negotiate it
+    /// themselves. Like the connection settings below, this is ignored when a
+    /// custom `http_client` or `transport` is set.
+    pub fn gzip(mut self, enabled: bool) -> Self {
+        self.http.gzip = enabled;
+        self
+    }
+
+    /// Accept deflate-compressed responses (on by default)
+    pub fn deflate(mut self, enabled: bool) -> Self {
+        self.http.deflate = enabled;
+        self
+    }
+
+    /// HTTP version for REST requests; `Auto` negotiates HTTP/2 over TLS when the server supports it
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::transport::HttpVersion;
+    /// use openalgo::OpenAlgo;
+    /// use std::time::Duration;
+    ///
+    /// // Keep warm connections for a strategy loop making many small calls
+    /// let client = OpenAlgo::builder("your-api-key")
+    ///     .http_version(HttpVersion::Http2)
+    ///     .pool_idle_timeout(Some(Duration::from_secs(300)))
+    ///     .pool_max_idle_per_host(4)
+    ///     .tcp_keepalive(Some(Duration::from_secs(30)))
+    ///     .build();
+    /// ```
+    pub fn http_version(mut self, version: transport::HttpVersion) -> Self {
+        self.http.version = version;
+        self
+    }
+
+    /// How long idle connections stay in the pool (90 s by default); `None` keeps them indefinitely
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.http.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Idle connections kept per host (unlimited by default)
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.http.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// TCP keep-alive interval for pooled connections (off by default)
+    pub fn tcp_keepalive(mut self, interval: Option<Duration>) -> Self {
+        self.http.tcp_keepalive = interval;
+        self
+    }
+
+    /// Send requests through a custom HTTP transport
+    pub fn transport(mut self, transport: Arc<dyn transport::Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Run a hook before every request, e.g. to add headers, sign or audit it
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::OpenAlgo;
+    /// let client = OpenAlgo::builder("your-api-key")
+    ///     .on_request(|req| req.header("X-Request-Source", "strategy-7"))
+    ///     .on_request(|req| println!("{} {}", req.method.as_str(), req.endpoint))
+    ///     .build();
+    /// ```
+    pub fn on_request(
+        mut self,
+        hook: impl Fn(&mut middleware::RequestParts) + Send + Sync + 'static,
+    ) -> Self {
+        self.middleware.on_request(hook);
+        self
+    }
+
+    /// Run a hook on every response body or error, e.g. for custom error mapping
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::client::OpenAlgoError;
+    /// use openalgo::OpenAlgo;
+    /// let client = OpenAlgo::builder("your-api-key")
+    ///     .on_response(|req, result| match result {
+    ///         Ok(body) if body.contains("Invalid openalgo apikey") => {
+    ///             Err(OpenAlgoError::ApiError(format!("{}: API key rejected", req.endpoint).into()))
+    ///         }
+    ///         other => other,
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn on_response(
+        mut self,
+        hook: impl Fn(&middleware::RequestParts, Result<String, client::OpenAlgoError>) -> Result<String, client::OpenAlgoError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.middleware.on_response(hook);
+        self
+    }
+
+    /// Log request and response bodies at debug level, with the API key redacted
+    pub fn log_bodies(mut self, enabled: bool) -> Self {
+        self.log_bodies = enabled;
+        self
+    }
+
+    /// Append every order placement, modification and cancellation to an audit log
+    ///
+    /// See [`audit::AuditLog`] for the format.
+    #[cfg(all(feature = "audit", not(target_arch = "wasm32")))]
+    pub fn audit_log(mut self, log: audit::AuditLog) -> Self {
+        let log = Arc::new(log);
+        self.middleware.on_response(move |request, result| {
+            if audit::AuditLog::records(&request.endpoint) {
+                log.record(request, &result);
+            }
+            result
+        });
+        self
+    }
+
+    /// Save trading halts to a file so they survive restarts
+    ///
+    /// A halt already in the file is picked up when the client is built. A
+    /// file that exists but cannot be read or parsed halts trading for the
+    /// day rather than letting a tripped strategy resume. See [`circuit`].
+    ///
+    /// # Example
+    /// ```rust
+    /// let path = std::env::temp_dir().join(format!("openalgo-halt-{}.json", std::process::id()));
+    /// std::fs::write(&path, "{not json").unwrap();
+    /// let client = openalgo::OpenAlgo::builder("test-key").trading_halt_file(&path).build();
+    /// assert!(client.trading_halt().unwrap().reason.contains("unreadable"));
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn trading_halt_file(mut self, path: impl AsRef<std::path::Path>) -> Self {
+        self.halt_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Send a unique `X-Request-ID` header with every request
+    pub fn request_ids(mut self, enabled: bool) -> Self {
+        self.request_ids = enabled;
+        self
+    }
+
+    /// Reject history intervals the server does not list in `intervals()`
+    ///
+    /// The supported intervals are fetched on the first history call and cached.
+    pub fn validate_intervals(mut self, enabled: bool) -> Self {
+        self.validate_intervals = enabled;
+        self
+    }
+
+    /// Check order prices against the tick size and quantities against the lot size before sending
+    ///
+    /// Symbol metadata is fetched once per symbol and cached. Orders that
+    /// fail return `OpenAlgoError::ValidationError` with the nearest valid
+    /// values and are not sent. Options orders, whose symbols the server
+    /// resolves, are not checked.
+    pub fn validate_orders(mut self, enabled: bool) -> Self {
+        self.validate_orders = enabled;
+        self
+    }
+
+    /// Check CNC sells against holdings before sending
+    ///
+    /// Holdings and the positionbook are fetched before every CNC sell;
+    /// sells above the shares held return `OpenAlgoError::ValidationError`
+    /// and are not sent.
+    pub fn guard_cnc_sells(mut self, enabled: bool) -> Self {
+        self.guard_cnc_sells = enabled;
+        self
+    }
+
+    /// Reject responses with fields the SDK does not model
+    ///
+    /// Such responses return `OpenAlgoError::JsonError` naming the fields
+    /// instead of keeping them in `extra`. Meant for contract tests against
+    /// a new server version, not for trading.
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
+
+    /// Let `place_smart_order` consult the position cache
+    ///
+    /// A smart order whose `position_size` equals a cached position no older
+    /// than `max_age` is not sent. Keep the cache current with a
+    /// `PositionWatcher` or `refresh_positions`; orders placed through the
+    /// client drop the affected entries.
+    pub fn smart_order_position_cache(mut self, max_age: Duration) -> Self {
+        self.smart_order_cache = Some(max_age);
+        self
+    }
+
+    /// Retry transient failures of safe endpoints; orders are never retried
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::retry::RetryPolicy;
+    /// use openalgo::OpenAlgo;
+    /// use std::time::Duration;
+    ///
+    /// let client = OpenAlgo::builder("your-api-key")
+    ///     .retry(RetryPolicy::new(3).backoff(Duration::from_millis(100), Duration::from_secs(2)))
+    ///     .build();
+    /// ```
+    pub fn retry(mut self, policy: retry::RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Build the client
+    ///
+    /// # Panics
+    /// If the built-in HTTP client cannot be created, for example because the
+    /// TLS backend fails to initialise. Use `try_build` to handle that as an error.
+    pub fn build(self) -> OpenAlgo {
+        match self.try_build() {
+            Ok(client) => client,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Build the client, returning `OpenAlgoError::ConfigError` if the
+    /// built-in HTTP client cannot be created
+    pub fn try_build(self) -> Result<OpenAlgo, client::OpenAlgoError> {
+        let ws_url = self
+            .ws_url
+            .unwrap_or_else(|| client::derive_ws_url(&self.host, self.ws_port));
+        let api_key = self.api_key.expose_secret();
+        let client = match (self.transport, self.http_client) {
+            (Some(transport), _) => {
+                OpenAlgoClient::with_transport(api_key, &self.host, &self.version, &ws_url, transport)
+            }
+            (None, Some(http)) => OpenAlgoClient::with_http_client(api_key, &self.host, &self.version, &ws_url, http),
+            (None, None) => {
+                OpenAlgoClient::with_http_client(api_key, &self.host, &self.version, &ws_url, self.http.client()?)
+            }
+        };
+        let client = client
+            .with_middleware(self.middleware)
+            .log_bodies(self.log_bodies)
+            .request_ids(self.request_ids)
+            .validate_intervals(self.validate_intervals)
+            .validate_orders(self.validate_orders)
+            .guard_cnc_sells(self.guard_cnc_sells)
+            .strict(self.strict)
+            .retry(self.retry);
+        let client = match self.smart_order_cache {
+            Some(max_age) => client.smart_order_position_cache(max_age),
+            None => client,
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let client = match self.halt_file {
+            Some(path) => client.with_halt_state(circuit::HaltState::with_file(path)),
+            None => client,
+        };
+        Ok(OpenAlgo::from_client(client))
+    }
+}
 
 /// OpenAlgo API client combining all API modules
+///
+/// Cloning is cheap: clones share the HTTP client, settings and caches, so
+/// hand each tokio task its own clone instead of wrapping the client in an `Arc`.
+///
+/// # Example
+/// ```rust,no_run
+/// use openalgo::OpenAlgo;
+///
+/// # async fn example() {
+/// let client = OpenAlgo::new("your-api-key");
+/// let tasks: Vec<_> = ["RELIANCE", "TCS", "INFY"]
+///     .into_iter()
+///     .map(|symbol| {
+///         let client = client.clone();
+///         tokio::spawn(async move { client.quotes(symbol, "NSE").await })
+///     })
+///     .collect();
+/// for task in tasks {
+///     println!("{:?}", task.await);
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
 pub struct OpenAlgo {
     client: Arc<OpenAlgoClient>,
     pub orders: OrderAPI,
@@ -51,6 +569,8 @@ pub struct OpenAlgo {
     pub account: AccountAPI,
     pub utilities: UtilitiesAPI,
     pub analyzer: AnalyzerAPI,
+    pub volatility: VolatilityAPI,
+    pub futures: FuturesAPI,
 }
 
 impl OpenAlgo {
@@ -63,6 +583,56 @@ impl OpenAlgo {
         Self::with_config(api_key, "http://127.0.0.1:5000", "v1", "ws://127.0.0.1:8765")
     }
 
+    /// Create a new OpenAlgo client for a host, deriving the WebSocket URL
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::OpenAlgo;
+    /// let client = OpenAlgo::with_host("your-api-key", "https://algo.example.com");
+    /// ```
+    pub fn with_host(api_key: &str, host: &str) -> Self {
+        Self::builder(api_key).host(host).build()
+    }
+
+    /// Start building a client with custom settings
+    pub fn builder(api_key: &str) -> OpenAlgoBuilder {
+        OpenAlgoBuilder::new(api_key)
+    }
+
+    /// Create a client from `OPENALGO_API_KEY`, `OPENALGO_HOST`, `OPENALGO_WS_URL` and `OPENALGO_VERSION`
+    ///
+    /// See [`OpenAlgoBuilder::from_env`]; use that to add further settings.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use openalgo::OpenAlgo;
+    /// # fn example() -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let client = OpenAlgo::from_env()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_env() -> Result<Self, client::OpenAlgoError> {
+        OpenAlgoBuilder::from_env()?.try_build()
+    }
+
+    /// Create a client from a TOML config file
+    ///
+    /// See [`config::OpenAlgoConfig`] for the file format; load that directly
+    /// to also read the default strategy, risk limits and watchlists.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use openalgo::OpenAlgo;
+    /// # fn example() -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let client = OpenAlgo::from_config_file("openalgo.toml")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn from_config_file(path: impl AsRef<std::path::Path>) -> Result<Self, client::OpenAlgoError> {
+        config::OpenAlgoConfig::load(path)?.client()
+    }
+
     /// Create a new OpenAlgo client with custom configuration
     ///
     /// # Arguments
@@ -70,9 +640,19 @@ impl OpenAlgo {
     /// * `api_key` - Your OpenAlgo API key
     /// * `host` - API host URL
     /// * `version` - API version
-    /// * `ws_url` - WebSocket URL
+    /// * `ws_url` - WebSocket URL (derived from `host` when empty)
     pub fn with_config(api_key: &str, host: &str, version: &str, ws_url: &str) -> Self {
-        let client = Arc::new(OpenAlgoClient::new(api_key, host, version, ws_url));
+        let ws_url = if ws_url.is_empty() {
+            client::derive_ws_url(host, client::DEFAULT_WS_PORT)
+        } else {
+            ws_url.to_string()
+        };
+        Self::from_client(OpenAlgoClient::new(api_key, host, version, &ws_url))
+    }
+
+    /// Create an OpenAlgo client from a configured HTTP client
+    pub fn from_client(client: OpenAlgoClient) -> Self {
+        let client = Arc::new(client);
 
         Self {
             orders: OrderAPI::new(Arc::clone(&client)),
@@ -80,13 +660,205 @@ impl OpenAlgo {
             account: AccountAPI::new(Arc::clone(&client)),
             utilities: UtilitiesAPI::new(Arc::clone(&client)),
             analyzer: AnalyzerAPI::new(Arc::clone(&client)),
+            volatility: VolatilityAPI::new(Arc::clone(&client)),
+            futures: FuturesAPI::new(Arc::clone(&client)),
             client,
         }
     }
 
+    /// Latency percentiles per endpoint over recent requests
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// client.quotes("RELIANCE", "NSE").await?;
+    /// if let Some(quotes) = client.latency_stats().endpoint("quotes") {
+    ///     println!("quotes p50 {:.0} ms, p99 {:.0} ms", quotes.p50_ms, quotes.p99_ms);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn latency_stats(&self) -> latency::LatencyStats {
+        self.client.latency_stats()
+    }
+
+    /// Underlying HTTP client shared by the API modules
+    pub fn client(&self) -> &Arc<OpenAlgoClient> {
+        &self.client
+    }
+
+    /// Call an endpoint the SDK does not wrap yet
+    ///
+    /// `body` must serialize to a JSON object; the API key is added as
+    /// `apikey`. Wrap such calls in an extension trait to keep them typed:
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use openalgo::client::OpenAlgoError;
+    /// use openalgo::OpenAlgo;
+    /// use serde::Deserialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct BrokerInfo {
+    ///     status: String,
+    ///     data: Option<serde_json::Value>,
+    /// }
+    ///
+    /// trait BrokerInfoExt {
+    ///     async fn broker_info(&self, segment: &str) -> Result<BrokerInfo, OpenAlgoError>;
+    /// }
+    ///
+    /// impl BrokerInfoExt for OpenAlgo {
+    ///     async fn broker_info(&self, segment: &str) -> Result<BrokerInfo, OpenAlgoError> {
+    ///         self.post_custom("brokerinfo", &json!({ "segment": segment })).await
+    ///     }
+    /// }
+    ///
+    /// # async fn example(client: OpenAlgo) -> Result<(), OpenAlgoError> {
+    /// let info = client.broker_info("equity").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn post_custom<T, R>(&self, endpoint: &str, body: &T) -> Result<R, crate::client::OpenAlgoError>
+    where
+        T: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        self.client.post_custom(endpoint, body).await
+    }
+
+    /// Client for another account on the same server
+    ///
+    /// Shares the connection pool and transport, so it is cheap to create per call.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let order = client
+    ///     .with_api_key("second-account-key")
+    ///     .place_order("Strategy1", "RELIANCE", "BUY", "NSE", "MARKET", "MIS", "1")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_api_key(&self, api_key: &str) -> Self {
+        Self::from_client(self.client.with_api_key(api_key))
+    }
+
+    /// Rotate the API key without rebuilding the client
+    ///
+    /// Unlike `with_api_key`, this changes the key in place: every clone of
+    /// this client and every WebSocket created from it picks up the new key,
+    /// and open WebSocket connections reconnect and resubscribe with it.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let ws = client.websocket();
+    /// let _rx = ws.connect().await?;
+    /// // Nightly session refresh: REST calls and the live socket move over together
+    /// client.set_api_key("refreshed-key");
+    /// let funds = client.funds().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_api_key(&self, api_key: &str) {
+        self.client.set_api_key(api_key);
+    }
+
+    /// Start a background task that probes the API and reports health changes
+    ///
+    /// Emits a `HealthEvent` for the first check and whenever the state
+    /// changes. Dropping the receiver stops the monitor.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) {
+    /// use openalgo::health::{HealthConfig, HealthState};
+    /// use std::time::Duration;
+    ///
+    /// let ws = client.websocket();
+    /// let config = HealthConfig::default()
+    ///     .interval(Duration::from_secs(5))
+    ///     .websocket(ws.clone());
+    /// let (mut events, _handle) = client.start_health_monitor(config);
+    /// while let Some(event) = events.recv().await {
+    ///     if event.state != HealthState::Healthy {
+    ///         println!("Pausing trading: {:?}", event.reason);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_health_monitor(
+        &self,
+        config: health::HealthConfig,
+    ) -> (tokio::sync::mpsc::Receiver<health::HealthEvent>, tokio::task::JoinHandle<()>) {
+        health::start_health_monitor(Arc::clone(&self.client), config)
+    }
+
     /// Create a WebSocket client for real-time data
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn websocket(&self) -> OpenAlgoWebSocket {
-        OpenAlgoWebSocket::new(&self.client.api_key, &self.client.ws_url)
+        OpenAlgoWebSocket::with_shared_key(self.client.shared_api_key(), &self.client.ws_url)
+            .with_rest_client(Arc::clone(&self.client))
+    }
+
+    /// Create a browser WebSocket client for real-time data
+    #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+    pub fn websocket(&self) -> OpenAlgoWebSocket {
+        OpenAlgoWebSocket::with_shared_key(self.client.shared_api_key(), &self.client.ws_url)
+    }
+
+    /// Subscribe an option chain on a new WebSocket connection
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// use openalgo::chain::{ChainEvent, ChainSubscription};
+    /// let sub = ChainSubscription::new("NIFTY", "NSE_INDEX", "26DEC24").strikes_around_atm(10);
+    /// let mut stream = client.subscribe_option_chain(&sub).await?;
+    /// while let Some(ChainEvent::Tick { key, data }) = stream.recv().await {
+    ///     println!("{} {:?}: {:?}", key.strike, key.option_type, data);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn subscribe_option_chain(
+        &self,
+        subscription: &chain::ChainSubscription,
+    ) -> Result<chain::OptionChainStream, crate::client::OpenAlgoError> {
+        chain::subscribe_option_chain(&self.websocket(), &self.data, subscription).await
+    }
+
+    /// Poll an option chain in the background and receive per-strike LTP, OI and IV changes
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) {
+    /// use openalgo::chain::{ChainChange, ChainMonitor, ChangeThresholds};
+    ///
+    /// let monitor = ChainMonitor::new("NIFTY", "NSE_INDEX", "26DEC24")
+    ///     .strikes_around_atm(10)
+    ///     .thresholds(ChangeThresholds { oi: 100_000, ..Default::default() });
+    /// let (mut updates, _handle) = client.start_chain_monitor(&monitor);
+    /// while let Some(update) = updates.recv().await {
+    ///     for change in update.changes {
+    ///         if let ChainChange::OpenInterest { key, previous, current } = change {
+    ///             println!("{} {:?} OI {} -> {}", key.strike, key.option_type, previous, current);
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_chain_monitor(
+        &self,
+        monitor: &chain::ChainMonitor,
+    ) -> (tokio::sync::mpsc::Receiver<chain::ChainUpdate>, tokio::task::JoinHandle<()>) {
+        monitor.start(&self.data)
     }
 
     // =========================================================================
@@ -97,8 +869,12 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let order = client.place_order("Strategy1", "RELIANCE", "BUY", "NSE", "MARKET", "MIS", "1").await?;
+    /// # Ok(())
+    /// # }
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn place_order(
         &self,
         strategy: &str,
@@ -116,8 +892,12 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let order = client.place_limit_order("Strategy1", "RELIANCE", "BUY", "NSE", "MIS", "1", "2500.00").await?;
+    /// # Ok(())
+    /// # }
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn place_limit_order(
         &self,
         strategy: &str,
@@ -131,12 +911,46 @@ impl OpenAlgo {
         self.orders.place_limit_order(strategy, symbol, action, exchange, product, quantity, price).await
     }
 
+    /// Place an after-market order, queued by the broker for the next session
+    ///
+    /// Pass a `price` for LIMIT orders and `None` for MARKET orders. To hold
+    /// orders in the SDK instead and send them live at the open, use an
+    /// `OrderQueue`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let order = client
+    ///     .place_amo_order("Strategy1", "RELIANCE", "BUY", "NSE", "LIMIT", "CNC", "10", Some("2480"))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_amo_order(
+        &self,
+        strategy: &str,
+        symbol: &str,
+        action: &str,
+        exchange: &str,
+        pricetype: &str,
+        product: &str,
+        quantity: &str,
+        price: Option<&str>,
+    ) -> Result<OrderResponse, crate::client::OpenAlgoError> {
+        self.orders.place_amo_order(strategy, symbol, action, exchange, pricetype, product, quantity, price).await
+    }
+
     /// Place a stop-loss order
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let order = client.place_sl_order("Strategy1", "RELIANCE", "BUY", "NSE", "MIS", "1", "2500.00", "2490.00").await?;
+    /// # Ok(())
+    /// # }
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn place_sl_order(
         &self,
         strategy: &str,
@@ -151,12 +965,133 @@ impl OpenAlgo {
         self.orders.place_sl_order(strategy, symbol, action, exchange, product, quantity, price, trigger_price).await
     }
 
+    /// Place a derivative order after checking it against the symbol's lot and freeze quantity
+    ///
+    /// Looks up the symbol, rejects quantities that are not whole lots with
+    /// `OpenAlgoError::QuantityError`, and splits quantities above the freeze
+    /// quantity into several orders. Slices are placed in sequence and
+    /// placing stops at the first failure, whose error is the last result;
+    /// slices already placed are not cancelled.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let results = client
+    ///     .place_sliced_order("Strategy1", "NIFTY30DEC25FUT", "BUY", "NFO", "MARKET", "NRML", 3900)
+    ///     .await?;
+    /// for result in results {
+    ///     println!("{:?}", result?.orderid);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_sliced_order(
+        &self,
+        strategy: &str,
+        symbol: &str,
+        action: &str,
+        exchange: &str,
+        pricetype: &str,
+        product: &str,
+        quantity: i64,
+    ) -> Result<Vec<Result<OrderResponse, crate::client::OpenAlgoError>>, crate::client::OpenAlgoError> {
+        let slices = self.data.split_by_freeze_qty(symbol, exchange, quantity).await?;
+
+        let mut results = Vec::with_capacity(slices.len());
+        for slice in slices {
+            let result = self
+                .orders
+                .place_order(strategy, symbol, action, exchange, pricetype, product, &slice.to_string())
+                .await;
+            let failed = result.is_err();
+            results.push(result);
+            if failed {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Child order quantities for `total_qty`, each within the symbol's freeze quantity
+    ///
+    /// Looks up the symbol's lot size and freeze quantity (cached) and returns
+    /// the fewest whole-lot slices, largest first. `place_sliced_order` places
+    /// them.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// // NIFTY futures: lot size 75, freeze quantity 1800
+    /// let slices = client.split_by_freeze_qty("NIFTY30DEC25FUT", "NFO", 3900).await?;
+    /// assert_eq!(slices, vec![1800, 1800, 300]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn split_by_freeze_qty(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        total_qty: i64,
+    ) -> Result<Vec<i64>, crate::client::OpenAlgoError> {
+        self.data.split_by_freeze_qty(symbol, exchange, total_qty).await
+    }
+
+    /// Send a prepared order, e.g. one that squares off a position
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// use openalgo::PlaceOrderRequest;
+    /// let positions = client.positionbook().await?;
+    /// for position in positions.data.unwrap_or_default() {
+    ///     if let Ok(mut exit) = PlaceOrderRequest::try_from(&position) {
+    ///         exit.strategy = "Strategy1".to_string();
+    ///         client.place_order_request(exit).await?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn place_order_request(
+        &self,
+        request: PlaceOrderRequest,
+    ) -> Result<OrderResponse, crate::client::OpenAlgoError> {
+        self.orders.place_order_request(request).await
+    }
+
+    /// Send an order, retrying transient failures only after checking the
+    /// orderbook that it was not placed
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// use openalgo::retry::OrderRecovery;
+    /// use openalgo::PlaceOrderRequest;
+    /// let request = PlaceOrderRequest::new("Strategy1", "SBIN", "BUY", "NSE", "MARKET", "MIS", "10");
+    /// let response = client.place_order_recovering(request, &OrderRecovery::new(2)).await?;
+    /// println!("Order {:?}", response.orderid);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn place_order_recovering(
+        &self,
+        request: PlaceOrderRequest,
+        recovery: &retry::OrderRecovery,
+    ) -> Result<OrderResponse, crate::client::OpenAlgoError> {
+        self.orders.place_order_recovering(request, recovery).await
+    }
+
     /// Place a smart order with position sizing
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let order = client.place_smart_order("Strategy1", "RELIANCE", "BUY", "NSE", "MARKET", "MIS", "1", "5").await?;
+    /// # Ok(())
+    /// # }
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn place_smart_order(
         &self,
         strategy: &str,
@@ -175,8 +1110,12 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let order = client.options_order("Strategy1", "NIFTY", "NFO", "241226", "0", "CE", "BUY", "50", "MARKET", "MIS", "50").await?;
+    /// # Ok(())
+    /// # }
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn options_order(
         &self,
         strategy: &str,
@@ -198,12 +1137,15 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// use openalgo::OptionsLeg;
     /// let legs = vec![
     ///     OptionsLeg::new("0", "CE", "BUY", "50"),
     ///     OptionsLeg::new("2", "CE", "SELL", "50"),
     /// ];
     /// let order = client.options_multi_order("Strategy1", "NIFTY", "NFO", "241226", legs).await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn options_multi_order(
         &self,
@@ -220,12 +1162,15 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// use openalgo::BasketOrderItem;
     /// let orders = vec![
-    ///     BasketOrderItem::new("RELIANCE", "NSE", "BUY", "1", "MARKET", "MIS"),
-    ///     BasketOrderItem::new("TCS", "NSE", "BUY", "1", "MARKET", "MIS"),
+    ///     BasketOrderItem::new("RELIANCE", "NSE", "BUY", 1, "MARKET", "MIS"),
+    ///     BasketOrderItem::new("TCS", "NSE", "BUY", 1, "MARKET", "MIS"),
     /// ];
     /// let result = client.basket_order("Strategy1", orders).await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn basket_order(
         &self,
@@ -239,8 +1184,12 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let result = client.split_order("Strategy1", "RELIANCE", "BUY", "NSE", 100, 25, "MARKET", "MIS").await?;
+    /// # Ok(())
+    /// # }
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn split_order(
         &self,
         strategy: &str,
@@ -259,8 +1208,12 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let result = client.modify_order("1234567890", "Strategy1", "RELIANCE", "BUY", "NSE", "LIMIT", "MIS", "1", "2550.00").await?;
+    /// # Ok(())
+    /// # }
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn modify_order(
         &self,
         orderid: &str,
@@ -276,11 +1229,34 @@ impl OpenAlgo {
         self.orders.modify_order(orderid, strategy, symbol, action, exchange, pricetype, product, quantity, price).await
     }
 
+    /// Send a prepared modify request, e.g. one built from an orderbook entry
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo, order: openalgo::OrderbookOrder) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// use openalgo::ModifyOrderRequest;
+    /// let mut modify = ModifyOrderRequest::try_from(&order)?;
+    /// modify.strategy = "Strategy1".to_string();
+    /// modify.price = "2550.00".to_string();
+    /// let result = client.modify_order_request(modify).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn modify_order_request(
+        &self,
+        request: ModifyOrderRequest,
+    ) -> Result<OrderResponse, crate::client::OpenAlgoError> {
+        self.orders.modify_order_request(request).await
+    }
+
     /// Cancel an order
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let result = client.cancel_order("1234567890", "Strategy1").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn cancel_order(
         &self,
@@ -294,81 +1270,293 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
-    /// let result = client.cancel_all_order("Strategy1").await?;
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let result = client.cancel_all_order("Strategy1").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn cancel_all_order(
+        &self,
+        strategy: &str,
+    ) -> Result<CancelAllOrderResponse, crate::client::OpenAlgoError> {
+        self.orders.cancel_all_order(strategy).await
+    }
+
+    /// Close all positions
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let result = client.close_position("Strategy1").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn close_position(
+        &self,
+        strategy: &str,
+    ) -> Result<StatusResponse, crate::client::OpenAlgoError> {
+        self.orders.close_position(strategy).await
+    }
+
+    /// Cancel all open orders, then close all positions
+    ///
+    /// Both calls are made even if the first fails; the first error is returned.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// client.halt_trading("manual stop");
+    /// client.kill_switch("Strategy1").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn kill_switch(&self, strategy: &str) -> Result<(), crate::client::OpenAlgoError> {
+        let cancelled = self.orders.cancel_all_order(strategy).await;
+        let closed = self.orders.close_position(strategy).await;
+        cancelled?;
+        closed?;
+        Ok(())
+    }
+
+    /// Refuse new and modified orders until the next IST trading day
+    ///
+    /// Applies to this client and all its clones. Cancellations and position
+    /// closes still go through.
+    ///
+    /// # Example
+    /// ```rust
+    /// let client = openalgo::OpenAlgo::new("test-key");
+    /// client.halt_trading("daily loss limit");
+    /// assert_eq!(client.trading_halt().unwrap().reason, "daily loss limit");
+    /// let result = tokio_test::block_on(client.place_order("S", "SBIN", "BUY", "NSE", "MARKET", "MIS", "1"));
+    /// assert!(result.unwrap_err().to_string().contains("halted"));
+    /// client.resume_trading();
+    /// assert!(client.trading_halt().is_none());
+    /// ```
+    pub fn halt_trading(&self, reason: &str) {
+        self.client.halt_state().set(Some(circuit::TradingHalt::today(reason)));
+    }
+
+    /// Lift a trading halt before the next trading day
+    pub fn resume_trading(&self) {
+        self.client.halt_state().set(None);
+    }
+
+    /// The trading halt in force, if any
+    pub fn trading_halt(&self) -> Option<circuit::TradingHalt> {
+        self.client.halt_state().active()
+    }
+
+    /// Get order status
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let status = client.order_status("1234567890", "Strategy1").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn order_status(
+        &self,
+        orderid: &str,
+        strategy: &str,
+    ) -> Result<OrderStatusResponse, crate::client::OpenAlgoError> {
+        self.orders.order_status(orderid, strategy).await
+    }
+
+    /// Get the status of many orders, at most `concurrency` requests at a time
+    ///
+    /// Returns each order ID with its own result, in input order.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) {
+    /// for (orderid, status) in client.bulk_order_status(&["2403", "2404", "2405"], "Strategy1", 4).await {
+    ///     println!("{}: {:?}", orderid, status.map(|s| s.data));
+    /// }
+    /// # }
+    /// ```
+    pub async fn bulk_order_status<'a>(
+        &self,
+        orderids: &[&'a str],
+        strategy: &str,
+        concurrency: usize,
+    ) -> Vec<(&'a str, Result<OrderStatusResponse, crate::client::OpenAlgoError>)> {
+        bulk::run(orderids.iter().copied(), concurrency, |orderid| self.orders.order_status(orderid, strategy)).await
+    }
+
+    /// Get open position
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let position = client.open_position("Strategy1", "RELIANCE", "NSE", "MIS").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn open_position(
+        &self,
+        strategy: &str,
+        symbol: &str,
+        exchange: &str,
+        product: &str,
+    ) -> Result<OpenPositionResponse, crate::client::OpenAlgoError> {
+        self.orders.open_position(strategy, symbol, exchange, product).await
+    }
+
+    /// Net quantity of an open position, as an integer
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let quantity = client.open_position_quantity("Strategy1", "RELIANCE", "NSE", "MIS").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn open_position_quantity(
+        &self,
+        strategy: &str,
+        symbol: &str,
+        exchange: &str,
+        product: &str,
+    ) -> Result<i64, crate::client::OpenAlgoError> {
+        self.orders.open_position_quantity(strategy, symbol, exchange, product).await
+    }
+
+    /// Reload the position cache from the positionbook, returning the positions that changed
+    pub async fn refresh_positions(&self) -> Result<Vec<positions::PositionChange>, crate::client::OpenAlgoError> {
+        let positions = self.account.positionbook().await?.data.unwrap_or_default();
+        Ok(self.client.position_cache().replace(&positions))
+    }
+
+    /// Cached net quantity of a position, if looked up within `max_age`
+    pub fn cached_position(&self, symbol: &str, exchange: &str, product: &str, max_age: Duration) -> Option<i64> {
+        self.client.position_cache().get(symbol, exchange, product, max_age)
+    }
+
+    // =========================================================================
+    // Data API
+    // =========================================================================
+
+    /// Get quotes for a symbol
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let quotes = client.quotes("RELIANCE", "NSE").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn quotes(
+        &self,
+        symbol: &str,
+        exchange: &str,
+    ) -> Result<QuotesResponse, crate::client::OpenAlgoError> {
+        self.data.quotes(symbol, exchange).await
+    }
+
+    /// Get a quote with a GET request, e.g. to let an HTTP cache serve it
+    ///
+    /// The API key is sent as a query parameter.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// // Same as: curl "http://127.0.0.1:5000/api/v1/quotes?apikey=...&symbol=RELIANCE&exchange=NSE"
+    /// let quotes = client.quotes_get("RELIANCE", "NSE").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn quotes_get(
+        &self,
+        symbol: &str,
+        exchange: &str,
+    ) -> Result<QuotesResponse, crate::client::OpenAlgoError> {
+        self.data.quotes_get(symbol, exchange).await
+    }
+
+    /// Get quotes one request per symbol, at most `concurrency` requests at a time
+    ///
+    /// Unlike `multi_quotes`, each symbol gets its own result, so one bad
+    /// symbol does not fail the rest.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) {
+    /// let quotes = client.bulk_quotes(&[("RELIANCE", "NSE"), ("TCS", "NSE")], 4).await;
+    /// for ((symbol, _), quote) in quotes {
+    ///     println!("{}: {:?}", symbol, quote.map(|q| q.data));
+    /// }
+    /// # }
     /// ```
-    pub async fn cancel_all_order(
+    pub async fn bulk_quotes<'a>(
         &self,
-        strategy: &str,
-    ) -> Result<CancelAllOrderResponse, crate::client::OpenAlgoError> {
-        self.orders.cancel_all_order(strategy).await
+        symbols: &[(&'a str, &'a str)],
+        concurrency: usize,
+    ) -> Vec<((&'a str, &'a str), Result<QuotesResponse, crate::client::OpenAlgoError>)> {
+        bulk::run(symbols.iter().copied(), concurrency, |(symbol, exchange)| self.data.quotes(symbol, exchange)).await
     }
 
-    /// Close all positions
+    /// Get the last traded price for a symbol
     ///
     /// # Example
     /// ```rust,no_run
-    /// let result = client.close_position("Strategy1").await?;
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let ltp = client.ltp("RELIANCE", "NSE").await?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn close_position(
-        &self,
-        strategy: &str,
-    ) -> Result<StatusResponse, crate::client::OpenAlgoError> {
-        self.orders.close_position(strategy).await
+    pub async fn ltp(&self, symbol: &str, exchange: &str) -> Result<price::Price, crate::client::OpenAlgoError> {
+        self.data.ltp(symbol, exchange).await
     }
 
-    /// Get order status
+    /// Get the bid/ask spread for a symbol
     ///
     /// # Example
     /// ```rust,no_run
-    /// let status = client.order_status("1234567890", "Strategy1").await?;
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let spread = client.bid_ask_spread("RELIANCE", "NSE").await?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn order_status(
-        &self,
-        orderid: &str,
-        strategy: &str,
-    ) -> Result<OrderStatusResponse, crate::client::OpenAlgoError> {
-        self.orders.order_status(orderid, strategy).await
+    pub async fn bid_ask_spread(&self, symbol: &str, exchange: &str) -> Result<price::Price, crate::client::OpenAlgoError> {
+        self.data.bid_ask_spread(symbol, exchange).await
     }
 
-    /// Get open position
+    /// Get the percentage change from previous close for a symbol
     ///
     /// # Example
     /// ```rust,no_run
-    /// let position = client.open_position("Strategy1", "RELIANCE", "NSE", "MIS").await?;
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let change = client.change_percent("RELIANCE", "NSE").await?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn open_position(
-        &self,
-        strategy: &str,
-        symbol: &str,
-        exchange: &str,
-        product: &str,
-    ) -> Result<OpenPositionResponse, crate::client::OpenAlgoError> {
-        self.orders.open_position(strategy, symbol, exchange, product).await
+    pub async fn change_percent(&self, symbol: &str, exchange: &str) -> Result<f64, crate::client::OpenAlgoError> {
+        self.data.change_percent(symbol, exchange).await
     }
 
-    // =========================================================================
-    // Data API
-    // =========================================================================
-
-    /// Get quotes for a symbol
+    /// Get the spot quote of an index by common name
     ///
     /// # Example
     /// ```rust,no_run
-    /// let quotes = client.quotes("RELIANCE", "NSE").await?;
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let nifty = client.index_quote("NIFTY").await?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn quotes(
-        &self,
-        symbol: &str,
-        exchange: &str,
-    ) -> Result<QuotesResponse, crate::client::OpenAlgoError> {
-        self.data.quotes(symbol, exchange).await
+    pub async fn index_quote(&self, name: &str) -> Result<QuotesResponse, crate::client::OpenAlgoError> {
+        self.data.index_quote(name).await
     }
 
     /// Get quotes for multiple symbols
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let quotes = client.multi_quotes(&[("RELIANCE", "NSE"), ("TCS", "NSE")]).await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn multi_quotes(
         &self,
@@ -381,7 +1569,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let depth = client.depth("RELIANCE", "NSE").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn depth(
         &self,
@@ -395,7 +1586,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let history = client.history("RELIANCE", "NSE", "5m").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn history(
         &self,
@@ -410,7 +1604,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let history = client.history_range("RELIANCE", "NSE", "5m", "2024-01-01", "2024-01-31").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn history_range(
         &self,
@@ -423,21 +1620,91 @@ impl OpenAlgo {
         self.data.history_range(symbol, exchange, interval, start_date, end_date).await
     }
 
+    /// Get historical candles as typed data
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let history = client.history_candles("NIFTY24DECFUT", "NFO", "5m", Some("2024-12-01"), Some("2024-12-20")).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn history_candles(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        interval: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> Result<HistoryResponse, crate::client::OpenAlgoError> {
+        self.data.history_candles(symbol, exchange, interval, start_date, end_date).await
+    }
+
+    /// Fetch recent history to warm up a live candle series
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo, price: openalgo::price::Price, timestamp: i64) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// // A 50-bar average is ready as soon as the strategy starts
+    /// let mut series = client.warm_up("SBIN", "NSE", "5m", 50).await?;
+    /// if let Some(bar) = series.update(timestamp, price, None) {
+    ///     let closes: Vec<_> = series.candles().iter().rev().take(50).map(|c| c.close).collect();
+    ///     println!("bar at {} closed, {} closes for the average", bar.timestamp, closes.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn warm_up(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        interval: &str,
+        bars: usize,
+    ) -> Result<candles::LiveCandles, crate::client::OpenAlgoError> {
+        self.data.warm_up(symbol, exchange, interval, bars).await
+    }
+
     /// Get available intervals
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let intervals = client.intervals().await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn intervals(&self) -> Result<IntervalsResponse, crate::client::OpenAlgoError> {
         self.data.intervals().await
     }
 
+    /// Get the supported intervals with a GET request
+    pub async fn intervals_get(&self) -> Result<IntervalsResponse, crate::client::OpenAlgoError> {
+        self.data.intervals_get().await
+    }
+
+    /// Intervals the server supports, shortest first (cached after the first call)
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// use openalgo::interval::Interval;
+    /// let supported = client.supported_intervals().await?;
+    /// let finest_intraday = supported.iter().find(|i| *i >= &Interval::minutes(1));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn supported_intervals(&self) -> Result<Vec<interval::Interval>, crate::client::OpenAlgoError> {
+        self.data.supported_intervals().await
+    }
+
     /// Get option chain
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let chain = client.option_chain("NIFTY", "NFO", "241226").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn option_chain(
         &self,
@@ -452,7 +1719,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let info = client.symbol("RELIANCE", "NSE").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn symbol(
         &self,
@@ -462,11 +1732,59 @@ impl OpenAlgo {
         self.data.symbol(symbol, exchange).await
     }
 
+    /// Symbol metadata from the in-memory cache, fetched on a miss
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let nifty = client.symbol_cached("NIFTY30DEC25FUT", "NFO").await?;
+    /// println!("lot size {:?}, tick size {:?}", nifty.lotsize, nifty.tick_size);
+    ///
+    /// // After a contract revision
+    /// client.invalidate_symbol("NIFTY30DEC25FUT", "NFO");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn symbol_cached(&self, symbol: &str, exchange: &str) -> Result<SymbolData, crate::client::OpenAlgoError> {
+        self.data.symbol_cached(symbol, exchange).await
+    }
+
+    /// Drop the cached metadata of one symbol
+    pub fn invalidate_symbol(&self, symbol: &str, exchange: &str) {
+        self.data.invalidate_symbol(symbol, exchange)
+    }
+
+    /// Fetch and cache metadata for every instrument of a watchlist
+    ///
+    /// Returns the instruments that could not be loaded.
+    pub async fn preload_symbols(&self, watchlist: &Watchlist) -> Vec<(WsInstrument, crate::client::OpenAlgoError)> {
+        self.data.preload_symbols(&watchlist.instruments, bulk::DEFAULT_CONCURRENCY).await
+    }
+
+    /// Look up many symbols, at most `concurrency` requests at a time
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) {
+    /// let symbols = client.bulk_symbols(&[("NIFTY30DEC25FUT", "NFO"), ("BANKNIFTY30DEC25FUT", "NFO")], 4).await;
+    /// # }
+    /// ```
+    pub async fn bulk_symbols<'a>(
+        &self,
+        symbols: &[(&'a str, &'a str)],
+        concurrency: usize,
+    ) -> Vec<((&'a str, &'a str), Result<SymbolResponse, crate::client::OpenAlgoError>)> {
+        bulk::run(symbols.iter().copied(), concurrency, |(symbol, exchange)| self.data.symbol(symbol, exchange)).await
+    }
+
     /// Search symbols
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let results = client.search("RELI", "NSE").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn search(
         &self,
@@ -476,11 +1794,61 @@ impl OpenAlgo {
         self.data.search(query, exchange).await
     }
 
+    /// Search symbols with instrument type, expiry and paging filters
+    pub async fn search_filtered(
+        &self,
+        query: &str,
+        exchange: &str,
+        filters: &SearchFilters,
+    ) -> Result<SearchResponse, crate::client::OpenAlgoError> {
+        self.data.search_filtered(query, exchange, filters).await
+    }
+
+    /// One page of search results
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// use openalgo::SearchFilters;
+    ///
+    /// let mut filters = SearchFilters::default().instrumenttype("OPTIDX").limit(100);
+    /// loop {
+    ///     let page = client.search_page("NIFTY", "NFO", &filters).await?;
+    ///     println!("{} results from {}", page.results.len(), page.offset);
+    ///     match page.next_offset() {
+    ///         Some(offset) => filters = filters.offset(offset),
+    ///         None => break,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_page(
+        &self,
+        query: &str,
+        exchange: &str,
+        filters: &SearchFilters,
+    ) -> Result<SearchResultPage, crate::client::OpenAlgoError> {
+        self.data.search_page(query, exchange, filters).await
+    }
+
+    /// Search for symbols with a GET request
+    pub async fn search_get(
+        &self,
+        query: &str,
+        exchange: &str,
+    ) -> Result<SearchResponse, crate::client::OpenAlgoError> {
+        self.data.search_get(query, exchange).await
+    }
+
     /// Get option symbol
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let symbol = client.option_symbol("NIFTY", "NFO", "241226", "0", "CE").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn option_symbol(
         &self,
@@ -493,11 +1861,56 @@ impl OpenAlgo {
         self.data.option_symbol(underlying, exchange, expiry_date, offset, option_type).await
     }
 
+    /// Fetch the option chain of an expiry once and build a local strike resolver
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// use openalgo::OptionType;
+    ///
+    /// let resolver = client.strike_resolver("NIFTY", "NSE_INDEX", "26DEC24").await?;
+    /// let atm_call = client.resolve_option(&resolver, 0, OptionType::CE).await?;
+    /// let otm_put = client.resolve_option(&resolver, 2, OptionType::PE).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn strike_resolver(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_date: &str,
+    ) -> Result<options::StrikeResolver, crate::client::OpenAlgoError> {
+        self.data.strike_resolver(underlying, exchange, expiry_date).await
+    }
+
+    /// Resolve an ATM/offset option locally, calling `optionsymbol` only when the resolver cannot
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// # let resolver = client.strike_resolver("NIFTY", "NSE_INDEX", "26DEC24").await?;
+    /// let option = client.resolve_option(&resolver, -1, openalgo::OptionType::CE).await?;
+    /// println!("{} lot {:?}", option.symbol, option.lotsize);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resolve_option(
+        &self,
+        resolver: &options::StrikeResolver,
+        offset: i32,
+        option_type: OptionType,
+    ) -> Result<options::ResolvedOption, crate::client::OpenAlgoError> {
+        self.data.resolve_option(resolver, offset, option_type).await
+    }
+
     /// Get synthetic future price
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let future = client.synthetic_future("NIFTY", "NFO", "241226").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn synthetic_future(
         &self,
@@ -512,7 +1925,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let greeks = client.option_greeks("NIFTY24DEC24000CE", "NFO", 6.5, "NIFTY", "NSE").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn option_greeks(
         &self,
@@ -529,7 +1945,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let expiries = client.expiry("NIFTY", "NFO", "OPT").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn expiry(
         &self,
@@ -544,7 +1963,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let instruments = client.instruments("NSE").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn instruments(
         &self,
@@ -553,6 +1975,21 @@ impl OpenAlgo {
         self.data.instruments(exchange).await
     }
 
+    /// Fetch the instruments of each exchange and build a broker symbol map
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let map = client.symbol_map(&["NSE", "NFO"]).await?;
+    /// let (symbol, exchange) = map.resolve("NSE:RELIANCE-EQ", "NSE").unwrap();
+    /// let broker = map.broker("RELIANCE", "NSE");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn symbol_map(&self, exchanges: &[&str]) -> Result<symbols::SymbolMap, crate::client::OpenAlgoError> {
+        self.data.symbol_map(exchanges).await
+    }
+
     // =========================================================================
     // Account API
     // =========================================================================
@@ -561,7 +1998,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let funds = client.funds().await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn funds(&self) -> Result<FundsResponse, crate::client::OpenAlgoError> {
         self.account.funds().await
@@ -571,7 +2011,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let orderbook = client.orderbook().await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn orderbook(&self) -> Result<OrderbookResponse, crate::client::OpenAlgoError> {
         self.account.orderbook().await
@@ -581,7 +2024,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let tradebook = client.tradebook().await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn tradebook(&self) -> Result<TradebookResponse, crate::client::OpenAlgoError> {
         self.account.tradebook().await
@@ -591,31 +2037,75 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let positions = client.positionbook().await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn positionbook(&self) -> Result<PositionbookResponse, crate::client::OpenAlgoError> {
         self.account.positionbook().await
     }
 
+    /// Exposure per underlying, beta-weighted delta and parametric VaR of open positions
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let report = client.risk_report(&openalgo::risk::RiskConfig::default()).await?;
+    /// println!("Net {:.0}, beta-weighted delta {:?} NIFTY, VaR {:.0}", report.net_exposure, report.beta_weighted_delta, report.var);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn risk_report(&self, config: &risk::RiskConfig) -> Result<risk::RiskReport, crate::client::OpenAlgoError> {
+        risk::report(self, config).await
+    }
+
     /// Get holdings
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let holdings = client.holdings().await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn holdings(&self) -> Result<HoldingsResponse, crate::client::OpenAlgoError> {
         self.account.holdings().await
     }
 
+    /// Quantity that can still be sold as delivery, per symbol
+    ///
+    /// Holdings minus today's CNC sells from the positionbook.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let sellable = client.sellable_quantities().await?;
+    /// println!("TCS: {}", sellable.get("TCS").copied().unwrap_or(0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sellable_quantities(&self) -> Result<std::collections::BTreeMap<String, i64>, crate::client::OpenAlgoError> {
+        self.account.sellable_quantities().await
+    }
+
+    /// Fail with `ValidationError::InsufficientHoldings` if a CNC sell exceeds the shares held
+    pub async fn check_cnc_sell(&self, symbol: &str, quantity: i64) -> Result<(), crate::client::OpenAlgoError> {
+        self.account.check_cnc_sell(symbol, quantity).await
+    }
+
     /// Get margin requirement
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// use openalgo::MarginPosition;
     /// let positions = vec![
     ///     MarginPosition::new("NIFTY24DEC24000CE", "NFO", "BUY", "MIS", "MARKET", "50"),
     /// ];
     /// let margin = client.margin(positions).await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn margin(
         &self,
@@ -628,11 +2118,42 @@ impl OpenAlgo {
     // Utilities API
     // =========================================================================
 
+    /// Check connectivity and API key, measuring the round trip
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let ping = client.ping().await?;
+    /// println!("{} in {:?}", ping.is_ok(), ping.latency);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ping(&self) -> Result<PingReport, crate::client::OpenAlgoError> {
+        self.utilities.ping().await
+    }
+
+    /// Get the OpenAlgo server version
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let version = client.server_version().await?;
+    /// let compatible = version.data.is_some_and(|d| d.at_least("1.0.0"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn server_version(&self) -> Result<VersionResponse, crate::client::OpenAlgoError> {
+        self.utilities.server_version().await
+    }
+
     /// Get market holidays
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let holidays = client.holidays(2024).await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn holidays(
         &self,
@@ -645,7 +2166,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let timings = client.timings("2024-12-25").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn timings(
         &self,
@@ -658,7 +2182,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let result = client.telegram("username", "Hello from OpenAlgo!").await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn telegram(
         &self,
@@ -672,7 +2199,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let result = client.telegram_priority("username", "Urgent alert!", 10).await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn telegram_priority(
         &self,
@@ -691,7 +2221,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let status = client.analyzer_status().await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn analyzer_status(&self) -> Result<AnalyzerStatusResponse, crate::client::OpenAlgoError> {
         self.analyzer.status().await
@@ -701,7 +2234,10 @@ impl OpenAlgo {
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
     /// let result = client.analyzer_toggle(true).await?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn analyzer_toggle(
         &self,
@@ -709,6 +2245,201 @@ impl OpenAlgo {
     ) -> Result<AnalyzerToggleResponse, crate::client::OpenAlgoError> {
         self.analyzer.toggle(mode).await
     }
+
+    // =========================================================================
+    // Volatility API
+    // =========================================================================
+
+    /// Build an implied volatility surface across expiries
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let surface = client.iv_surface("NIFTY", "NSE_INDEX", &["26DEC24", "30JAN25"], 6.5).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn iv_surface(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_dates: &[&str],
+        interest_rate: f64,
+    ) -> Result<volatility::IvSurface, crate::client::OpenAlgoError> {
+        self.volatility.iv_surface(underlying, exchange, expiry_dates, interest_rate).await
+    }
+
+    /// Get the ATM implied volatility term structure
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let term = client.term_structure("NIFTY", "NSE_INDEX", &["26DEC24", "30JAN25"], 6.5).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn term_structure(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_dates: &[&str],
+        interest_rate: f64,
+    ) -> Result<Vec<volatility::TermStructurePoint>, crate::client::OpenAlgoError> {
+        self.volatility.term_structure(underlying, exchange, expiry_dates, interest_rate).await
+    }
+
+    /// Compute local Greeks for every option in a chain
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let greeks = client.chain_greeks("NIFTY", "NSE_INDEX", "26DEC24", 6.5).await?;
+    /// for option in &greeks.options {
+    ///     println!("{} {:?} delta {:.2}", option.strike, option.option_type, option.delta);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn chain_greeks(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_date: &str,
+        interest_rate: f64,
+    ) -> Result<volatility::ChainGreeks, crate::client::OpenAlgoError> {
+        self.volatility.chain_greeks(underlying, exchange, expiry_date, interest_rate).await
+    }
+
+    /// Option whose delta is closest to `delta` (sign ignored)
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// use openalgo::OptionType;
+    ///
+    /// let put = client.strike_by_delta("NIFTY", "NSE_INDEX", "26DEC24", 0.25, OptionType::PE, 6.5).await?;
+    /// println!("Sell {:?} at {} (delta {:.2})", put.symbol, put.strike, put.delta);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn strike_by_delta(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_date: &str,
+        delta: f64,
+        option_type: OptionType,
+        interest_rate: f64,
+    ) -> Result<volatility::StrikeGreeks, crate::client::OpenAlgoError> {
+        self.volatility
+            .strike_by_delta(underlying, exchange, expiry_date, delta, option_type, interest_rate)
+            .await
+    }
+
+    // =========================================================================
+    // Futures API
+    // =========================================================================
+
+    /// Get the basis between traded and synthetic futures for every expiry
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let report = client.basis_report("NIFTY", "NSE_INDEX").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn basis_report(
+        &self,
+        underlying: &str,
+        exchange: &str,
+    ) -> Result<futures::BasisReport, crate::client::OpenAlgoError> {
+        self.futures.basis_report(underlying, exchange).await
+    }
+
+    /// List the futures contracts of an underlying, nearest expiry first
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let chain = client.futures_chain("NIFTY", "NSE_INDEX").await?;
+    /// if let Some(front) = chain.front() {
+    ///     println!("{} lot {:?}", front.symbol, front.lotsize);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn futures_chain(
+        &self,
+        underlying: &str,
+        exchange: &str,
+    ) -> Result<futures::FuturesChain, crate::client::OpenAlgoError> {
+        self.futures.futures_chain(underlying, exchange).await
+    }
+
+    /// Nearest unexpired futures contract of an underlying
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let front = client.front_month_future("NIFTY", "NSE_INDEX").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn front_month_future(
+        &self,
+        underlying: &str,
+        exchange: &str,
+    ) -> Result<futures::FuturesContract, crate::client::OpenAlgoError> {
+        self.futures.front_month(underlying, exchange).await
+    }
+
+    /// Futures contract after the front month of an underlying
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let next = client.next_month_future("NIFTY", "NSE_INDEX").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn next_month_future(
+        &self,
+        underlying: &str,
+        exchange: &str,
+    ) -> Result<futures::FuturesContract, crate::client::OpenAlgoError> {
+        self.futures.next_month(underlying, exchange).await
+    }
+
+    /// Back-adjusted continuous futures series from successive contracts, oldest first
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let candles = client
+    ///     .continuous_futures_history(
+    ///         &["NIFTY28NOV24FUT", "NIFTY26DEC24FUT"],
+    ///         "NFO",
+    ///         "D",
+    ///         Some("2024-10-01"),
+    ///         Some("2024-12-26"),
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn continuous_futures_history(
+        &self,
+        symbols: &[&str],
+        exchange: &str,
+        interval: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> Result<Vec<types::HistoryCandle>, crate::client::OpenAlgoError> {
+        self.futures
+            .continuous_history(symbols, exchange, interval, start_date, end_date)
+            .await
+    }
 }
 
 /// Library version
@@ -0,0 +1,250 @@
+//! WebSocket metrics for OpenAlgo.
+//!
+//! Counts messages by mode, parse failures, dropped messages and reconnects,
+//! and tracks tick latency from the message timestamp to receive time.
+
+use crate::websocket::{WsData, WsMode};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Window over which message rates are measured
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Snapshot of WebSocket metrics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WsStats {
+    pub ltp_messages: u64,
+    pub quote_messages: u64,
+    pub depth_messages: u64,
+    pub ltp_per_sec: f64,
+    pub quote_per_sec: f64,
+    pub depth_per_sec: f64,
+    /// Messages that could not be parsed
+    pub parse_failures: u64,
+    /// Messages discarded by the backpressure policy
+    pub dropped_messages: u64,
+    pub connections: u64,
+    pub reconnects: u64,
+    /// Latency of the most recent tick in milliseconds
+    pub last_latency_ms: Option<f64>,
+    pub avg_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<f64>,
+}
+
+impl WsStats {
+    /// Total market data messages received
+    pub fn total_messages(&self) -> u64 {
+        self.ltp_messages + self.quote_messages + self.depth_messages
+    }
+
+    /// Render the stats in the Prometheus text exposition format
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::metrics::WsStats;
+    /// let text = WsStats::default().to_prometheus();
+    /// assert!(text.contains("openalgo_ws_messages_total{mode=\"ltp\"} 0"));
+    /// ```
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# TYPE openalgo_ws_messages_total counter\n");
+        for (mode, count) in [
+            ("ltp", self.ltp_messages),
+            ("quote", self.quote_messages),
+            ("depth", self.depth_messages),
+        ] {
+            out.push_str(&format!("openalgo_ws_messages_total{{mode=\"{}\"}} {}\n", mode, count));
+        }
+        out.push_str("# TYPE openalgo_ws_messages_per_second gauge\n");
+        for (mode, rate) in [
+            ("ltp", self.ltp_per_sec),
+            ("quote", self.quote_per_sec),
+            ("depth", self.depth_per_sec),
+        ] {
+            out.push_str(&format!("openalgo_ws_messages_per_second{{mode=\"{}\"}} {}\n", mode, rate));
+        }
+        for (name, kind, value) in [
+            ("openalgo_ws_parse_failures_total", "counter", self.parse_failures),
+            ("openalgo_ws_dropped_messages_total", "counter", self.dropped_messages),
+            ("openalgo_ws_connections_total", "counter", self.connections),
+            ("openalgo_ws_reconnects_total", "counter", self.reconnects),
+        ] {
+            out.push_str(&format!("# TYPE {} {}\n{} {}\n", name, kind, name, value));
+        }
+        for (name, value) in [
+            ("openalgo_ws_last_latency_ms", self.last_latency_ms),
+            ("openalgo_ws_avg_latency_ms", self.avg_latency_ms),
+            ("openalgo_ws_max_latency_ms", self.max_latency_ms),
+        ] {
+            if let Some(value) = value {
+                out.push_str(&format!("# TYPE {} gauge\n{} {}\n", name, name, value));
+            }
+        }
+        out
+    }
+}
+
+#[derive(Default)]
+struct ModeCounter {
+    total: AtomicU64,
+    rate: Mutex<RateWindow>,
+}
+
+struct RateWindow {
+    start: Instant,
+    count: u64,
+    rate: f64,
+}
+
+impl Default for RateWindow {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            count: 0,
+            rate: 0.0,
+        }
+    }
+}
+
+impl ModeCounter {
+    fn record(&self, now: Instant) {
+        self.total.fetch_add(1, Ordering::Relaxed);
+        let mut window = self.rate.lock().unwrap();
+        window.count += 1;
+        let elapsed = now.duration_since(window.start);
+        if elapsed >= RATE_WINDOW {
+            window.rate = window.count as f64 / elapsed.as_secs_f64();
+            window.start = now;
+            window.count = 0;
+        }
+    }
+
+    fn rate(&self, now: Instant) -> f64 {
+        let window = self.rate.lock().unwrap();
+        let elapsed = now.duration_since(window.start);
+        // Decay towards the current window once the stream goes quiet
+        if elapsed >= RATE_WINDOW * 2 {
+            window.count as f64 / elapsed.as_secs_f64()
+        } else {
+            window.rate
+        }
+    }
+}
+
+#[derive(Default)]
+struct Latency {
+    count: u64,
+    sum_ms: f64,
+    last_ms: Option<f64>,
+    max_ms: Option<f64>,
+}
+
+/// Live counters shared by a WebSocket client and its connections
+#[derive(Default)]
+pub(crate) struct WsMetrics {
+    ltp: ModeCounter,
+    quote: ModeCounter,
+    depth: ModeCounter,
+    parse_failures: AtomicU64,
+    dropped: AtomicU64,
+    connections: AtomicU64,
+    open: AtomicU64,
+    latency: Mutex<Latency>,
+}
+
+impl WsMetrics {
+    pub(crate) fn record_connect(&self) {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        self.open.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_disconnect(&self) {
+        let _ = self.open.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    }
+
+    /// Sockets currently open
+    pub(crate) fn open(&self) -> u64 {
+        self.open.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_parse_failure(&self) {
+        self.parse_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Record a received message
+    pub(crate) fn record(&self, data: &WsData) {
+        let now = Instant::now();
+        let (mode, timestamp) = match data {
+            WsData::Ltp(d) => (WsMode::Ltp, d.timestamp),
+            WsData::Quote(d) => (WsMode::Quote, d.timestamp),
+            WsData::Depth(d) => (WsMode::Depth, d.timestamp),
+            WsData::Error(_) => return self.record_parse_failure(),
+            _ => return,
+        };
+        self.counter(mode).record(now);
+
+        if let Some(timestamp) = timestamp {
+            let latency_ms = now_millis() - timestamp_millis(timestamp);
+            let mut latency = self.latency.lock().unwrap();
+            latency.count += 1;
+            latency.sum_ms += latency_ms;
+            latency.last_ms = Some(latency_ms);
+            latency.max_ms = Some(latency.max_ms.map_or(latency_ms, |m| m.max(latency_ms)));
+        }
+    }
+
+    fn counter(&self, mode: WsMode) -> &ModeCounter {
+        match mode {
+            WsMode::Ltp => &self.ltp,
+            WsMode::Quote => &self.quote,
+            WsMode::Depth => &self.depth,
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> WsStats {
+        let now = Instant::now();
+        let latency = self.latency.lock().unwrap();
+        let connections = self.connections.load(Ordering::Relaxed);
+        WsStats {
+            ltp_messages: self.ltp.total.load(Ordering::Relaxed),
+            quote_messages: self.quote.total.load(Ordering::Relaxed),
+            depth_messages: self.depth.total.load(Ordering::Relaxed),
+            ltp_per_sec: self.ltp.rate(now),
+            quote_per_sec: self.quote.rate(now),
+            depth_per_sec: self.depth.rate(now),
+            parse_failures: self.parse_failures.load(Ordering::Relaxed),
+            dropped_messages: self.dropped(),
+            connections,
+            reconnects: connections.saturating_sub(1),
+            last_latency_ms: latency.last_ms,
+            avg_latency_ms: (latency.count > 0).then(|| latency.sum_ms / latency.count as f64),
+            max_latency_ms: latency.max_ms,
+        }
+    }
+}
+
+fn now_millis() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .unwrap_or(0.0)
+}
+
+/// Message timestamps may be in seconds or milliseconds
+fn timestamp_millis(timestamp: i64) -> f64 {
+    if timestamp > 100_000_000_000 {
+        timestamp as f64
+    } else {
+        timestamp as f64 * 1000.0
+    }
+}
@@ -0,0 +1,118 @@
+//! Request and response hooks for OpenAlgo.
+//!
+//! Request hooks run before every HTTP call and can add headers, sign or
+//! audit the request. Response hooks run on every result and can map server
+//! replies to custom errors.
+
+use crate::client::OpenAlgoError;
+use std::sync::Arc;
+
+/// HTTP method of a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+impl Method {
+    /// Method name, e.g. "POST"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+        }
+    }
+}
+
+/// A request about to be sent, open to modification by request hooks
+#[derive(Debug, Clone)]
+pub struct RequestParts {
+    pub method: Method,
+    /// Endpoint such as "placeorder"
+    pub endpoint: String,
+    pub url: String,
+    /// Extra headers sent with the request
+    pub headers: Vec<(String, String)>,
+    /// JSON body of POST requests
+    pub body: Option<String>,
+    /// Query parameters of GET requests
+    pub query: Vec<(String, String)>,
+}
+
+impl RequestParts {
+    /// Add a header
+    pub fn header(&mut self, name: &str, value: &str) {
+        self.headers.push((name.to_string(), value.to_string()));
+    }
+
+    /// Value of a header, matching the name case-insensitively
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Hook run before each request
+pub type RequestHook = Arc<dyn Fn(&mut RequestParts) + Send + Sync>;
+
+/// Hook run on each response body or error, in registration order
+pub type ResponseHook =
+    Arc<dyn Fn(&RequestParts, Result<String, OpenAlgoError>) -> Result<String, OpenAlgoError> + Send + Sync>;
+
+/// Request and response hooks of a client
+#[derive(Clone, Default)]
+pub struct Middleware {
+    request: Vec<RequestHook>,
+    response: Vec<ResponseHook>,
+}
+
+impl Middleware {
+    /// Add a request hook
+    pub fn on_request(&mut self, hook: impl Fn(&mut RequestParts) + Send + Sync + 'static) {
+        self.request.push(Arc::new(hook));
+    }
+
+    /// Add a response hook
+    pub fn on_response(
+        &mut self,
+        hook: impl Fn(&RequestParts, Result<String, OpenAlgoError>) -> Result<String, OpenAlgoError>
+            + Send
+            + Sync
+            + 'static,
+    ) {
+        self.response.push(Arc::new(hook));
+    }
+
+    /// Whether no hooks are registered
+    pub fn is_empty(&self) -> bool {
+        self.request.is_empty() && self.response.is_empty()
+    }
+
+    pub(crate) fn before(&self, parts: &mut RequestParts) {
+        for hook in &self.request {
+            hook(parts);
+        }
+    }
+
+    pub(crate) fn after(
+        &self,
+        parts: &RequestParts,
+        mut result: Result<String, OpenAlgoError>,
+    ) -> Result<String, OpenAlgoError> {
+        for hook in &self.response {
+            result = hook(parts, result);
+        }
+        result
+    }
+}
+
+impl std::fmt::Debug for Middleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Middleware")
+            .field("request_hooks", &self.request.len())
+            .field("response_hooks", &self.response.len())
+            .finish()
+    }
+}
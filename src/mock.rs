@@ -0,0 +1,292 @@
+//! In-memory OpenAlgo test double.
+//!
+//! `MockOpenAlgo` hands out real `OpenAlgo` clients whose requests are served
+//! from memory: canned responses per endpoint, scripted order fills, prices
+//! for quotes, and optional simulated latency. Enabled by the `test-utils`
+//! feature.
+
+use crate::chaos::Chaos;
+use crate::client::{OpenAlgoClient, OpenAlgoError};
+use crate::transport::{Transport, TransportFuture};
+use crate::OpenAlgo;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How a scripted order is filled
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockFill {
+    /// Filled at the given price
+    Complete(f64),
+    /// Left open
+    Open,
+    /// Rejected by the broker
+    Rejected,
+}
+
+/// A request received by the mock
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// Endpoint such as "placeorder"
+    pub endpoint: String,
+    pub body: Value,
+}
+
+/// Order placed through the mock
+#[derive(Debug, Clone)]
+pub struct MockOrder {
+    pub orderid: String,
+    pub request: Value,
+    pub fill: MockFill,
+}
+
+#[derive(Default)]
+struct MockState {
+    responses: HashMap<String, Value>,
+    queued: HashMap<String, VecDeque<Result<Value, String>>>,
+    prices: HashMap<String, f64>,
+    fills: HashMap<String, MockFill>,
+    orders: Vec<MockOrder>,
+    requests: Vec<MockRequest>,
+    latency: Duration,
+}
+
+/// In-memory fake OpenAlgo server
+///
+/// # Example
+/// ```rust
+/// use openalgo::mock::{MockFill, MockOpenAlgo};
+/// use openalgo::price::to_f64;
+///
+/// let mock = MockOpenAlgo::new();
+/// mock.set_price("RELIANCE", 2500.0);
+/// mock.script_fill("RELIANCE", MockFill::Complete(2501.0));
+/// let client = mock.client();
+///
+/// tokio_test::block_on(async {
+///     assert_eq!(to_f64(client.ltp("RELIANCE", "NSE").await.unwrap()), 2500.0);
+///
+///     let order = client.place_order("Test", "RELIANCE", "BUY", "NSE", "MARKET", "MIS", "1").await.unwrap();
+///     let status = client.order_status(order.orderid.as_deref().unwrap(), "Test").await.unwrap();
+///     assert_eq!(status.data.unwrap().average_price.map(to_f64), Some(2501.0));
+/// });
+/// assert_eq!(mock.orders().len(), 1);
+/// ```
+#[derive(Clone, Default)]
+pub struct MockOpenAlgo {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockOpenAlgo {
+    /// Create an empty mock
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Client whose requests are served by this mock
+    pub fn client(&self) -> OpenAlgo {
+        self.client_with(self.transport())
+    }
+
+    /// Client whose requests are served by this mock through injected faults
+    ///
+    /// See the `chaos` module.
+    pub fn chaos_client(&self, chaos: &Chaos) -> OpenAlgo {
+        self.client_with(Arc::new(chaos.wrap(self.transport())))
+    }
+
+    /// Transport serving requests from this mock, to wrap or pass to a builder
+    pub fn transport(&self) -> Arc<dyn Transport> {
+        Arc::new(MockTransport { state: Arc::clone(&self.state) })
+    }
+
+    fn client_with(&self, transport: Arc<dyn Transport>) -> OpenAlgo {
+        OpenAlgo::from_client(OpenAlgoClient::with_transport(
+            "mock-api-key",
+            "http://mock.openalgo",
+            "v1",
+            "ws://mock.openalgo:8765",
+            transport,
+        ))
+    }
+
+    /// Always answer `endpoint` with `response`
+    pub fn respond(&self, endpoint: &str, response: Value) {
+        self.lock().responses.insert(endpoint.to_string(), response);
+    }
+
+    /// Answer the next call to `endpoint` with `response`, before any standing response
+    pub fn respond_once(&self, endpoint: &str, response: Value) {
+        self.lock()
+            .queued
+            .entry(endpoint.to_string())
+            .or_default()
+            .push_back(Ok(response));
+    }
+
+    /// Fail the next call to `endpoint` with an API error
+    pub fn fail_once(&self, endpoint: &str, message: &str) {
+        self.lock()
+            .queued
+            .entry(endpoint.to_string())
+            .or_default()
+            .push_back(Err(message.to_string()));
+    }
+
+    /// Price returned by quotes and used for default fills
+    pub fn set_price(&self, symbol: &str, price: f64) {
+        self.lock().prices.insert(symbol.to_string(), price);
+    }
+
+    /// How orders for `symbol` are filled
+    pub fn script_fill(&self, symbol: &str, fill: MockFill) {
+        self.lock().fills.insert(symbol.to_string(), fill);
+    }
+
+    /// Delay every response by `latency`
+    pub fn set_latency(&self, latency: Duration) {
+        self.lock().latency = latency;
+    }
+
+    /// Requests received so far
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// Requests received for one endpoint
+    pub fn requests_for(&self, endpoint: &str) -> Vec<MockRequest> {
+        self.lock()
+            .requests
+            .iter()
+            .filter(|r| r.endpoint == endpoint)
+            .cloned()
+            .collect()
+    }
+
+    /// Orders placed so far
+    pub fn orders(&self) -> Vec<MockOrder> {
+        self.lock().orders.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+}
+
+struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl Transport for MockTransport {
+    fn post_json<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let endpoint = endpoint_of(url);
+            let body: Value = serde_json::from_str(&body)?;
+            let (latency, response) = {
+                let mut state = self.state.lock().unwrap();
+                state.requests.push(MockRequest {
+                    endpoint: endpoint.clone(),
+                    body: body.clone(),
+                });
+                (state.latency, state.answer(&endpoint, &body))
+            };
+            if !latency.is_zero() {
+                tokio::time::sleep(latency).await;
+            }
+            response.map(|value| value.to_string())
+        })
+    }
+
+    fn get_json<'a>(&'a self, url: &'a str, query: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        let body: serde_json::Map<String, Value> = query
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::String(v.to_string())))
+            .collect();
+        self.post_json(url, Value::Object(body).to_string())
+    }
+}
+
+/// Endpoint path after `/api/<version>/`
+fn endpoint_of(url: &str) -> String {
+    let path = url.split("/api/").nth(1).unwrap_or(url);
+    path.split_once('/').map_or(path, |(_, rest)| rest).to_string()
+}
+
+fn text(body: &Value, key: &str) -> String {
+    match body.get(key) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+impl MockState {
+    fn answer(&mut self, endpoint: &str, body: &Value) -> Result<Value, OpenAlgoError> {
+        if let Some(queued) = self.queued.get_mut(endpoint).and_then(|q| q.pop_front()) {
+            return queued.map_err(|message| OpenAlgoError::ApiError(message.into()));
+        }
+        if let Some(response) = self.responses.get(endpoint) {
+            return Ok(response.clone());
+        }
+
+        match endpoint {
+            "placeorder" | "placesmartorder" | "optionsorder" => Ok(self.place(body)),
+            "orderstatus" => Ok(self.order_status(&text(body, "orderid"))),
+            "cancelorder" => Ok(json!({"status": "success", "orderid": text(body, "orderid")})),
+            "quotes" => {
+                let ltp = self.prices.get(&text(body, "symbol")).copied();
+                Ok(json!({"status": "success", "data": {"ltp": ltp}}))
+            }
+            "ping" => Ok(json!({"status": "success", "data": {"broker": "mock", "message": "pong"}})),
+            "orderbook" | "tradebook" | "positionbook" | "holdings" => {
+                Ok(json!({"status": "success", "data": []}))
+            }
+            _ => Err(OpenAlgoError::ApiError(format!(
+                "MockOpenAlgo has no response for {}",
+                endpoint
+            ).into())),
+        }
+    }
+
+    fn place(&mut self, body: &Value) -> Value {
+        let symbol = text(body, "symbol");
+        let fill = self.fills.get(&symbol).cloned().unwrap_or_else(|| {
+            MockFill::Complete(self.prices.get(&symbol).copied().unwrap_or(0.0))
+        });
+        let orderid = format!("MOCK{}", self.orders.len() + 1);
+        self.orders.push(MockOrder {
+            orderid: orderid.clone(),
+            request: body.clone(),
+            fill,
+        });
+        json!({"status": "success", "orderid": orderid})
+    }
+
+    fn order_status(&self, orderid: &str) -> Value {
+        let Some(order) = self.orders.iter().find(|o| o.orderid == orderid) else {
+            return json!({"status": "error", "message": format!("Order {} not found", orderid)});
+        };
+        let (order_status, average_price) = match order.fill {
+            MockFill::Complete(price) => ("complete", price),
+            MockFill::Open => ("open", 0.0),
+            MockFill::Rejected => ("rejected", 0.0),
+        };
+        let request = &order.request;
+        json!({
+            "status": "success",
+            "data": {
+                "orderid": order.orderid,
+                "symbol": text(request, "symbol"),
+                "exchange": text(request, "exchange"),
+                "action": text(request, "action"),
+                "quantity": text(request, "quantity"),
+                "pricetype": text(request, "pricetype"),
+                "product": text(request, "product"),
+                "order_status": order_status,
+                "average_price": average_price,
+                "price": request.get("price").and_then(|p| p.as_f64()).unwrap_or(0.0),
+            }
+        })
+    }
+}
@@ -0,0 +1,312 @@
+//! Exposure netting across strategies.
+//!
+//! When several strategies trade the same symbol from one account, their
+//! opposing orders can be coalesced before they reach the broker: strategy A
+//! buying 50 while strategy B sells 30 becomes one order to buy 20. A
+//! `NettingDesk` queues child orders, sends one net market order per symbol,
+//! exchange and product on `flush`, and keeps each strategy's virtual position
+//! in a `VirtualBook`, so every strategy still sees its own fills.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::netting::{ChildOrder, NettingDesk};
+//!
+//! # async fn example(client: openalgo::OpenAlgo) {
+//! let desk = NettingDesk::new(&client, "netted");
+//! desk.submit(ChildOrder::new("StrategyA", "SBIN", "NSE", "MIS", "BUY", 50));
+//! desk.submit(ChildOrder::new("StrategyB", "SBIN", "NSE", "MIS", "SELL", 30));
+//! for (net, result) in desk.flush().await {
+//!     println!("{} {} {}: {:?}", net.action, net.quantity, net.symbol, result);
+//! }
+//! let a = desk.book().position("StrategyA", "SBIN", "NSE", "MIS");
+//! # }
+//! ```
+
+use crate::client::OpenAlgoError;
+use crate::price;
+use crate::OpenAlgo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// Order a strategy wants to send, before netting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChildOrder {
+    pub strategy: String,
+    pub symbol: String,
+    pub exchange: String,
+    pub product: String,
+    /// "BUY" or "SELL"
+    pub action: String,
+    pub quantity: i64,
+}
+
+impl ChildOrder {
+    /// A child order; the action is upper-cased
+    pub fn new(strategy: &str, symbol: &str, exchange: &str, product: &str, action: &str, quantity: i64) -> Self {
+        Self {
+            strategy: strategy.to_string(),
+            symbol: symbol.to_string(),
+            exchange: exchange.to_string(),
+            product: product.to_string(),
+            action: action.to_ascii_uppercase(),
+            quantity,
+        }
+    }
+
+    /// Quantity signed by action: positive for buys, negative for sells
+    pub fn signed_quantity(&self) -> i64 {
+        if self.action == "SELL" {
+            -self.quantity
+        } else {
+            self.quantity
+        }
+    }
+}
+
+/// One broker order netting the child orders of a symbol, exchange and product
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetOrder {
+    pub symbol: String,
+    pub exchange: String,
+    pub product: String,
+    /// "BUY" or "SELL"; "BUY" when the children cancel out
+    pub action: String,
+    /// Quantity sent to the broker; zero when the children cancel out
+    pub quantity: i64,
+    pub children: Vec<ChildOrder>,
+}
+
+impl NetOrder {
+    /// Quantity crossed between strategies without reaching the broker
+    pub fn crossed_quantity(&self) -> i64 {
+        let gross: i64 = self.children.iter().map(|c| c.quantity.abs()).sum();
+        (gross - self.quantity) / 2
+    }
+}
+
+/// Net child orders per symbol, exchange and product, in first-seen order
+///
+/// # Example
+/// ```rust
+/// use openalgo::netting::{net_orders, ChildOrder};
+///
+/// let nets = net_orders(&[
+///     ChildOrder::new("A", "SBIN", "NSE", "MIS", "BUY", 50),
+///     ChildOrder::new("B", "SBIN", "NSE", "MIS", "SELL", 30),
+///     ChildOrder::new("C", "INFY", "NSE", "MIS", "SELL", 10),
+/// ]);
+/// assert_eq!((nets[0].action.as_str(), nets[0].quantity), ("BUY", 20));
+/// assert_eq!(nets[0].crossed_quantity(), 30);
+/// assert_eq!((nets[1].action.as_str(), nets[1].quantity), ("SELL", 10));
+/// ```
+pub fn net_orders(children: &[ChildOrder]) -> Vec<NetOrder> {
+    let mut nets: Vec<NetOrder> = Vec::new();
+    for child in children {
+        let net = match nets
+            .iter_mut()
+            .find(|n| n.symbol == child.symbol && n.exchange == child.exchange && n.product == child.product)
+        {
+            Some(net) => net,
+            None => {
+                nets.push(NetOrder {
+                    symbol: child.symbol.clone(),
+                    exchange: child.exchange.clone(),
+                    product: child.product.clone(),
+                    action: String::new(),
+                    quantity: 0,
+                    children: Vec::new(),
+                });
+                nets.last_mut().unwrap()
+            }
+        };
+        net.children.push(child.clone());
+    }
+    for net in &mut nets {
+        let signed: i64 = net.children.iter().map(ChildOrder::signed_quantity).sum();
+        net.action = if signed < 0 { "SELL" } else { "BUY" }.to_string();
+        net.quantity = signed.abs();
+    }
+    nets
+}
+
+/// Position a strategy holds inside the netted account
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VirtualPosition {
+    pub strategy: String,
+    pub symbol: String,
+    pub exchange: String,
+    pub product: String,
+    /// Positive for long, negative for short
+    pub quantity: i64,
+    /// Average entry price, when fills were priced
+    pub average_price: Option<f64>,
+    pub realized_pnl: f64,
+}
+
+impl VirtualPosition {
+    fn apply(&mut self, signed: i64, price: Option<f64>) {
+        let before = self.quantity;
+        let after = before + signed;
+        if let (Some(price), Some(average)) = (price, self.average_price) {
+            if before != 0 && before.signum() != signed.signum() {
+                let closed = signed.abs().min(before.abs()) as f64;
+                self.realized_pnl += closed * (price - average) * before.signum() as f64;
+            }
+        }
+        self.average_price = match price {
+            _ if after == 0 => None,
+            // Flipped or opened: the remainder is at the fill price
+            Some(price) if before == 0 || before.signum() != after.signum() => Some(price),
+            // Added to the position: weighted average
+            Some(price) if before.signum() == signed.signum() => self
+                .average_price
+                .map(|avg| (avg * before.abs() as f64 + price * signed.abs() as f64) / after.abs() as f64),
+            // Reduced: entry price unchanged
+            _ => self.average_price,
+        };
+        self.quantity = after;
+    }
+}
+
+/// Virtual positions of every strategy trading through a desk
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VirtualBook {
+    positions: BTreeMap<(String, String, String, String), VirtualPosition>,
+}
+
+impl VirtualBook {
+    /// Record a child order as filled at `price`, if known
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::netting::{ChildOrder, VirtualBook};
+    ///
+    /// let mut book = VirtualBook::default();
+    /// book.apply(&ChildOrder::new("A", "SBIN", "NSE", "MIS", "BUY", 50), Some(800.0));
+    /// book.apply(&ChildOrder::new("A", "SBIN", "NSE", "MIS", "SELL", 20), Some(810.0));
+    /// let a = book.position("A", "SBIN", "NSE", "MIS").unwrap();
+    /// assert_eq!(a.quantity, 30);
+    /// assert_eq!(a.average_price, Some(800.0));
+    /// assert_eq!(a.realized_pnl, 200.0);
+    /// ```
+    pub fn apply(&mut self, child: &ChildOrder, price: Option<f64>) {
+        let key = (
+            child.strategy.clone(),
+            child.symbol.clone(),
+            child.exchange.clone(),
+            child.product.clone(),
+        );
+        self.positions
+            .entry(key)
+            .or_insert_with(|| VirtualPosition {
+                strategy: child.strategy.clone(),
+                symbol: child.symbol.clone(),
+                exchange: child.exchange.clone(),
+                product: child.product.clone(),
+                ..VirtualPosition::default()
+            })
+            .apply(child.signed_quantity(), price);
+    }
+
+    /// Position of a strategy in a symbol
+    pub fn position(&self, strategy: &str, symbol: &str, exchange: &str, product: &str) -> Option<&VirtualPosition> {
+        self.positions
+            .get(&(strategy.to_string(), symbol.to_string(), exchange.to_string(), product.to_string()))
+    }
+
+    /// All positions of a strategy
+    pub fn strategy_positions(&self, strategy: &str) -> Vec<&VirtualPosition> {
+        self.positions.values().filter(|p| p.strategy == strategy).collect()
+    }
+
+    /// Sum of all strategies' quantities in a symbol; matches the broker position of the netted account
+    pub fn net_quantity(&self, symbol: &str, exchange: &str, product: &str) -> i64 {
+        self.positions
+            .values()
+            .filter(|p| p.symbol == symbol && p.exchange == exchange && p.product == product)
+            .map(|p| p.quantity)
+            .sum()
+    }
+
+    /// Every position
+    pub fn positions(&self) -> impl Iterator<Item = &VirtualPosition> {
+        self.positions.values()
+    }
+}
+
+/// Queues child orders from several strategies and sends them netted
+///
+/// Clones share the queue and the virtual book.
+#[derive(Clone)]
+pub struct NettingDesk {
+    client: OpenAlgo,
+    strategy: String,
+    pending: Arc<Mutex<Vec<ChildOrder>>>,
+    book: Arc<Mutex<VirtualBook>>,
+}
+
+impl NettingDesk {
+    /// A desk sending net orders under the strategy name `strategy`
+    pub fn new(client: &OpenAlgo, strategy: &str) -> Self {
+        Self {
+            client: client.clone(),
+            strategy: strategy.to_string(),
+            pending: Arc::default(),
+            book: Arc::default(),
+        }
+    }
+
+    /// Queue a child order for the next `flush`
+    pub fn submit(&self, child: ChildOrder) {
+        self.pending.lock().unwrap().push(child);
+    }
+
+    /// Child orders waiting for the next `flush`
+    pub fn pending(&self) -> Vec<ChildOrder> {
+        self.pending.lock().unwrap().clone()
+    }
+
+    /// Snapshot of the strategies' virtual positions
+    pub fn book(&self) -> VirtualBook {
+        self.book.lock().unwrap().clone()
+    }
+
+    /// Net the queued child orders and send one market order per symbol
+    ///
+    /// Each result holds the broker order ID, or `None` when the children
+    /// cancelled out and nothing was sent. Children of a successful net order
+    /// are booked at the symbol's LTP (unpriced if the quote fails); children
+    /// of a failed one are dropped and not booked.
+    pub async fn flush(&self) -> Vec<(NetOrder, Result<Option<String>, OpenAlgoError>)> {
+        let children = std::mem::take(&mut *self.pending.lock().unwrap());
+        let mut results = Vec::new();
+        for net in net_orders(&children) {
+            let result = if net.quantity == 0 {
+                Ok(None)
+            } else {
+                self.client
+                    .place_order(
+                        &self.strategy,
+                        &net.symbol,
+                        &net.action,
+                        &net.exchange,
+                        "MARKET",
+                        &net.product,
+                        &net.quantity.to_string(),
+                    )
+                    .await
+                    .map(|response| response.orderid)
+            };
+            if result.is_ok() {
+                let price = self.client.ltp(&net.symbol, &net.exchange).await.ok().map(price::to_f64);
+                let mut book = self.book.lock().unwrap();
+                for child in &net.children {
+                    book.apply(child, price);
+                }
+            }
+            results.push((net, result));
+        }
+        results
+    }
+}
@@ -0,0 +1,356 @@
+//! Parameter sweeps and walk-forward optimization for backtests.
+//!
+//! The optimizer is independent of how a strategy is simulated: it calls a
+//! function with a parameter set and a range of bar indices, and the function
+//! returns the strategy's per-bar returns over that range (from replaying
+//! candles, the [`crate::simulator`] fill model, or any other backtest).
+//! `Optimizer::sweep` evaluates every combination of a `ParamGrid` with
+//! bounded parallelism; `Optimizer::walk_forward` picks the best parameters
+//! on each training window and measures them on the following test window.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::optimize::{walk_forward_splits, Optimizer, ParamGrid};
+//!
+//! let closes: Vec<f64> = (0..300).map(|i| 100.0 + (i as f64 / 7.0).sin() * 5.0 + i as f64 * 0.05).collect();
+//! // Long while the close is above its `lookback`-bar average
+//! let backtest = |params: &openalgo::optimize::Params, bars: std::ops::Range<usize>| -> Vec<f64> {
+//!     let lookback = params["lookback"] as usize;
+//!     bars.filter(|&i| i >= lookback && i + 1 < closes.len())
+//!         .map(|i| {
+//!             let average = closes[i - lookback..i].iter().sum::<f64>() / lookback as f64;
+//!             if closes[i] > average { closes[i + 1] / closes[i] - 1.0 } else { 0.0 }
+//!         })
+//!         .collect()
+//! };
+//!
+//! let optimizer = Optimizer::new(ParamGrid::new().range("lookback", 5.0, 30.0, 5.0)).concurrency(2);
+//! let results = optimizer.sweep(0..closes.len(), backtest);
+//! assert_eq!(results.len(), 6);
+//!
+//! let report = optimizer.walk_forward(&walk_forward_splits(closes.len(), 100, 50, false), backtest);
+//! assert_eq!(report.steps.len(), 4);
+//! println!("out-of-sample Sharpe {:.2}", report.out_of_sample.sharpe);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// One parameter combination, by name
+pub type Params = BTreeMap<String, f64>;
+
+/// Values to try for each named parameter
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParamGrid {
+    axes: Vec<(String, Vec<f64>)>,
+}
+
+impl ParamGrid {
+    /// An empty grid, with one empty combination
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Try each of `values` for `name`
+    pub fn axis(mut self, name: &str, values: impl IntoIterator<Item = f64>) -> Self {
+        self.axes.push((name.to_string(), values.into_iter().collect()));
+        self
+    }
+
+    /// Try `start`, `start + step`, ... up to and including `end`
+    pub fn range(self, name: &str, start: f64, end: f64, step: f64) -> Self {
+        let count = if step > 0.0 && end >= start { ((end - start) / step + 1e-9).floor() as usize + 1 } else { 1 };
+        self.axis(name, (0..count).map(|i| start + step * i as f64))
+    }
+
+    /// Every combination, varying the last axis fastest
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::optimize::ParamGrid;
+    ///
+    /// let grid = ParamGrid::new().axis("fast", [5.0, 10.0]).range("slow", 20.0, 40.0, 10.0);
+    /// let combinations = grid.combinations();
+    /// assert_eq!(grid.len(), 6);
+    /// assert_eq!(combinations[1]["fast"], 5.0);
+    /// assert_eq!(combinations[1]["slow"], 30.0);
+    /// ```
+    pub fn combinations(&self) -> Vec<Params> {
+        let mut combinations = vec![Params::new()];
+        for (name, values) in &self.axes {
+            combinations = combinations
+                .iter()
+                .flat_map(|params| {
+                    values.iter().map(move |value| {
+                        let mut params = params.clone();
+                        params.insert(name.clone(), *value);
+                        params
+                    })
+                })
+                .collect();
+        }
+        combinations
+    }
+
+    /// Number of combinations
+    pub fn len(&self) -> usize {
+        self.axes.iter().map(|(_, values)| values.len()).product()
+    }
+
+    /// Whether the grid has no combination
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Training and test bar ranges of one walk-forward step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Split {
+    pub train: Range<usize>,
+    pub test: Range<usize>,
+}
+
+/// Walk-forward splits over `len` bars
+///
+/// Each step trains on `train` bars and tests on the next `test` bars, then
+/// moves forward by `test`. Anchored splits keep the training window starting
+/// at bar 0 and grow it instead. A trailing test window shorter than `test` is
+/// dropped.
+///
+/// # Example
+/// ```rust
+/// use openalgo::optimize::walk_forward_splits;
+///
+/// let rolling = walk_forward_splits(10, 4, 2, false);
+/// assert_eq!(rolling.len(), 3);
+/// assert_eq!((rolling[1].train.clone(), rolling[1].test.clone()), (2..6, 6..8));
+///
+/// let anchored = walk_forward_splits(10, 4, 2, true);
+/// assert_eq!((anchored[2].train.clone(), anchored[2].test.clone()), (0..8, 8..10));
+/// ```
+pub fn walk_forward_splits(len: usize, train: usize, test: usize, anchored: bool) -> Vec<Split> {
+    let mut splits = Vec::new();
+    if train == 0 || test == 0 {
+        return splits;
+    }
+    let mut start = 0;
+    while start + train + test <= len {
+        let train_end = start + train;
+        splits.push(Split {
+            train: if anchored { 0..train_end } else { start..train_end },
+            test: train_end..train_end + test,
+        });
+        start += test;
+    }
+    splits
+}
+
+/// Performance statistics of a per-bar return series
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceMetrics {
+    /// Number of returns
+    pub periods: usize,
+    /// Compounded return over all periods
+    pub total_return: f64,
+    pub annualized_return: f64,
+    /// Annualized standard deviation of returns
+    pub volatility: f64,
+    /// Annualized mean return over volatility; zero when flat
+    pub sharpe: f64,
+    /// Largest peak-to-trough fall of the compounded equity, as a positive fraction
+    pub max_drawdown: f64,
+    /// Share of non-zero returns that were positive
+    pub win_rate: f64,
+}
+
+impl PerformanceMetrics {
+    /// Metrics of `returns` with `periods_per_year` bars a year
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::optimize::PerformanceMetrics;
+    ///
+    /// let metrics = PerformanceMetrics::from_returns(&[0.1, -0.5, 0.2, 0.0], 252.0);
+    /// assert!((metrics.total_return - (1.1 * 0.5 * 1.2 - 1.0)).abs() < 1e-12);
+    /// assert!((metrics.max_drawdown - 0.5).abs() < 1e-12);
+    /// assert!((metrics.win_rate - 2.0 / 3.0).abs() < 1e-12);
+    /// ```
+    pub fn from_returns(returns: &[f64], periods_per_year: f64) -> Self {
+        let periods = returns.len();
+        if periods == 0 {
+            return Self::default();
+        }
+        let mut equity = 1.0_f64;
+        let mut peak = 1.0_f64;
+        let mut max_drawdown = 0.0_f64;
+        for r in returns {
+            equity *= 1.0 + r;
+            peak = peak.max(equity);
+            max_drawdown = max_drawdown.max(1.0 - equity / peak);
+        }
+        let mean = returns.iter().sum::<f64>() / periods as f64;
+        let variance = if periods > 1 {
+            returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (periods - 1) as f64
+        } else {
+            0.0
+        };
+        let volatility = variance.sqrt() * periods_per_year.sqrt();
+        let (wins, trades) = returns
+            .iter()
+            .filter(|r| **r != 0.0)
+            .fold((0, 0), |(wins, trades), r| (wins + usize::from(*r > 0.0), trades + 1));
+        Self {
+            periods,
+            total_return: equity - 1.0,
+            annualized_return: if equity > 0.0 { equity.powf(periods_per_year / periods as f64) - 1.0 } else { -1.0 },
+            volatility,
+            sharpe: if volatility > 0.0 { mean * periods_per_year / volatility } else { 0.0 },
+            max_drawdown,
+            win_rate: if trades > 0 { wins as f64 / trades as f64 } else { 0.0 },
+        }
+    }
+}
+
+/// Metrics of one parameter combination
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepResult {
+    pub params: Params,
+    pub metrics: PerformanceMetrics,
+}
+
+/// One walk-forward step: the parameters chosen in training and how they did out of sample
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardStep {
+    pub split: Split,
+    pub params: Params,
+    pub train: PerformanceMetrics,
+    pub test: PerformanceMetrics,
+}
+
+/// Result of a walk-forward optimization
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardReport {
+    pub steps: Vec<WalkForwardStep>,
+    /// Test windows' returns, concatenated in order
+    pub test_returns: Vec<f64>,
+    /// Metrics of `test_returns`
+    pub out_of_sample: PerformanceMetrics,
+}
+
+/// Runs a backtest function over a parameter grid
+#[derive(Debug, Clone)]
+pub struct Optimizer {
+    grid: ParamGrid,
+    concurrency: usize,
+    periods_per_year: f64,
+    objective: fn(&PerformanceMetrics) -> f64,
+}
+
+impl Optimizer {
+    /// Optimizer over `grid`, ranking by Sharpe ratio on daily bars (252 a year)
+    pub fn new(grid: ParamGrid) -> Self {
+        Self {
+            grid,
+            concurrency: 0,
+            periods_per_year: 252.0,
+            objective: |metrics| metrics.sharpe,
+        }
+    }
+
+    /// Backtests run at once; zero uses the available cores
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Bars per year, used to annualize metrics
+    pub fn periods_per_year(mut self, periods: f64) -> Self {
+        self.periods_per_year = periods;
+        self
+    }
+
+    /// Score to maximize when choosing parameters
+    pub fn objective(mut self, objective: fn(&PerformanceMetrics) -> f64) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Evaluate every combination over `bars`, in grid order
+    pub fn sweep<F>(&self, bars: Range<usize>, backtest: F) -> Vec<SweepResult>
+    where
+        F: Fn(&Params, Range<usize>) -> Vec<f64> + Sync,
+    {
+        let combinations = self.grid.combinations();
+        parallel_map(&combinations, self.concurrency, |params| SweepResult {
+            params: params.clone(),
+            metrics: PerformanceMetrics::from_returns(&backtest(params, bars.clone()), self.periods_per_year),
+        })
+    }
+
+    /// Highest-scoring result by the objective; the first one on ties
+    pub fn best<'a>(&self, results: &'a [SweepResult]) -> Option<&'a SweepResult> {
+        results.iter().fold(None, |best: Option<&SweepResult>, result| match best {
+            Some(best) if (self.objective)(&best.metrics) >= (self.objective)(&result.metrics) => Some(best),
+            _ => Some(result),
+        })
+    }
+
+    /// Optimize on each split's training window and test the winner on its test window
+    pub fn walk_forward<F>(&self, splits: &[Split], backtest: F) -> WalkForwardReport
+    where
+        F: Fn(&Params, Range<usize>) -> Vec<f64> + Sync,
+    {
+        let mut report = WalkForwardReport::default();
+        for split in splits {
+            let results = self.sweep(split.train.clone(), &backtest);
+            let Some(best) = self.best(&results) else { continue };
+            let returns = backtest(&best.params, split.test.clone());
+            report.steps.push(WalkForwardStep {
+                split: split.clone(),
+                params: best.params.clone(),
+                train: best.metrics.clone(),
+                test: PerformanceMetrics::from_returns(&returns, self.periods_per_year),
+            });
+            report.test_returns.extend(returns);
+        }
+        report.out_of_sample = PerformanceMetrics::from_returns(&report.test_returns, self.periods_per_year);
+        report
+    }
+}
+
+/// Map `items` on up to `concurrency` threads, keeping input order
+#[cfg(not(target_arch = "wasm32"))]
+fn parallel_map<T: Sync, R: Send>(items: &[T], concurrency: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    let workers = if concurrency == 0 {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        concurrency
+    }
+    .min(items.len());
+    if workers <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else { break };
+                let result = f(item);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+    results.into_inner().unwrap().into_iter().flatten().collect()
+}
+
+/// Map `items` in order; the browser has no threads to spread them over
+#[cfg(target_arch = "wasm32")]
+fn parallel_map<T: Sync, R: Send>(items: &[T], _concurrency: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    items.iter().map(f).collect()
+}
@@ -0,0 +1,569 @@
+//! Option pricing helpers for OpenAlgo.
+//!
+//! This module contains a local Black-Scholes implementation used to compute
+//! implied volatility from option chain prices without a server round-trip,
+//! and `StrikeResolver`, which resolves ATM and offset strikes from one option
+//! chain instead of calling `option_symbol` per strike.
+
+use crate::calendar::days_from_civil;
+use crate::client::OpenAlgoError;
+use crate::futures::derivatives_exchange;
+use crate::price::{self, Price};
+use crate::types::{OptionChainResponse, OptionChainStrike};
+use serde::{Deserialize, Serialize};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds in a (365 day) year, used for time-to-expiry calculations
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+/// Option type (call or put)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OptionType {
+    CE,
+    PE,
+}
+
+impl OptionType {
+    /// Parse an option type from the OpenAlgo string form ("CE"/"PE")
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_uppercase().as_str() {
+            "CE" | "CALL" | "C" => Some(OptionType::CE),
+            "PE" | "PUT" | "P" => Some(OptionType::PE),
+            _ => None,
+        }
+    }
+
+    /// OpenAlgo string form of the option type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OptionType::CE => "CE",
+            OptionType::PE => "PE",
+        }
+    }
+}
+
+/// Standard normal probability density function
+pub fn norm_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Numerator coefficients for Hart's normal CDF approximation (highest power first)
+const HART_N: [f64; 7] = [
+    0.035_262_496_599_891_1,
+    0.700_383_064_443_688,
+    6.373_962_203_531_65,
+    33.912_866_078_383,
+    112.079_291_497_871,
+    221.213_596_169_931,
+    220.206_867_912_376,
+];
+
+/// Denominator coefficients for Hart's normal CDF approximation (highest power first)
+const HART_D: [f64; 8] = [
+    0.088_388_347_648_318_4,
+    1.755_667_163_182_64,
+    16.064_177_579_207,
+    86.780_732_202_946_1,
+    296.564_248_779_674,
+    637.333_633_378_831,
+    793.826_512_519_948,
+    440.413_735_824_752,
+];
+
+/// Standard normal cumulative distribution function (Hart's double precision algorithm)
+pub fn norm_cdf(x: f64) -> f64 {
+    let z = x.abs();
+    let c = if z > 37.0 {
+        0.0
+    } else {
+        let e = (-z * z / 2.0).exp();
+        if z < 7.071_067_811_865_475 {
+            let n = HART_N.iter().fold(0.0, |acc, c| acc * z + c);
+            let d = HART_D.iter().fold(0.0, |acc, c| acc * z + c);
+            e * n / d
+        } else {
+            let f = z + 1.0 / (z + 2.0 / (z + 3.0 / (z + 4.0 / (z + 0.65))));
+            e / (f * 2.506_628_274_631)
+        }
+    };
+
+    if x > 0.0 {
+        1.0 - c
+    } else {
+        c
+    }
+}
+
+/// Black-Scholes d1 and d2 terms
+fn d1_d2(spot: f64, strike: f64, time_to_expiry: f64, rate: f64, volatility: f64) -> (f64, f64) {
+    let sqrt_t = time_to_expiry.sqrt();
+    let d1 = ((spot / strike).ln() + (rate + 0.5 * volatility * volatility) * time_to_expiry)
+        / (volatility * sqrt_t);
+    (d1, d1 - volatility * sqrt_t)
+}
+
+/// Black-Scholes theoretical price of a European option
+///
+/// # Arguments
+///
+/// * `spot` - Underlying price
+/// * `strike` - Strike price
+/// * `time_to_expiry` - Time to expiry in years
+/// * `rate` - Risk-free rate as a decimal (0.065 for 6.5%)
+/// * `volatility` - Volatility as a decimal (0.15 for 15%)
+/// * `option_type` - Call or put
+pub fn black_scholes_price(
+    spot: f64,
+    strike: f64,
+    time_to_expiry: f64,
+    rate: f64,
+    volatility: f64,
+    option_type: OptionType,
+) -> f64 {
+    if time_to_expiry <= 0.0 || volatility <= 0.0 {
+        return intrinsic_value(spot, strike, option_type);
+    }
+
+    let (d1, d2) = d1_d2(spot, strike, time_to_expiry, rate, volatility);
+    let discount = (-rate * time_to_expiry).exp();
+
+    match option_type {
+        OptionType::CE => spot * norm_cdf(d1) - strike * discount * norm_cdf(d2),
+        OptionType::PE => strike * discount * norm_cdf(-d2) - spot * norm_cdf(-d1),
+    }
+}
+
+/// Black-Scholes vega (price change per 1.00 change in volatility)
+pub fn black_scholes_vega(spot: f64, strike: f64, time_to_expiry: f64, rate: f64, volatility: f64) -> f64 {
+    if time_to_expiry <= 0.0 || volatility <= 0.0 {
+        return 0.0;
+    }
+    let (d1, _) = d1_d2(spot, strike, time_to_expiry, rate, volatility);
+    spot * norm_pdf(d1) * time_to_expiry.sqrt()
+}
+
+/// Black-Scholes delta (price change per 1.00 change in the underlying)
+///
+/// # Example
+/// ```rust
+/// use openalgo::options::{black_scholes_delta, OptionType};
+/// let call = black_scholes_delta(24000.0, 24000.0, 30.0 / 365.0, 0.065, 0.15, OptionType::CE);
+/// let put = black_scholes_delta(24000.0, 24000.0, 30.0 / 365.0, 0.065, 0.15, OptionType::PE);
+/// assert!(call > 0.5 && call < 0.6);
+/// assert!((call - put - 1.0).abs() < 1e-9);
+/// ```
+pub fn black_scholes_delta(
+    spot: f64,
+    strike: f64,
+    time_to_expiry: f64,
+    rate: f64,
+    volatility: f64,
+    option_type: OptionType,
+) -> f64 {
+    if time_to_expiry <= 0.0 || volatility <= 0.0 {
+        let itm = match option_type {
+            OptionType::CE => spot > strike,
+            OptionType::PE => spot < strike,
+        };
+        return match (itm, option_type) {
+            (false, _) => 0.0,
+            (true, OptionType::CE) => 1.0,
+            (true, OptionType::PE) => -1.0,
+        };
+    }
+    let (d1, _) = d1_d2(spot, strike, time_to_expiry, rate, volatility);
+    match option_type {
+        OptionType::CE => norm_cdf(d1),
+        OptionType::PE => norm_cdf(d1) - 1.0,
+    }
+}
+
+/// Black-Scholes gamma (delta change per 1.00 change in the underlying)
+pub fn black_scholes_gamma(spot: f64, strike: f64, time_to_expiry: f64, rate: f64, volatility: f64) -> f64 {
+    if time_to_expiry <= 0.0 || volatility <= 0.0 {
+        return 0.0;
+    }
+    let (d1, _) = d1_d2(spot, strike, time_to_expiry, rate, volatility);
+    norm_pdf(d1) / (spot * volatility * time_to_expiry.sqrt())
+}
+
+/// Black-Scholes theta per calendar day
+pub fn black_scholes_theta(
+    spot: f64,
+    strike: f64,
+    time_to_expiry: f64,
+    rate: f64,
+    volatility: f64,
+    option_type: OptionType,
+) -> f64 {
+    if time_to_expiry <= 0.0 || volatility <= 0.0 {
+        return 0.0;
+    }
+    let (d1, d2) = d1_d2(spot, strike, time_to_expiry, rate, volatility);
+    let decay = -spot * norm_pdf(d1) * volatility / (2.0 * time_to_expiry.sqrt());
+    let carry = rate * strike * (-rate * time_to_expiry).exp();
+    let annual = match option_type {
+        OptionType::CE => decay - carry * norm_cdf(d2),
+        OptionType::PE => decay + carry * norm_cdf(-d2),
+    };
+    annual / 365.0
+}
+
+/// Intrinsic value of an option
+pub fn intrinsic_value(spot: f64, strike: f64, option_type: OptionType) -> f64 {
+    match option_type {
+        OptionType::CE => (spot - strike).max(0.0),
+        OptionType::PE => (strike - spot).max(0.0),
+    }
+}
+
+/// Compute implied volatility from an option price
+///
+/// Uses Newton-Raphson on vega with a bisection fallback. In-the-money
+/// options are solved through their out-of-the-money counterpart (put-call
+/// parity), whose price is the time value alone. Returns `None` when the
+/// price is outside the no-arbitrage bounds, inputs are invalid (including no
+/// time left to expiry), or no volatility between 0.01% and 500% reproduces
+/// the price.
+///
+/// # Example
+/// ```rust
+/// use openalgo::options::{black_scholes_price, implied_volatility, OptionType};
+/// let price = black_scholes_price(24000.0, 24200.0, 30.0 / 365.0, 0.065, 0.14, OptionType::CE);
+/// let iv = implied_volatility(price, 24000.0, 24200.0, 30.0 / 365.0, 0.065, OptionType::CE).unwrap();
+/// assert!((iv - 0.14).abs() < 1e-6);
+///
+/// // Deep in and deep out of the money
+/// let t = 30.0 / 365.0;
+/// let itm = black_scholes_price(24000.0, 28000.0, t, 0.065, 0.15, OptionType::PE);
+/// assert!((implied_volatility(itm, 24000.0, 28000.0, t, 0.065, OptionType::PE).unwrap() - 0.15).abs() < 1e-6);
+/// let otm = black_scholes_price(24000.0, 20000.0, t, 0.065, 0.15, OptionType::PE);
+/// assert!((implied_volatility(otm, 24000.0, 20000.0, t, 0.065, OptionType::PE).unwrap() - 0.15).abs() < 1e-6);
+///
+/// // No time to expiry, and a price no volatility up to 500% reaches
+/// assert_eq!(implied_volatility(150.0, 24000.0, 24000.0, 0.0, 0.065, OptionType::CE), None);
+/// let wild = black_scholes_price(24000.0, 24000.0, t, 0.065, 8.0, OptionType::CE);
+/// assert_eq!(implied_volatility(wild, 24000.0, 24000.0, t, 0.065, OptionType::CE), None);
+/// ```
+pub fn implied_volatility(
+    price: f64,
+    spot: f64,
+    strike: f64,
+    time_to_expiry: f64,
+    rate: f64,
+    option_type: OptionType,
+) -> Option<f64> {
+    if !(price > 0.0 && spot > 0.0 && strike > 0.0 && time_to_expiry > 0.0) {
+        return None;
+    }
+
+    let discount = (-rate * time_to_expiry).exp();
+    let lower = match option_type {
+        OptionType::CE => (spot - strike * discount).max(0.0),
+        OptionType::PE => (strike * discount - spot).max(0.0),
+    };
+    let upper = match option_type {
+        OptionType::CE => spot,
+        OptionType::PE => strike * discount,
+    };
+    if price <= lower || price >= upper {
+        return None;
+    }
+
+    // C - P = S - K e^(-rT), so an ITM option's counterpart is worth its time value
+    let (price, option_type) = if lower > 0.0 {
+        let counterpart = match option_type {
+            OptionType::CE => OptionType::PE,
+            OptionType::PE => OptionType::CE,
+        };
+        (price - lower, counterpart)
+    } else {
+        (price, option_type)
+    };
+
+    const TOLERANCE: f64 = 1e-10;
+    let (mut low, mut high) = (1e-4, 5.0);
+    let mut vol = 0.2;
+
+    for _ in 0..200 {
+        let diff = black_scholes_price(spot, strike, time_to_expiry, rate, vol, option_type) - price;
+        if diff.abs() <= TOLERANCE * price {
+            return Some(vol);
+        }
+
+        if diff > 0.0 {
+            high = vol;
+        } else {
+            low = vol;
+        }
+        if high - low < 1e-12 {
+            break;
+        }
+
+        let vega = black_scholes_vega(spot, strike, time_to_expiry, rate, vol);
+        let newton = vol - diff / vega;
+        vol = if vega > 1e-10 && newton > low && newton < high {
+            newton
+        } else {
+            0.5 * (low + high)
+        };
+    }
+
+    // A collapsed bracket only counts if the price was actually reached
+    let diff = black_scholes_price(spot, strike, time_to_expiry, rate, vol, option_type) - price;
+    (diff.abs() <= 1e-6 * price).then_some(vol)
+}
+
+/// Parse an OpenAlgo expiry date into the Unix timestamp of the expiry close
+///
+/// Accepts the formats used across OpenAlgo endpoints ("26DEC24", "26-DEC-24",
+/// "26DEC2024", "26-DEC-2024"). Expiry is taken as 15:30 IST on that date.
+pub fn parse_expiry_date(expiry: &str) -> Option<i64> {
+    let cleaned: String = expiry.chars().filter(|c| *c != '-').collect();
+    if cleaned.len() < 7 || !cleaned.is_ascii() {
+        return None;
+    }
+
+    let day: u32 = cleaned[0..2].parse().ok()?;
+    let month = match cleaned[2..5].to_ascii_uppercase().as_str() {
+        "JAN" => 1,
+        "FEB" => 2,
+        "MAR" => 3,
+        "APR" => 4,
+        "MAY" => 5,
+        "JUN" => 6,
+        "JUL" => 7,
+        "AUG" => 8,
+        "SEP" => 9,
+        "OCT" => 10,
+        "NOV" => 11,
+        "DEC" => 12,
+        _ => return None,
+    };
+    let year: i64 = match cleaned.len() {
+        7 => 2000 + cleaned[5..7].parse::<i64>().ok()?,
+        9 => cleaned[5..9].parse().ok()?,
+        _ => return None,
+    };
+    if !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // 15:30 IST is 10:00 UTC
+    Some(days_from_civil(year, month, day) * 86_400 + 10 * 3_600)
+}
+
+/// Time to expiry in years from now, or `None` if the date is invalid
+pub fn time_to_expiry(expiry: &str) -> Option<f64> {
+    let expiry_ts = parse_expiry_date(expiry)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs_f64();
+    Some((expiry_ts as f64 - now) / SECONDS_PER_YEAR)
+}
+
+/// Smallest gap between consecutive strikes of a chain
+///
+/// # Example
+/// ```rust
+/// use openalgo::options::strike_interval;
+/// assert_eq!(strike_interval(&[24100.0, 24000.0, 24050.0, 24300.0]), Some(50.0));
+/// assert_eq!(strike_interval(&[24000.0]), None);
+/// ```
+pub fn strike_interval(strikes: &[f64]) -> Option<f64> {
+    let mut sorted: Vec<f64> = strikes.iter().copied().filter(|s| *s > 0.0).collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .filter(|gap| *gap > 1e-9)
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+/// Strike `offset` steps from ATM, where positive offsets are out of the money
+///
+/// ATM is the multiple of `interval` nearest the underlying price. For calls
+/// OTM strikes are above ATM; for puts they are below. Returns `None` when
+/// the interval is not positive or the strike would not be positive.
+///
+/// # Example
+/// ```rust
+/// use openalgo::options::{offset_strike, OptionType};
+/// assert_eq!(offset_strike(24_032.0, 50.0, 0, OptionType::CE), Some(24_050.0));
+/// assert_eq!(offset_strike(24_032.0, 50.0, 2, OptionType::CE), Some(24_150.0));
+/// assert_eq!(offset_strike(24_032.0, 50.0, 2, OptionType::PE), Some(23_950.0));
+/// assert_eq!(offset_strike(24_032.0, 50.0, -1, OptionType::PE), Some(24_100.0));
+/// ```
+pub fn offset_strike(underlying_ltp: f64, interval: f64, offset: i32, option_type: OptionType) -> Option<f64> {
+    if interval <= 0.0 || !underlying_ltp.is_finite() {
+        return None;
+    }
+    let atm = (underlying_ltp / interval).round() * interval;
+    let steps = match option_type {
+        OptionType::CE => offset as f64,
+        OptionType::PE => -(offset as f64),
+    };
+    Some(atm + steps * interval).filter(|strike| *strike > 0.0)
+}
+
+/// OpenAlgo option symbol (e.g. "NIFTY26DEC2424000CE")
+///
+/// Returns `None` when the convention is ambiguous: an expiry that is not a
+/// day-month-year date like "26DEC24", or a fractional strike, whose
+/// formatting differs between brokers.
+///
+/// # Example
+/// ```rust
+/// use openalgo::options::{format_option_symbol, OptionType};
+/// assert_eq!(format_option_symbol("NIFTY", "26-DEC-24", 24000.0, OptionType::CE).as_deref(), Some("NIFTY26DEC2424000CE"));
+/// assert_eq!(format_option_symbol("NIFTY", "26DEC2024", 24000.0, OptionType::PE).as_deref(), Some("NIFTY26DEC2424000PE"));
+/// assert_eq!(format_option_symbol("NIFTY", "241226", 24000.0, OptionType::CE), None);
+/// assert_eq!(format_option_symbol("ITC", "26DEC24", 452.5, OptionType::CE), None);
+/// ```
+pub fn format_option_symbol(underlying: &str, expiry: &str, strike: f64, option_type: OptionType) -> Option<String> {
+    parse_expiry_date(expiry)?;
+    if strike.fract().abs() > 1e-9 || strike <= 0.0 {
+        return None;
+    }
+    let cleaned: String = expiry.chars().filter(|c| *c != '-').collect::<String>().to_ascii_uppercase();
+    let compact = format!("{}{}", &cleaned[..5], &cleaned[cleaned.len() - 2..]);
+    Some(format!("{}{}{}{}", underlying, compact, strike as i64, option_type.as_str()))
+}
+
+/// Option contract picked by a `StrikeResolver` or the `optionsymbol` endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedOption {
+    pub symbol: String,
+    pub exchange: String,
+    /// Strike, when resolved locally
+    pub strike: Option<f64>,
+    pub lotsize: Option<i32>,
+    pub tick_size: Option<Price>,
+}
+
+/// Resolves ATM and offset strikes locally from one option chain
+///
+/// Built once per expiry with `DataAPI::strike_resolver` (or `from_chain`),
+/// it derives the strike interval from the chain and turns an offset into a
+/// symbol without a round-trip. Update the underlying price with `set_ltp`,
+/// e.g. from a WebSocket feed. `DataAPI::resolve_option` falls back to the
+/// `optionsymbol` endpoint when `resolve` returns `None`.
+///
+/// # Example
+/// ```rust
+/// use openalgo::options::{OptionType, StrikeResolver};
+/// use openalgo::OptionChainResponse;
+///
+/// let chain: OptionChainResponse = serde_json::from_str(r#"{
+///     "status": "success",
+///     "underlying_ltp": 24032.5,
+///     "chain": [
+///         {"strike": 24000, "ce": {"symbol": "NIFTY26DEC2424000CE", "lotsize": 75}, "pe": {"symbol": "NIFTY26DEC2424000PE", "lotsize": 75}},
+///         {"strike": 24050, "ce": {"symbol": "NIFTY26DEC2424050CE", "lotsize": 75}, "pe": {"symbol": "NIFTY26DEC2424050PE", "lotsize": 75}},
+///         {"strike": 24100, "ce": {"symbol": "NIFTY26DEC2424100CE", "lotsize": 75}, "pe": {"symbol": "NIFTY26DEC2424100PE", "lotsize": 75}}
+///     ]
+/// }"#).unwrap();
+///
+/// let mut resolver = StrikeResolver::from_chain("NIFTY", "NSE_INDEX", "26DEC24", &chain).unwrap();
+/// assert_eq!(resolver.strike_interval, Some(50.0));
+///
+/// let atm = resolver.resolve(0, OptionType::CE).unwrap();
+/// assert_eq!(atm.symbol, "NIFTY26DEC2424050CE");
+/// assert_eq!(atm.exchange, "NFO");
+/// assert_eq!(atm.lotsize, Some(75));
+///
+/// // Strikes outside the fetched chain are formatted locally
+/// assert_eq!(resolver.resolve(3, OptionType::CE).unwrap().symbol, "NIFTY26DEC2424200CE");
+///
+/// resolver.set_ltp(24_010.0);
+/// assert_eq!(resolver.resolve(1, OptionType::PE).unwrap().symbol, "NIFTY26DEC2423950PE");
+/// ```
+#[derive(Debug, Clone)]
+pub struct StrikeResolver {
+    pub underlying: String,
+    /// Exchange of the underlying (e.g. "NSE_INDEX")
+    pub exchange: String,
+    pub expiry_date: String,
+    pub underlying_ltp: f64,
+    /// Smallest strike gap in the chain; `None` for a chain with fewer than two strikes
+    pub strike_interval: Option<f64>,
+    strikes: Vec<OptionChainStrike>,
+}
+
+impl StrikeResolver {
+    /// Build a resolver from an option chain response
+    pub fn from_chain(
+        underlying: &str,
+        exchange: &str,
+        expiry_date: &str,
+        chain: &OptionChainResponse,
+    ) -> Result<Self, OpenAlgoError> {
+        let strikes = chain.chain.clone().ok_or_else(|| {
+            OpenAlgoError::ApiError(
+                chain
+                    .message
+                    .clone()
+                    .unwrap_or_else(|| "Option chain has no data".to_string())
+                    .into(),
+            )
+        })?;
+        let underlying_ltp = chain
+            .underlying_ltp
+            .map(price::to_f64)
+            .ok_or_else(|| OpenAlgoError::ApiError("Option chain has no underlying LTP".into()))?;
+        let strike_values: Vec<f64> = strikes.iter().map(|s| price::to_f64(s.strike)).collect();
+
+        Ok(Self {
+            underlying: underlying.to_string(),
+            exchange: exchange.to_string(),
+            expiry_date: expiry_date.to_string(),
+            underlying_ltp,
+            strike_interval: strike_interval(&strike_values),
+            strikes,
+        })
+    }
+
+    /// Update the underlying price used to find ATM
+    pub fn set_ltp(&mut self, underlying_ltp: f64) {
+        self.underlying_ltp = underlying_ltp;
+    }
+
+    /// Strike `offset` steps from ATM (positive is OTM, negative is ITM)
+    pub fn strike(&self, offset: i32, option_type: OptionType) -> Option<f64> {
+        offset_strike(self.underlying_ltp, self.strike_interval?, offset, option_type)
+    }
+
+    /// Resolve an offset locally, or `None` when the symbol cannot be determined without the server
+    ///
+    /// Strikes listed in the chain use the chain's symbol, lot and tick size;
+    /// other strikes are formatted with `format_option_symbol`.
+    pub fn resolve(&self, offset: i32, option_type: OptionType) -> Option<ResolvedOption> {
+        let strike = self.strike(offset, option_type)?;
+        let exchange = derivatives_exchange(&self.exchange).to_string();
+        let listed = self
+            .strikes
+            .iter()
+            .find(|s| (price::to_f64(s.strike) - strike).abs() < 1e-6)
+            .and_then(|s| match option_type {
+                OptionType::CE => s.ce.as_ref(),
+                OptionType::PE => s.pe.as_ref(),
+            });
+        if let Some(data) = listed {
+            if let Some(symbol) = &data.symbol {
+                return Some(ResolvedOption {
+                    symbol: symbol.clone(),
+                    exchange,
+                    strike: Some(strike),
+                    lotsize: data.lotsize,
+                    tick_size: data.tick_size,
+                });
+            }
+        }
+
+        Some(ResolvedOption {
+            symbol: format_option_symbol(&self.underlying, &self.expiry_date, strike, option_type)?,
+            exchange,
+            strike: Some(strike),
+            lotsize: None,
+            tick_size: None,
+        })
+    }
+}
@@ -1,10 +1,18 @@
 //! Order API module for OpenAlgo.
 
-use crate::client::{OpenAlgoClient, OpenAlgoError};
+use crate::client::{sleep, OpenAlgoClient, OpenAlgoError};
+use crate::account::AccountAPI;
+use crate::data::DataAPI;
+use crate::holdings;
+use crate::price::Price;
+use crate::retry::{self, OrderRecovery};
 use crate::types::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use web_time::{SystemTime, UNIX_EPOCH};
 
 /// Order API client
+#[derive(Clone)]
 pub struct OrderAPI {
     client: Arc<OpenAlgoClient>,
 }
@@ -16,6 +24,7 @@ impl OrderAPI {
     }
 
     /// Place an order (simple form)
+    #[allow(clippy::too_many_arguments)]
     pub async fn place_order(
         &self,
         strategy: &str,
@@ -27,7 +36,7 @@ impl OrderAPI {
         quantity: &str,
     ) -> Result<OrderResponse, OpenAlgoError> {
         let request = PlaceOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
             symbol: symbol.to_string(),
             action: action.to_string(),
@@ -38,12 +47,19 @@ impl OrderAPI {
             price: None,
             trigger_price: None,
             disclosed_quantity: None,
+            validity: None,
+            amo: false,
         };
+        self.check_order(symbol, exchange, quantity, &[]).await?;
+        self.check_cnc_sell(symbol, action, product, quantity).await?;
 
-        self.client.post("placeorder", &request).await
+        let response = self.client.post("placeorder", &request).await;
+        self.client.position_cache().invalidate(&request.symbol, &request.exchange, &request.product);
+        response
     }
 
     /// Place a limit order with price
+    #[allow(clippy::too_many_arguments)]
     pub async fn place_limit_order(
         &self,
         strategy: &str,
@@ -55,7 +71,7 @@ impl OrderAPI {
         price: &str,
     ) -> Result<OrderResponse, OpenAlgoError> {
         let request = PlaceOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
             symbol: symbol.to_string(),
             action: action.to_string(),
@@ -66,12 +82,19 @@ impl OrderAPI {
             price: Some(price.to_string()),
             trigger_price: None,
             disclosed_quantity: None,
+            validity: None,
+            amo: false,
         };
+        self.check_order(symbol, exchange, quantity, &[price]).await?;
+        self.check_cnc_sell(symbol, action, product, quantity).await?;
 
-        self.client.post("placeorder", &request).await
+        let response = self.client.post("placeorder", &request).await;
+        self.client.position_cache().invalidate(&request.symbol, &request.exchange, &request.product);
+        response
     }
 
     /// Place a stop-loss order
+    #[allow(clippy::too_many_arguments)]
     pub async fn place_sl_order(
         &self,
         strategy: &str,
@@ -84,7 +107,7 @@ impl OrderAPI {
         trigger_price: &str,
     ) -> Result<OrderResponse, OpenAlgoError> {
         let request = PlaceOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
             symbol: symbol.to_string(),
             action: action.to_string(),
@@ -95,12 +118,122 @@ impl OrderAPI {
             price: Some(price.to_string()),
             trigger_price: Some(trigger_price.to_string()),
             disclosed_quantity: None,
+            validity: None,
+            amo: false,
         };
+        self.check_order(symbol, exchange, quantity, &[price, trigger_price]).await?;
+        self.check_cnc_sell(symbol, action, product, quantity).await?;
 
-        self.client.post("placeorder", &request).await
+        let response = self.client.post("placeorder", &request).await;
+        self.client.position_cache().invalidate(&request.symbol, &request.exchange, &request.product);
+        response
+    }
+
+    /// Place an after-market order, queued by the broker for the next session
+    ///
+    /// Pass a `price` for LIMIT orders and `None` for MARKET orders.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_amo_order(
+        &self,
+        strategy: &str,
+        symbol: &str,
+        action: &str,
+        exchange: &str,
+        pricetype: &str,
+        product: &str,
+        quantity: &str,
+        price: Option<&str>,
+    ) -> Result<OrderResponse, OpenAlgoError> {
+        let mut request = PlaceOrderRequest::new(strategy, symbol, action, exchange, pricetype, product, quantity).amo(true);
+        request.price = price.map(str::to_string);
+
+        self.place_order_request(request).await
+    }
+
+    /// Send a prepared order, e.g. one built from a `PositionbookPosition`
+    ///
+    /// The client's API key is used when `apikey` is empty.
+    pub async fn place_order_request(
+        &self,
+        mut request: PlaceOrderRequest,
+    ) -> Result<OrderResponse, OpenAlgoError> {
+        if request.apikey.is_empty() {
+            request.apikey = self.client.api_key();
+        }
+        request.validate()?;
+        let prices: Vec<&str> = [&request.price, &request.trigger_price].into_iter().flatten().map(String::as_str).collect();
+        self.check_order(&request.symbol, &request.exchange, &request.quantity, &prices).await?;
+        self.check_cnc_sell(&request.symbol, &request.action, &request.product, &request.quantity).await?;
+
+        let response = self.client.post("placeorder", &request).await;
+        self.client.position_cache().invalidate(&request.symbol, &request.exchange, &request.product);
+        response
+    }
+
+    /// Place an order, retrying transient failures without placing it twice
+    ///
+    /// After a timeout or connection error the order may already be at the
+    /// broker. The orderbook is read once before the first attempt, and each
+    /// retry is preceded by another check: a matching order that was not
+    /// there before (see `retry::find_placed_order`) is returned instead of
+    /// sending a second one. A recovered response has status "success", the
+    /// order's ID and no message. If the orderbook cannot be read, the
+    /// original error is returned; if it cannot be read before the first
+    /// attempt, the order is sent once without retries.
+    pub async fn place_order_recovering(
+        &self,
+        request: PlaceOrderRequest,
+        recovery: &OrderRecovery,
+    ) -> Result<OrderResponse, OpenAlgoError> {
+        let now = || SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+        let account = AccountAPI::new(Arc::clone(&self.client));
+        let known: Option<HashSet<String>> = match account.orderbook().await {
+            Ok(orderbook) => {
+                let orders = orderbook.data.and_then(|d| d.orders).unwrap_or_default();
+                Some(retry::matching_orders(&orders, &request).filter_map(|o| o.orderid.clone()).collect())
+            }
+            Err(e) => {
+                log::warn!("Could not read the orderbook before placing order {}, not retrying: {}", request.symbol, e);
+                None
+            }
+        };
+        let since = now() - recovery.window.as_secs() as i64;
+        let mut attempt = 0;
+        loop {
+            let error = match self.place_order_request(request.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(e) => e,
+            };
+            let retryable = attempt < recovery.max_retries && retry::is_transient(&error);
+            let Some(known) = known.as_ref().filter(|_| retryable) else {
+                return Err(error);
+            };
+            attempt += 1;
+            sleep(recovery.settle).await;
+
+            let orderbook = match account.orderbook().await {
+                Ok(orderbook) => orderbook,
+                Err(check) => {
+                    log::warn!("Could not check the orderbook for order {}: {}", request.symbol, check);
+                    return Err(error);
+                }
+            };
+            let orders = orderbook.data.and_then(|d| d.orders).unwrap_or_default();
+            if let Some(order) = retry::find_placed_order(&orders, &request, known, since) {
+                log::warn!("Order for {} reached the broker despite {}; not retrying", request.symbol, error);
+                return Ok(OrderResponse {
+                    status: "success".to_string(),
+                    orderid: order.orderid.clone(),
+                    message: None,
+                    extra: HashMap::new(),
+                });
+            }
+            log::warn!("Retrying order for {} after {}", request.symbol, error);
+        }
     }
 
     /// Place a smart order
+    #[allow(clippy::too_many_arguments)]
     pub async fn place_smart_order(
         &self,
         strategy: &str,
@@ -113,7 +246,7 @@ impl OrderAPI {
         position_size: &str,
     ) -> Result<OrderResponse, OpenAlgoError> {
         let request = PlaceSmartOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
             symbol: symbol.to_string(),
             action: action.to_string(),
@@ -123,11 +256,27 @@ impl OrderAPI {
             quantity: quantity.to_string(),
             position_size: position_size.to_string(),
         };
+        self.check_order(symbol, exchange, quantity, &[]).await?;
+        if let Some(max_age) = self.client.smart_order_cache_age() {
+            let cache = self.client.position_cache();
+            let target = position_size.trim().parse::<i64>().ok();
+            if target.is_some() && cache.get(symbol, exchange, product, max_age) == target {
+                return Ok(OrderResponse {
+                    status: "success".to_string(),
+                    orderid: None,
+                    message: Some("Position already at the target size; no order sent".to_string()),
+                    extra: HashMap::new(),
+                });
+            }
+        }
 
-        self.client.post("placesmartorder", &request).await
+        let response = self.client.post("placesmartorder", &request).await;
+        self.client.position_cache().invalidate(symbol, exchange, product);
+        response
     }
 
     /// Place an options order
+    #[allow(clippy::too_many_arguments)]
     pub async fn options_order(
         &self,
         strategy: &str,
@@ -143,7 +292,7 @@ impl OrderAPI {
         splitsize: &str,
     ) -> Result<OptionsOrderResponse, OpenAlgoError> {
         let request = OptionsOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
             underlying: underlying.to_string(),
             exchange: exchange.to_string(),
@@ -157,7 +306,10 @@ impl OrderAPI {
             splitsize: splitsize.to_string(),
         };
 
-        self.client.post("optionsorder", &request).await
+        let response = self.client.post("optionsorder", &request).await;
+        // The server resolves the option symbols, so any cached position may be stale
+        self.client.position_cache().clear();
+        response
     }
 
     /// Place a multi-leg options order
@@ -170,7 +322,7 @@ impl OrderAPI {
         legs: Vec<OptionsLeg>,
     ) -> Result<OptionsMultiOrderResponse, OpenAlgoError> {
         let request = OptionsMultiOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
             underlying: underlying.to_string(),
             exchange: exchange.to_string(),
@@ -178,7 +330,10 @@ impl OrderAPI {
             legs,
         };
 
-        self.client.post("optionsmultiorder", &request).await
+        let response = self.client.post("optionsmultiorder", &request).await;
+        // The server resolves the option symbols, so any cached position may be stale
+        self.client.position_cache().clear();
+        response
     }
 
     /// Place basket orders
@@ -187,16 +342,37 @@ impl OrderAPI {
         strategy: &str,
         orders: Vec<BasketOrderItem>,
     ) -> Result<BasketOrderResponse, OpenAlgoError> {
+        for order in &orders {
+            self.check_order(&order.symbol, &order.exchange, &order.quantity.to_string(), &[]).await?;
+        }
+        if self.client.guards_cnc_sells() {
+            // Several sells of one symbol draw on the same holding
+            let mut sells: BTreeMap<&str, i64> = BTreeMap::new();
+            for order in orders.iter().filter(|o| holdings::is_cnc_sell(&o.action, &o.product)) {
+                *sells.entry(order.symbol.as_str()).or_default() += i64::from(order.quantity);
+            }
+            if !sells.is_empty() {
+                let sellable = AccountAPI::new(Arc::clone(&self.client)).sellable_quantities().await?;
+                for (symbol, quantity) in sells {
+                    holdings::check_cnc_sell(&sellable, symbol, quantity)?;
+                }
+            }
+        }
         let request = BasketOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
             orders,
         };
 
-        self.client.post("basketorder", &request).await
+        let response = self.client.post("basketorder", &request).await;
+        for order in &request.orders {
+            self.client.position_cache().invalidate(&order.symbol, &order.exchange, &order.product);
+        }
+        response
     }
 
     /// Place split orders
+    #[allow(clippy::too_many_arguments)]
     pub async fn split_order(
         &self,
         strategy: &str,
@@ -209,7 +385,7 @@ impl OrderAPI {
         product: &str,
     ) -> Result<SplitOrderResponse, OpenAlgoError> {
         let request = SplitOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
             symbol: symbol.to_string(),
             action: action.to_string(),
@@ -219,11 +395,17 @@ impl OrderAPI {
             pricetype: pricetype.to_string(),
             product: product.to_string(),
         };
+        self.check_order(symbol, exchange, &quantity.to_string(), &[]).await?;
+        self.check_order(symbol, exchange, &splitsize.to_string(), &[]).await?;
+        self.check_cnc_sell(symbol, action, product, &quantity.to_string()).await?;
 
-        self.client.post("splitorder", &request).await
+        let response = self.client.post("splitorder", &request).await;
+        self.client.position_cache().invalidate(symbol, exchange, product);
+        response
     }
 
     /// Modify an order
+    #[allow(clippy::too_many_arguments)]
     pub async fn modify_order(
         &self,
         orderid: &str,
@@ -237,7 +419,7 @@ impl OrderAPI {
         price: &str,
     ) -> Result<OrderResponse, OpenAlgoError> {
         let request = ModifyOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             orderid: orderid.to_string(),
             strategy: strategy.to_string(),
             symbol: symbol.to_string(),
@@ -249,11 +431,56 @@ impl OrderAPI {
             price: price.to_string(),
             disclosed_quantity: None,
             trigger_price: None,
+            validity: None,
         };
+        self.check_order(symbol, exchange, quantity, &[price]).await?;
+
+        self.client.post("modifyorder", &request).await
+    }
+
+    /// Send a prepared modify request, e.g. one built from an `OrderbookOrder`
+    ///
+    /// The client's API key is used when `apikey` is empty.
+    pub async fn modify_order_request(
+        &self,
+        mut request: ModifyOrderRequest,
+    ) -> Result<OrderResponse, OpenAlgoError> {
+        if request.apikey.is_empty() {
+            request.apikey = self.client.api_key();
+        }
+        request.validate()?;
+        let prices: Vec<&str> = [Some(&request.price), request.trigger_price.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        self.check_order(&request.symbol, &request.exchange, &request.quantity, &prices).await?;
 
         self.client.post("modifyorder", &request).await
     }
 
+    /// Check quantity and prices against the symbol's lot and tick size when `validate_orders` is on
+    ///
+    /// Values that do not parse as numbers are left for the server to reject.
+    async fn check_order(&self, symbol: &str, exchange: &str, quantity: &str, prices: &[&str]) -> Result<(), OpenAlgoError> {
+        if !self.client.validates_orders() {
+            return Ok(());
+        }
+        let info = DataAPI::new(Arc::clone(&self.client)).symbol_cached(symbol, exchange).await?;
+        let quantity = quantity.trim().parse().unwrap_or(0);
+        let prices: Vec<Price> = prices.iter().filter_map(|p| p.trim().parse().ok()).collect();
+        Ok(info.validate_order(quantity, &prices)?)
+    }
+
+    /// Check a CNC sell against holdings when `guard_cnc_sells` is on
+    async fn check_cnc_sell(&self, symbol: &str, action: &str, product: &str, quantity: &str) -> Result<(), OpenAlgoError> {
+        if !self.client.guards_cnc_sells() || !holdings::is_cnc_sell(action, product) {
+            return Ok(());
+        }
+        let quantity = quantity.trim().parse().unwrap_or(0);
+        AccountAPI::new(Arc::clone(&self.client)).check_cnc_sell(symbol, quantity).await
+    }
+
     /// Cancel an order
     pub async fn cancel_order(
         &self,
@@ -261,7 +488,7 @@ impl OrderAPI {
         strategy: &str,
     ) -> Result<OrderResponse, OpenAlgoError> {
         let request = CancelOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             orderid: orderid.to_string(),
             strategy: strategy.to_string(),
         };
@@ -275,7 +502,7 @@ impl OrderAPI {
         strategy: &str,
     ) -> Result<CancelAllOrderResponse, OpenAlgoError> {
         let request = CancelAllOrderRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
         };
 
@@ -288,13 +515,15 @@ impl OrderAPI {
         strategy: &str,
     ) -> Result<StatusResponse, OpenAlgoError> {
         let request = ClosePositionRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
             product: None,
             symbolgroup: None,
         };
 
-        self.client.post("closeposition", &request).await
+        let response = self.client.post("closeposition", &request).await;
+        self.client.position_cache().clear();
+        response
     }
 
     /// Get order status
@@ -304,7 +533,7 @@ impl OrderAPI {
         strategy: &str,
     ) -> Result<OrderStatusResponse, OpenAlgoError> {
         let request = OrderStatusRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             orderid: orderid.to_string(),
             strategy: strategy.to_string(),
         };
@@ -321,7 +550,7 @@ impl OrderAPI {
         product: &str,
     ) -> Result<OpenPositionResponse, OpenAlgoError> {
         let request = OpenPositionRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             strategy: strategy.to_string(),
             symbol: symbol.to_string(),
             exchange: exchange.to_string(),
@@ -330,4 +559,22 @@ impl OrderAPI {
 
         self.client.post("openposition", &request).await
     }
+
+    /// Net quantity of an open position, as an integer
+    ///
+    /// The result is stored in the client's position cache.
+    pub async fn open_position_quantity(
+        &self,
+        strategy: &str,
+        symbol: &str,
+        exchange: &str,
+        product: &str,
+    ) -> Result<i64, OpenAlgoError> {
+        let response = self.open_position(strategy, symbol, exchange, product).await?;
+        let quantity = response.net_quantity().ok_or_else(|| {
+            OpenAlgoError::ApiError(format!("Invalid open position quantity: {:?}", response.quantity).into())
+        })?;
+        self.client.position_cache().set(symbol, exchange, product, quantity);
+        Ok(quantity)
+    }
 }
@@ -0,0 +1,313 @@
+//! Event-sourced persistence for strategy state.
+//!
+//! A long-running strategy records every event that changes its state
+//! (ticks, fills, signals) in a `StateStore`: an append-only JSONL event log
+//! plus a periodic snapshot of the state, both in one directory. On restart,
+//! `recover` loads the snapshot and replays the events logged after it, so
+//! the strategy resumes exactly where it stopped. `PersistentState` wraps a
+//! state type and does the logging, replay and snapshotting.
+//!
+//! Snapshots are written to a temporary file and renamed into place, and
+//! every event carries a sequence number, so a crash at any point leaves a
+//! recoverable directory. A torn last line in the log is skipped.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::persistence::{PersistentState, StrategyEvent, StrategyState};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Default, Serialize, Deserialize)]
+//! struct Momentum {
+//!     position: i64,
+//!     last_price: f64,
+//! }
+//!
+//! impl StrategyState for Momentum {
+//!     fn apply(&mut self, event: &StrategyEvent) {
+//!         match event {
+//!             StrategyEvent::Tick { ltp, .. } => self.last_price = *ltp,
+//!             StrategyEvent::Fill { action, quantity, .. } => {
+//!                 self.position += if action == "SELL" { -quantity } else { *quantity };
+//!             }
+//!             _ => {}
+//!         }
+//!     }
+//! }
+//!
+//! # fn example() -> std::io::Result<()> {
+//! let mut state = PersistentState::<Momentum>::open("state/momentum")?.snapshot_every(1_000);
+//! println!("recovered position {}", state.state().position);
+//! state.apply(StrategyEvent::Tick {
+//!     symbol: "SBIN".into(),
+//!     exchange: "NSE".into(),
+//!     ltp: 801.5,
+//!     timestamp: 1733111100,
+//! })?;
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const LOG_FILE: &str = "events.jsonl";
+const SNAPSHOT_FILE: &str = "snapshot.json";
+
+/// Something that happened to a strategy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StrategyEvent {
+    /// Market price update
+    Tick {
+        symbol: String,
+        exchange: String,
+        ltp: f64,
+        /// Unix seconds or milliseconds
+        timestamp: i64,
+    },
+    /// Order fill
+    Fill {
+        orderid: String,
+        symbol: String,
+        exchange: String,
+        /// "BUY" or "SELL"
+        action: String,
+        quantity: i64,
+        price: f64,
+        timestamp: i64,
+    },
+    /// Signal produced by the strategy
+    Signal {
+        name: String,
+        symbol: String,
+        value: f64,
+        timestamp: i64,
+    },
+    /// Strategy-specific event
+    Custom { name: String, data: serde_json::Value },
+}
+
+/// Strategy state rebuilt by replaying events
+pub trait StrategyState: Serialize + DeserializeOwned + Default {
+    /// Update the state with one event
+    fn apply(&mut self, event: &StrategyEvent);
+}
+
+/// Logged event with its sequence number
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedEvent {
+    pub seq: u64,
+    pub event: StrategyEvent,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot<S> {
+    /// Sequence number of the last event included in the state
+    seq: u64,
+    state: S,
+}
+
+/// Event log and snapshot of one strategy, in a directory
+#[derive(Debug)]
+pub struct StateStore {
+    dir: PathBuf,
+    log: Mutex<(File, u64)>,
+}
+
+impl StateStore {
+    /// Open the store in `dir`, creating the directory if needed
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let snapshot_seq = read_snapshot_seq(&dir.join(SNAPSHOT_FILE))?;
+        let last_seq = read_log(&dir.join(LOG_FILE))?
+            .last()
+            .map_or(0, |e| e.seq)
+            .max(snapshot_seq);
+        let file = OpenOptions::new().create(true).append(true).open(dir.join(LOG_FILE))?;
+        Ok(Self {
+            dir,
+            log: Mutex::new((file, last_seq)),
+        })
+    }
+
+    /// Directory of the store
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Append an event and return its sequence number
+    pub fn append(&self, event: &StrategyEvent) -> io::Result<u64> {
+        let mut log = self.log.lock().unwrap();
+        let seq = log.1 + 1;
+        let line = serde_json::to_string(&LoggedEvent { seq, event: event.clone() })?;
+        writeln!(log.0, "{}", line)?;
+        log.0.flush()?;
+        log.1 = seq;
+        Ok(seq)
+    }
+
+    /// Sequence number of the last event appended
+    pub fn last_seq(&self) -> u64 {
+        self.log.lock().unwrap().1
+    }
+
+    /// Save `state` as including every event so far, then clear the log
+    pub fn snapshot<S: Serialize>(&self, state: &S) -> io::Result<()> {
+        let mut log = self.log.lock().unwrap();
+        let snapshot = Snapshot { seq: log.1, state };
+        let temp = self.dir.join(format!("{}.tmp", SNAPSHOT_FILE));
+        fs::write(&temp, serde_json::to_vec(&snapshot)?)?;
+        fs::rename(&temp, self.dir.join(SNAPSHOT_FILE))?;
+        log.0 = File::create(self.dir.join(LOG_FILE))?;
+        Ok(())
+    }
+
+    /// Events logged after the last snapshot, in order
+    pub fn events(&self) -> io::Result<Vec<LoggedEvent>> {
+        let snapshot_seq = read_snapshot_seq(&self.dir.join(SNAPSHOT_FILE))?;
+        let _log = self.log.lock().unwrap();
+        Ok(read_log(&self.dir.join(LOG_FILE))?
+            .into_iter()
+            .filter(|e| e.seq > snapshot_seq)
+            .collect())
+    }
+
+    /// Rebuild the state: the last snapshot (or the default) with later events replayed
+    pub fn recover<S: StrategyState>(&self) -> io::Result<S> {
+        let mut state = match fs::read(self.dir.join(SNAPSHOT_FILE)) {
+            Ok(bytes) => serde_json::from_slice::<Snapshot<S>>(&bytes)?.state,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => S::default(),
+            Err(e) => return Err(e),
+        };
+        for logged in self.events()? {
+            state.apply(&logged.event);
+        }
+        Ok(state)
+    }
+}
+
+fn read_snapshot_seq(path: &Path) -> io::Result<u64> {
+    #[derive(Deserialize)]
+    struct Seq {
+        seq: u64,
+    }
+    match fs::read(path) {
+        Ok(bytes) => Ok(serde_json::from_slice::<Seq>(&bytes)?.seq),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e),
+    }
+}
+
+fn read_log(path: &Path) -> io::Result<Vec<LoggedEvent>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut events = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(event) => events.push(event),
+            Err(e) => log::warn!("persistence: skipping unreadable event in {}: {}", path.display(), e),
+        }
+    }
+    Ok(events)
+}
+
+/// Strategy state kept in step with its event log
+///
+/// # Example
+/// ```rust
+/// use openalgo::persistence::{PersistentState, StrategyEvent, StrategyState};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Default, Serialize, Deserialize)]
+/// struct Signals(Vec<f64>);
+///
+/// impl StrategyState for Signals {
+///     fn apply(&mut self, event: &StrategyEvent) {
+///         if let StrategyEvent::Signal { value, .. } = event {
+///             self.0.push(*value);
+///         }
+///     }
+/// }
+///
+/// let dir = std::env::temp_dir().join(format!("openalgo-state-{}", std::process::id()));
+/// let signal = |value| StrategyEvent::Signal { name: "z".into(), symbol: "SBIN".into(), value, timestamp: 0 };
+/// {
+///     let mut state = PersistentState::<Signals>::open(&dir).unwrap().snapshot_every(2);
+///     for value in [1.0, 2.0, 3.0] {
+///         state.apply(signal(value)).unwrap();
+///     }
+/// }
+/// // After a restart: snapshot of the first two, then the third replayed
+/// let state = PersistentState::<Signals>::open(&dir).unwrap();
+/// assert_eq!(state.state().0, vec![1.0, 2.0, 3.0]);
+/// assert_eq!(state.store().events().unwrap().len(), 1);
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct PersistentState<S> {
+    store: StateStore,
+    state: S,
+    snapshot_every: u64,
+    since_snapshot: u64,
+}
+
+impl<S: StrategyState> PersistentState<S> {
+    /// Open the store in `dir` and recover the state from it
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let store = StateStore::open(dir)?;
+        let state = store.recover()?;
+        let since_snapshot = store.events()?.len() as u64;
+        Ok(Self {
+            store,
+            state,
+            snapshot_every: 0,
+            since_snapshot,
+        })
+    }
+
+    /// Snapshot automatically after every `events` events; zero (the default) never does
+    pub fn snapshot_every(mut self, events: u64) -> Self {
+        self.snapshot_every = events;
+        self
+    }
+
+    /// Log an event, then apply it to the state
+    pub fn apply(&mut self, event: StrategyEvent) -> io::Result<()> {
+        self.store.append(&event)?;
+        self.state.apply(&event);
+        self.since_snapshot += 1;
+        if self.snapshot_every > 0 && self.since_snapshot >= self.snapshot_every {
+            self.snapshot()?;
+        }
+        Ok(())
+    }
+
+    /// Snapshot the state now and clear the log
+    pub fn snapshot(&mut self) -> io::Result<()> {
+        self.store.snapshot(&self.state)?;
+        self.since_snapshot = 0;
+        Ok(())
+    }
+
+    /// Current state
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Underlying store
+    pub fn store(&self) -> &StateStore {
+        &self.store
+    }
+}
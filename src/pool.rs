@@ -0,0 +1,260 @@
+//! WebSocket connection pool for OpenAlgo.
+//!
+//! Shards subscriptions across several WebSocket connections when they
+//! exceed a per-connection symbol limit, merging all data into one stream
+//! and moving subscriptions to healthy connections when one drops.
+
+use crate::client::OpenAlgoError;
+use crate::types::*;
+use crate::websocket::{OpenAlgoWebSocket, WsData, WsMode, WsSubscriber};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// How often subscriptions without a connection are retried
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A single subscription: (mode, exchange, symbol)
+type Subscription = (WsMode, String, String);
+
+enum PoolCommand {
+    Subscribe(WsMode, Vec<WsInstrument>),
+    Unsubscribe(WsMode, Vec<WsInstrument>),
+    ConnectionCount(oneshot::Sender<usize>),
+    Disconnect,
+}
+
+#[allow(clippy::large_enum_variant)]
+enum PoolEvent {
+    Data(WsData),
+    Closed(usize),
+}
+
+struct PoolConnection {
+    subscriber: WsSubscriber,
+    subscriptions: HashSet<Subscription>,
+}
+
+/// Handle to a pool of WebSocket connections
+///
+/// Cloning the handle is cheap; the pool shuts down when `disconnect` is
+/// called or every handle is dropped.
+#[derive(Clone)]
+pub struct WsPool {
+    cmd_tx: mpsc::Sender<PoolCommand>,
+}
+
+impl WsPool {
+    /// Open the pool and return it with the merged data stream
+    ///
+    /// # Arguments
+    ///
+    /// * `ws` - WebSocket client used to open connections
+    /// * `max_symbols_per_connection` - Subscriptions allowed on one connection
+    pub async fn connect(
+        ws: OpenAlgoWebSocket,
+        max_symbols_per_connection: usize,
+    ) -> Result<(Self, mpsc::Receiver<WsData>), OpenAlgoError> {
+        let (cmd_tx, cmd_rx) = mpsc::channel::<PoolCommand>(32);
+        let (data_tx, data_rx) = mpsc::channel::<WsData>(128);
+        let (event_tx, event_rx) = mpsc::channel::<PoolEvent>(128);
+
+        let mut state = PoolState {
+            ws,
+            max_symbols: max_symbols_per_connection.max(1),
+            connections: Vec::new(),
+            pending: Vec::new(),
+            event_tx,
+        };
+        state.open_connection().await?;
+
+        let _ = data_tx.send(WsData::Connected).await;
+        tokio::spawn(state.run(cmd_rx, event_rx, data_tx));
+
+        Ok((Self { cmd_tx }, data_rx))
+    }
+
+    /// Subscribe instruments, opening connections as needed
+    pub async fn subscribe(&self, mode: WsMode, instruments: Vec<WsInstrument>) -> Result<(), OpenAlgoError> {
+        self.send(PoolCommand::Subscribe(mode, instruments)).await
+    }
+
+    /// Unsubscribe instruments from whichever connection holds them
+    pub async fn unsubscribe(&self, mode: WsMode, instruments: Vec<WsInstrument>) -> Result<(), OpenAlgoError> {
+        self.send(PoolCommand::Unsubscribe(mode, instruments)).await
+    }
+
+    /// Number of live connections in the pool
+    pub async fn connection_count(&self) -> Result<usize, OpenAlgoError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(PoolCommand::ConnectionCount(tx)).await?;
+        rx.await
+            .map_err(|e| OpenAlgoError::WebSocketError(e.to_string()))
+    }
+
+    /// Disconnect every connection in the pool
+    pub async fn disconnect(&self) -> Result<(), OpenAlgoError> {
+        self.send(PoolCommand::Disconnect).await
+    }
+
+    async fn send(&self, cmd: PoolCommand) -> Result<(), OpenAlgoError> {
+        self.cmd_tx
+            .send(cmd)
+            .await
+            .map_err(|e| OpenAlgoError::WebSocketError(e.to_string()))
+    }
+}
+
+struct PoolState {
+    ws: OpenAlgoWebSocket,
+    max_symbols: usize,
+    connections: Vec<Option<PoolConnection>>,
+    pending: Vec<Subscription>,
+    event_tx: mpsc::Sender<PoolEvent>,
+}
+
+impl PoolState {
+    async fn run(
+        mut self,
+        mut cmd_rx: mpsc::Receiver<PoolCommand>,
+        mut event_rx: mpsc::Receiver<PoolEvent>,
+        data_tx: mpsc::Sender<WsData>,
+    ) {
+        let mut retry = tokio::time::interval(RETRY_INTERVAL);
+
+        loop {
+            tokio::select! {
+                cmd = cmd_rx.recv() => match cmd {
+                    Some(PoolCommand::Subscribe(mode, instruments)) => {
+                        let subs = instruments
+                            .into_iter()
+                            .map(|i| (mode, i.exchange, i.symbol))
+                            .collect();
+                        self.assign(subs).await;
+                    }
+                    Some(PoolCommand::Unsubscribe(mode, instruments)) => {
+                        self.remove(mode, instruments).await;
+                    }
+                    Some(PoolCommand::ConnectionCount(reply)) => {
+                        let _ = reply.send(self.connections.iter().flatten().count());
+                    }
+                    Some(PoolCommand::Disconnect) | None => {
+                        for conn in self.connections.iter().flatten() {
+                            let _ = conn.subscriber.disconnect().await;
+                        }
+                        let _ = data_tx.send(WsData::Disconnected).await;
+                        break;
+                    }
+                },
+                Some(event) = event_rx.recv() => match event {
+                    PoolEvent::Data(WsData::Connected) | PoolEvent::Data(WsData::Disconnected) => {}
+                    PoolEvent::Data(data) => {
+                        if data_tx.send(data).await.is_err() {
+                            break;
+                        }
+                    }
+                    PoolEvent::Closed(index) => self.rebalance(index).await,
+                },
+                _ = retry.tick(), if !self.pending.is_empty() => {
+                    let pending = std::mem::take(&mut self.pending);
+                    self.assign(pending).await;
+                }
+            }
+        }
+    }
+
+    /// Open a connection and forward its data into the pool's event channel
+    async fn open_connection(&mut self) -> Result<usize, OpenAlgoError> {
+        let (cmd_tx, mut data_rx) = self.ws.connect().await?;
+        let index = self.connections.len();
+        let event_tx = self.event_tx.clone();
+
+        tokio::spawn(async move {
+            while let Some(data) = data_rx.recv().await {
+                if event_tx.send(PoolEvent::Data(data)).await.is_err() {
+                    return;
+                }
+            }
+            let _ = event_tx.send(PoolEvent::Closed(index)).await;
+        });
+
+        self.connections.push(Some(PoolConnection {
+            subscriber: WsSubscriber::new(cmd_tx),
+            subscriptions: HashSet::new(),
+        }));
+        Ok(index)
+    }
+
+    /// Place subscriptions on connections with spare capacity
+    async fn assign(&mut self, subs: Vec<Subscription>) {
+        let mut batches: HashMap<(usize, WsMode), Vec<WsInstrument>> = HashMap::new();
+
+        for sub in subs {
+            let already = self
+                .connections
+                .iter()
+                .flatten()
+                .any(|c| c.subscriptions.contains(&sub));
+            if already {
+                continue;
+            }
+
+            let slot = self.connections.iter().position(|c| {
+                c.as_ref()
+                    .map(|c| c.subscriptions.len() < self.max_symbols)
+                    .unwrap_or(false)
+            });
+            let index = match slot {
+                Some(index) => index,
+                None => match self.open_connection().await {
+                    Ok(index) => index,
+                    Err(e) => {
+                        log::warn!("WebSocket pool could not open a connection: {}", e);
+                        self.pending.push(sub);
+                        continue;
+                    }
+                },
+            };
+
+            if let Some(conn) = self.connections[index].as_mut() {
+                batches
+                    .entry((index, sub.0))
+                    .or_default()
+                    .push(WsInstrument::new(&sub.1, &sub.2));
+                conn.subscriptions.insert(sub);
+            }
+        }
+
+        for ((index, mode), instruments) in batches {
+            if let Some(conn) = &self.connections[index] {
+                let _ = conn.subscriber.subscribe(mode, instruments).await;
+            }
+        }
+    }
+
+    /// Remove subscriptions from their connections
+    async fn remove(&mut self, mode: WsMode, instruments: Vec<WsInstrument>) {
+        for instrument in instruments {
+            let sub = (mode, instrument.exchange.clone(), instrument.symbol.clone());
+            self.pending.retain(|p| *p != sub);
+            for conn in self.connections.iter_mut().flatten() {
+                if conn.subscriptions.remove(&sub) {
+                    let _ = conn.subscriber.unsubscribe(mode, vec![instrument.clone()]).await;
+                }
+            }
+        }
+    }
+
+    /// Move the subscriptions of a closed connection onto live connections
+    async fn rebalance(&mut self, index: usize) {
+        let Some(conn) = self.connections.get_mut(index).and_then(|c| c.take()) else {
+            return;
+        };
+        log::warn!(
+            "WebSocket pool connection {} closed, moving {} subscriptions",
+            index,
+            conn.subscriptions.len()
+        );
+        self.assign(conn.subscriptions.into_iter().collect()).await;
+    }
+}
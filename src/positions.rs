@@ -0,0 +1,228 @@
+//! Open position cache and watcher.
+//!
+//! Every client keeps the net quantity per (symbol, exchange, product) from
+//! its last positionbook or `openposition` lookup, shared with its copies.
+//! A `PositionWatcher` refreshes the cache from the positionbook in the
+//! background. With `OpenAlgoBuilder::smart_order_position_cache`,
+//! `place_smart_order` skips the call when the cached position already
+//! matches the target size.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::positions::PositionWatcher;
+//! use openalgo::OpenAlgo;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), openalgo::client::OpenAlgoError> {
+//! let client = OpenAlgo::builder("your-api-key")
+//!     .smart_order_position_cache(Duration::from_secs(10))
+//!     .build();
+//! let (mut changes, _handle) = PositionWatcher::new().interval(Duration::from_secs(5)).start(&client);
+//!
+//! // Sent only if the position is not already 10
+//! client.place_smart_order("Strategy1", "SBIN", "BUY", "NSE", "MARKET", "MIS", "10", "10").await?;
+//! while let Some(change) = changes.recv().await {
+//!     println!("{} {}: {} -> {}", change.symbol, change.product, change.previous, change.quantity);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::types::{OpenPositionResponse, PositionbookPosition};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use web_time::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::sync::mpsc;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::task::JoinHandle;
+
+type Key = (String, String, String);
+
+fn key(symbol: &str, exchange: &str, product: &str) -> Key {
+    (
+        symbol.trim().to_ascii_uppercase(),
+        exchange.trim().to_ascii_uppercase(),
+        product.trim().to_ascii_uppercase(),
+    )
+}
+
+/// Net quantity of a position, parsed from the positionbook
+pub(crate) fn position_quantity(position: &PositionbookPosition) -> i64 {
+    position.quantity.as_deref().and_then(|q| q.trim().parse().ok()).unwrap_or(0)
+}
+
+impl OpenPositionResponse {
+    /// Net quantity as an integer; `None` if missing or not a number
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::OpenPositionResponse;
+    /// let response: OpenPositionResponse = serde_json::from_str(r#"{"status": "success", "quantity": "-25"}"#).unwrap();
+    /// assert_eq!(response.net_quantity(), Some(-25));
+    /// ```
+    pub fn net_quantity(&self) -> Option<i64> {
+        let quantity = self.quantity.as_deref()?.trim();
+        quantity.parse().ok().or_else(|| quantity.parse::<f64>().ok().filter(|q| q.fract() == 0.0).map(|q| q as i64))
+    }
+}
+
+/// Net positions keyed by (symbol, exchange, product), shared by a client and its copies
+#[derive(Default)]
+pub(crate) struct PositionCache {
+    /// Quantity and when it was looked up; `None` once an order made it stale
+    entries: Mutex<HashMap<Key, (Option<Instant>, i64)>>,
+    /// When the whole positionbook was last loaded; symbols missing from it are flat
+    refreshed_at: Mutex<Option<Instant>>,
+}
+
+impl PositionCache {
+    /// Cached quantity, if looked up within `max_age`
+    pub(crate) fn get(&self, symbol: &str, exchange: &str, product: &str, max_age: Duration) -> Option<i64> {
+        let key = key(symbol, exchange, product);
+        if let Some((at, quantity)) = self.entries.lock().unwrap().get(&key) {
+            return at.filter(|at| at.elapsed() <= max_age).map(|_| *quantity);
+        }
+        let refreshed_at = *self.refreshed_at.lock().unwrap();
+        refreshed_at.filter(|at| at.elapsed() <= max_age).map(|_| 0)
+    }
+
+    pub(crate) fn set(&self, symbol: &str, exchange: &str, product: &str, quantity: i64) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key(symbol, exchange, product), (Some(Instant::now()), quantity));
+    }
+
+    /// Replace the cache with a positionbook, returning the positions that changed
+    pub(crate) fn replace(&self, positions: &[PositionbookPosition]) -> Vec<PositionChange> {
+        let now = Instant::now();
+        let mut next = HashMap::new();
+        for position in positions {
+            let (Some(symbol), Some(exchange), Some(product)) = (&position.symbol, &position.exchange, &position.product)
+            else {
+                continue;
+            };
+            next.insert(key(symbol, exchange, product), (Some(now), position_quantity(position)));
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        let mut changes: Vec<PositionChange> = next
+            .iter()
+            .filter_map(|(key, (_, quantity))| {
+                let previous = entries.get(key).map_or(0, |(_, q)| *q);
+                (previous != *quantity).then(|| PositionChange::new(key, previous, *quantity))
+            })
+            .chain(
+                entries
+                    .iter()
+                    .filter(|(key, (_, quantity))| *quantity != 0 && !next.contains_key(*key))
+                    .map(|(key, (_, quantity))| PositionChange::new(key, *quantity, 0)),
+            )
+            .collect();
+        changes.sort_by(|a, b| (&a.symbol, &a.exchange, &a.product).cmp(&(&b.symbol, &b.exchange, &b.product)));
+        *entries = next;
+        *self.refreshed_at.lock().unwrap() = Some(now);
+        changes
+    }
+
+    /// Mark a position stale, keeping its last quantity for change detection
+    pub(crate) fn invalidate(&self, symbol: &str, exchange: &str, product: &str) {
+        self.entries.lock().unwrap().entry(key(symbol, exchange, product)).or_insert((None, 0)).0 = None;
+    }
+
+    /// Mark every position stale
+    pub(crate) fn clear(&self) {
+        for entry in self.entries.lock().unwrap().values_mut() {
+            entry.0 = None;
+        }
+        *self.refreshed_at.lock().unwrap() = None;
+    }
+}
+
+/// A position whose net quantity changed between two positionbook polls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PositionChange {
+    pub symbol: String,
+    pub exchange: String,
+    pub product: String,
+    pub previous: i64,
+    pub quantity: i64,
+}
+
+impl PositionChange {
+    fn new((symbol, exchange, product): &Key, previous: i64, quantity: i64) -> Self {
+        Self {
+            symbol: symbol.clone(),
+            exchange: exchange.clone(),
+            product: product.clone(),
+            previous,
+            quantity,
+        }
+    }
+}
+
+/// Polls the positionbook and keeps the client's position cache current
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct PositionWatcher {
+    pub interval: Duration,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for PositionWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PositionWatcher {
+    /// Poll every 5 seconds
+    pub fn new() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+        }
+    }
+
+    /// Time between positionbook polls
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Start polling in the background
+    ///
+    /// Sends the positions that changed after each poll. Failed polls are
+    /// logged and skipped. Dropping the receiver stops the watcher.
+    pub fn start(&self, client: &crate::OpenAlgo) -> (mpsc::Receiver<PositionChange>, JoinHandle<()>) {
+        let (change_tx, change_rx) = mpsc::channel::<PositionChange>(256);
+        let client = client.clone();
+        let interval = self.interval;
+
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if change_tx.is_closed() {
+                    break;
+                }
+                let changes = match client.refresh_positions().await {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        log::warn!("Position watcher poll failed: {}", e);
+                        continue;
+                    }
+                };
+                for change in changes {
+                    if change_tx.send(change).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        (change_rx, handle)
+    }
+}
@@ -0,0 +1,106 @@
+//! Price type and tick-size rounding.
+//!
+//! Prices in responses are `f64` by default. With the `decimal` feature they
+//! are `rust_decimal::Decimal`, so tick arithmetic is exact.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::price::{ceil_to_tick, from_f64, round_to_tick};
+//!
+//! let tick = from_f64(0.05);
+//! assert_eq!(round_to_tick(from_f64(2501.17), tick).to_string(), "2501.15");
+//! assert_eq!(ceil_to_tick(from_f64(2501.11), tick).to_string(), "2501.15");
+//! ```
+
+/// Price of an instrument: `f64`, or `Decimal` with the `decimal` feature
+#[cfg(not(feature = "decimal"))]
+pub type Price = f64;
+
+/// Price of an instrument: `f64`, or `Decimal` with the `decimal` feature
+#[cfg(feature = "decimal")]
+pub type Price = rust_decimal::Decimal;
+
+/// Round to the nearest multiple of `tick_size`; a zero or negative tick leaves the price unchanged
+pub fn round_to_tick(price: Price, tick_size: Price) -> Price {
+    to_tick(price, tick_size, Rounding::Nearest)
+}
+
+/// Round down to a multiple of `tick_size`, e.g. for a buy limit below the market
+pub fn floor_to_tick(price: Price, tick_size: Price) -> Price {
+    to_tick(price, tick_size, Rounding::Down)
+}
+
+/// Round up to a multiple of `tick_size`, e.g. for a sell limit above the market
+pub fn ceil_to_tick(price: Price, tick_size: Price) -> Price {
+    to_tick(price, tick_size, Rounding::Up)
+}
+
+enum Rounding {
+    Nearest,
+    Down,
+    Up,
+}
+
+#[cfg(not(feature = "decimal"))]
+fn to_tick(price: Price, tick_size: Price, rounding: Rounding) -> Price {
+    if tick_size <= 0.0 || !tick_size.is_finite() {
+        return price;
+    }
+    // Snap the tick count first so 2501.15 / 0.05 = 50022.999... counts as 50023
+    let ticks = price / tick_size;
+    let snapped = (ticks * 1e6).round() / 1e6;
+    let ticks = match rounding {
+        Rounding::Nearest => snapped.round(),
+        Rounding::Down => snapped.floor(),
+        Rounding::Up => snapped.ceil(),
+    };
+    // Trim the binary noise of the multiplication to the tick's precision
+    let decimals = decimals(tick_size);
+    let scale = 10f64.powi(decimals);
+    (ticks * tick_size * scale).round() / scale
+}
+
+#[cfg(not(feature = "decimal"))]
+fn decimals(tick_size: f64) -> i32 {
+    (0..10).find(|d| (tick_size * 10f64.powi(*d)).fract().abs() < 1e-9).unwrap_or(10)
+}
+
+#[cfg(feature = "decimal")]
+fn to_tick(price: Price, tick_size: Price, rounding: Rounding) -> Price {
+    if tick_size <= Price::ZERO {
+        return price;
+    }
+    let ticks = price / tick_size;
+    let ticks = match rounding {
+        Rounding::Nearest => ticks.round(),
+        Rounding::Down => ticks.floor(),
+        Rounding::Up => ticks.ceil(),
+    };
+    (ticks * tick_size).normalize()
+}
+
+/// A price as `f64`, for analytics that work in floating point
+pub fn to_f64(price: Price) -> f64 {
+    #[cfg(not(feature = "decimal"))]
+    {
+        price
+    }
+    #[cfg(feature = "decimal")]
+    {
+        use rust_decimal::prelude::ToPrimitive;
+        price.to_f64().unwrap_or(f64::NAN)
+    }
+}
+
+/// An `f64` as a price; non-finite values become zero with the `decimal` feature
+pub fn from_f64(value: f64) -> Price {
+    #[cfg(not(feature = "decimal"))]
+    {
+        value
+    }
+    #[cfg(feature = "decimal")]
+    {
+        use rust_decimal::prelude::FromPrimitive;
+        Price::from_f64(value).unwrap_or_default()
+    }
+}
@@ -0,0 +1,100 @@
+//! Lot-size and freeze-quantity checks for derivative orders.
+//!
+//! Exchanges reject F&O quantities that are not a multiple of the lot size
+//! and freeze orders above the freeze quantity. These helpers catch both
+//! before the order is sent and split large quantities into orders the
+//! exchange accepts.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::quantity::{split_quantity, validate_quantity, QuantityError};
+//!
+//! // NIFTY: lot size 75, freeze quantity 1800
+//! assert_eq!(validate_quantity(100, 75, None), Err(QuantityError::NotLotMultiple { quantity: 100, lot_size: 75 }));
+//! assert_eq!(split_quantity(3900, 75, Some(1800)).unwrap(), vec![1800, 1800, 300]);
+//! ```
+
+use crate::types::SymbolData;
+use thiserror::Error;
+
+/// Why a quantity cannot be ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+pub enum QuantityError {
+    #[error("quantity must be positive, got {0}")]
+    NotPositive(i64),
+
+    #[error("quantity {quantity} is not a multiple of the lot size {lot_size}")]
+    NotLotMultiple { quantity: i64, lot_size: i64 },
+
+    #[error("quantity {quantity} is above the freeze quantity {freeze_qty}")]
+    AboveFreeze { quantity: i64, freeze_qty: i64 },
+}
+
+/// Check a quantity for a single order
+///
+/// A lot size or freeze quantity of zero or one means no limit, as for
+/// equities.
+pub fn validate_quantity(quantity: i64, lot_size: i64, freeze_qty: Option<i64>) -> Result<(), QuantityError> {
+    if quantity <= 0 {
+        return Err(QuantityError::NotPositive(quantity));
+    }
+    if lot_size > 1 && quantity % lot_size != 0 {
+        return Err(QuantityError::NotLotMultiple { quantity, lot_size });
+    }
+    match max_per_order(lot_size, freeze_qty) {
+        Some(max) if quantity > max => Err(QuantityError::AboveFreeze {
+            quantity,
+            freeze_qty: freeze_qty.unwrap_or(max),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Split a quantity into order sizes at or below the freeze quantity
+///
+/// Every slice is a whole number of lots; the remainder goes last.
+pub fn split_quantity(quantity: i64, lot_size: i64, freeze_qty: Option<i64>) -> Result<Vec<i64>, QuantityError> {
+    validate_quantity(quantity, lot_size, None)?;
+    let Some(max) = max_per_order(lot_size, freeze_qty) else {
+        return Ok(vec![quantity]);
+    };
+
+    let mut slices = vec![max; (quantity / max) as usize];
+    if quantity % max != 0 {
+        slices.push(quantity % max);
+    }
+    Ok(slices)
+}
+
+/// Largest quantity one order may carry: the freeze quantity rounded down to whole lots
+fn max_per_order(lot_size: i64, freeze_qty: Option<i64>) -> Option<i64> {
+    let freeze_qty = freeze_qty.filter(|f| *f > 1)?;
+    let lot_size = lot_size.max(1);
+    let max = freeze_qty / lot_size * lot_size;
+    (max > 0).then_some(max)
+}
+
+impl SymbolData {
+    fn lot_size(&self) -> i64 {
+        self.lotsize.map(i64::from).unwrap_or(1)
+    }
+
+    /// Check a quantity for a single order against this symbol's lot size and freeze quantity
+    ///
+    /// # Example
+    /// ```rust
+    /// # use openalgo::SymbolData;
+    /// let nifty: SymbolData = serde_json::from_str(r#"{"symbol": "NIFTY30DEC25FUT", "lotsize": 75, "freeze_qty": 1800}"#).unwrap();
+    /// assert!(nifty.validate_quantity(150).is_ok());
+    /// assert!(nifty.validate_quantity(160).is_err());
+    /// assert!(nifty.validate_quantity(1875).is_err());
+    /// ```
+    pub fn validate_quantity(&self, quantity: i64) -> Result<(), QuantityError> {
+        validate_quantity(quantity, self.lot_size(), self.freeze_qty.map(i64::from))
+    }
+
+    /// Split a quantity into orders that stay at or below the freeze quantity
+    pub fn split_quantity(&self, quantity: i64) -> Result<Vec<i64>, QuantityError> {
+        split_quantity(quantity, self.lot_size(), self.freeze_qty.map(i64::from))
+    }
+}
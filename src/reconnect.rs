@@ -0,0 +1,175 @@
+//! Reconnect handling for the OpenAlgo WebSocket client.
+//!
+//! Backoff settings for automatic reconnects, REST polling while the socket is
+//! down, and REST snapshots that refresh consumer state for resubscribed
+//! instruments once the connection is back.
+
+use crate::data::DataAPI;
+use crate::registry::SubscriptionRegistry;
+use crate::types::*;
+use crate::websocket::{WsData, WsMode};
+use std::time::Duration;
+
+/// Automatic reconnect settings
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    /// Give up after this many failed attempts, or retry forever when `None`
+    pub max_attempts: Option<u32>,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Emit REST quote/depth snapshots for resubscribed instruments
+    pub snapshot: bool,
+    /// Poll REST quotes while the socket stays down
+    pub rest_fallback: Option<RestFallback>,
+}
+
+/// Degraded mode that polls `multi_quotes` while the WebSocket is down
+#[derive(Debug, Clone)]
+pub struct RestFallback {
+    /// How long to wait for a reconnect before polling starts
+    pub after: Duration,
+    /// Polling interval
+    pub interval: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: None,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            snapshot: true,
+            rest_fallback: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Give up after `attempts` failed attempts
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Backoff starting at `initial` and doubling up to `max`
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_delay = initial;
+        self.max_delay = max.max(initial);
+        self
+    }
+
+    /// Whether to emit REST snapshots after reconnecting
+    pub fn snapshot(mut self, enabled: bool) -> Self {
+        self.snapshot = enabled;
+        self
+    }
+
+    /// Poll REST quotes every `interval` if not reconnected within `after`
+    ///
+    /// Polled LTP and Quote events are fed into the same data stream, framed by
+    /// `WsData::Degraded` and `WsData::Reconnected`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::reconnect::ReconnectPolicy;
+    /// use std::time::Duration;
+    /// let policy = ReconnectPolicy::default()
+    ///     .rest_fallback(Duration::from_secs(10), Duration::from_secs(2));
+    /// ```
+    pub fn rest_fallback(mut self, after: Duration, interval: Duration) -> Self {
+        self.rest_fallback = Some(RestFallback {
+            after,
+            interval: interval.max(Duration::from_millis(100)),
+        });
+        self
+    }
+
+    /// Delay before the given attempt (1-based)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Fetch REST snapshots for every subscribed instrument as synthetic events
+///
+/// LTP and Quote instruments are fetched with one multi-quotes call, Depth
+/// instruments one at a time. Snapshot events carry no timestamp.
+pub(crate) async fn snapshot_events(data: &DataAPI, registry: &SubscriptionRegistry) -> Vec<WsData> {
+    let mut events = quote_events(data, registry).await;
+
+    for instrument in registry.instruments(WsMode::Depth) {
+        match data.depth(&instrument.symbol, &instrument.exchange).await {
+            Ok(DepthResponse { data: Some(d), .. }) => events.push(WsData::Depth(WsDepthData {
+                exchange: Some(instrument.exchange),
+                symbol: Some(instrument.symbol),
+                ltp: d.ltp,
+                open: d.open,
+                high: d.high,
+                low: d.low,
+                close: d.prev_close,
+                volume: d.volume,
+                bids: d.bids,
+                asks: d.asks,
+                timestamp: None,
+                extra: Default::default(),
+            })),
+            Ok(_) => {}
+            Err(e) => log::warn!("Reconnect snapshot for {} depth failed: {}", instrument.symbol, e),
+        }
+    }
+
+    events
+}
+
+/// LTP and Quote events for subscribed instruments from one multi-quotes call
+pub(crate) async fn quote_events(data: &DataAPI, registry: &SubscriptionRegistry) -> Vec<WsData> {
+    let mut events = Vec::new();
+
+    let ltp = registry.instruments(WsMode::Ltp);
+    let quote = registry.instruments(WsMode::Quote);
+    let mut quoted: Vec<&WsInstrument> = ltp.iter().chain(quote.iter()).collect();
+    quoted.sort_by(|a, b| (&a.exchange, &a.symbol).cmp(&(&b.exchange, &b.symbol)));
+    quoted.dedup_by(|a, b| a.exchange == b.exchange && a.symbol == b.symbol);
+
+    if !quoted.is_empty() {
+        let symbols: Vec<(&str, &str)> = quoted
+            .iter()
+            .map(|i| (i.symbol.as_str(), i.exchange.as_str()))
+            .collect();
+        match data.multi_quotes(&symbols).await {
+            Ok(response) => {
+                for result in response.results.unwrap_or_default() {
+                    let Some(q) = result.data else { continue };
+                    let modes = registry.modes(&result.exchange, &result.symbol);
+                    if modes.contains(&WsMode::Ltp) {
+                        events.push(WsData::Ltp(WsLtpData {
+                            exchange: Some(result.exchange.clone()),
+                            symbol: Some(result.symbol.clone()),
+                            ltp: q.ltp,
+                            timestamp: None,
+                            extra: Default::default(),
+                        }));
+                    }
+                    if modes.contains(&WsMode::Quote) {
+                        events.push(WsData::Quote(WsQuoteData {
+                            exchange: Some(result.exchange.clone()),
+                            symbol: Some(result.symbol.clone()),
+                            ltp: q.ltp,
+                            open: q.open,
+                            high: q.high,
+                            low: q.low,
+                            close: q.prev_close,
+                            volume: q.volume,
+                            timestamp: None,
+                            extra: Default::default(),
+                        }));
+                    }
+                }
+            }
+            Err(e) => log::warn!("REST quotes for subscribed instruments failed: {}", e),
+        }
+    }
+
+    events
+}
@@ -0,0 +1,157 @@
+//! Subscription registry for OpenAlgo WebSocket connections.
+//!
+//! Tracks which modes each instrument is subscribed in, so subscriptions
+//! can be inspected and replayed after a reconnect.
+
+use crate::types::WsInstrument;
+use crate::websocket::{WsCommand, WsMode};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Instruments and the modes they are subscribed in
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionRegistry {
+    entries: BTreeMap<(String, String), BTreeSet<WsMode>>,
+}
+
+impl SubscriptionRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record instruments as subscribed in a mode
+    pub fn add(&mut self, mode: WsMode, instruments: &[WsInstrument]) {
+        for i in instruments {
+            self.entries
+                .entry((i.exchange.clone(), i.symbol.clone()))
+                .or_default()
+                .insert(mode);
+        }
+    }
+
+    /// Record instruments as unsubscribed from a mode
+    pub fn remove(&mut self, mode: WsMode, instruments: &[WsInstrument]) {
+        for i in instruments {
+            let key = (i.exchange.clone(), i.symbol.clone());
+            if let Some(modes) = self.entries.get_mut(&key) {
+                modes.remove(&mode);
+                if modes.is_empty() {
+                    self.entries.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Apply a WebSocket command to the registry
+    pub fn apply(&mut self, cmd: &WsCommand) {
+        match cmd {
+            WsCommand::SubscribeLtp(i) => self.add(WsMode::Ltp, i),
+            WsCommand::SubscribeQuote(i) => self.add(WsMode::Quote, i),
+            WsCommand::SubscribeDepth(i) => self.add(WsMode::Depth, i),
+            WsCommand::UnsubscribeLtp(i) => self.remove(WsMode::Ltp, i),
+            WsCommand::UnsubscribeQuote(i) => self.remove(WsMode::Quote, i),
+            WsCommand::UnsubscribeDepth(i) => self.remove(WsMode::Depth, i),
+            WsCommand::Disconnect => self.clear(),
+        }
+    }
+
+    /// Modes an instrument is subscribed in
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::registry::SubscriptionRegistry;
+    /// use openalgo::{WsInstrument, WsMode};
+    ///
+    /// let mut registry = SubscriptionRegistry::new();
+    /// registry.add(WsMode::Depth, &[WsInstrument::new("NSE", "RELIANCE")]);
+    /// registry.add(WsMode::Ltp, &[WsInstrument::new("NSE", "RELIANCE"), WsInstrument::new("NSE", "TCS")]);
+    /// assert_eq!(registry.modes("NSE", "RELIANCE"), vec![WsMode::Ltp, WsMode::Depth]);
+    /// assert_eq!(registry.len(), 2);
+    /// ```
+    pub fn modes(&self, exchange: &str, symbol: &str) -> Vec<WsMode> {
+        self.entries
+            .get(&(exchange.to_string(), symbol.to_string()))
+            .map(|modes| modes.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Instruments subscribed in a mode
+    pub fn instruments(&self, mode: WsMode) -> Vec<WsInstrument> {
+        self.entries
+            .iter()
+            .filter(|(_, modes)| modes.contains(&mode))
+            .map(|((exchange, symbol), _)| WsInstrument::new(exchange, symbol))
+            .collect()
+    }
+
+    /// Subscribed instruments grouped by mode, skipping empty modes
+    pub fn by_mode(&self) -> Vec<(WsMode, Vec<WsInstrument>)> {
+        [WsMode::Ltp, WsMode::Quote, WsMode::Depth]
+            .into_iter()
+            .map(|mode| (mode, self.instruments(mode)))
+            .filter(|(_, instruments)| !instruments.is_empty())
+            .collect()
+    }
+
+    /// Commands that recreate every subscription
+    pub fn subscribe_commands(&self) -> Vec<WsCommand> {
+        self.by_mode()
+            .into_iter()
+            .map(|(mode, instruments)| WsCommand::subscribe(mode, instruments))
+            .collect()
+    }
+
+    /// Number of subscribed instruments
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing is subscribed
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Forget all subscriptions
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Subscriptions of every connection opened through a client, keyed by connection
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionRegistries {
+    next_id: u64,
+    connections: BTreeMap<u64, SubscriptionRegistry>,
+}
+
+impl ConnectionRegistries {
+    /// Register a new connection and return its id
+    pub(crate) fn open(&mut self) -> u64 {
+        self.next_id += 1;
+        self.connections.insert(self.next_id, SubscriptionRegistry::new());
+        self.next_id
+    }
+
+    /// Apply a command to one connection's subscriptions
+    pub(crate) fn apply(&mut self, id: u64, cmd: &WsCommand) {
+        if let Some(registry) = self.connections.get_mut(&id) {
+            registry.apply(cmd);
+        }
+    }
+
+    /// Forget a connection that has ended
+    pub(crate) fn close(&mut self, id: u64) {
+        self.connections.remove(&id);
+    }
+
+    /// Union of the subscriptions of every open connection
+    pub(crate) fn merged(&self) -> SubscriptionRegistry {
+        let mut merged = SubscriptionRegistry::new();
+        for registry in self.connections.values() {
+            for (mode, instruments) in registry.by_mode() {
+                merged.add(mode, &instruments);
+            }
+        }
+        merged
+    }
+}
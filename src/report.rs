@@ -0,0 +1,366 @@
+//! Backtest reports.
+//!
+//! `BacktestReport` turns an equity curve and a trade list into the data a
+//! backtest review needs: the drawdown series, a monthly returns table (by
+//! IST calendar month), return statistics and trade statistics. It
+//! serializes to JSON, and with the `report` feature renders a
+//! self-contained HTML page with inline SVG charts and no external assets.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::report::{BacktestReport, EquityPoint, TradeRecord};
+//!
+//! let equity = vec![
+//!     EquityPoint { timestamp: 1733111100, equity: 100_000.0 }, // 2 Dec 2024
+//!     EquityPoint { timestamp: 1733197500, equity: 104_000.0 },
+//!     EquityPoint { timestamp: 1735789500, equity: 98_800.0 },  // 2 Jan 2025
+//! ];
+//! let trades = vec![TradeRecord {
+//!     symbol: "SBIN".into(), action: "BUY".into(), quantity: 50,
+//!     entry_time: 1733111100, entry_price: 800.0, exit_time: 1733197500, exit_price: 880.0, pnl: 4_000.0,
+//! }];
+//! let report = BacktestReport::new("SMA cross", equity, trades, 252.0);
+//! assert!((report.drawdown[2].drawdown - 0.05).abs() < 1e-12);
+//! assert_eq!((report.monthly_returns[0].year, report.monthly_returns[0].month), (2024, 12));
+//! assert!((report.monthly_returns[1].return_ - (-0.05)).abs() < 1e-12);
+//! assert_eq!(report.trade_stats.winners, 1);
+//! let json = report.to_json();
+//! assert!(json.contains("\"monthly_returns\""));
+//! ```
+
+use crate::calendar;
+use crate::optimize::PerformanceMetrics;
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+/// Account value at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    /// Unix seconds
+    pub timestamp: i64,
+    pub equity: f64,
+}
+
+/// Fall from the running equity peak at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DrawdownPoint {
+    /// Unix seconds
+    pub timestamp: i64,
+    /// Fraction below the peak, zero at a new high
+    pub drawdown: f64,
+}
+
+/// Return of one IST calendar month
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MonthlyReturn {
+    pub year: i32,
+    /// 1 to 12
+    pub month: u32,
+    /// Month-end equity over the previous month-end (or the starting equity), minus one
+    #[serde(rename = "return")]
+    pub return_: f64,
+}
+
+/// A closed trade
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub symbol: String,
+    /// Entry side, "BUY" or "SELL"
+    pub action: String,
+    pub quantity: i64,
+    /// Unix seconds
+    pub entry_time: i64,
+    pub entry_price: f64,
+    /// Unix seconds
+    pub exit_time: i64,
+    pub exit_price: f64,
+    pub pnl: f64,
+}
+
+/// Summary of the trade list
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TradeStats {
+    pub trades: usize,
+    pub winners: usize,
+    pub losers: usize,
+    pub gross_profit: f64,
+    /// Sum of losing trades, as a positive number
+    pub gross_loss: f64,
+    pub net_pnl: f64,
+    /// Gross profit over gross loss; `None` without losing trades
+    pub profit_factor: Option<f64>,
+    pub average_pnl: f64,
+}
+
+impl TradeStats {
+    /// Statistics of a trade list
+    pub fn from_trades(trades: &[TradeRecord]) -> Self {
+        let gross_profit: f64 = trades.iter().map(|t| t.pnl).filter(|p| *p > 0.0).sum();
+        let gross_loss: f64 = -trades.iter().map(|t| t.pnl).filter(|p| *p < 0.0).sum::<f64>();
+        let net_pnl = gross_profit - gross_loss;
+        Self {
+            trades: trades.len(),
+            winners: trades.iter().filter(|t| t.pnl > 0.0).count(),
+            losers: trades.iter().filter(|t| t.pnl < 0.0).count(),
+            gross_profit,
+            gross_loss,
+            net_pnl,
+            profit_factor: (gross_loss > 0.0).then(|| gross_profit / gross_loss),
+            average_pnl: if trades.is_empty() { 0.0 } else { net_pnl / trades.len() as f64 },
+        }
+    }
+}
+
+/// Report of one backtest run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub title: String,
+    pub equity_curve: Vec<EquityPoint>,
+    pub drawdown: Vec<DrawdownPoint>,
+    pub monthly_returns: Vec<MonthlyReturn>,
+    /// Statistics of the point-to-point equity returns
+    pub metrics: PerformanceMetrics,
+    pub trade_stats: TradeStats,
+    pub trades: Vec<TradeRecord>,
+}
+
+impl BacktestReport {
+    /// Build a report from an equity curve in time order and the closed trades
+    ///
+    /// `periods_per_year` is the number of equity points a year, used to
+    /// annualize the metrics (252 for daily points).
+    pub fn new(title: &str, equity_curve: Vec<EquityPoint>, trades: Vec<TradeRecord>, periods_per_year: f64) -> Self {
+        let returns: Vec<f64> = equity_curve
+            .windows(2)
+            .filter(|w| w[0].equity != 0.0)
+            .map(|w| w[1].equity / w[0].equity - 1.0)
+            .collect();
+        Self {
+            title: title.to_string(),
+            drawdown: drawdown(&equity_curve),
+            monthly_returns: monthly_returns(&equity_curve),
+            metrics: PerformanceMetrics::from_returns(&returns, periods_per_year),
+            trade_stats: TradeStats::from_trades(&trades),
+            equity_curve,
+            trades,
+        }
+    }
+
+    /// The report as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// The report as a self-contained HTML page
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::report::{BacktestReport, EquityPoint};
+    ///
+    /// let equity = vec![EquityPoint { timestamp: 1733111100, equity: 1e5 }];
+    /// let html = BacktestReport::new("<SMA>", equity, Vec::new(), 252.0).to_html();
+    /// assert!(html.contains("<title>&lt;SMA&gt;</title>"));
+    /// ```
+    #[cfg(feature = "report")]
+    pub fn to_html(&self) -> String {
+        html::render(self)
+    }
+
+    /// Write the HTML page to `path`
+    #[cfg(all(feature = "report", not(target_arch = "wasm32")))]
+    pub fn save_html(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_html())
+    }
+}
+
+/// Drawdown from the running peak at every equity point
+pub fn drawdown(equity_curve: &[EquityPoint]) -> Vec<DrawdownPoint> {
+    let mut peak = f64::MIN;
+    equity_curve
+        .iter()
+        .map(|point| {
+            peak = peak.max(point.equity);
+            DrawdownPoint {
+                timestamp: point.timestamp,
+                drawdown: if peak > 0.0 { 1.0 - point.equity / peak } else { 0.0 },
+            }
+        })
+        .collect()
+}
+
+/// Returns per IST calendar month, oldest first
+///
+/// The first month is measured from the first equity point.
+pub fn monthly_returns(equity_curve: &[EquityPoint]) -> Vec<MonthlyReturn> {
+    let mut months: Vec<MonthlyReturn> = Vec::new();
+    let Some(first) = equity_curve.first() else { return months };
+    let mut base = first.equity;
+    let mut month_end = first.equity;
+    let mut current: Option<(i32, u32)> = None;
+    for point in equity_curve {
+        let Some(time) = calendar::ist_datetime(point.timestamp) else { continue };
+        let key = (time.year(), time.month());
+        if current.is_some_and(|c| c != key) {
+            base = month_end;
+        }
+        current = Some(key);
+        month_end = point.equity;
+        let return_ = if base != 0.0 { month_end / base - 1.0 } else { 0.0 };
+        match months.last_mut() {
+            Some(last) if (last.year, last.month) == key => last.return_ = return_,
+            _ => months.push(MonthlyReturn { year: key.0, month: key.1, return_ }),
+        }
+    }
+    months
+}
+
+#[cfg(feature = "report")]
+mod html {
+    use super::{BacktestReport, DrawdownPoint, EquityPoint};
+    use crate::calendar;
+    use std::fmt::Write;
+
+    const WIDTH: f64 = 900.0;
+    const HEIGHT: f64 = 220.0;
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+    pub(super) fn render(report: &BacktestReport) -> String {
+        let mut page = String::new();
+        let title = escape(&report.title);
+        let _ = write!(
+            page,
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title><style>\
+             body{{font-family:sans-serif;margin:2em;color:#222}}\
+             table{{border-collapse:collapse;margin:1em 0}}\
+             td,th{{border:1px solid #ccc;padding:4px 8px;text-align:right}}\
+             .pos{{color:#1a7f37}}.neg{{color:#cf222e}}\
+             svg{{background:#fafafa;border:1px solid #ddd}}\
+             </style></head><body>\n<h1>{title}</h1>\n"
+        );
+
+        let m = &report.metrics;
+        let t = &report.trade_stats;
+        page.push_str("<h2>Summary</h2>\n<table>\n");
+        for (name, value) in [
+            ("Total return", percent(m.total_return)),
+            ("Annualized return", percent(m.annualized_return)),
+            ("Volatility", percent(m.volatility)),
+            ("Sharpe", format!("{:.2}", m.sharpe)),
+            ("Max drawdown", percent(m.max_drawdown)),
+            ("Trades", t.trades.to_string()),
+            ("Winners / losers", format!("{} / {}", t.winners, t.losers)),
+            ("Net P&amp;L", format!("{:.2}", t.net_pnl)),
+            ("Profit factor", t.profit_factor.map(|p| format!("{:.2}", p)).unwrap_or_else(|| "-".into())),
+        ] {
+            let _ = writeln!(page, "<tr><th>{}</th><td>{}</td></tr>", name, value);
+        }
+        page.push_str("</table>\n");
+
+        page.push_str("<h2>Equity</h2>\n");
+        page.push_str(&chart(
+            &report.equity_curve.iter().map(|p: &EquityPoint| (p.timestamp, p.equity)).collect::<Vec<_>>(),
+            "#0969da",
+        ));
+        page.push_str("<h2>Drawdown</h2>\n");
+        page.push_str(&chart(
+            &report.drawdown.iter().map(|p: &DrawdownPoint| (p.timestamp, -p.drawdown)).collect::<Vec<_>>(),
+            "#cf222e",
+        ));
+
+        page.push_str("<h2>Monthly returns</h2>\n<table>\n<tr><th>Year</th>");
+        for month in MONTHS {
+            let _ = write!(page, "<th>{}</th>", month);
+        }
+        page.push_str("</tr>\n");
+        let mut years: Vec<i32> = report.monthly_returns.iter().map(|r| r.year).collect();
+        years.dedup();
+        for year in years {
+            let _ = write!(page, "<tr><th>{}</th>", year);
+            for month in 1..=12 {
+                match report.monthly_returns.iter().find(|r| r.year == year && r.month == month) {
+                    Some(r) => {
+                        let _ = write!(page, "<td class=\"{}\">{}</td>", class(r.return_), percent(r.return_));
+                    }
+                    None => page.push_str("<td></td>"),
+                }
+            }
+            page.push_str("</tr>\n");
+        }
+        page.push_str("</table>\n");
+
+        page.push_str(
+            "<h2>Trades</h2>\n<table>\n<tr><th>Symbol</th><th>Side</th><th>Qty</th><th>Entry</th>\
+             <th>Entry price</th><th>Exit</th><th>Exit price</th><th>P&amp;L</th></tr>\n",
+        );
+        for trade in &report.trades {
+            let _ = writeln!(
+                page,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{}</td><td>{:.2}</td>\
+                 <td class=\"{}\">{:.2}</td></tr>",
+                escape(&trade.symbol),
+                escape(&trade.action),
+                trade.quantity,
+                time(trade.entry_time),
+                trade.entry_price,
+                time(trade.exit_time),
+                trade.exit_price,
+                class(trade.pnl),
+                trade.pnl,
+            );
+        }
+        page.push_str("</table>\n</body></html>\n");
+        page
+    }
+
+    /// Line chart of (time, value) points as inline SVG
+    fn chart(points: &[(i64, f64)], color: &str) -> String {
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            return String::new();
+        };
+        let low = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let high = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+        let span_x = (last.0 - first.0).max(1) as f64;
+        let span_y = if high > low { high - low } else { 1.0 };
+        let coordinates: Vec<String> = points
+            .iter()
+            .map(|(t, v)| {
+                let x = (t - first.0) as f64 / span_x * WIDTH;
+                let y = HEIGHT - (v - low) / span_y * HEIGHT;
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect();
+        format!(
+            "<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\
+             <polyline fill=\"none\" stroke=\"{color}\" stroke-width=\"1.5\" points=\"{points}\"/></svg>\n",
+            w = WIDTH,
+            h = HEIGHT,
+            color = color,
+            points = coordinates.join(" "),
+        )
+    }
+
+    fn percent(value: f64) -> String {
+        format!("{:.2}%", value * 100.0)
+    }
+
+    fn class(value: f64) -> &'static str {
+        if value < 0.0 {
+            "neg"
+        } else {
+            "pos"
+        }
+    }
+
+    fn time(timestamp: i64) -> String {
+        calendar::ist_datetime(timestamp)
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+}
@@ -0,0 +1,337 @@
+//! Retries for failed REST requests.
+//!
+//! Every endpoint is classified as safe or unsafe to retry. Data, account and
+//! status calls are safe; calls that place, modify or cancel orders or send
+//! messages are unsafe, because a request that timed out may still have been
+//! executed and retrying it could place the order twice. `RetryPolicy` only
+//! ever retries safe endpoints, and only after transient failures (timeouts,
+//! connection errors, HTTP 429 and 5xx gateway errors).
+//!
+//! Orders have their own recovery: `OrderRecovery` checks the orderbook for
+//! an order matching the one that failed before sending it again, so a
+//! timeout that hid an accepted order does not place it twice.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::retry::{classify, RetryClass, RetryPolicy};
+//! use openalgo::OpenAlgo;
+//!
+//! assert_eq!(classify("quotes"), RetryClass::Safe);
+//! assert_eq!(classify("placeorder"), RetryClass::Unsafe);
+//!
+//! let policy = RetryPolicy::new(3).endpoint("custom/rebalance", RetryClass::Unsafe);
+//! let client = OpenAlgo::builder("your-api-key").retry(policy).build();
+//! ```
+
+use crate::calendar::parse_timestamp;
+use crate::client::OpenAlgoError;
+use crate::price::{self, Price};
+use crate::types::{OrderbookOrder, PlaceOrderRequest};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Whether an endpoint may be sent again after a failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetryClass {
+    /// Reads and idempotent settings; repeating the request has no extra effect
+    Safe,
+    /// Order placement, modification and cancellation; never retried
+    Unsafe,
+}
+
+/// Endpoints that place or modify orders
+const ORDER_ENTRY_ENDPOINTS: &[&str] = &[
+    "placeorder",
+    "placesmartorder",
+    "optionsorder",
+    "optionsmultiorder",
+    "basketorder",
+    "splitorder",
+    "modifyorder",
+];
+
+/// Endpoints that cancel orders or close positions
+const ORDER_EXIT_ENDPOINTS: &[&str] = &["cancelorder", "cancelallorder", "closeposition"];
+
+/// Built-in classification of an endpoint
+///
+/// Order-mutating endpoints and `telegram/*` are unsafe; everything else is
+/// safe. Use `RetryPolicy::endpoint` to classify your own endpoints.
+pub fn classify(endpoint: &str) -> RetryClass {
+    if mutates_orders(endpoint) || endpoint.trim_matches('/').starts_with("telegram") {
+        RetryClass::Unsafe
+    } else {
+        RetryClass::Safe
+    }
+}
+
+/// Whether an endpoint places, modifies or cancels orders or closes positions
+pub(crate) fn mutates_orders(endpoint: &str) -> bool {
+    enters_orders(endpoint) || ORDER_EXIT_ENDPOINTS.contains(&endpoint.trim_matches('/'))
+}
+
+/// Whether an endpoint places or modifies orders, the calls a trading halt refuses
+pub(crate) fn enters_orders(endpoint: &str) -> bool {
+    ORDER_ENTRY_ENDPOINTS.contains(&endpoint.trim_matches('/'))
+}
+
+/// Whether an error is worth retrying: timeouts, connection failures, HTTP 429 and 502-504
+pub fn is_transient(error: &OpenAlgoError) -> bool {
+    match error.inner() {
+        #[cfg(not(target_arch = "wasm32"))]
+        OpenAlgoError::RequestError(e) => e.is_timeout() || e.is_connect(),
+        #[cfg(target_arch = "wasm32")]
+        OpenAlgoError::RequestError(e) => e.is_timeout(),
+        OpenAlgoError::IoError(e) => matches!(
+            e.kind(),
+            std::io::ErrorKind::TimedOut
+                | std::io::ErrorKind::ConnectionRefused
+                | std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+        ),
+        OpenAlgoError::ApiError(details) => matches!(details.http_status, Some(429 | 502 | 503 | 504)),
+        _ => false,
+    }
+}
+
+/// Retry settings for REST requests
+///
+/// The default makes no retries.
+///
+/// # Example
+/// ```rust
+/// use openalgo::client::{ApiErrorDetails, OpenAlgoError};
+/// use openalgo::retry::RetryPolicy;
+/// use openalgo::transport::{Transport, TransportFuture};
+/// use std::sync::atomic::{AtomicU32, Ordering};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// /// Fails with HTTP 503 on every other request
+/// struct Flaky(AtomicU32);
+///
+/// impl Transport for Flaky {
+///     fn post_json<'a>(&'a self, _url: &'a str, _body: String) -> TransportFuture<'a> {
+///         Box::pin(async move {
+///             match self.0.fetch_add(1, Ordering::SeqCst) % 2 {
+///                 0 => Err(OpenAlgoError::ApiError(Box::new(ApiErrorDetails::from_body(Some(503), "unavailable")))),
+///                 _ => Ok(r#"{"status":"success","data":{"ltp":101.5}}"#.to_string()),
+///             }
+///         })
+///     }
+/// }
+///
+/// let transport = Arc::new(Flaky(AtomicU32::new(0)));
+/// let client = openalgo::OpenAlgo::builder("test-key")
+///     .transport(transport.clone())
+///     .retry(RetryPolicy::new(2).backoff(Duration::from_millis(1), Duration::from_millis(1)))
+///     .build();
+///
+/// tokio_test::block_on(async {
+///     // The quote is retried after the 503
+///     assert!(client.ltp("RELIANCE", "NSE").await.is_ok());
+///     // The order is not, so it cannot be placed twice
+///     assert!(client.place_order("S", "RELIANCE", "BUY", "NSE", "MARKET", "MIS", "1").await.is_err());
+/// });
+/// assert_eq!(transport.0.load(Ordering::SeqCst), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    overrides: HashMap<String, RetryClass>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl RetryPolicy {
+    /// Retry safe endpoints up to `max_retries` times, backing off from 200 ms to 5 s
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Backoff starting at `initial` and doubling up to `max`
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_delay = initial;
+        self.max_delay = max.max(initial);
+        self
+    }
+
+    /// Classify an endpoint, e.g. one called with `post_custom`, overriding the built-in table
+    pub fn endpoint(mut self, endpoint: &str, class: RetryClass) -> Self {
+        self.overrides.insert(endpoint.trim_matches('/').to_string(), class);
+        self
+    }
+
+    /// Classification of an endpoint under this policy
+    pub fn classify(&self, endpoint: &str) -> RetryClass {
+        self.overrides
+            .get(endpoint.trim_matches('/'))
+            .copied()
+            .unwrap_or_else(|| classify(endpoint))
+    }
+
+    /// Whether a request that failed with `error` on retry number `attempt` (from 0) should be sent again
+    pub fn should_retry(&self, endpoint: &str, error: &OpenAlgoError, attempt: u32) -> bool {
+        attempt < self.max_retries && self.classify(endpoint) == RetryClass::Safe && is_transient(error)
+    }
+
+    /// Wait before retry number `attempt` (from 0)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// How `place_order_recovering` retries an order after a transient failure
+#[derive(Debug, Clone)]
+pub struct OrderRecovery {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Wait before checking the orderbook, for the broker to list the order
+    pub settle: Duration,
+    /// Orders timestamped up to this long before the first attempt still count as matches,
+    /// allowing for clock skew with the broker
+    pub window: Duration,
+}
+
+impl Default for OrderRecovery {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+impl OrderRecovery {
+    /// Retry up to `max_retries` times, checking the orderbook 1 s after each
+    /// failure and allowing 5 s of clock skew with the broker
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            settle: Duration::from_secs(1),
+            window: Duration::from_secs(5),
+        }
+    }
+
+    /// Wait before checking the orderbook
+    pub fn settle(mut self, settle: Duration) -> Self {
+        self.settle = settle;
+        self
+    }
+
+    /// How far before the first attempt a matching order may be timestamped
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+}
+
+/// Orders in an orderbook that could be `request`
+///
+/// Symbol, exchange, action, product, price type and quantity must match,
+/// and so must the limit price of LIMIT and SL orders and the trigger price
+/// of SL and SL-M orders; the orderbook does not carry the strategy.
+///
+/// # Example
+/// ```rust
+/// use openalgo::retry::matching_orders;
+/// use openalgo::{OrderbookOrder, PlaceOrderRequest};
+///
+/// let orders: Vec<OrderbookOrder> = serde_json::from_str(r#"[
+///     {"orderid": "1", "symbol": "SBIN", "exchange": "NSE", "action": "BUY", "product": "MIS",
+///      "pricetype": "LIMIT", "quantity": "10", "price": 800.0},
+///     {"orderid": "2", "symbol": "SBIN", "exchange": "NSE", "action": "BUY", "product": "MIS",
+///      "pricetype": "LIMIT", "quantity": "10", "price": 795.5}
+/// ]"#).unwrap();
+/// let mut request = PlaceOrderRequest::new("S", "SBIN", "BUY", "NSE", "LIMIT", "MIS", "10");
+/// request.price = Some("795.50".into());
+/// let found: Vec<_> = matching_orders(&orders, &request).collect();
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].orderid.as_deref(), Some("2"));
+/// ```
+pub fn matching_orders<'a: 'r, 'r>(
+    orders: &'a [OrderbookOrder],
+    request: &'r PlaceOrderRequest,
+) -> impl Iterator<Item = &'a OrderbookOrder> + 'r {
+    let same = |field: &Option<String>, value: &str| field.as_deref().is_some_and(|f| f.trim().eq_ignore_ascii_case(value.trim()));
+    // An unset request price is zero, as the server reads it
+    let same_price = |field: Option<Price>, value: &Option<String>| {
+        let wanted = value.as_deref().and_then(|v| v.trim().parse::<f64>().ok()).unwrap_or(0.0);
+        field.is_some_and(|p| (price::to_f64(p) - wanted).abs() < 1e-6)
+    };
+    let pricetype = request.pricetype.trim().to_ascii_uppercase();
+    let limit = matches!(pricetype.as_str(), "LIMIT" | "SL");
+    let stop = matches!(pricetype.as_str(), "SL" | "SL-M");
+    orders.iter().filter(move |o| {
+        same(&o.symbol, &request.symbol)
+            && same(&o.exchange, &request.exchange)
+            && same(&o.action, &request.action)
+            && same(&o.product, &request.product)
+            && same(&o.pricetype, &request.pricetype)
+            && same(&o.quantity, &request.quantity)
+            && (!limit || same_price(o.price, &request.price))
+            && (!stop || same_price(o.trigger_price, &request.trigger_price))
+    })
+}
+
+/// The most recent new order in an orderbook that could be `request`
+///
+/// `known` holds the IDs of matching orders that were already in the
+/// orderbook before the first attempt (see `matching_orders`); those are
+/// someone else's. The order must also be timestamped at or after `since`
+/// (Unix seconds). An order without an ID or whose timestamp cannot be read
+/// never matches, so an unfamiliar timestamp format resends rather than
+/// claiming another order.
+///
+/// # Example
+/// ```rust
+/// use openalgo::retry::find_placed_order;
+/// use openalgo::{OrderbookOrder, PlaceOrderRequest};
+/// use std::collections::HashSet;
+///
+/// let orders: Vec<OrderbookOrder> = serde_json::from_str(r#"[
+///     {"orderid": "1", "symbol": "SBIN", "exchange": "NSE", "action": "BUY", "product": "MIS",
+///      "pricetype": "MARKET", "quantity": "10", "timestamp": "02-Dec-2024 09:59:58"},
+///     {"orderid": "2", "symbol": "SBIN", "exchange": "NSE", "action": "BUY", "product": "MIS",
+///      "pricetype": "MARKET", "quantity": "10", "timestamp": "02-Dec-2024 10:00:02"},
+///     {"orderid": "3", "symbol": "SBIN", "exchange": "NSE", "action": "BUY", "product": "MIS",
+///      "pricetype": "MARKET", "quantity": "10", "timestamp": "2 Dec, 10:00"}
+/// ]"#).unwrap();
+/// let request = PlaceOrderRequest::new("S", "SBIN", "BUY", "NSE", "MARKET", "MIS", "10");
+///
+/// // First attempt at 10:00:00 IST; order 1 was in the orderbook before it
+/// let known: HashSet<String> = HashSet::from(["1".to_string()]);
+/// let found = find_placed_order(&orders, &request, &known, 1_733_113_795).unwrap();
+/// assert_eq!(found.orderid.as_deref(), Some("2"));
+///
+/// // Order 2 was there too; order 3's timestamp cannot be read
+/// let known: HashSet<String> = HashSet::from(["1".to_string(), "2".to_string()]);
+/// assert!(find_placed_order(&orders, &request, &known, 1_733_113_795).is_none());
+///
+/// let mut larger = request;
+/// larger.quantity = "20".into();
+/// assert!(find_placed_order(&orders, &larger, &HashSet::new(), 1_733_113_795).is_none());
+/// ```
+pub fn find_placed_order<'a>(
+    orders: &'a [OrderbookOrder],
+    request: &PlaceOrderRequest,
+    known: &HashSet<String>,
+    since: i64,
+) -> Option<&'a OrderbookOrder> {
+    let time = |order: &OrderbookOrder| order.timestamp.as_deref().and_then(parse_timestamp).map(|t| t.timestamp());
+    matching_orders(orders, request)
+        .filter(|o| o.orderid.as_ref().is_some_and(|id| !known.contains(id)))
+        .filter_map(|o| time(o).filter(|&t| t >= since).map(|t| (t, o)))
+        .max_by_key(|(t, _)| *t)
+        .map(|(_, o)| o)
+}
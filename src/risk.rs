@@ -0,0 +1,428 @@
+//! Portfolio risk report for OpenAlgo.
+//!
+//! `report` combines the positionbook, quotes and daily history into gross
+//! and net exposure per underlying, exposure weighted by each underlying's
+//! beta to a benchmark (NIFTY by default), and a parametric value-at-risk
+//! estimate from the covariance of daily returns. Options count at their
+//! delta, computed with Black-Scholes from the underlying's historical
+//! volatility.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::risk::{self, RiskConfig};
+//!
+//! # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+//! let report = risk::report(&client, &RiskConfig::default()).await?;
+//! for u in &report.underlyings {
+//!     println!("{}: net {:.0} gross {:.0} beta {:?}", u.underlying, u.net_exposure, u.gross_exposure, u.beta);
+//! }
+//! println!("1-day 95% VaR: {:.0}", report.var);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::bulk;
+use crate::calendar::{format_date, ist_date, ist_day_and_secs};
+use crate::client::OpenAlgoError;
+use crate::options::{black_scholes_delta, norm_cdf, time_to_expiry};
+use crate::price;
+use crate::symbols::{parse_derivative_symbol, resolve_index, DerivativeKind};
+use crate::OpenAlgo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Trading days per year used to annualise volatility
+const TRADING_DAYS: f64 = 252.0;
+
+/// Risk report settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskConfig {
+    pub benchmark_symbol: String,
+    pub benchmark_exchange: String,
+    /// Daily returns used for volatility, beta and covariance
+    pub lookback_days: u32,
+    /// VaR confidence level (e.g. 0.95)
+    pub confidence: f64,
+    /// VaR horizon in trading days
+    pub horizon_days: f64,
+    /// Risk-free rate in percent, for option deltas
+    pub interest_rate: f64,
+    /// Quote and history requests in flight; zero uses `bulk::DEFAULT_CONCURRENCY`
+    pub concurrency: usize,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            benchmark_symbol: "NIFTY".to_string(),
+            benchmark_exchange: "NSE_INDEX".to_string(),
+            lookback_days: 250,
+            confidence: 0.95,
+            horizon_days: 1.0,
+            interest_rate: 6.5,
+            concurrency: 0,
+        }
+    }
+}
+
+impl RiskConfig {
+    /// Benchmark the beta-weighted exposure is measured against
+    pub fn benchmark(mut self, symbol: &str, exchange: &str) -> Self {
+        self.benchmark_symbol = symbol.to_string();
+        self.benchmark_exchange = exchange.to_string();
+        self
+    }
+
+    /// Daily returns used for volatility, beta and covariance
+    pub fn lookback_days(mut self, days: u32) -> Self {
+        self.lookback_days = days;
+        self
+    }
+
+    /// VaR confidence level and horizon in trading days
+    pub fn var(mut self, confidence: f64, horizon_days: f64) -> Self {
+        self.confidence = confidence;
+        self.horizon_days = horizon_days;
+        self
+    }
+
+    /// Risk-free rate in percent, for option deltas
+    pub fn interest_rate(mut self, interest_rate: f64) -> Self {
+        self.interest_rate = interest_rate;
+        self
+    }
+}
+
+/// Exposure of one open position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionRisk {
+    pub symbol: String,
+    pub exchange: String,
+    pub underlying: String,
+    pub underlying_exchange: String,
+    pub quantity: i64,
+    pub underlying_ltp: f64,
+    /// Delta per unit: 1 for stocks and futures
+    pub delta: f64,
+    /// Quantity times delta times underlying price
+    pub delta_exposure: f64,
+}
+
+/// Exposure aggregated per underlying
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnderlyingRisk {
+    pub underlying: String,
+    pub exchange: String,
+    /// Sum of absolute position delta exposures
+    pub gross_exposure: f64,
+    /// Sum of signed position delta exposures
+    pub net_exposure: f64,
+    /// Beta of daily returns to the benchmark
+    pub beta: Option<f64>,
+    /// Net exposure times beta
+    pub beta_weighted_exposure: Option<f64>,
+    /// Annualised historical volatility
+    pub volatility: Option<f64>,
+}
+
+/// Portfolio risk report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskReport {
+    pub benchmark: String,
+    pub benchmark_ltp: Option<f64>,
+    pub positions: Vec<PositionRisk>,
+    pub underlyings: Vec<UnderlyingRisk>,
+    pub gross_exposure: f64,
+    pub net_exposure: f64,
+    /// Sum of beta-weighted exposures of underlyings with a beta
+    pub beta_weighted_exposure: f64,
+    /// Beta-weighted exposure in benchmark units (e.g. NIFTY points of delta)
+    pub beta_weighted_delta: Option<f64>,
+    /// Parametric VaR in rupees over `horizon_days` at `confidence`
+    pub var: f64,
+    pub confidence: f64,
+    pub horizon_days: f64,
+    /// Positions left out because they could not be priced or mapped to an underlying
+    pub unpriced: Vec<String>,
+}
+
+/// Build a risk report from the positionbook, quotes and daily history
+///
+/// Underlyings whose quotes fail are listed in `unpriced`; underlyings whose
+/// history fails have no volatility or beta and are left out of VaR.
+pub async fn report(client: &OpenAlgo, config: &RiskConfig) -> Result<RiskReport, OpenAlgoError> {
+    let book = client.positionbook().await?;
+    let rows = book.data.ok_or_else(|| {
+        OpenAlgoError::ApiError(book.message.unwrap_or_else(|| "Positionbook has no data".to_string()).into())
+    })?;
+
+    let mut unpriced = Vec::new();
+    let mut open = Vec::new();
+    for row in rows {
+        let (Some(symbol), Some(exchange)) = (row.symbol, row.exchange) else { continue };
+        let quantity = row.quantity.as_deref().and_then(parse_number).unwrap_or(0.0).round() as i64;
+        if quantity == 0 {
+            continue;
+        }
+        match underlying_of(&symbol, &exchange) {
+            Some((underlying, underlying_exchange, kind)) => {
+                open.push((symbol, exchange, quantity, underlying, underlying_exchange, kind))
+            }
+            None => unpriced.push(symbol),
+        }
+    }
+
+    let benchmark = (config.benchmark_symbol.clone(), config.benchmark_exchange.clone());
+    let instruments: Vec<(String, String)> = open
+        .iter()
+        .map(|p| (p.3.clone(), p.4.clone()))
+        .chain(std::iter::once(benchmark.clone()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    let today = ist_day_and_secs(now).0;
+    let end_date = format_date(today);
+    // Calendar days covering the lookback in trading days, plus holidays
+    let start_date = format_date(today - (config.lookback_days as i64 * 7 / 5 + 15));
+
+    let ltps: BTreeMap<(String, String), f64> = bulk::run(instruments.clone(), config.concurrency, |(symbol, exchange)| {
+        let client = client.clone();
+        async move { client.ltp(&symbol, &exchange).await }
+    })
+    .await
+    .into_iter()
+    .filter_map(|(key, ltp)| ltp.ok().map(|ltp| (key, price::to_f64(ltp))))
+    .collect();
+
+    let closes: BTreeMap<(String, String), BTreeMap<String, f64>> =
+        bulk::run(instruments, config.concurrency, |(symbol, exchange)| {
+            let client = client.clone();
+            let (start_date, end_date) = (start_date.clone(), end_date.clone());
+            async move {
+                client
+                    .data
+                    .history_candles(&symbol, &exchange, "D", Some(&start_date), Some(&end_date))
+                    .await
+            }
+        })
+        .await
+        .into_iter()
+        .filter_map(|(key, history)| {
+            let candles = history.ok()?.data?;
+            let closes = candles.iter().map(|c| (ist_date(c.timestamp), price::to_f64(c.close))).collect();
+            Some((key, closes))
+        })
+        .collect();
+
+    let trim = |returns: Vec<f64>| -> Vec<f64> {
+        let skip = returns.len().saturating_sub(config.lookback_days as usize);
+        returns[skip..].to_vec()
+    };
+    let benchmark_closes = closes.get(&benchmark);
+
+    let mut positions = Vec::new();
+    let mut underlyings: BTreeMap<(String, String), UnderlyingRisk> = BTreeMap::new();
+    for (symbol, exchange, quantity, underlying, underlying_exchange, kind) in open {
+        let key = (underlying.clone(), underlying_exchange.clone());
+        let Some(&spot) = ltps.get(&key) else {
+            unpriced.push(symbol);
+            continue;
+        };
+        let series = closes.get(&key);
+        let volatility = series.and_then(|s| historical_volatility(&trim(aligned_returns(&[s]).remove(0))));
+
+        let delta = match kind {
+            DerivativeKind::Future => 1.0,
+            DerivativeKind::Option { strike, option_type } => {
+                let expiry = parse_derivative_symbol(&symbol).map(|d| d.expiry_date).unwrap_or_default();
+                let t = time_to_expiry(&expiry).unwrap_or(0.0);
+                let rate = config.interest_rate / 100.0;
+                black_scholes_delta(spot, strike, t, rate, volatility.unwrap_or(0.0), option_type)
+            }
+        };
+        let delta_exposure = quantity as f64 * delta * spot;
+
+        let entry = underlyings.entry(key).or_insert_with(|| {
+            let beta = match (series, benchmark_closes) {
+                (Some(s), Some(b)) => {
+                    let mut aligned = aligned_returns(&[s, b]);
+                    let b = trim(aligned.remove(1));
+                    beta(&trim(aligned.remove(0)), &b)
+                }
+                _ => None,
+            };
+            UnderlyingRisk {
+                underlying: underlying.clone(),
+                exchange: underlying_exchange.clone(),
+                gross_exposure: 0.0,
+                net_exposure: 0.0,
+                beta,
+                beta_weighted_exposure: None,
+                volatility,
+            }
+        });
+        entry.gross_exposure += delta_exposure.abs();
+        entry.net_exposure += delta_exposure;
+
+        positions.push(PositionRisk {
+            symbol,
+            exchange,
+            underlying,
+            underlying_exchange,
+            quantity,
+            underlying_ltp: spot,
+            delta,
+            delta_exposure,
+        });
+    }
+    for u in underlyings.values_mut() {
+        u.beta_weighted_exposure = u.beta.map(|beta| beta * u.net_exposure);
+    }
+
+    // VaR over the underlyings with history, on the dates all of them traded
+    let with_history: Vec<(&(String, String), &UnderlyingRisk)> =
+        underlyings.iter().filter(|(key, _)| closes.contains_key(*key)).collect();
+    let series: Vec<&BTreeMap<String, f64>> = with_history.iter().map(|(key, _)| &closes[*key]).collect();
+    let returns: Vec<Vec<f64>> = aligned_returns(&series).into_iter().map(trim).collect();
+    let exposures: Vec<f64> = with_history.iter().map(|(_, u)| u.net_exposure).collect();
+    let var = parametric_var(&exposures, &returns, config.confidence, config.horizon_days).unwrap_or(0.0);
+
+    let underlyings: Vec<UnderlyingRisk> = underlyings.into_values().collect();
+    let beta_weighted_exposure: f64 = underlyings.iter().filter_map(|u| u.beta_weighted_exposure).sum();
+    let benchmark_ltp = ltps.get(&benchmark).copied();
+    Ok(RiskReport {
+        benchmark: config.benchmark_symbol.clone(),
+        benchmark_ltp,
+        gross_exposure: underlyings.iter().map(|u| u.gross_exposure).sum(),
+        net_exposure: underlyings.iter().map(|u| u.net_exposure).sum(),
+        beta_weighted_exposure,
+        beta_weighted_delta: benchmark_ltp.filter(|ltp| *ltp > 0.0).map(|ltp| beta_weighted_exposure / ltp),
+        var,
+        confidence: config.confidence,
+        horizon_days: config.horizon_days,
+        positions,
+        underlyings,
+        unpriced,
+    })
+}
+
+/// Underlying (symbol, exchange) of a position, and whether it is linear or an option
+fn underlying_of(symbol: &str, exchange: &str) -> Option<(String, String, DerivativeKind)> {
+    let cash = match exchange {
+        "NFO" => "NSE",
+        "BFO" => "BSE",
+        _ => return Some((symbol.to_string(), exchange.to_string(), DerivativeKind::Future)),
+    };
+    let parsed = parse_derivative_symbol(symbol)?;
+    let underlying_exchange = resolve_index(&parsed.underlying).map(|(_, exchange)| exchange).unwrap_or(cash);
+    Some((parsed.underlying, underlying_exchange.to_string(), parsed.kind))
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.trim().parse().ok()
+}
+
+/// Log returns of each series on the dates present in all of them
+///
+/// # Example
+/// ```rust
+/// use openalgo::risk::aligned_returns;
+/// use std::collections::BTreeMap;
+///
+/// let a: BTreeMap<String, f64> = [("2024-12-02", 100.0), ("2024-12-03", 110.0), ("2024-12-04", 99.0)]
+///     .into_iter().map(|(d, c)| (d.to_string(), c)).collect();
+/// let b: BTreeMap<String, f64> = [("2024-12-02", 50.0), ("2024-12-04", 55.0)]
+///     .into_iter().map(|(d, c)| (d.to_string(), c)).collect();
+/// let returns = aligned_returns(&[&a, &b]);
+/// assert_eq!(returns[0].len(), 1);
+/// assert!((returns[0][0] - (99.0f64 / 100.0).ln()).abs() < 1e-12);
+/// ```
+pub fn aligned_returns(series: &[&BTreeMap<String, f64>]) -> Vec<Vec<f64>> {
+    let Some((first, rest)) = series.split_first() else { return Vec::new() };
+    let dates: Vec<&String> = first.keys().filter(|d| rest.iter().all(|s| s.contains_key(*d))).collect();
+    series
+        .iter()
+        .map(|s| {
+            dates
+                .windows(2)
+                .filter_map(|w| {
+                    let (prev, next) = (s[w[0]], s[w[1]]);
+                    (prev > 0.0 && next > 0.0).then(|| (next / prev).ln())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Annualised volatility of daily returns
+pub fn historical_volatility(returns: &[f64]) -> Option<f64> {
+    Some(covariance(returns, returns)?.sqrt() * TRADING_DAYS.sqrt())
+}
+
+/// Beta of `returns` to `benchmark_returns` (equal-length, date-aligned)
+///
+/// # Example
+/// ```rust
+/// use openalgo::risk::beta;
+/// let benchmark = [0.01, -0.02, 0.015, 0.005];
+/// let stock: Vec<f64> = benchmark.iter().map(|r| r * 1.5).collect();
+/// assert!((beta(&stock, &benchmark).unwrap() - 1.5).abs() < 1e-9);
+/// ```
+pub fn beta(returns: &[f64], benchmark_returns: &[f64]) -> Option<f64> {
+    let variance = covariance(benchmark_returns, benchmark_returns).filter(|v| *v > 0.0)?;
+    Some(covariance(returns, benchmark_returns)? / variance)
+}
+
+/// Parametric VaR of exposures with daily returns `returns[i]` (equal-length, date-aligned)
+///
+/// The portfolio's daily standard deviation comes from the sample covariance
+/// of the returns, scaled by the square root of the horizon and the normal
+/// quantile of `confidence`.
+///
+/// # Example
+/// ```rust
+/// use openalgo::risk::parametric_var;
+/// // One position with a 1% daily standard deviation
+/// let returns = vec![vec![0.01, -0.01, 0.01, -0.01, 0.01, -0.01, 0.01, -0.01]];
+/// let var = parametric_var(&[1_000_000.0], &returns, 0.95, 1.0).unwrap();
+/// assert!((var - 17_584.0).abs() < 10.0);
+/// ```
+pub fn parametric_var(exposures: &[f64], returns: &[Vec<f64>], confidence: f64, horizon_days: f64) -> Option<f64> {
+    if exposures.is_empty() || exposures.len() != returns.len() || !(0.5..1.0).contains(&confidence) {
+        return None;
+    }
+    let mut variance = 0.0;
+    for (i, ei) in exposures.iter().enumerate() {
+        for (j, ej) in exposures.iter().enumerate() {
+            variance += ei * ej * covariance(&returns[i], &returns[j])?;
+        }
+    }
+    Some(normal_quantile(confidence) * variance.max(0.0).sqrt() * horizon_days.max(0.0).sqrt())
+}
+
+/// Sample covariance of two equal-length series
+fn covariance(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() != b.len() || a.len() < 2 {
+        return None;
+    }
+    let n = a.len() as f64;
+    let (mean_a, mean_b) = (a.iter().sum::<f64>() / n, b.iter().sum::<f64>() / n);
+    Some(a.iter().zip(b).map(|(x, y)| (x - mean_a) * (y - mean_b)).sum::<f64>() / (n - 1.0))
+}
+
+/// Inverse of the standard normal CDF, by bisection
+fn normal_quantile(p: f64) -> f64 {
+    let (mut low, mut high) = (-10.0, 10.0);
+    for _ in 0..100 {
+        let mid = 0.5 * (low + high);
+        if norm_cdf(mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    0.5 * (low + high)
+}
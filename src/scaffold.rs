@@ -0,0 +1,335 @@
+//! Strategy crate templates for `openalgo new-strategy`.
+//!
+//! `Scaffold` writes a ready-to-run binary crate:
+//!
+//! - `openalgo.toml` holds the connection settings, order defaults, risk
+//!   limits and a watchlist. It is loaded with `OpenAlgoConfig`.
+//! - `src/main.rs` streams LTP ticks for the watchlist with automatic
+//!   reconnects. It runs a `LossCircuit` and checks `RiskLimits` before every
+//!   order.
+//! - `src/strategy.rs` holds the signal logic, a moving-average crossover to
+//!   replace, together with a unit test.
+//!
+//! Built with the `cli` feature.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::scaffold::Scaffold;
+//!
+//! let scaffold = Scaffold::new("momentum-bot").unwrap();
+//! let files = scaffold.files();
+//! assert!(files.iter().any(|(path, _)| path.ends_with("src/strategy.rs")));
+//! let manifest = &files.iter().find(|(path, _)| path.ends_with("Cargo.toml")).unwrap().1;
+//! assert!(manifest.contains(r#"name = "momentum-bot""#));
+//! assert!(Scaffold::new("2fast").is_err());
+//! ```
+
+use crate::client::OpenAlgoError;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Generator for a new strategy crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scaffold {
+    name: String,
+    sdk_path: Option<PathBuf>,
+}
+
+impl Scaffold {
+    /// Strategy crate called `name`, which must be a valid crate name
+    pub fn new(name: &str) -> Result<Self, OpenAlgoError> {
+        let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(OpenAlgoError::ConfigError(format!(
+                "`{}` is not a valid crate name: use letters, digits, `-` and `_`, starting with a letter",
+                name
+            )));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            sdk_path: None,
+        })
+    }
+
+    /// Depend on a local checkout of the SDK instead of crates.io
+    pub fn sdk_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.sdk_path = Some(path.into());
+        self
+    }
+
+    /// Relative paths and contents of the generated files
+    pub fn files(&self) -> Vec<(PathBuf, String)> {
+        let sdk = match &self.sdk_path {
+            Some(path) => format!("path = {:?}, ", path.display().to_string()),
+            None => {
+                let version = env!("CARGO_PKG_VERSION");
+                let minor = version.rsplit_once('.').map_or(version, |(minor, _)| minor);
+                format!("version = \"{}\", ", minor)
+            }
+        };
+        let render = |template: &str| {
+            template
+                .replace("{{name}}", &self.name)
+                .replace("{{sdk}}", &sdk)
+        };
+        [
+            ("Cargo.toml", CARGO_TOML),
+            ("openalgo.toml", OPENALGO_TOML),
+            (".env.example", ENV_EXAMPLE),
+            (".gitignore", GITIGNORE),
+            ("README.md", README),
+            ("src/main.rs", MAIN_RS),
+            ("src/strategy.rs", STRATEGY_RS),
+        ]
+        .into_iter()
+        .map(|(path, template)| (PathBuf::from(path), render(template)))
+        .collect()
+    }
+
+    /// Write the crate into `dir`, which must not exist or be empty
+    ///
+    /// Returns the paths written.
+    pub fn write(&self, dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        if dir.exists() && std::fs::read_dir(dir)?.next().is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists and is not empty", dir.display()),
+            ));
+        }
+        let mut written = Vec::new();
+        for (relative, content) in self.files() {
+            let path = dir.join(relative);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, content)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+const CARGO_TOML: &str = r#"[package]
+name = "{{name}}"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+openalgo = { {{sdk}}features = ["config"] }
+tokio = { version = "1", features = ["full"] }
+dotenvy = "0.15"
+env_logger = "0.10"
+log = "0.4"
+"#;
+
+const OPENALGO_TOML: &str = r#"# Connection settings; the API key comes from OPENALGO_API_KEY (see .env.example)
+host = "http://127.0.0.1:5000"
+max_retries = 2
+
+[defaults]
+strategy = "{{name}}"
+exchange = "NSE"
+product = "MIS"
+
+# Checked before every order
+[risk]
+max_order_quantity = 100
+max_order_value = 200000.0
+allowed_exchanges = ["NSE"]
+
+# Instruments the strategy trades
+[[watchlists]]
+name = "{{name}}"
+instruments = [
+    { exchange = "NSE", symbol = "SBIN" },
+    { exchange = "NSE", symbol = "INFY" },
+]
+"#;
+
+const ENV_EXAMPLE: &str = "# Copy to .env and fill in\nOPENALGO_API_KEY=your-api-key\n";
+
+const GITIGNORE: &str = "/target\n.env\n";
+
+const README: &str = r#"# {{name}}
+
+Trading strategy built on the [openalgo](https://crates.io/crates/openalgo) SDK.
+
+1. Copy `.env.example` to `.env` and set `OPENALGO_API_KEY`.
+2. Adjust `openalgo.toml`: host, order defaults, risk limits and watchlist.
+3. Put your signal logic in `src/strategy.rs`.
+4. Try it with the OpenAlgo server in analyzer mode, then run:
+
+```text
+cargo run --release
+```
+
+The bot trips a loss circuit (cancel all orders and close positions) at the
+day loss set by `MAX_DAY_LOSS` in `src/main.rs`.
+"#;
+
+const MAIN_RS: &str = r#"//! {{name}}: streams LTP ticks, asks the strategy for signals and places
+//! orders within the risk limits of `openalgo.toml`.
+
+mod strategy;
+
+use openalgo::circuit::{CircuitEvent, LossCircuit};
+use openalgo::config::OpenAlgoConfig;
+use openalgo::reconnect::ReconnectPolicy;
+use openalgo::{price, OpenAlgo, WsCommand, WsConfig, WsData};
+use strategy::{Signal, Strategy};
+
+/// Day loss in rupees that halts trading and flattens positions
+const MAX_DAY_LOSS: f64 = 5000.0;
+
+/// Quantity per order
+const QUANTITY: i64 = 1;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = dotenvy::dotenv();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let config = OpenAlgoConfig::load("openalgo.toml")?;
+    let client = config.client()?;
+    let watchlist = config
+        .watchlist("{{name}}")
+        .ok_or("openalgo.toml has no watchlist named {{name}}")?
+        .clone();
+
+    let (mut circuit, _) = LossCircuit::new(MAX_DAY_LOSS)
+        .strategy(config.strategy())
+        .start(&client);
+
+    let ws = client
+        .websocket()
+        .with_config(WsConfig::default().reconnect(ReconnectPolicy::default()));
+    let (commands, mut ticks) = ws.connect().await?;
+    commands
+        .send(WsCommand::SubscribeLtp(watchlist.instruments.clone()))
+        .await?;
+    log::info!("{{name}} watching {} instruments", watchlist.instruments.len());
+
+    let mut strategy = Strategy::default();
+    loop {
+        tokio::select! {
+            Some(event) = circuit.recv() => {
+                if let CircuitEvent::Tripped { pnl, errors } = event {
+                    log::error!("loss circuit tripped at {:.2}; stopping ({:?})", pnl.total, errors);
+                    break;
+                }
+            }
+            data = ticks.recv() => match data {
+                Some(WsData::Ltp(tick)) => {
+                    let (Some(symbol), Some(exchange), Some(ltp)) = (tick.symbol, tick.exchange, tick.ltp) else {
+                        continue;
+                    };
+                    let ltp = price::to_f64(ltp);
+                    if let Some(signal) = strategy.on_tick(&symbol, ltp) {
+                        if let Err(e) = execute(&client, &config, &symbol, &exchange, ltp, signal).await {
+                            log::warn!("{} {:?} not placed: {}", symbol, signal, e);
+                        }
+                    }
+                }
+                Some(WsData::Reconnected { attempt }) => log::info!("feed reconnected after {} attempt(s)", attempt),
+                Some(_) => {}
+                None => break,
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Check the risk limits and place a market order for a signal
+async fn execute(
+    client: &OpenAlgo,
+    config: &OpenAlgoConfig,
+    symbol: &str,
+    exchange: &str,
+    ltp: f64,
+    signal: Signal,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(halt) = client.trading_halt() {
+        return Err(format!("trading halted: {}", halt.reason).into());
+    }
+    config.risk.check_order(exchange, QUANTITY, ltp)?;
+    let action = match signal {
+        Signal::Buy => "BUY",
+        Signal::Sell => "SELL",
+    };
+    let order = client
+        .place_order(config.strategy(), symbol, action, exchange, "MARKET", config.product(), &QUANTITY.to_string())
+        .await?;
+    log::info!("{} {} {} at ~{:.2}: order {:?}", action, QUANTITY, symbol, ltp, order.orderid);
+    Ok(())
+}
+"#;
+
+const STRATEGY_RS: &str = r#"//! Signal logic. Replace the moving-average crossover with your own.
+
+use std::collections::{HashMap, VecDeque};
+
+const FAST: usize = 5;
+const SLOW: usize = 20;
+
+/// What to do after a tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Buy,
+    Sell,
+}
+
+/// Moving-average crossover on ticks, one position per symbol
+#[derive(Debug, Default)]
+pub struct Strategy {
+    prices: HashMap<String, VecDeque<f64>>,
+    long: HashMap<String, bool>,
+}
+
+impl Strategy {
+    /// Feed a tick; returns a signal when the fast average crosses the slow one
+    pub fn on_tick(&mut self, symbol: &str, ltp: f64) -> Option<Signal> {
+        let prices = self.prices.entry(symbol.to_string()).or_default();
+        prices.push_back(ltp);
+        if prices.len() > SLOW {
+            prices.pop_front();
+        }
+        if prices.len() < SLOW {
+            return None;
+        }
+        let mean = |n: usize| prices.iter().rev().take(n).sum::<f64>() / n as f64;
+        let (fast, slow) = (mean(FAST), mean(SLOW));
+
+        let long = self.long.entry(symbol.to_string()).or_default();
+        match (*long, fast > slow) {
+            (false, true) => {
+                *long = true;
+                Some(Signal::Buy)
+            }
+            (true, false) => {
+                *long = false;
+                Some(Signal::Sell)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buys_on_uptrend_and_sells_on_reversal() {
+        let mut strategy = Strategy::default();
+        let mut signals = Vec::new();
+        let prices = (0..30).map(|i| 100.0 + i as f64).chain((0..30).map(|i| 130.0 - 2.0 * i as f64));
+        for price in prices {
+            signals.extend(strategy.on_tick("SBIN", price));
+        }
+        assert_eq!(signals, vec![Signal::Buy, Signal::Sell]);
+    }
+}
+"#;
@@ -0,0 +1,204 @@
+//! Order intents held until the market opens.
+//!
+//! An AMO is queued at the broker, and not every broker or segment accepts
+//! one. An `OrderQueue` instead accepts orders at any time, holds them in the
+//! process while the `MarketCalendar` session is closed, and sends them as
+//! regular orders at the next open, so a strategy can decide overnight and
+//! trade at the open. Orders can go out ahead of the open, for example into
+//! the NSE pre-open session, and the queue can be kept in a JSON file so
+//! pending intents survive a restart.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::calendar::MarketCalendar;
+//! use openalgo::scheduler::OrderQueue;
+//! use openalgo::PlaceOrderRequest;
+//! use std::time::Duration;
+//!
+//! # async fn example(client: openalgo::OpenAlgo) -> std::io::Result<()> {
+//! let queue = OrderQueue::new(MarketCalendar::nse())
+//!     .pre_open(Duration::from_secs(10 * 60))
+//!     .persist("orders.json")?;
+//! queue.submit(PlaceOrderRequest::new("Gap", "RELIANCE", "BUY", "NSE", "LIMIT", "CNC", "10").price("2480"))?;
+//!
+//! let (mut placed, _handle) = queue.start(&client);
+//! while let Some(order) = placed.recv().await {
+//!     println!("{}: {:?}", order.request.symbol, order.result.map(|r| r.orderid));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::calendar::MarketCalendar;
+use crate::client::OpenAlgoError;
+use crate::types::{OrderResponse, PlaceOrderRequest};
+use crate::OpenAlgo;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// An order sent by the queue, with the server's reply
+#[derive(Debug)]
+pub struct ScheduledOrder {
+    pub request: PlaceOrderRequest,
+    pub result: Result<OrderResponse, OpenAlgoError>,
+}
+
+/// Holds orders while the market is closed, then places them
+///
+/// Clones share one queue.
+#[derive(Debug, Clone)]
+pub struct OrderQueue {
+    calendar: MarketCalendar,
+    interval: Duration,
+    pre_open: Duration,
+    path: Option<PathBuf>,
+    queue: Arc<Mutex<Vec<PlaceOrderRequest>>>,
+}
+
+impl OrderQueue {
+    /// Place queued orders during the sessions of `calendar`, checking every second
+    pub fn new(calendar: MarketCalendar) -> Self {
+        Self {
+            calendar,
+            interval: Duration::from_secs(1),
+            pre_open: Duration::ZERO,
+            path: None,
+            queue: Arc::default(),
+        }
+    }
+
+    /// Time between checks of the clock
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Place orders this long before the session opens
+    pub fn pre_open(mut self, lead: Duration) -> Self {
+        self.pre_open = lead;
+        self
+    }
+
+    /// Keep the queue in a JSON file, loading any orders already saved there
+    ///
+    /// The file is rewritten on every change. A missing file starts an empty queue.
+    pub fn persist(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let saved: Vec<PlaceOrderRequest> = match fs::read(&path) {
+            Ok(content) => serde_json::from_slice(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let mut queue = self.queue.lock().unwrap();
+        queue.splice(0..0, saved);
+        save(&path, &queue)?;
+        drop(queue);
+        self.path = Some(path);
+        Ok(self)
+    }
+
+    /// Queue an order for the next session
+    ///
+    /// The AMO flag and API key are cleared: the order is sent live with the
+    /// client's key once the session is open, and no key is written to disk.
+    pub fn submit(&self, mut request: PlaceOrderRequest) -> io::Result<()> {
+        request.amo = false;
+        request.apikey = Default::default();
+        let mut queue = self.queue.lock().unwrap();
+        queue.push(request);
+        self.save(&queue)
+    }
+
+    /// Orders still waiting for the session
+    pub fn pending(&self) -> Vec<PlaceOrderRequest> {
+        self.queue.lock().unwrap().clone()
+    }
+
+    /// Drop all waiting orders, returning them
+    pub fn cancel_all(&self) -> io::Result<Vec<PlaceOrderRequest>> {
+        let mut queue = self.queue.lock().unwrap();
+        let cancelled = std::mem::take(&mut *queue);
+        self.save(&queue)?;
+        Ok(cancelled)
+    }
+
+    /// Whether queued orders are due at `timestamp` (Unix seconds)
+    pub fn is_due(&self, timestamp: i64) -> bool {
+        self.calendar.is_trading_time(timestamp)
+            || self.calendar.is_trading_time(timestamp + self.pre_open.as_secs() as i64)
+    }
+
+    /// Place the queued orders if they are due at `timestamp` (Unix seconds)
+    ///
+    /// Orders are sent in the order they were submitted and leave the queue
+    /// whether or not the server accepts them. Returns nothing while the
+    /// market is closed.
+    pub async fn place_due(&self, client: &OpenAlgo, timestamp: i64) -> Vec<ScheduledOrder> {
+        if !self.is_due(timestamp) {
+            return Vec::new();
+        }
+        let due = {
+            let mut queue = self.queue.lock().unwrap();
+            let due = std::mem::take(&mut *queue);
+            if let Err(e) = self.save(&queue) {
+                log::warn!("Order queue could not be saved: {}", e);
+            }
+            due
+        };
+        let mut placed = Vec::with_capacity(due.len());
+        for request in due {
+            let result = client.place_order_request(request.clone()).await;
+            placed.push(ScheduledOrder { request, result });
+        }
+        placed
+    }
+
+    /// Check the clock in the background and place orders as the session opens
+    ///
+    /// Orders submitted while the market is open are placed at the next check.
+    /// Dropping the receiver stops the queue.
+    pub fn start(&self, client: &OpenAlgo) -> (mpsc::Receiver<ScheduledOrder>, JoinHandle<()>) {
+        let (placed_tx, placed_rx) = mpsc::channel::<ScheduledOrder>(64);
+        let client = client.clone();
+        let queue = self.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(queue.interval);
+            loop {
+                ticker.tick().await;
+                if placed_tx.is_closed() {
+                    break;
+                }
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+                for order in queue.place_due(&client, now).await {
+                    if placed_tx.send(order).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        (placed_rx, handle)
+    }
+
+    fn save(&self, queue: &[PlaceOrderRequest]) -> io::Result<()> {
+        match &self.path {
+            Some(path) => save(path, queue),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Write the queue through a temporary file so a crash never leaves it half-written
+fn save(path: &Path, queue: &[PlaceOrderRequest]) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, serde_json::to_vec_pretty(queue)?)?;
+    fs::rename(&temp, path)
+}
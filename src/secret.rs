@@ -0,0 +1,114 @@
+//! Secret handling for OpenAlgo.
+//!
+//! The API key is kept in an `ApiKey` wrapper that never appears in `Debug`
+//! or `Display` output, is only written out when a request is serialized, and
+//! is wiped from memory when dropped. It keeps the key out of logs, not out
+//! of memory: serialized request bodies and the WebSocket authentication
+//! message hold plain copies until they are sent.
+
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::sync::RwLock;
+use tokio::sync::watch;
+use zeroize::Zeroize;
+
+/// OpenAlgo API key, redacted in debug output and zeroized on drop
+///
+/// # Example
+/// ```rust
+/// use openalgo::secret::ApiKey;
+///
+/// let key = ApiKey::new("a1b2c3");
+/// assert_eq!(format!("{:?}", key), "ApiKey([REDACTED])");
+/// assert_eq!(key.expose_secret(), "a1b2c3");
+/// assert_eq!(serde_json::to_string(&key).unwrap(), "\"a1b2c3\"");
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+    /// Wrap an API key
+    pub fn new(key: &str) -> Self {
+        Self(key.to_string())
+    }
+
+    /// The key itself, for sending to the server
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the key is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<&str> for ApiKey {
+    fn from(key: &str) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<String> for ApiKey {
+    fn from(key: String) -> Self {
+        Self(key)
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ApiKey([REDACTED])")
+    }
+}
+
+impl fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl Serialize for ApiKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl Drop for ApiKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// API key shared by a client and its WebSockets, replaceable while in use
+#[derive(Debug)]
+pub(crate) struct SharedApiKey {
+    key: RwLock<ApiKey>,
+    /// Number of rotations so far; live sockets watch it to reconnect
+    rotations: watch::Sender<u64>,
+}
+
+impl SharedApiKey {
+    pub(crate) fn new(key: &str) -> Self {
+        Self {
+            key: RwLock::new(ApiKey::new(key)),
+            rotations: watch::channel(0).0,
+        }
+    }
+
+    /// The current key
+    pub(crate) fn get(&self) -> ApiKey {
+        self.key.read().unwrap().clone()
+    }
+
+    /// Replace the key and notify watchers
+    pub(crate) fn set(&self, key: &str) {
+        *self.key.write().unwrap() = ApiKey::new(key);
+        self.rotations.send_modify(|count| *count += 1);
+    }
+
+    /// Receiver that changes on every rotation
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub(crate) fn rotations(&self) -> watch::Receiver<u64> {
+        self.rotations.subscribe()
+    }
+}
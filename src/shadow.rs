@@ -0,0 +1,317 @@
+//! Paper-vs-live shadow execution.
+//!
+//! Before promoting a strategy from simulation, run it live with a paper
+//! twin. `ShadowExecutor` places every order live through an `OrderJournal`
+//! and, from the depth snapshot taken just before, simulates what the paper
+//! broker would have filled: the order sweeps the visible book like a market
+//! order. `ShadowReport` then sets the live fills against the paper ones,
+//! per strategy and symbol: how often the two disagree on filling at all,
+//! and how far live prices diverge from paper. Divergence is signed so that
+//! positive is a cost: live bought above or sold below paper.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::shadow::ShadowExecutor;
+//!
+//! # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+//! let shadow = ShadowExecutor::new();
+//! shadow.place_order(&client, "Momentum", "SBIN", "BUY", "NSE", "MARKET", "MIS", "500", &[]).await?;
+//!
+//! shadow.refresh(&client).await;
+//! let report = shadow.report();
+//! println!("{}", report.to_csv());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::OpenAlgoError;
+use crate::execution::{side, slippage_bps};
+use crate::journal::{JournalEntry, OrderJournal};
+use crate::simulator::sweep;
+use crate::types::OrderResponse;
+use crate::OpenAlgo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// What the paper broker filled for an order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PaperFill {
+    pub orderid: String,
+    /// Quantity the visible book could fill
+    pub quantity: i64,
+    /// `None` when nothing filled
+    pub average_price: Option<f64>,
+}
+
+impl PaperFill {
+    /// Paper fill of `quantity` swept through `levels` of (price, quantity)
+    fn from_levels(orderid: &str, levels: &[(f64, i64)]) -> Self {
+        let quantity: i64 = levels.iter().map(|(_, q)| q).sum();
+        let notional: f64 = levels.iter().map(|(p, q)| p * *q as f64).sum();
+        Self {
+            orderid: orderid.to_string(),
+            quantity,
+            average_price: (quantity > 0).then(|| notional / quantity as f64),
+        }
+    }
+}
+
+/// Live order placement with a simulated paper twin
+///
+/// Clones share the journal and the paper fills.
+#[derive(Debug, Clone, Default)]
+pub struct ShadowExecutor {
+    journal: OrderJournal,
+    paper: Arc<Mutex<BTreeMap<String, PaperFill>>>,
+}
+
+impl ShadowExecutor {
+    /// Executor with an empty journal
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Executor recording live orders in an existing journal
+    pub fn with_journal(journal: OrderJournal) -> Self {
+        Self {
+            journal,
+            paper: Arc::default(),
+        }
+    }
+
+    /// Journal of the live orders
+    pub fn journal(&self) -> &OrderJournal {
+        &self.journal
+    }
+
+    /// Paper fill of an order
+    pub fn paper_fill(&self, orderid: &str) -> Option<PaperFill> {
+        self.paper.lock().unwrap().get(orderid).cloned()
+    }
+
+    /// Record a paper fill, such as one from a `FillSimulator` for a limit order
+    pub fn record_paper(&self, fill: PaperFill) {
+        self.paper.lock().unwrap().insert(fill.orderid.clone(), fill);
+    }
+
+    /// Place an order live and simulate it against the depth fetched just before
+    ///
+    /// A failed depth request does not stop the live order; the order then
+    /// has no paper fill. Orders the broker did not assign an ID to are
+    /// neither journaled nor simulated.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_order(
+        &self,
+        client: &OpenAlgo,
+        strategy: &str,
+        symbol: &str,
+        action: &str,
+        exchange: &str,
+        pricetype: &str,
+        product: &str,
+        quantity: &str,
+        tags: &[(&str, &str)],
+    ) -> Result<OrderResponse, OpenAlgoError> {
+        let depth = match client.depth(symbol, exchange).await {
+            Ok(response) => response.data,
+            Err(e) => {
+                log::warn!("shadow: no depth for {}: {}", symbol, e);
+                None
+            }
+        };
+        let levels = depth.map(|depth| {
+            let buy = !action.trim().eq_ignore_ascii_case("SELL");
+            sweep(&depth, buy, quantity.trim().parse().unwrap_or(0), None)
+        });
+
+        let response = self
+            .journal
+            .place_tagged_order(client, strategy, symbol, action, exchange, pricetype, product, quantity, tags)
+            .await?;
+        if let (Some(orderid), Some(levels)) = (&response.orderid, levels) {
+            self.record_paper(PaperFill::from_levels(orderid, &levels));
+        }
+        Ok(response)
+    }
+
+    /// Fetch the status of unfinished live orders; see `OrderJournal::refresh`
+    pub async fn refresh(&self, client: &OpenAlgo) -> usize {
+        self.journal.refresh(client).await
+    }
+
+    /// Compare the live orders with their paper fills
+    pub fn report(&self) -> ShadowReport {
+        ShadowReport::new(&self.journal.entries(), &self.paper.lock().unwrap())
+    }
+}
+
+/// One order, live and on paper
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShadowOrder {
+    pub orderid: String,
+    pub strategy: String,
+    pub symbol: String,
+    pub action: String,
+    pub quantity: i64,
+    /// Live average price, once the order completed
+    pub live_price: Option<f64>,
+    pub live_filled: bool,
+    /// Whether the order has finished live (completed, rejected or cancelled)
+    pub live_finished: bool,
+    pub paper_price: Option<f64>,
+    /// Paper filled the whole quantity
+    pub paper_filled: bool,
+    /// Live price against paper in bps, when both filled
+    pub divergence_bps: Option<f64>,
+}
+
+impl ShadowOrder {
+    fn new(entry: &JournalEntry, paper: Option<&PaperFill>) -> Self {
+        let live_price = entry.average_price.filter(|_| entry.is_filled());
+        let paper_filled = paper.is_some_and(|p| p.quantity >= entry.quantity);
+        let paper_price = paper.and_then(|p| p.average_price);
+        Self {
+            orderid: entry.orderid.clone(),
+            strategy: entry.strategy.clone(),
+            symbol: entry.symbol.clone(),
+            action: entry.action.clone(),
+            quantity: entry.quantity,
+            live_price,
+            live_filled: entry.is_filled(),
+            live_finished: entry.is_finished(),
+            paper_price,
+            paper_filled,
+            divergence_bps: match (live_price, paper_price.filter(|_| paper_filled)) {
+                (Some(live), Some(paper)) if paper > 0.0 => Some(slippage_bps(&entry.action, paper, live)),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Live-against-paper statistics of one strategy and symbol
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShadowStats {
+    pub strategy: String,
+    pub symbol: String,
+    pub orders: usize,
+    pub live_filled: usize,
+    pub paper_filled: usize,
+    /// Finished orders that filled on one side only
+    pub fill_mismatches: usize,
+    /// Quantity-weighted divergence of orders filled on both sides, in bps
+    pub mean_divergence_bps: Option<f64>,
+    /// Money lost live against paper: quantity times price difference, summed
+    pub divergence_cost: f64,
+}
+
+impl ShadowStats {
+    fn from_orders(strategy: &str, symbol: &str, orders: &[&ShadowOrder]) -> Self {
+        let mut weighted_bps = 0.0;
+        let mut compared_quantity = 0.0;
+        let mut divergence_cost = 0.0;
+        for order in orders {
+            if let (Some(bps), Some(live), Some(paper)) = (order.divergence_bps, order.live_price, order.paper_price) {
+                let quantity = order.quantity as f64;
+                weighted_bps += bps * quantity;
+                compared_quantity += quantity;
+                divergence_cost += side(&order.action) * (live - paper) * quantity;
+            }
+        }
+        Self {
+            strategy: strategy.to_string(),
+            symbol: symbol.to_string(),
+            orders: orders.len(),
+            live_filled: orders.iter().filter(|o| o.live_filled).count(),
+            paper_filled: orders.iter().filter(|o| o.paper_filled).count(),
+            fill_mismatches: orders
+                .iter()
+                .filter(|o| o.live_finished && o.live_filled != o.paper_filled)
+                .count(),
+            mean_divergence_bps: (compared_quantity > 0.0).then(|| weighted_bps / compared_quantity),
+            divergence_cost,
+        }
+    }
+}
+
+/// Live against paper, per order and per strategy and symbol
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShadowReport {
+    /// Every live order, in placement order
+    pub orders: Vec<ShadowOrder>,
+    /// One row per strategy and symbol, sorted by both
+    pub rows: Vec<ShadowStats>,
+    /// All orders together; strategy and symbol are empty
+    pub total: ShadowStats,
+}
+
+impl ShadowReport {
+    /// Report over journal entries and the paper fills keyed by order ID
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::journal::JournalEntry;
+    /// use openalgo::shadow::{PaperFill, ShadowReport};
+    /// use std::collections::BTreeMap;
+    ///
+    /// let live = JournalEntry {
+    ///     orderid: "1".into(), strategy: "S".into(), symbol: "SBIN".into(), action: "BUY".into(),
+    ///     quantity: 100, status: Some("complete".into()), average_price: Some(800.4),
+    ///     ..Default::default()
+    /// };
+    /// let rejected = JournalEntry { orderid: "2".into(), status: Some("rejected".into()), average_price: None, ..live.clone() };
+    /// let paper: BTreeMap<String, PaperFill> = ["1", "2"]
+    ///     .into_iter()
+    ///     .map(|id| (id.to_string(), PaperFill { orderid: id.into(), quantity: 100, average_price: Some(800.0) }))
+    ///     .collect();
+    ///
+    /// let report = ShadowReport::new(&[live, rejected], &paper);
+    /// let sbin = &report.rows[0];
+    /// assert_eq!((sbin.live_filled, sbin.paper_filled, sbin.fill_mismatches), (1, 2, 1));
+    /// assert!((sbin.mean_divergence_bps.unwrap() - 5.0).abs() < 1e-9);
+    /// assert!((sbin.divergence_cost - 40.0).abs() < 1e-6);
+    /// ```
+    pub fn new(entries: &[JournalEntry], paper: &BTreeMap<String, PaperFill>) -> Self {
+        let orders: Vec<ShadowOrder> = entries
+            .iter()
+            .map(|entry| ShadowOrder::new(entry, paper.get(&entry.orderid)))
+            .collect();
+        let mut groups: BTreeMap<(&str, &str), Vec<&ShadowOrder>> = BTreeMap::new();
+        for order in &orders {
+            groups
+                .entry((order.strategy.as_str(), order.symbol.as_str()))
+                .or_default()
+                .push(order);
+        }
+        let all: Vec<&ShadowOrder> = orders.iter().collect();
+        let rows = groups
+            .iter()
+            .map(|((strategy, symbol), group)| ShadowStats::from_orders(strategy, symbol, group))
+            .collect();
+        let total = ShadowStats::from_orders("", "", &all);
+        Self { orders, rows, total }
+    }
+
+    /// Rows as CSV with a header line; missing values are empty cells
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "strategy,symbol,orders,live_filled,paper_filled,fill_mismatches,mean_divergence_bps,divergence_cost\n",
+        );
+        for row in &self.rows {
+            let cells = [
+                crate::csv::field(&row.strategy),
+                crate::csv::field(&row.symbol),
+                row.orders.to_string(),
+                row.live_filled.to_string(),
+                row.paper_filled.to_string(),
+                row.fill_mismatches.to_string(),
+                row.mean_divergence_bps.map(|v| format!("{:.2}", v)).unwrap_or_default(),
+                format!("{:.2}", row.divergence_cost),
+            ];
+            csv.push_str(&cells.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
@@ -0,0 +1,268 @@
+//! Depth-calibrated fill simulation for backtests and paper trading.
+//!
+//! `FillSimulator` fills simulated orders against market depth snapshots
+//! (recorded REST depth or live WebSocket depth) of one instrument, instead
+//! of assuming a full fill at the touch:
+//!
+//! - marketable quantity walks the opposite side level by level, so large
+//!   orders fill partially and at worse prices;
+//! - market orders cancel whatever the visible book cannot fill;
+//! - resting limit orders join the back of the queue at their price and only
+//!   fill once the quantity ahead of them has traded, or when the market
+//!   trades through their price.
+//!
+//! Depth only shows level quantities, not trades, so a drop in the quantity
+//! at a resting order's price is split: `trade_fraction` of it is taken as
+//! trades from the front of the queue, and the rest as cancellations spread
+//! evenly through the level.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::simulator::{FillSimulator, SimOrder};
+//! use openalgo::DepthData;
+//!
+//! let book = |bid_qty: i64| -> DepthData {
+//!     serde_json::from_value(serde_json::json!({
+//!         "bids": [{"price": 100.0, "quantity": bid_qty}, {"price": 99.95, "quantity": 500}],
+//!         "asks": [{"price": 100.05, "quantity": 200}, {"price": 100.1, "quantity": 300}],
+//!     })).unwrap()
+//! };
+//!
+//! let mut sim = FillSimulator::new().trade_fraction(1.0);
+//! // Buy 250 at market: 200 at 100.05 and 50 at 100.1
+//! let (_, fills) = sim.submit(SimOrder::market("BUY", 250), &book(400));
+//! assert_eq!(fills.iter().map(|f| f.quantity).sum::<i64>(), 250);
+//!
+//! // Join the bid behind 400: 300 trades, then the level trades away
+//! let (id, fills) = sim.submit(SimOrder::limit("BUY", 100, 100.0), &book(400));
+//! assert!(fills.is_empty());
+//! assert!(sim.on_depth(&book(100)).is_empty());
+//! let fills = sim.on_depth(&book(0));
+//! assert_eq!((fills[0].order_id, fills[0].quantity, fills[0].price), (id, 100, 100.0));
+//! ```
+
+use crate::depth::DepthMetrics;
+use crate::price;
+use crate::types::DepthLevel;
+use serde::{Deserialize, Serialize};
+
+/// Order sent to the simulator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimOrder {
+    /// "BUY" or "SELL"
+    pub action: String,
+    pub quantity: i64,
+    /// Limit price; `None` for a market order
+    pub limit: Option<f64>,
+}
+
+impl SimOrder {
+    /// Market order; the action is upper-cased
+    pub fn market(action: &str, quantity: i64) -> Self {
+        Self { action: action.to_ascii_uppercase(), quantity, limit: None }
+    }
+
+    /// Limit order; the action is upper-cased
+    pub fn limit(action: &str, quantity: i64, price: f64) -> Self {
+        Self { action: action.to_ascii_uppercase(), quantity, limit: Some(price) }
+    }
+
+    fn is_buy(&self) -> bool {
+        self.action != "SELL"
+    }
+}
+
+/// Simulated fill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimFill {
+    pub order_id: u64,
+    pub quantity: i64,
+    pub price: f64,
+    /// Whether the fill came from resting in the book rather than crossing it
+    pub maker: bool,
+}
+
+/// Limit order resting in the simulated book
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestingOrder {
+    pub id: u64,
+    pub order: SimOrder,
+    /// Quantity still to fill
+    pub remaining: i64,
+    /// Estimated quantity queued ahead at the same price
+    pub queue_ahead: f64,
+    /// Quantity visible at the order's price in the last snapshot
+    level_quantity: i64,
+}
+
+/// Walk the opposite side of the book for up to `quantity`
+///
+/// Returns (price, quantity) per level taken, best first, stopping at
+/// `limit` when given. The total may be short of `quantity` when the visible
+/// book is too thin.
+///
+/// # Example
+/// ```rust
+/// use openalgo::simulator::sweep;
+/// use openalgo::DepthData;
+///
+/// let depth: DepthData = serde_json::from_str(r#"{
+///     "bids": [{"price": 100.0, "quantity": 50}],
+///     "asks": [{"price": 100.5, "quantity": 100}, {"price": 101.0, "quantity": 100}]
+/// }"#).unwrap();
+/// assert_eq!(sweep(&depth, true, 150, None), vec![(100.5, 100), (101.0, 50)]);
+/// assert_eq!(sweep(&depth, true, 150, Some(100.5)), vec![(100.5, 100)]);
+/// assert_eq!(sweep(&depth, false, 80, None), vec![(100.0, 50)]);
+/// ```
+pub fn sweep<D: DepthMetrics + ?Sized>(depth: &D, buy: bool, quantity: i64, limit: Option<f64>) -> Vec<(f64, i64)> {
+    let levels = if buy { depth.ask_levels() } else { depth.bid_levels() };
+    let mut left = quantity;
+    let mut taken = Vec::new();
+    for (level_price, level_quantity) in active(levels) {
+        if left <= 0 || limit.is_some_and(|limit| !crosses(buy, level_price, limit)) {
+            break;
+        }
+        let quantity = left.min(level_quantity);
+        taken.push((level_price, quantity));
+        left -= quantity;
+    }
+    taken
+}
+
+fn active(levels: &[DepthLevel]) -> impl Iterator<Item = (f64, i64)> + '_ {
+    levels
+        .iter()
+        .map(|l| (price::to_f64(l.price), l.quantity))
+        .filter(|(price, quantity)| *price > 0.0 && *quantity > 0)
+}
+
+/// Whether an opposite-side price is at or better than a limit
+fn crosses(buy: bool, price: f64, limit: f64) -> bool {
+    if buy {
+        price <= limit + 1e-9
+    } else {
+        price >= limit - 1e-9
+    }
+}
+
+/// Quantity on an order's own side of the book at its price
+fn own_level<D: DepthMetrics + ?Sized>(depth: &D, buy: bool, limit: f64) -> Option<i64> {
+    let levels = if buy { depth.bid_levels() } else { depth.ask_levels() };
+    active(levels).find(|(price, _)| (price - limit).abs() < 1e-9).map(|(_, q)| q)
+}
+
+/// Whether the order's own side has moved past its price, i.e. its level traded away
+fn traded_through<D: DepthMetrics + ?Sized>(depth: &D, buy: bool, limit: f64) -> bool {
+    let levels = if buy { depth.bid_levels() } else { depth.ask_levels() };
+    match active(levels).next() {
+        Some((best, _)) if buy => best < limit - 1e-9,
+        Some((best, _)) => best > limit + 1e-9,
+        None => false,
+    }
+}
+
+/// Fills simulated orders of one instrument against its depth snapshots
+#[derive(Debug, Clone)]
+pub struct FillSimulator {
+    trade_fraction: f64,
+    next_id: u64,
+    resting: Vec<RestingOrder>,
+}
+
+impl Default for FillSimulator {
+    fn default() -> Self {
+        Self { trade_fraction: 0.5, next_id: 1, resting: Vec::new() }
+    }
+}
+
+impl FillSimulator {
+    /// A simulator with no orders, taking half of each queue drop as trades
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Share of a drop in level quantity taken as trades rather than cancellations (0 to 1)
+    pub fn trade_fraction(mut self, fraction: f64) -> Self {
+        self.trade_fraction = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Send an order against the current depth
+    ///
+    /// Returns the order ID and the immediate fills. A limit order's
+    /// remainder rests at the back of its price level; a market order's
+    /// remainder is cancelled.
+    pub fn submit<D: DepthMetrics + ?Sized>(&mut self, order: SimOrder, depth: &D) -> (u64, Vec<SimFill>) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let fills: Vec<SimFill> = sweep(depth, order.is_buy(), order.quantity, order.limit)
+            .into_iter()
+            .map(|(price, quantity)| SimFill { order_id: id, quantity, price, maker: false })
+            .collect();
+        let remaining = order.quantity - fills.iter().map(|f| f.quantity).sum::<i64>();
+        if let (Some(limit), true) = (order.limit, remaining > 0) {
+            let level_quantity = own_level(depth, order.is_buy(), limit).unwrap_or(0);
+            self.resting.push(RestingOrder {
+                id,
+                order,
+                remaining,
+                queue_ahead: level_quantity as f64,
+                level_quantity,
+            });
+        }
+        (id, fills)
+    }
+
+    /// Advance resting orders to a new depth snapshot and return their fills
+    pub fn on_depth<D: DepthMetrics + ?Sized>(&mut self, depth: &D) -> Vec<SimFill> {
+        let trade_fraction = self.trade_fraction;
+        let mut fills = Vec::new();
+        for resting in &mut self.resting {
+            let buy = resting.order.is_buy();
+            let limit = resting.order.limit.unwrap_or_default();
+            let filled = if sweep(depth, buy, 1, Some(limit)).is_empty() {
+                match own_level(depth, buy, limit) {
+                    Some(quantity) => {
+                        let drop = (resting.level_quantity - quantity).max(0) as f64;
+                        let traded = drop * trade_fraction;
+                        let behind = (resting.level_quantity as f64 - resting.queue_ahead).max(0.0);
+                        let cancelled_ahead = if resting.level_quantity > 0 {
+                            (drop - traded) * resting.queue_ahead / (resting.queue_ahead + behind)
+                        } else {
+                            0.0
+                        };
+                        let ahead = resting.queue_ahead - cancelled_ahead;
+                        resting.queue_ahead = (ahead - traded).max(0.0);
+                        resting.level_quantity = quantity;
+                        ((traded - ahead).max(0.0).floor() as i64).min(resting.remaining)
+                    }
+                    None if traded_through(depth, buy, limit) => resting.remaining,
+                    None => 0,
+                }
+            } else {
+                // The opposite side reached the limit: fill what it offers there
+                let offered: i64 = sweep(depth, buy, resting.remaining, Some(limit)).iter().map(|(_, q)| q).sum();
+                resting.queue_ahead = 0.0;
+                offered
+            };
+            if filled > 0 {
+                resting.remaining -= filled;
+                fills.push(SimFill { order_id: resting.id, quantity: filled, price: limit, maker: true });
+            }
+        }
+        self.resting.retain(|r| r.remaining > 0);
+        fills
+    }
+
+    /// Cancel a resting order; false if it is not resting
+    pub fn cancel(&mut self, id: u64) -> bool {
+        let before = self.resting.len();
+        self.resting.retain(|r| r.id != id);
+        self.resting.len() != before
+    }
+
+    /// Limit orders still resting
+    pub fn open_orders(&self) -> &[RestingOrder] {
+        &self.resting
+    }
+}
@@ -0,0 +1,295 @@
+//! Panic-safe supervision of strategy tasks.
+//!
+//! A `Supervisor` runs a strategy as a task and watches how it ends. A panic
+//! or a returned error is a crash: the supervisor first makes the strategy's
+//! book safe (`CrashAction`), notifies over Telegram and its event channel,
+//! and then restarts the strategy as its `RestartPolicy` allows, backing off
+//! between restarts. A strategy that returns `Ok` has finished and is not
+//! restarted.
+//!
+//! # Example
+//! ```rust,no_run
+//! use openalgo::supervisor::{CrashAction, RestartPolicy, Supervisor, SupervisorEvent};
+//! use openalgo::OpenAlgo;
+//! use std::time::Duration;
+//!
+//! async fn momentum(client: OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+//!     loop {
+//!         let quote = client.quotes("SBIN", "NSE").await?;
+//!         // ...
+//! #       let _ = quote;
+//!     }
+//! }
+//!
+//! # async fn example(client: OpenAlgo) {
+//! let strategy_client = client.clone();
+//! let (mut events, _handle) = Supervisor::new("Momentum")
+//!     .restart(RestartPolicy::limited(3).backoff(Duration::from_secs(5), Duration::from_secs(60)))
+//!     .on_crash(CrashAction::Flatten)
+//!     .notify_telegram("trader")
+//!     .start(&client, move || momentum(strategy_client.clone()));
+//!
+//! while let Some(event) = events.recv().await {
+//!     println!("{:?}", event);
+//! }
+//! # }
+//! ```
+
+use crate::OpenAlgo;
+use std::any::Any;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::task::{AbortHandle, JoinHandle};
+
+/// How often a crashed strategy is restarted
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestartPolicy {
+    /// Restarts allowed over the supervisor's lifetime; `None` for no limit
+    pub max_restarts: Option<u32>,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self::never()
+    }
+}
+
+impl RestartPolicy {
+    /// Leave a crashed strategy stopped
+    pub fn never() -> Self {
+        Self::limited(0)
+    }
+
+    /// Restart up to `max_restarts` times, backing off from 1 s to 60 s
+    pub fn limited(max_restarts: u32) -> Self {
+        Self {
+            max_restarts: Some(max_restarts),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+
+    /// Restart after every crash, backing off from 1 s to 60 s
+    pub fn always() -> Self {
+        Self {
+            max_restarts: None,
+            ..Self::limited(0)
+        }
+    }
+
+    /// Backoff starting at `initial` and doubling up to `max`
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Wait before restart number `restart` (from 0), or `None` once restarts are used up
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::supervisor::RestartPolicy;
+    /// use std::time::Duration;
+    ///
+    /// let policy = RestartPolicy::limited(3).backoff(Duration::from_secs(2), Duration::from_secs(5));
+    /// assert_eq!(policy.delay(0), Some(Duration::from_secs(2)));
+    /// assert_eq!(policy.delay(2), Some(Duration::from_secs(5)));
+    /// assert_eq!(policy.delay(3), None);
+    /// assert_eq!(RestartPolicy::never().delay(0), None);
+    /// ```
+    pub fn delay(&self, restart: u32) -> Option<Duration> {
+        if self.max_restarts.is_some_and(|max| restart >= max) {
+            return None;
+        }
+        let factor = 2u32.saturating_pow(restart);
+        Some(self.initial_backoff.saturating_mul(factor).min(self.max_backoff))
+    }
+}
+
+/// What to do with a strategy's orders and positions when it crashes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CrashAction {
+    /// Leave orders and positions as they are
+    #[default]
+    Nothing,
+    /// Cancel the strategy's open orders
+    CancelOrders,
+    /// Cancel open orders and close positions, as `OpenAlgo::kill_switch`
+    Flatten,
+}
+
+/// Event sent by a running `Supervisor`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SupervisorEvent {
+    /// The strategy panicked or returned an error
+    Crashed {
+        strategy: String,
+        reason: String,
+        /// Failures of the crash action or the notification
+        errors: Vec<String>,
+        /// Wait before the restart; `None` when the supervisor gives up
+        restart_in: Option<Duration>,
+    },
+    /// The strategy was started again; `restarts` counts from 1
+    Restarted { strategy: String, restarts: u32 },
+    /// The strategy returned `Ok` and was not restarted
+    Finished { strategy: String },
+}
+
+/// Runs a strategy task, restarting it after crashes
+#[derive(Debug, Clone)]
+pub struct Supervisor {
+    /// Strategy name for the crash action calls and notifications
+    pub strategy: String,
+    pub restart: RestartPolicy,
+    pub on_crash: CrashAction,
+    /// Telegram username notified of crashes
+    pub telegram_username: Option<String>,
+}
+
+impl Supervisor {
+    /// Supervise `strategy` without restarts or a crash action
+    pub fn new(strategy: &str) -> Self {
+        Self {
+            strategy: strategy.to_string(),
+            restart: RestartPolicy::default(),
+            on_crash: CrashAction::default(),
+            telegram_username: None,
+        }
+    }
+
+    /// When to restart after a crash
+    pub fn restart(mut self, policy: RestartPolicy) -> Self {
+        self.restart = policy;
+        self
+    }
+
+    /// What to do with the strategy's orders and positions after a crash, before any restart
+    pub fn on_crash(mut self, action: CrashAction) -> Self {
+        self.on_crash = action;
+        self
+    }
+
+    /// Notify a Telegram user of every crash
+    pub fn notify_telegram(mut self, username: &str) -> Self {
+        self.telegram_username = Some(username.to_string());
+        self
+    }
+
+    /// Start the strategy in the background, calling `strategy` for each (re)start
+    ///
+    /// Events are published without waiting: they are dropped when the
+    /// receiver is gone or its buffer of 32 is full, so supervision never
+    /// stalls on an unread receiver. Aborting the returned handle aborts the
+    /// strategy as well.
+    pub fn start<F, Fut, E>(&self, client: &OpenAlgo, strategy: F) -> (mpsc::Receiver<SupervisorEvent>, JoinHandle<()>)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display + Send + 'static,
+    {
+        let (event_tx, event_rx) = mpsc::channel::<SupervisorEvent>(32);
+        let client = client.clone();
+        let supervisor = self.clone();
+
+        let handle = tokio::spawn(async move {
+            let name = supervisor.strategy.clone();
+            let mut restarts = 0;
+            loop {
+                let task = tokio::spawn(strategy());
+                let _abort = AbortOnDrop(task.abort_handle());
+                let reason = match task.await {
+                    Ok(Ok(())) => {
+                        publish(&event_tx, SupervisorEvent::Finished { strategy: name });
+                        break;
+                    }
+                    Ok(Err(e)) => format!("returned an error: {}", e),
+                    Err(e) if e.is_panic() => format!("panicked: {}", panic_message(e.into_panic())),
+                    Err(e) => e.to_string(),
+                };
+                log::warn!("Strategy {} {}", name, reason);
+
+                let restart_in = supervisor.restart.delay(restarts);
+                let errors = supervisor.handle_crash(&client, &reason, restart_in).await;
+                let event = SupervisorEvent::Crashed {
+                    strategy: name.clone(),
+                    reason,
+                    errors,
+                    restart_in,
+                };
+                publish(&event_tx, event);
+
+                let Some(delay) = restart_in else { break };
+                tokio::time::sleep(delay).await;
+                restarts += 1;
+                publish(
+                    &event_tx,
+                    SupervisorEvent::Restarted {
+                        strategy: name.clone(),
+                        restarts,
+                    },
+                );
+            }
+        });
+
+        (event_rx, handle)
+    }
+
+    /// Run the crash action and the notification, returning what failed
+    async fn handle_crash(&self, client: &OpenAlgo, reason: &str, restart_in: Option<Duration>) -> Vec<String> {
+        let mut errors = Vec::new();
+        let action = match self.on_crash {
+            CrashAction::Nothing => Ok(()),
+            CrashAction::CancelOrders => client.cancel_all_order(&self.strategy).await.map(|_| ()),
+            CrashAction::Flatten => client.kill_switch(&self.strategy).await,
+        };
+        if let Err(e) = action {
+            errors.push(format!("{:?}: {}", self.on_crash, e));
+        }
+        if let Some(username) = &self.telegram_username {
+            let next = match restart_in {
+                Some(delay) => format!("restarting in {:?}", delay),
+                None => "not restarting".to_string(),
+            };
+            let message = format!("Strategy {} {}; {}", self.strategy, reason, next);
+            if let Err(e) = client.telegram(username, &message).await {
+                errors.push(format!("telegram: {}", e));
+            }
+        }
+        for error in &errors {
+            log::warn!("Supervisor of {}: {}", self.strategy, error);
+        }
+        errors
+    }
+}
+
+/// Aborts the strategy task when the supervisor itself is aborted
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Send an event if there is room, as the WebSocket data sink does for ticks
+fn publish(event_tx: &mpsc::Sender<SupervisorEvent>, event: SupervisorEvent) {
+    if let Err(TrySendError::Full(event)) = event_tx.try_send(event) {
+        log::debug!("Supervisor event dropped, receiver is full: {:?}", event);
+    }
+}
+
+/// Message of a panic payload, which is a `&str` or `String` for `panic!`
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map_or_else(|| "unknown panic".to_string(), |message| message.to_string()),
+    }
+}
@@ -0,0 +1,311 @@
+//! Symbol convention helpers for OpenAlgo.
+//!
+//! Maps common index names to the OpenAlgo symbol and exchange used for
+//! spot quotes, parses futures and option symbols back into their parts,
+//! translates broker and data-vendor notations (`NSE:RELIANCE`,
+//! `RELIANCE-EQ`) with a `SymbolMap`, and caches `symbol()` metadata (lot
+//! size, tick size, token) for `DataAPI::symbol_cached`.
+
+use crate::options::{parse_expiry_date, OptionType};
+use crate::types::SymbolData;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Default time symbol metadata is cached for
+pub const DEFAULT_SYMBOL_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Known indices as (OpenAlgo symbol, exchange, accepted aliases)
+const INDICES: &[(&str, &str, &[&str])] = &[
+    ("NIFTY", "NSE_INDEX", &["NIFTY", "NIFTY50", "NIFTY 50", "NIFTY_50"]),
+    ("BANKNIFTY", "NSE_INDEX", &["BANKNIFTY", "BANK NIFTY", "NIFTY BANK", "NIFTYBANK", "NIFTY_BANK"]),
+    ("FINNIFTY", "NSE_INDEX", &["FINNIFTY", "FIN NIFTY", "NIFTY FIN SERVICE", "NIFTY FINANCIAL SERVICES"]),
+    ("MIDCPNIFTY", "NSE_INDEX", &["MIDCPNIFTY", "MIDCAP NIFTY", "NIFTY MID SELECT", "NIFTY MIDCAP SELECT"]),
+    ("NIFTYNXT50", "NSE_INDEX", &["NIFTYNXT50", "NIFTY NEXT 50", "NIFTY NXT 50", "NIFTYNEXT50"]),
+    ("INDIAVIX", "NSE_INDEX", &["INDIAVIX", "INDIA VIX", "VIX"]),
+    ("SENSEX", "BSE_INDEX", &["SENSEX", "BSE SENSEX", "S&P BSE SENSEX"]),
+    ("BANKEX", "BSE_INDEX", &["BANKEX", "BSE BANKEX", "S&P BSE BANKEX"]),
+    ("SENSEX50", "BSE_INDEX", &["SENSEX50", "SENSEX 50", "BSE SENSEX 50"]),
+];
+
+/// Resolve a common index name to its OpenAlgo (symbol, exchange)
+///
+/// Matching is case-insensitive and accepts common spellings.
+///
+/// # Example
+/// ```rust
+/// use openalgo::symbols::resolve_index;
+/// assert_eq!(resolve_index("Nifty Bank"), Some(("BANKNIFTY", "NSE_INDEX")));
+/// assert_eq!(resolve_index("sensex"), Some(("SENSEX", "BSE_INDEX")));
+/// ```
+pub fn resolve_index(name: &str) -> Option<(&'static str, &'static str)> {
+    let name = name.trim().to_ascii_uppercase();
+    INDICES
+        .iter()
+        .find(|(_, _, aliases)| aliases.contains(&name.as_str()))
+        .map(|(symbol, exchange, _)| (*symbol, *exchange))
+}
+
+/// OpenAlgo symbols of all known indices with their exchanges
+pub fn known_indices() -> impl Iterator<Item = (&'static str, &'static str)> {
+    INDICES.iter().map(|(symbol, exchange, _)| (*symbol, *exchange))
+}
+
+/// Contract type of a derivative symbol
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DerivativeKind {
+    Future,
+    Option { strike: f64, option_type: OptionType },
+}
+
+/// Parts of an OpenAlgo futures or option symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivativeSymbol {
+    pub underlying: String,
+    /// Expiry in compact form ("26DEC24")
+    pub expiry_date: String,
+    pub kind: DerivativeKind,
+}
+
+/// Parse an OpenAlgo futures or option symbol
+///
+/// Returns `None` for symbols that are not in the `UNDERLYING + DDMMMYY +
+/// FUT` or `UNDERLYING + DDMMMYY + STRIKE + CE/PE` form.
+///
+/// # Example
+/// ```rust
+/// use openalgo::options::OptionType;
+/// use openalgo::symbols::{parse_derivative_symbol, DerivativeKind};
+///
+/// let future = parse_derivative_symbol("BANKNIFTY26DEC24FUT").unwrap();
+/// assert_eq!(future.underlying, "BANKNIFTY");
+/// assert_eq!(future.kind, DerivativeKind::Future);
+///
+/// let option = parse_derivative_symbol("NIFTY26DEC2424000PE").unwrap();
+/// assert_eq!(option.expiry_date, "26DEC24");
+/// assert_eq!(option.kind, DerivativeKind::Option { strike: 24000.0, option_type: OptionType::PE });
+///
+/// assert!(parse_derivative_symbol("RELIANCE").is_none());
+/// ```
+pub fn parse_derivative_symbol(symbol: &str) -> Option<DerivativeSymbol> {
+    let symbol = symbol.trim().to_ascii_uppercase();
+    if !symbol.is_ascii() {
+        return None;
+    }
+    // The expiry is the last DDMMMYY group, so underlyings containing digits still parse
+    let start = (1..symbol.len().saturating_sub(7) + 1)
+        .rev()
+        .find(|&i| is_expiry(&symbol[i..i + 7]))?;
+    let underlying = &symbol[..start];
+    let expiry_date = &symbol[start..start + 7];
+    let rest = &symbol[start + 7..];
+
+    let kind = if rest == "FUT" {
+        DerivativeKind::Future
+    } else {
+        let option_type = OptionType::parse(rest.get(rest.len().checked_sub(2)?..)?)?;
+        let strike: f64 = rest[..rest.len() - 2].parse().ok()?;
+        if strike <= 0.0 {
+            return None;
+        }
+        DerivativeKind::Option { strike, option_type }
+    };
+    Some(DerivativeSymbol {
+        underlying: underlying.to_string(),
+        expiry_date: expiry_date.to_string(),
+        kind,
+    })
+}
+
+fn is_expiry(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes[..2].iter().all(u8::is_ascii_digit)
+        && bytes[2..5].iter().all(u8::is_ascii_alphabetic)
+        && bytes[5..].iter().all(u8::is_ascii_digit)
+        && parse_expiry_date(text).is_some()
+}
+
+/// NSE/BSE equity series that vendors append to a symbol (`RELIANCE-EQ`)
+const SERIES: &[&str] = &["EQ", "BE", "BZ", "BL", "SM", "ST", "IL", "GB", "GS", "E1", "X1", "N1"];
+
+/// Split a vendor symbol of the form `EXCHANGE:SYMBOL` (TradingView, Fyers)
+///
+/// # Example
+/// ```rust
+/// use openalgo::symbols::split_prefixed;
+/// assert_eq!(split_prefixed("NSE:RELIANCE"), Some(("NSE", "RELIANCE")));
+/// assert_eq!(split_prefixed("RELIANCE"), None);
+/// ```
+pub fn split_prefixed(text: &str) -> Option<(&str, &str)> {
+    let (exchange, symbol) = text.trim().split_once(':')?;
+    (!exchange.is_empty() && !symbol.is_empty()).then_some((exchange, symbol))
+}
+
+/// Format a symbol as `EXCHANGE:SYMBOL`
+pub fn to_prefixed(symbol: &str, exchange: &str) -> String {
+    format!("{}:{}", exchange, symbol)
+}
+
+/// Remove an equity series suffix such as `-EQ` or `-BE`
+///
+/// # Example
+/// ```rust
+/// use openalgo::symbols::strip_series;
+/// assert_eq!(strip_series("RELIANCE-EQ"), "RELIANCE");
+/// assert_eq!(strip_series("BAJAJ-AUTO"), "BAJAJ-AUTO");
+/// ```
+pub fn strip_series(symbol: &str) -> &str {
+    match symbol.rsplit_once('-') {
+        Some((base, series)) if !base.is_empty() && SERIES.contains(&series) => base,
+        _ => symbol,
+    }
+}
+
+/// Two-way lookup between OpenAlgo and broker symbols
+///
+/// Built from `instruments()` data, using the `brsymbol` and `brexchange`
+/// fields of each instrument.
+///
+/// # Example
+/// ```rust
+/// use openalgo::symbols::SymbolMap;
+/// use openalgo::SymbolData;
+///
+/// let data: SymbolData = serde_json::from_value(serde_json::json!({
+///     "symbol": "RELIANCE", "exchange": "NSE",
+///     "brsymbol": "RELIANCE-EQ", "brexchange": "NSE"
+/// })).unwrap();
+/// let map = SymbolMap::from_instruments(&[data]);
+///
+/// assert_eq!(map.broker("RELIANCE", "NSE"), Some(("RELIANCE-EQ", "NSE")));
+/// assert_eq!(map.openalgo("RELIANCE-EQ", "NSE"), Some(("RELIANCE", "NSE")));
+/// assert_eq!(map.resolve("NSE:RELIANCE-EQ", "NSE"), Some(("RELIANCE", "NSE")));
+/// assert_eq!(map.resolve("reliance", "NSE"), Some(("RELIANCE", "NSE")));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SymbolMap {
+    /// (symbol, exchange) -> (brsymbol, brexchange)
+    to_broker: HashMap<(String, String), (String, String)>,
+    /// (brsymbol, brexchange) -> (symbol, exchange)
+    from_broker: HashMap<(String, String), (String, String)>,
+}
+
+impl SymbolMap {
+    /// Map built from `instruments()` data
+    pub fn from_instruments(instruments: &[SymbolData]) -> Self {
+        let mut map = Self::default();
+        map.extend(instruments);
+        map
+    }
+
+    /// Add instruments, e.g. from another exchange
+    ///
+    /// Instruments without a symbol or exchange are skipped; a missing broker
+    /// exchange defaults to the OpenAlgo exchange.
+    pub fn extend(&mut self, instruments: &[SymbolData]) {
+        for instrument in instruments {
+            let (Some(symbol), Some(exchange)) = (&instrument.symbol, &instrument.exchange) else {
+                continue;
+            };
+            let brsymbol = instrument.brsymbol.clone().unwrap_or_else(|| symbol.clone());
+            let brexchange = instrument.brexchange.clone().unwrap_or_else(|| exchange.clone());
+            let openalgo = (symbol.to_ascii_uppercase(), exchange.to_ascii_uppercase());
+            let broker = (brsymbol.to_ascii_uppercase(), brexchange.to_ascii_uppercase());
+            self.to_broker.insert(openalgo.clone(), broker.clone());
+            self.from_broker.insert(broker, openalgo);
+        }
+    }
+
+    /// Number of instruments
+    pub fn len(&self) -> usize {
+        self.to_broker.len()
+    }
+
+    /// Whether the map has no instruments
+    pub fn is_empty(&self) -> bool {
+        self.to_broker.is_empty()
+    }
+
+    /// Broker (symbol, exchange) of an OpenAlgo symbol
+    pub fn broker(&self, symbol: &str, exchange: &str) -> Option<(&str, &str)> {
+        let key = (symbol.trim().to_ascii_uppercase(), exchange.trim().to_ascii_uppercase());
+        self.to_broker.get(&key).map(|(s, e)| (s.as_str(), e.as_str()))
+    }
+
+    /// OpenAlgo (symbol, exchange) of a broker symbol
+    pub fn openalgo(&self, brsymbol: &str, brexchange: &str) -> Option<(&str, &str)> {
+        let key = (brsymbol.trim().to_ascii_uppercase(), brexchange.trim().to_ascii_uppercase());
+        self.from_broker.get(&key).map(|(s, e)| (s.as_str(), e.as_str()))
+    }
+
+    /// OpenAlgo (symbol, exchange) of a symbol in any known notation
+    ///
+    /// Accepts OpenAlgo symbols, broker symbols, `EXCHANGE:SYMBOL` and
+    /// series-suffixed symbols such as `RELIANCE-EQ`. The prefix, when
+    /// present, overrides `exchange`.
+    pub fn resolve(&self, text: &str, exchange: &str) -> Option<(&str, &str)> {
+        let text = text.trim().to_ascii_uppercase();
+        let exchange = exchange.trim().to_ascii_uppercase();
+        // Some brokers use prefixed symbols themselves ("NSE:RELIANCE-EQ")
+        if let Some(found) = self.openalgo(&text, &exchange) {
+            return Some(found);
+        }
+        let (exchange, symbol) = split_prefixed(&text).unwrap_or((&exchange, &text));
+        let found = [symbol, strip_series(symbol)].into_iter().find_map(|candidate| {
+            let key = (candidate.to_string(), exchange.to_string());
+            match self.to_broker.get_key_value(&key) {
+                Some(((symbol, exchange), _)) => Some((symbol.as_str(), exchange.as_str())),
+                None => self.openalgo(candidate, exchange),
+            }
+        });
+        found
+    }
+}
+
+/// In-memory symbol metadata keyed by (symbol, exchange), shared by a client and its copies
+pub(crate) struct SymbolCache {
+    entries: Mutex<HashMap<(String, String), (Instant, SymbolData)>>,
+    ttl: Mutex<Duration>,
+}
+
+impl Default for SymbolCache {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::default(),
+            ttl: Mutex::new(DEFAULT_SYMBOL_TTL),
+        }
+    }
+}
+
+impl SymbolCache {
+    fn key(symbol: &str, exchange: &str) -> (String, String) {
+        (symbol.to_string(), exchange.to_string())
+    }
+
+    pub(crate) fn get(&self, symbol: &str, exchange: &str) -> Option<SymbolData> {
+        let ttl = *self.ttl.lock().unwrap();
+        let entries = self.entries.lock().unwrap();
+        let (fetched_at, data) = entries.get(&Self::key(symbol, exchange))?;
+        (fetched_at.elapsed() < ttl).then(|| data.clone())
+    }
+
+    pub(crate) fn insert(&self, symbol: &str, exchange: &str, data: SymbolData) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(Self::key(symbol, exchange), (Instant::now(), data));
+    }
+
+    pub(crate) fn invalidate(&self, symbol: &str, exchange: &str) {
+        self.entries.lock().unwrap().remove(&Self::key(symbol, exchange));
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    pub(crate) fn set_ttl(&self, ttl: Duration) {
+        *self.ttl.lock().unwrap() = ttl;
+    }
+}
@@ -0,0 +1,488 @@
+//! Local mock OpenAlgo server for integration tests.
+//!
+//! `TestServer` listens on loopback ports. It serves the REST API through a
+//! `wiremock` server and the WebSocket feed through a small tokio-tungstenite
+//! server. Unlike `MockOpenAlgo`, which swaps the transport, requests travel
+//! over real HTTP and WebSocket connections, so the SDK's retries, timeouts,
+//! error mapping and reconnects run exactly as in production. Enabled by the
+//! `test-server` feature.
+//!
+//! REST replies are canned per endpoint. `respond` sets the standing reply,
+//! `respond_once` queues replies that are used first, and `Reply` sets the
+//! status code and delay. `load_fixtures` serves a directory of recorded
+//! responses, such as one written by `Recorder` against a real server.
+//!
+//! On the WebSocket side the server answers authentication and subscription
+//! requests. `push` and `push_ltp` send market data, and `drop_connections`
+//! closes every socket so reconnect handling can be tested.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::retry::RetryPolicy;
+//! use openalgo::test_server::{Reply, TestServer};
+//! use serde_json::json;
+//! use std::time::Duration;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let server = TestServer::start().await;
+//! // Two gateway errors, then a quote
+//! server.respond_once("quotes", Reply::error(502, "bad gateway"));
+//! server.respond_once("quotes", Reply::error(502, "bad gateway"));
+//! server.respond("quotes", json!({"status": "success", "data": {"ltp": 801.5}}));
+//!
+//! let retry = RetryPolicy::new(3).backoff(Duration::from_millis(1), Duration::from_millis(1));
+//! let client = server.builder().retry(retry).build();
+//! let quote = client.quotes("SBIN", "NSE").await.unwrap();
+//! assert_eq!(quote.data.unwrap().ltp.map(openalgo::price::to_f64), Some(801.5));
+//! assert_eq!(server.requests("quotes").len(), 3);
+//! # }
+//! ```
+
+use crate::client::OpenAlgoError;
+use crate::transport::{Transport, TransportFuture};
+use crate::{OpenAlgo, OpenAlgoBuilder};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+/// API key of clients made by `TestServer::client`
+pub const TEST_API_KEY: &str = "test-api-key";
+
+/// A canned REST reply
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reply {
+    pub status: u16,
+    pub body: Value,
+    pub delay: Duration,
+}
+
+impl Reply {
+    /// HTTP 200 with a JSON body
+    pub fn json(body: Value) -> Self {
+        Self {
+            status: 200,
+            body,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// An error status with an OpenAlgo error body
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({"status": "error", "message": message}),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Use another status code
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Wait before answering, e.g. to trigger client timeouts
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+impl From<Value> for Reply {
+    fn from(body: Value) -> Self {
+        Reply::json(body)
+    }
+}
+
+/// A REST request received by the server
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceivedRequest {
+    /// Endpoint such as "placeorder" or "market/holidays"
+    pub endpoint: String,
+    /// JSON body, `Null` when it was not JSON
+    pub body: Value,
+}
+
+#[derive(Default)]
+struct HttpState {
+    replies: HashMap<String, Reply>,
+    queued: HashMap<String, VecDeque<Reply>>,
+    requests: Vec<ReceivedRequest>,
+}
+
+/// Catch-all responder answering from `HttpState`
+struct Responder {
+    state: Arc<Mutex<HttpState>>,
+}
+
+impl Respond for Responder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let endpoint = endpoint_of(request.url.path());
+        let body = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+        let mut state = self.state.lock().unwrap();
+        state.requests.push(ReceivedRequest {
+            endpoint: endpoint.clone(),
+            body,
+        });
+        let reply = state
+            .queued
+            .get_mut(&endpoint)
+            .and_then(VecDeque::pop_front)
+            .or_else(|| state.replies.get(&endpoint).cloned())
+            .unwrap_or_else(|| Reply::error(404, &format!("no reply configured for {}", endpoint)));
+        ResponseTemplate::new(reply.status)
+            .set_body_json(reply.body)
+            .set_delay(reply.delay)
+    }
+}
+
+/// `/api/v1/market/holidays` -> `market/holidays`
+fn endpoint_of(path: &str) -> String {
+    let path = path.trim_matches('/');
+    let mut parts = path.splitn(3, '/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some("api"), Some(_version), Some(endpoint)) => endpoint.to_string(),
+        _ => path.to_string(),
+    }
+}
+
+#[derive(Default)]
+struct WsState {
+    reject_auth: bool,
+    accepted: usize,
+    received: Vec<Value>,
+    clients: Vec<mpsc::UnboundedSender<Message>>,
+}
+
+/// Mock OpenAlgo REST and WebSocket server on loopback ports
+pub struct TestServer {
+    http: MockServer,
+    state: Arc<Mutex<HttpState>>,
+    ws: Arc<Mutex<WsState>>,
+    ws_url: String,
+    ws_task: JoinHandle<()>,
+}
+
+impl std::fmt::Debug for TestServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestServer")
+            .field("uri", &self.http.uri())
+            .field("ws_url", &self.ws_url)
+            .finish()
+    }
+}
+
+impl TestServer {
+    /// Start both servers on free loopback ports
+    pub async fn start() -> Self {
+        let http = MockServer::start().await;
+        let state = Arc::new(Mutex::new(HttpState::default()));
+        Mock::given(wiremock::matchers::any())
+            .respond_with(Responder { state: Arc::clone(&state) })
+            .with_priority(u8::MAX)
+            .mount(&http)
+            .await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind WebSocket test server");
+        let ws_url = format!("ws://{}", listener.local_addr().expect("WebSocket test server address"));
+        let ws = Arc::new(Mutex::new(WsState::default()));
+        let ws_task = tokio::spawn(accept_loop(listener, Arc::clone(&ws)));
+        Self {
+            http,
+            state,
+            ws,
+            ws_url,
+            ws_task,
+        }
+    }
+
+    /// Base URL of the REST server, for `OpenAlgoBuilder::host`
+    pub fn uri(&self) -> String {
+        self.http.uri()
+    }
+
+    /// URL of the WebSocket server
+    pub fn ws_url(&self) -> &str {
+        &self.ws_url
+    }
+
+    /// The underlying `wiremock` server, for mocks beyond canned replies
+    ///
+    /// Its mounted mocks take precedence over `respond`.
+    pub fn http(&self) -> &MockServer {
+        &self.http
+    }
+
+    /// Builder pointed at this server, to add retries, hooks and so on
+    pub fn builder(&self) -> OpenAlgoBuilder {
+        OpenAlgo::builder(TEST_API_KEY).host(&self.uri()).ws_url(&self.ws_url)
+    }
+
+    /// Client pointed at this server
+    pub fn client(&self) -> OpenAlgo {
+        self.builder().build()
+    }
+
+    /// Always answer `endpoint` with `reply` once the queued replies are used
+    pub fn respond(&self, endpoint: &str, reply: impl Into<Reply>) {
+        self.lock().replies.insert(endpoint.to_string(), reply.into());
+    }
+
+    /// Answer the next request to `endpoint` with `reply`
+    ///
+    /// Queued replies are used in order before the standing one.
+    pub fn respond_once(&self, endpoint: &str, reply: impl Into<Reply>) {
+        self.lock().queued.entry(endpoint.to_string()).or_default().push_back(reply.into());
+    }
+
+    /// Serve every `<endpoint>.json` in `dir`, returning how many were loaded
+    ///
+    /// File names follow `tests/fixtures` and `Recorder`: `/` in the endpoint
+    /// becomes `_`, and names with a `-N` suffix are extra examples that are
+    /// skipped.
+    pub fn load_fixtures(&self, dir: impl AsRef<Path>) -> io::Result<usize> {
+        let mut loaded = 0;
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+            if path.extension().is_none_or(|e| e != "json") || name.contains('-') {
+                continue;
+            }
+            let body: Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            self.respond(&name.replace('_', "/"), body);
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    /// REST requests received for `endpoint`, oldest first
+    pub fn requests(&self, endpoint: &str) -> Vec<Value> {
+        self.lock()
+            .requests
+            .iter()
+            .filter(|r| r.endpoint == endpoint)
+            .map(|r| r.body.clone())
+            .collect()
+    }
+
+    /// Every REST request received, oldest first
+    pub fn all_requests(&self) -> Vec<ReceivedRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// Forget replies and recorded requests
+    pub fn reset(&self) {
+        *self.lock() = HttpState::default();
+    }
+
+    /// Reject WebSocket authentication from now on
+    pub fn reject_auth(&self, reject: bool) {
+        self.ws().reject_auth = reject;
+    }
+
+    /// Send a JSON message to every connected WebSocket client
+    pub fn push(&self, message: Value) {
+        let text = message.to_string();
+        self.ws().clients.retain(|client| client.send(Message::Text(text.clone())).is_ok());
+    }
+
+    /// Send an LTP update to every connected WebSocket client
+    pub fn push_ltp(&self, symbol: &str, exchange: &str, ltp: f64) {
+        self.push(json!({
+            "type": "market_data",
+            "mode": 1,
+            "topic": format!("{}.{}", symbol, exchange),
+            "data": {"symbol": symbol, "exchange": exchange, "ltp": ltp},
+        }));
+    }
+
+    /// Close every WebSocket connection, as a server restart would
+    pub fn drop_connections(&self) {
+        for client in self.ws().clients.drain(..) {
+            let _ = client.send(Message::Close(None));
+        }
+    }
+
+    /// WebSocket connections accepted so far, including closed ones
+    pub fn ws_connections(&self) -> usize {
+        self.ws().accepted
+    }
+
+    /// Wait until `count` WebSocket connections have been accepted
+    ///
+    /// Returns false if that did not happen within `timeout`.
+    pub async fn wait_for_ws_connections(&self, count: usize, timeout: Duration) -> bool {
+        let wait = async {
+            while self.ws_connections() < count {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.is_ok()
+    }
+
+    /// JSON messages received from WebSocket clients, oldest first
+    pub fn ws_messages(&self) -> Vec<Value> {
+        self.ws().received.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HttpState> {
+        self.state.lock().unwrap()
+    }
+
+    fn ws(&self) -> std::sync::MutexGuard<'_, WsState> {
+        self.ws.lock().unwrap()
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.ws_task.abort();
+        self.drop_connections();
+    }
+}
+
+async fn accept_loop(listener: TcpListener, state: Arc<Mutex<WsState>>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(serve_socket(stream, Arc::clone(&state)));
+    }
+}
+
+async fn serve_socket(stream: TcpStream, state: Arc<Mutex<WsState>>) {
+    let Ok(socket) = tokio_tungstenite::accept_async(stream).await else { return };
+    let (mut write, mut read) = socket.split();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    {
+        let mut state = state.lock().unwrap();
+        state.accepted += 1;
+        state.clients.push(tx.clone());
+    }
+    loop {
+        tokio::select! {
+            outgoing = rx.recv() => match outgoing {
+                Some(Message::Close(_)) | None => {
+                    let _ = write.send(Message::Close(None)).await;
+                    break;
+                }
+                Some(message) => {
+                    if write.send(message).await.is_err() {
+                        break;
+                    }
+                }
+            },
+            incoming = read.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    let Ok(message) = serde_json::from_str::<Value>(&text) else { continue };
+                    let reply = ws_reply(&message, &state);
+                    state.lock().unwrap().received.push(message);
+                    if let Some(reply) = reply {
+                        let _ = tx.send(Message::Text(reply.to_string()));
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// Server answer to a client message, if any
+fn ws_reply(message: &Value, state: &Mutex<WsState>) -> Option<Value> {
+    let action = message.get("action").and_then(Value::as_str)?;
+    match action {
+        "authenticate" if state.lock().unwrap().reject_auth => Some(json!({
+            "type": "error",
+            "code": "AUTHENTICATION_ERROR",
+            "message": "Invalid API key",
+        })),
+        "authenticate" => Some(json!({"type": "auth", "status": "success", "message": "Authentication successful"})),
+        "subscribe" | "unsubscribe" => {
+            let symbols: Vec<Value> = message
+                .get("symbols")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(|s| json!({"symbol": s.get("symbol"), "exchange": s.get("exchange"), "status": "success"}))
+                .collect();
+            Some(json!({"type": action, "status": "success", "subscriptions": symbols}))
+        }
+        _ => None,
+    }
+}
+
+/// Transport that saves each response as a fixture while passing it through
+///
+/// Point a client at a real server through a `Recorder` to capture fixtures
+/// that `TestServer::load_fixtures` (or a contract test) can serve later.
+/// The file for an endpoint is overwritten by each new response.
+///
+/// # Example
+/// ```rust,no_run
+/// use openalgo::test_server::Recorder;
+/// use openalgo::transport::ReqwestTransport;
+/// use std::sync::Arc;
+///
+/// # async fn example() -> Result<(), openalgo::client::OpenAlgoError> {
+/// let recorder = Recorder::new(Arc::new(ReqwestTransport::default()), "tests/fixtures");
+/// let client = openalgo::OpenAlgo::builder("your-api-key")
+///     .host("http://127.0.0.1:5000")
+///     .transport(Arc::new(recorder))
+///     .build();
+/// client.funds().await?; // saved as tests/fixtures/funds.json
+/// # Ok(())
+/// # }
+/// ```
+pub struct Recorder {
+    inner: Arc<dyn Transport>,
+    dir: PathBuf,
+}
+
+impl Recorder {
+    /// Record responses from `inner` into `dir`
+    pub fn new(inner: Arc<dyn Transport>, dir: impl Into<PathBuf>) -> Self {
+        Self { inner, dir: dir.into() }
+    }
+
+    fn save(&self, url: &str, body: &str) {
+        let Ok(json) = serde_json::from_str::<Value>(body) else { return };
+        let path = url::Url::parse(url).map(|u| u.path().to_string()).unwrap_or_default();
+        let name = endpoint_of(&path).replace('/', "_");
+        if name.is_empty() {
+            return;
+        }
+        let write = std::fs::create_dir_all(&self.dir).and_then(|_| {
+            let pretty = serde_json::to_string_pretty(&json).map_err(io::Error::other)?;
+            std::fs::write(self.dir.join(format!("{}.json", name)), pretty + "\n")
+        });
+        if let Err(e) = write {
+            log::warn!("recorder: could not save {}: {}", name, e);
+        }
+    }
+}
+
+impl Transport for Recorder {
+    fn post_json<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let response = self.inner.post_json(url, body).await?;
+            self.save(url, &response);
+            Ok::<_, OpenAlgoError>(response)
+        })
+    }
+
+    fn get_json<'a>(&'a self, url: &'a str, query: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let response = self.inner.get_json(url, query).await?;
+            self.save(url, &response);
+            Ok(response)
+        })
+    }
+}
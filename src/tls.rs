@@ -0,0 +1,143 @@
+//! TLS backends for WebSocket connections.
+//!
+//! Builds the `wss://` connector for a `WsTlsConfig` with whichever backend
+//! is enabled: the `native-tls` feature takes precedence over the default
+//! `rustls`.
+
+use crate::client::OpenAlgoError;
+use crate::websocket::WsTlsConfig;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use url::Url;
+#[cfg(any(feature = "rustls", feature = "native-tls"))]
+use tokio_tungstenite::Connector;
+
+fn tls_error(e: impl std::fmt::Display) -> OpenAlgoError {
+    OpenAlgoError::WebSocketError(format!("TLS: {}", e))
+}
+
+/// Run the WebSocket handshake over an open TCP stream, adding TLS for `wss://`
+#[cfg(any(feature = "rustls", feature = "native-tls"))]
+pub(crate) async fn handshake(
+    url: &Url,
+    stream: TcpStream,
+    config: &WsTlsConfig,
+) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, OpenAlgoError> {
+    let connector = if url.scheme() == "wss" && !config.is_default() {
+        Some(connector(config)?)
+    } else {
+        None
+    };
+    let (ws_stream, _) = tokio_tungstenite::client_async_tls_with_config(url.as_str(), stream, None, connector)
+        .await
+        .map_err(|e| OpenAlgoError::WebSocketError(e.to_string()))?;
+    Ok(ws_stream)
+}
+
+/// Run the WebSocket handshake over an open TCP stream; `wss://` needs a TLS feature
+#[cfg(not(any(feature = "rustls", feature = "native-tls")))]
+pub(crate) async fn handshake(
+    url: &Url,
+    stream: TcpStream,
+    _config: &WsTlsConfig,
+) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, OpenAlgoError> {
+    if url.scheme() == "wss" {
+        return Err(tls_error("no TLS backend enabled; enable the `rustls` or `native-tls` feature"));
+    }
+    let (ws_stream, _) = tokio_tungstenite::client_async(url.as_str(), MaybeTlsStream::Plain(stream))
+        .await
+        .map_err(|e| OpenAlgoError::WebSocketError(e.to_string()))?;
+    Ok(ws_stream)
+}
+
+#[cfg(feature = "native-tls")]
+fn connector(config: &WsTlsConfig) -> Result<Connector, OpenAlgoError> {
+    let mut builder = native_tls::TlsConnector::builder();
+    for pem in &config.root_certificates {
+        builder.add_root_certificate(native_tls::Certificate::from_pem(pem).map_err(tls_error)?);
+    }
+    builder.danger_accept_invalid_certs(config.accept_invalid_certs);
+    Ok(Connector::NativeTls(builder.build().map_err(tls_error)?))
+}
+
+#[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+fn connector(config: &WsTlsConfig) -> Result<Connector, OpenAlgoError> {
+    use rustls::{ClientConfig, RootCertStore};
+    use std::sync::Arc;
+
+    let tls = if config.accept_invalid_certs {
+        ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(danger::AcceptAnyCert::new()))
+            .with_no_client_auth()
+    } else {
+        let mut roots = RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        for pem in &config.root_certificates {
+            for cert in rustls_pemfile::certs(&mut pem.as_slice()) {
+                roots.add(cert.map_err(tls_error)?).map_err(tls_error)?;
+            }
+        }
+        ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth()
+    };
+    Ok(Connector::Rustls(Arc::new(tls)))
+}
+
+#[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+mod danger {
+    use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+    use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, WebPkiSupportedAlgorithms};
+    use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+    use rustls::{DigitallySignedStruct, Error, SignatureScheme};
+
+    /// Accepts any server certificate while still checking handshake signatures
+    #[derive(Debug)]
+    pub(super) struct AcceptAnyCert {
+        algorithms: WebPkiSupportedAlgorithms,
+    }
+
+    impl AcceptAnyCert {
+        pub(super) fn new() -> Self {
+            Self {
+                algorithms: rustls::crypto::ring::default_provider().signature_verification_algorithms,
+            }
+        }
+    }
+
+    impl ServerCertVerifier for AcceptAnyCert {
+        fn verify_server_cert(
+            &self,
+            _end_entity: &CertificateDer<'_>,
+            _intermediates: &[CertificateDer<'_>],
+            _server_name: &ServerName<'_>,
+            _ocsp_response: &[u8],
+            _now: UnixTime,
+        ) -> Result<ServerCertVerified, Error> {
+            Ok(ServerCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, Error> {
+            verify_tls12_signature(message, cert, dss, &self.algorithms)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, Error> {
+            verify_tls13_signature(message, cert, dss, &self.algorithms)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.algorithms.supported_schemes()
+        }
+    }
+}
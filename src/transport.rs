@@ -0,0 +1,218 @@
+//! HTTP transport for OpenAlgo.
+//!
+//! `OpenAlgoClient` sends every request through a `Transport`, so tests can
+//! inject canned responses and advanced users can plug in their own HTTP
+//! stack.
+
+use crate::client::{ApiErrorDetails, OpenAlgoError};
+use crate::middleware::{Method, RequestParts};
+use reqwest::Client;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Future returned by transport methods, resolving to the response body
+#[cfg(not(target_arch = "wasm32"))]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<String, OpenAlgoError>> + Send + 'a>>;
+
+/// Future returned by transport methods, resolving to the response body
+///
+/// Browser futures are not `Send`, so neither is this one on `wasm32`.
+#[cfg(target_arch = "wasm32")]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<String, OpenAlgoError>> + 'a>>;
+
+/// Sends raw JSON requests to the OpenAlgo server
+///
+/// Implementations return the response body on success and an error for
+/// non-2xx responses.
+///
+/// # Example
+/// ```rust
+/// use openalgo::transport::{Transport, TransportFuture};
+/// use std::sync::Arc;
+///
+/// /// Answers every request with the same body
+/// struct Canned(String);
+///
+/// impl Transport for Canned {
+///     fn post_json<'a>(&'a self, _url: &'a str, _body: String) -> TransportFuture<'a> {
+///         Box::pin(async move { Ok(self.0.clone()) })
+///     }
+/// }
+///
+/// let client = openalgo::OpenAlgo::builder("test-key")
+///     .transport(Arc::new(Canned(r#"{"status":"success","data":{"ltp":101.5}}"#.into())))
+///     .build();
+///
+/// let ltp = tokio_test::block_on(client.ltp("RELIANCE", "NSE")).unwrap();
+/// assert_eq!(ltp.to_string(), "101.5");
+/// ```
+pub trait Transport: Send + Sync {
+    /// POST a JSON body to `url`
+    fn post_json<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a>;
+
+    /// GET `url` with query parameters
+    fn get_json<'a>(&'a self, url: &'a str, query: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        let _ = query;
+        Box::pin(async move {
+            Err(OpenAlgoError::ApiError(format!("GET not supported by this transport: {}", url).into()))
+        })
+    }
+
+    /// Send a request including any headers added by middleware
+    ///
+    /// The default forwards to `post_json` or `get_json` and ignores headers.
+    fn send<'a>(&'a self, request: &'a RequestParts) -> TransportFuture<'a> {
+        match request.method {
+            Method::Post => self.post_json(&request.url, request.body.clone().unwrap_or_default()),
+            Method::Get => Box::pin(async move {
+                let query: Vec<(&str, &str)> = request
+                    .query
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.as_str()))
+                    .collect();
+                self.get_json(&request.url, &query).await
+            }),
+        }
+    }
+}
+
+/// HTTP version used for REST requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HttpVersion {
+    /// HTTP/2 when the server offers it during the TLS handshake, else HTTP/1.1
+    #[default]
+    Auto,
+    /// HTTP/1.1 only
+    Http1,
+    /// HTTP/2 only, also over plain `http://` (prior knowledge)
+    Http2,
+}
+
+/// Settings for the `reqwest` client the builder creates
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+#[derive(Debug, Clone)]
+pub(crate) struct HttpSettings {
+    #[cfg_attr(not(feature = "compression"), allow(dead_code))]
+    pub gzip: bool,
+    #[cfg_attr(not(feature = "compression"), allow(dead_code))]
+    pub deflate: bool,
+    pub version: HttpVersion,
+    pub pool_idle_timeout: Option<Duration>,
+    pub pool_max_idle_per_host: usize,
+    pub tcp_keepalive: Option<Duration>,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            gzip: true,
+            deflate: true,
+            version: HttpVersion::Auto,
+            pool_idle_timeout: Some(DEFAULT_POOL_IDLE_TIMEOUT),
+            pool_max_idle_per_host: usize::MAX,
+            tcp_keepalive: None,
+        }
+    }
+}
+
+/// How long idle connections are kept by default, as in `reqwest`
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+impl HttpSettings {
+    /// Build a `reqwest` client with these settings
+    pub fn client(&self) -> Result<Client, OpenAlgoError> {
+        let builder = Client::builder();
+        #[cfg(all(feature = "compression", not(target_arch = "wasm32")))]
+        let builder = builder.gzip(self.gzip).deflate(self.deflate);
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = {
+            let builder = builder
+                .pool_idle_timeout(self.pool_idle_timeout)
+                .pool_max_idle_per_host(self.pool_max_idle_per_host)
+                .tcp_keepalive(self.tcp_keepalive);
+            match self.version {
+                HttpVersion::Auto => builder,
+                HttpVersion::Http1 => builder.http1_only(),
+                HttpVersion::Http2 => builder.http2_prior_knowledge(),
+            }
+        };
+        builder
+            .build()
+            .map_err(|e| OpenAlgoError::ConfigError(format!("cannot build the HTTP client: {}", e)))
+    }
+}
+
+/// Default transport backed by `reqwest`
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Use an existing `reqwest` client
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn post_json<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+                .await?;
+            read_body(response).await
+        })
+    }
+
+    fn get_json<'a>(&'a self, url: &'a str, query: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .get(url)
+                .header("Content-Type", "application/json")
+                .query(query)
+                .send()
+                .await?;
+            read_body(response).await
+        })
+    }
+
+    fn send<'a>(&'a self, request: &'a RequestParts) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let mut builder = match request.method {
+                Method::Post => self
+                    .client
+                    .post(&request.url)
+                    .body(request.body.clone().unwrap_or_default()),
+                Method::Get => self.client.get(&request.url).query(&request.query),
+            }
+            .header("Content-Type", "application/json");
+            for (name, value) in &request.headers {
+                builder = builder.header(name.as_str(), value.as_str());
+            }
+            read_body(builder.send().await?).await
+        })
+    }
+}
+
+async fn read_body(response: reqwest::Response) -> Result<String, OpenAlgoError> {
+    let status = response.status();
+    let request_id = ["x-request-id", "x-correlation-id"]
+        .iter()
+        .find_map(|name| response.headers().get(*name)?.to_str().ok())
+        .map(str::to_string);
+    let text = response.text().await?;
+
+    if !status.is_success() {
+        let mut details = ApiErrorDetails::from_body(Some(status.as_u16()), &text);
+        details.request_id = request_id;
+        return Err(OpenAlgoError::ApiError(Box::new(details)));
+    }
+    Ok(text)
+}
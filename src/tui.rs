@@ -0,0 +1,333 @@
+//! Terminal monitor for OpenAlgo.
+//!
+//! A ratatui screen that streams a watchlist over the WebSocket (LTP, change,
+//! volume) alongside open positions with live P&L. Enabled by the `tui`
+//! feature.
+
+use crate::client::OpenAlgoError;
+use crate::price;
+use crate::types::*;
+use crate::watchlist::Watchlist;
+use crate::websocket::WsData;
+use crate::OpenAlgo;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table};
+use ratatui::Frame;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Live watchlist and positions monitor
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+/// use openalgo::tui::Monitor;
+/// use openalgo::Watchlist;
+///
+/// let mut watchlist = Watchlist::new("Nifty heavyweights");
+/// watchlist.add("NSE", "RELIANCE");
+/// watchlist.add("NSE", "HDFCBANK");
+///
+/// // Runs until `q` or Esc is pressed
+/// Monitor::new(watchlist).run(&client).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Monitor {
+    watchlist: Watchlist,
+    positions_interval: Duration,
+    frame_interval: Duration,
+}
+
+impl Monitor {
+    /// Monitor a watchlist
+    pub fn new(watchlist: Watchlist) -> Self {
+        Self {
+            watchlist,
+            positions_interval: Duration::from_secs(5),
+            frame_interval: Duration::from_millis(250),
+        }
+    }
+
+    /// How often the positionbook is refreshed
+    pub fn positions_interval(mut self, interval: Duration) -> Self {
+        self.positions_interval = interval.max(Duration::from_secs(1));
+        self
+    }
+
+    /// How often the screen is redrawn
+    pub fn frame_interval(mut self, interval: Duration) -> Self {
+        self.frame_interval = interval.max(Duration::from_millis(20));
+        self
+    }
+
+    /// Take over the terminal and run until the user quits
+    pub async fn run(&self, client: &OpenAlgo) -> Result<(), OpenAlgoError> {
+        let mut state = MonitorState::new(&self.watchlist);
+        if let Ok(response) = self.watchlist.quotes(&client.data).await {
+            state.seed(&response);
+        }
+
+        let ws = client.websocket();
+        let (cmd_tx, mut data_rx) = ws.connect().await?;
+        let subscriber = crate::WsSubscriber::new(cmd_tx);
+        self.watchlist.subscribe_quote(&subscriber).await?;
+
+        let mut terminal = TerminalGuard::enter()?;
+        let mut frame_tick = tokio::time::interval(self.frame_interval);
+        let mut positions_tick = tokio::time::interval(self.positions_interval);
+
+        loop {
+            tokio::select! {
+                data = data_rx.recv() => match data {
+                    Some(data) => state.on_data(data),
+                    None => {
+                        state.status = "Disconnected".to_string();
+                        terminal.0.draw(|f| state.render(f))?;
+                        break;
+                    }
+                },
+                _ = positions_tick.tick() => match client.positionbook().await {
+                    Ok(response) => state.positions = response.data.unwrap_or_default(),
+                    Err(e) => state.status = format!("Positions failed: {}", e),
+                },
+                _ = frame_tick.tick() => {
+                    if quit_requested()? {
+                        break;
+                    }
+                    terminal.0.draw(|f| state.render(f))?;
+                }
+            }
+        }
+
+        let _ = subscriber.disconnect().await;
+        Ok(())
+    }
+}
+
+/// Restores the terminal when dropped, including on error
+struct TerminalGuard(ratatui::DefaultTerminal);
+
+impl TerminalGuard {
+    fn enter() -> Result<Self, OpenAlgoError> {
+        Ok(Self(ratatui::try_init()?))
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+/// Drain pending key presses, returning true on `q`, Esc or Ctrl-C
+fn quit_requested() -> Result<bool, OpenAlgoError> {
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+            if ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+#[derive(Debug, Clone, Default)]
+struct Ticker {
+    ltp: Option<f64>,
+    prev_close: Option<f64>,
+    volume: Option<i64>,
+}
+
+impl Ticker {
+    fn change(&self) -> Option<(f64, f64)> {
+        let (ltp, prev) = (self.ltp?, self.prev_close?);
+        if prev == 0.0 {
+            return None;
+        }
+        Some((ltp - prev, (ltp - prev) / prev * 100.0))
+    }
+}
+
+struct MonitorState {
+    title: String,
+    tickers: BTreeMap<(String, String), Ticker>,
+    positions: Vec<PositionbookPosition>,
+    status: String,
+}
+
+impl MonitorState {
+    fn new(watchlist: &Watchlist) -> Self {
+        Self {
+            title: watchlist.name.clone(),
+            tickers: watchlist
+                .instruments
+                .iter()
+                .map(|i| ((i.exchange.clone(), i.symbol.clone()), Ticker::default()))
+                .collect(),
+            positions: Vec::new(),
+            status: "Connected".to_string(),
+        }
+    }
+
+    /// Previous close and opening values from a REST multi-quotes call
+    fn seed(&mut self, response: &MultiQuotesResponse) {
+        for result in response.results.iter().flatten() {
+            let (Some(ticker), Some(q)) = (
+                self.tickers.get_mut(&(result.exchange.clone(), result.symbol.clone())),
+                result.data.as_ref(),
+            ) else {
+                continue;
+            };
+            ticker.ltp = q.ltp.map(price::to_f64);
+            ticker.prev_close = q.prev_close.map(price::to_f64);
+            ticker.volume = q.volume;
+        }
+    }
+
+    fn on_data(&mut self, data: WsData) {
+        match data {
+            WsData::Ltp(d) => {
+                if let Some(ticker) = self.ticker(d.exchange, d.symbol) {
+                    ticker.ltp = d.ltp.map(price::to_f64).or(ticker.ltp);
+                }
+            }
+            WsData::Quote(d) => {
+                if let Some(ticker) = self.ticker(d.exchange, d.symbol) {
+                    ticker.ltp = d.ltp.map(price::to_f64).or(ticker.ltp);
+                    ticker.volume = d.volume.or(ticker.volume);
+                    if ticker.prev_close.is_none() {
+                        ticker.prev_close = d.close.map(price::to_f64);
+                    }
+                }
+            }
+            WsData::Disconnected => self.status = "Disconnected, reconnecting".to_string(),
+            WsData::Degraded => self.status = "Degraded (REST polling)".to_string(),
+            WsData::Reconnected { .. } | WsData::Connected => self.status = "Connected".to_string(),
+            WsData::Error(message) | WsData::ServerError { message, .. } => self.status = message,
+            _ => {}
+        }
+    }
+
+    fn ticker(&mut self, exchange: Option<String>, symbol: Option<String>) -> Option<&mut Ticker> {
+        self.tickers.get_mut(&(exchange?, symbol?))
+    }
+
+    /// Live LTP for a position, falling back to the positionbook value
+    fn position_ltp(&self, position: &PositionbookPosition) -> Option<f64> {
+        let key = (position.exchange.clone()?, position.symbol.clone()?);
+        self.tickers
+            .get(&key)
+            .and_then(|t| t.ltp)
+            .or_else(|| parse(&position.ltp))
+    }
+
+    /// Mark-to-market P&L using the live LTP when available
+    fn position_pnl(&self, position: &PositionbookPosition) -> Option<f64> {
+        match (self.position_ltp(position), parse(&position.average_price), parse(&position.quantity)) {
+            (Some(ltp), Some(avg), Some(qty)) if qty != 0.0 => Some((ltp - avg) * qty),
+            _ => parse(&position.pnl),
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let [watch_area, positions_area, footer_area] = Layout::vertical([
+            Constraint::Min(self.tickers.len() as u16 + 3),
+            Constraint::Min(self.positions.len() as u16 + 3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let header_style = Style::default().add_modifier(Modifier::BOLD);
+        let watch_rows = self.tickers.iter().map(|((exchange, symbol), t)| {
+            let change = t.change();
+            Row::new([
+                symbol.clone(),
+                exchange.clone(),
+                fmt(t.ltp),
+                fmt(change.map(|c| c.0)),
+                change.map(|c| format!("{:+.2}%", c.1)).unwrap_or_default(),
+                t.volume.map(|v| v.to_string()).unwrap_or_default(),
+            ])
+            .style(signed_style(change.map(|c| c.0)))
+        });
+        let watch = Table::new(
+            watch_rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(["Symbol", "Exchange", "LTP", "Chg", "Chg%", "Volume"]).style(header_style))
+        .block(Block::default().borders(Borders::ALL).title(self.title.as_str()));
+        frame.render_widget(watch, watch_area);
+
+        let mut total = 0.0;
+        let position_rows: Vec<Row> = self
+            .positions
+            .iter()
+            .map(|p| {
+                let pnl = self.position_pnl(p);
+                total += pnl.unwrap_or(0.0);
+                Row::new([
+                    p.symbol.clone().unwrap_or_default(),
+                    p.product.clone().unwrap_or_default(),
+                    p.quantity.clone().unwrap_or_default(),
+                    p.average_price.clone().unwrap_or_default(),
+                    fmt(self.position_ltp(p)),
+                    fmt(pnl),
+                ])
+                .style(signed_style(pnl))
+            })
+            .collect();
+        let positions = Table::new(
+            position_rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(["Symbol", "Product", "Qty", "Avg", "LTP", "P&L"]).style(header_style))
+        .block(Block::default().borders(Borders::ALL).title("Positions"));
+        frame.render_widget(positions, positions_area);
+
+        let footer = Line::from(format!(
+            " P&L {:+.2}  |  {}  |  q to quit",
+            total, self.status
+        ))
+        .style(signed_style(Some(total)));
+        frame.render_widget(Paragraph::new(footer), footer_area);
+    }
+}
+
+fn parse(value: &Option<String>) -> Option<f64> {
+    value.as_deref()?.trim().parse().ok()
+}
+
+fn fmt(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.2}", v)).unwrap_or_default()
+}
+
+fn signed_style(value: Option<f64>) -> Style {
+    match value {
+        Some(v) if v > 0.0 => Style::default().fg(Color::Green),
+        Some(v) if v < 0.0 => Style::default().fg(Color::Red),
+        _ => Style::default(),
+    }
+}
@@ -1,13 +1,21 @@
 //! Type definitions for OpenAlgo API requests and responses.
 
+use crate::calendar::{epoch_secs, ist_datetime, parse_timestamp};
+use crate::client::OpenAlgoError;
+use crate::de;
+use crate::price::{self, Price};
+use crate::secret::ApiKey;
+use crate::validation::{check_validity, ValidationError};
 use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset};
+use std::collections::HashMap;
 
 // ============================================================================
 // Common Types
 // ============================================================================
 
 /// Generic API response wrapper
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
     pub status: String,
     #[serde(flatten)]
@@ -16,28 +24,75 @@ pub struct ApiResponse<T> {
 }
 
 /// Simple status response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StatusResponse {
     pub status: String,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Order response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderResponse {
     pub status: String,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub orderid: Option<String>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 // ============================================================================
 // Order Types
 // ============================================================================
 
+/// How long an order stays open
+///
+/// Servers that do not support `validity` treat every order as `Day`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Validity {
+    /// Open until filled, cancelled or the end of the session
+    Day,
+    /// Immediate or cancel: whatever does not fill at once is cancelled
+    Ioc,
+}
+
+impl Validity {
+    /// Parse the OpenAlgo string form ("DAY"/"IOC")
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "DAY" => Some(Validity::Day),
+            "IOC" => Some(Validity::Ioc),
+            _ => None,
+        }
+    }
+
+    /// OpenAlgo string form of the validity
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Validity::Day => "DAY",
+            Validity::Ioc => "IOC",
+        }
+    }
+}
+
+impl std::fmt::Display for Validity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Place order request
-#[derive(Debug, Clone, Serialize)]
+///
+/// Deserializing leaves `apikey` empty.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PlaceOrderRequest {
-    pub apikey: String,
+    #[serde(skip_deserializing)]
+    pub apikey: ApiKey,
     pub strategy: String,
     pub symbol: String,
     pub action: String,
@@ -51,12 +106,99 @@ pub struct PlaceOrderRequest {
     pub trigger_price: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disclosed_quantity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validity: Option<Validity>,
+    /// After-market order, queued by the broker for the next session
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub amo: bool,
+}
+
+impl PlaceOrderRequest {
+    /// Order with the required fields; `apikey` is filled in when it is sent
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::{PlaceOrderRequest, Validity};
+    ///
+    /// // Sweep the book up to 2505 and cancel whatever does not fill
+    /// let sweep = PlaceOrderRequest::new("Strategy1", "RELIANCE", "BUY", "NSE", "LIMIT", "MIS", "500")
+    ///     .price("2505")
+    ///     .validity(Validity::Ioc);
+    /// assert!(sweep.validate().is_ok());
+    ///
+    /// let stop = PlaceOrderRequest::new("Strategy1", "RELIANCE", "SELL", "NSE", "SL-M", "MIS", "500")
+    ///     .trigger_price("2480")
+    ///     .validity(Validity::Ioc);
+    /// assert!(stop.validate().is_err());
+    /// ```
+    pub fn new(
+        strategy: &str,
+        symbol: &str,
+        action: &str,
+        exchange: &str,
+        pricetype: &str,
+        product: &str,
+        quantity: &str,
+    ) -> Self {
+        Self {
+            apikey: ApiKey::default(),
+            strategy: strategy.to_string(),
+            symbol: symbol.to_string(),
+            action: action.to_string(),
+            exchange: exchange.to_string(),
+            pricetype: pricetype.to_string(),
+            product: product.to_string(),
+            quantity: quantity.to_string(),
+            price: None,
+            trigger_price: None,
+            disclosed_quantity: None,
+            validity: None,
+            amo: false,
+        }
+    }
+
+    /// Limit price
+    pub fn price(mut self, price: &str) -> Self {
+        self.price = Some(price.to_string());
+        self
+    }
+
+    /// Trigger price of a stop-loss order
+    pub fn trigger_price(mut self, trigger_price: &str) -> Self {
+        self.trigger_price = Some(trigger_price.to_string());
+        self
+    }
+
+    /// Quantity shown to the market
+    pub fn disclosed_quantity(mut self, disclosed_quantity: &str) -> Self {
+        self.disclosed_quantity = Some(disclosed_quantity.to_string());
+        self
+    }
+
+    /// How long the order stays open
+    pub fn validity(mut self, validity: Validity) -> Self {
+        self.validity = Some(validity);
+        self
+    }
+
+    /// Send as an after-market order, queued by the broker for the next session
+    pub fn amo(mut self, amo: bool) -> Self {
+        self.amo = amo;
+        self
+    }
+
+    /// Check the validity against the price type
+    ///
+    /// IOC applies to MARKET and LIMIT orders only.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_validity(self.validity, &self.pricetype)
+    }
 }
 
 /// Smart order request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct PlaceSmartOrderRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub strategy: String,
     pub symbol: String,
     pub action: String,
@@ -68,9 +210,9 @@ pub struct PlaceSmartOrderRequest {
 }
 
 /// Options order request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct OptionsOrderRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub strategy: String,
     pub underlying: String,
     pub exchange: String,
@@ -85,22 +227,27 @@ pub struct OptionsOrderRequest {
 }
 
 /// Options order response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionsOrderResponse {
     pub status: String,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub orderid: Option<String>,
     pub symbol: Option<String>,
     pub exchange: Option<String>,
     pub offset: Option<String>,
     pub option_type: Option<String>,
     pub underlying: Option<String>,
-    pub underlying_ltp: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub underlying_ltp: Option<Price>,
     pub mode: Option<String>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Options multi-order leg
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct OptionsLeg {
     pub offset: String,
     pub option_type: String,
@@ -147,9 +294,9 @@ impl OptionsLeg {
 }
 
 /// Options multi-order request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct OptionsMultiOrderRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub strategy: String,
     pub underlying: String,
     pub exchange: String,
@@ -159,30 +306,39 @@ pub struct OptionsMultiOrderRequest {
 }
 
 /// Options multi-order result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionsMultiOrderResult {
+    #[serde(deserialize_with = "de::number")]
     pub leg: i32,
     pub status: String,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub orderid: Option<String>,
     pub symbol: Option<String>,
     pub offset: Option<String>,
     pub option_type: Option<String>,
     pub action: Option<String>,
     pub mode: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Options multi-order response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionsMultiOrderResponse {
     pub status: String,
     pub underlying: Option<String>,
-    pub underlying_ltp: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub underlying_ltp: Option<Price>,
     pub results: Option<Vec<OptionsMultiOrderResult>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Basket order item
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct BasketOrderItem {
     pub symbol: String,
     pub exchange: String,
@@ -213,33 +369,40 @@ impl BasketOrderItem {
 }
 
 /// Basket order request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct BasketOrderRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub strategy: String,
     pub orders: Vec<BasketOrderItem>,
 }
 
 /// Basket order result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BasketOrderResult {
     pub symbol: String,
     pub status: String,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub orderid: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Basket order response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BasketOrderResponse {
     pub status: String,
     pub results: Option<Vec<BasketOrderResult>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Split order request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct SplitOrderRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub strategy: String,
     pub symbol: String,
     pub action: String,
@@ -251,28 +414,39 @@ pub struct SplitOrderRequest {
 }
 
 /// Split order result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SplitOrderResult {
+    #[serde(default, deserialize_with = "de::option_number")]
     pub order_num: Option<i32>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub orderid: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub quantity: Option<i32>,
     pub status: String,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Split order response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SplitOrderResponse {
     pub status: String,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub split_size: Option<i32>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub total_quantity: Option<i32>,
     pub results: Option<Vec<SplitOrderResult>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Modify order request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct ModifyOrderRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub orderid: String,
     pub strategy: String,
     pub symbol: String,
@@ -286,36 +460,48 @@ pub struct ModifyOrderRequest {
     pub disclosed_quantity: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trigger_price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validity: Option<Validity>,
+}
+
+impl ModifyOrderRequest {
+    /// Check the validity against the price type
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_validity(self.validity, &self.pricetype)
+    }
 }
 
 /// Cancel order request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct CancelOrderRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub orderid: String,
     pub strategy: String,
 }
 
 /// Cancel all orders request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct CancelAllOrderRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub strategy: String,
 }
 
 /// Cancel all orders response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CancelAllOrderResponse {
     pub status: String,
     pub message: Option<String>,
     pub canceled_orders: Option<Vec<String>>,
     pub failed_cancellations: Option<Vec<String>>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Close position request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct ClosePositionRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub strategy: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub product: Option<String>,
@@ -324,42 +510,54 @@ pub struct ClosePositionRequest {
 }
 
 /// Order status request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct OrderStatusRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub orderid: String,
     pub strategy: String,
 }
 
 /// Order status data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderStatusData {
     pub action: Option<String>,
-    pub average_price: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub average_price: Option<Price>,
     pub exchange: Option<String>,
     pub order_status: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub orderid: Option<String>,
-    pub price: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub price: Option<Price>,
     pub pricetype: Option<String>,
     pub product: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub quantity: Option<String>,
     pub symbol: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub timestamp: Option<String>,
-    pub trigger_price: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub trigger_price: Option<Price>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Order status response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderStatusResponse {
     pub status: String,
     pub data: Option<OrderStatusData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Open position request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct OpenPositionRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub strategy: String,
     pub symbol: String,
     pub exchange: String,
@@ -367,11 +565,15 @@ pub struct OpenPositionRequest {
 }
 
 /// Open position response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OpenPositionResponse {
     pub status: String,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub quantity: Option<String>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 // ============================================================================
@@ -379,37 +581,75 @@ pub struct OpenPositionResponse {
 // ============================================================================
 
 /// Quotes request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct QuotesRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub symbol: String,
     pub exchange: String,
 }
 
 /// Quotes data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QuotesData {
-    pub open: Option<f64>,
-    pub high: Option<f64>,
-    pub low: Option<f64>,
-    pub ltp: Option<f64>,
-    pub ask: Option<f64>,
-    pub bid: Option<f64>,
-    pub prev_close: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub open: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub high: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub low: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub ltp: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub ask: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub bid: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub prev_close: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub volume: Option<i64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub oi: Option<i64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl QuotesData {
+    /// Difference between best ask and best bid
+    ///
+    /// # Example
+    /// ```rust
+    /// # use openalgo::QuotesData;
+    /// # let quote: QuotesData = serde_json::from_str(r#"{"bid": 100.0, "ask": 100.5}"#).unwrap();
+    /// assert_eq!(quote.bid_ask_spread().map(openalgo::price::to_f64), Some(0.5));
+    /// ```
+    pub fn bid_ask_spread(&self) -> Option<Price> {
+        Some(self.ask? - self.bid?)
+    }
+
+    /// Percentage change of LTP from the previous close
+    pub fn change_percent(&self) -> Option<f64> {
+        let prev_close = price::to_f64(self.prev_close?);
+        if prev_close == 0.0 {
+            return None;
+        }
+        Some((price::to_f64(self.ltp?) - prev_close) / prev_close * 100.0)
+    }
 }
 
 /// Quotes response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QuotesResponse {
     pub status: String,
     pub data: Option<QuotesData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Multi-quotes symbol
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct MultiQuotesSymbol {
     pub symbol: String,
     pub exchange: String,
@@ -426,72 +666,99 @@ impl MultiQuotesSymbol {
 }
 
 /// Multi-quotes request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct MultiQuotesRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub symbols: Vec<MultiQuotesSymbol>,
 }
 
 /// Multi-quotes result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MultiQuotesResult {
     pub symbol: String,
     pub exchange: String,
     pub data: Option<QuotesData>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Multi-quotes response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MultiQuotesResponse {
     pub status: String,
     pub results: Option<Vec<MultiQuotesResult>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Depth request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct DepthRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub symbol: String,
     pub exchange: String,
 }
 
 /// Depth level
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DepthLevel {
-    pub price: f64,
+    #[serde(deserialize_with = "de::number")]
+    pub price: Price,
+    #[serde(deserialize_with = "de::number")]
     pub quantity: i64,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Depth data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DepthData {
-    pub open: Option<f64>,
-    pub high: Option<f64>,
-    pub low: Option<f64>,
-    pub ltp: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub open: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub high: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub low: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub ltp: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub ltq: Option<i64>,
-    pub prev_close: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub prev_close: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub volume: Option<i64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub oi: Option<i64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub totalbuyqty: Option<i64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub totalsellqty: Option<i64>,
     pub asks: Option<Vec<DepthLevel>>,
     pub bids: Option<Vec<DepthLevel>>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Depth response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DepthResponse {
     pub status: String,
     pub data: Option<DepthData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// History request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct HistoryRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub symbol: String,
     pub exchange: String,
     pub interval: String,
@@ -502,24 +769,48 @@ pub struct HistoryRequest {
 }
 
 /// History candle
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HistoryCandle {
+    /// Unix seconds; date-time strings from the server are converted
+    #[serde(deserialize_with = "de::timestamp")]
     pub timestamp: i64,
-    pub open: f64,
-    pub high: f64,
-    pub low: f64,
-    pub close: f64,
+    #[serde(deserialize_with = "de::number")]
+    pub open: Price,
+    #[serde(deserialize_with = "de::number")]
+    pub high: Price,
+    #[serde(deserialize_with = "de::number")]
+    pub low: Price,
+    #[serde(deserialize_with = "de::number")]
+    pub close: Price,
+    #[serde(deserialize_with = "de::number")]
     pub volume: i64,
+    /// Open interest (derivatives only)
+    #[serde(default, deserialize_with = "de::option_number", skip_serializing_if = "Option::is_none")]
+    pub oi: Option<i64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// History response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryResponse {
+    pub status: String,
+    pub data: Option<Vec<HistoryCandle>>,
+    pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Intervals request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct IntervalsRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
 }
 
 /// Intervals data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IntervalsData {
     pub months: Vec<String>,
     pub weeks: Vec<String>,
@@ -527,20 +818,26 @@ pub struct IntervalsData {
     pub hours: Vec<String>,
     pub minutes: Vec<String>,
     pub seconds: Vec<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Intervals response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IntervalsResponse {
     pub status: String,
     pub data: Option<IntervalsData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Option chain request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct OptionChainRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub underlying: String,
     pub exchange: String,
     pub expiry_date: String,
@@ -549,97 +846,278 @@ pub struct OptionChainRequest {
 }
 
 /// Option chain strike data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionStrikeData {
     pub symbol: Option<String>,
     pub label: Option<String>,
-    pub ltp: Option<f64>,
-    pub bid: Option<f64>,
-    pub ask: Option<f64>,
-    pub open: Option<f64>,
-    pub high: Option<f64>,
-    pub low: Option<f64>,
-    pub prev_close: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub ltp: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub bid: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub ask: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub open: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub high: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub low: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub prev_close: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub volume: Option<i64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub oi: Option<i64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub lotsize: Option<i32>,
-    pub tick_size: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub tick_size: Option<Price>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Option chain strike
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionChainStrike {
-    pub strike: f64,
+    #[serde(deserialize_with = "de::number")]
+    pub strike: Price,
     pub ce: Option<OptionStrikeData>,
     pub pe: Option<OptionStrikeData>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Option chain response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionChainResponse {
     pub status: String,
     pub underlying: Option<String>,
-    pub underlying_ltp: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub underlying_ltp: Option<Price>,
     pub expiry_date: Option<String>,
-    pub atm_strike: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub atm_strike: Option<Price>,
     pub chain: Option<Vec<OptionChainStrike>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Symbol request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct SymbolRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub symbol: String,
     pub exchange: String,
 }
 
 /// Symbol data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SymbolData {
     pub brexchange: Option<String>,
     pub brsymbol: Option<String>,
     pub exchange: Option<String>,
     pub expiry: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub freeze_qty: Option<i32>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub id: Option<i64>,
     pub instrumenttype: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub lotsize: Option<i32>,
     pub name: Option<String>,
-    pub strike: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub strike: Option<Price>,
     pub symbol: Option<String>,
-    pub tick_size: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub tick_size: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub token: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl SymbolData {
+    /// Round a price to the nearest valid tick; unchanged when the tick size is unknown
+    ///
+    /// # Example
+    /// ```rust
+    /// # use openalgo::SymbolData;
+    /// use openalgo::price::from_f64;
+    ///
+    /// let symbol: SymbolData = serde_json::from_str(r#"{"symbol": "RELIANCE", "tick_size": 0.05}"#).unwrap();
+    /// assert_eq!(symbol.round_to_tick(from_f64(2501.17)).to_string(), "2501.15");
+    /// ```
+    pub fn round_to_tick(&self, price: Price) -> Price {
+        match self.tick_size {
+            Some(tick) => price::round_to_tick(price, tick),
+            None => price,
+        }
+    }
+
+    /// Round a price down to a valid tick
+    pub fn floor_to_tick(&self, price: Price) -> Price {
+        match self.tick_size {
+            Some(tick) => price::floor_to_tick(price, tick),
+            None => price,
+        }
+    }
+
+    /// Round a price up to a valid tick
+    pub fn ceil_to_tick(&self, price: Price) -> Price {
+        match self.tick_size {
+            Some(tick) => price::ceil_to_tick(price, tick),
+            None => price,
+        }
+    }
 }
 
 /// Symbol response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SymbolResponse {
     pub status: String,
     pub data: Option<SymbolData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Search request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct SearchRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub query: String,
     pub exchange: String,
+    #[serde(flatten)]
+    pub filters: SearchFilters,
+}
+
+/// Optional search filters and paging, sent only when set
+///
+/// # Example
+/// ```rust
+/// use openalgo::SearchFilters;
+/// let filters = SearchFilters::default().instrumenttype("OPTIDX").expiry("30DEC25").limit(50);
+/// let next = filters.clone().offset(50);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct SearchFilters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instrumenttype: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+}
+
+impl SearchFilters {
+    /// Only instruments of this type, e.g. "EQ", "FUTIDX" or "OPTIDX"
+    pub fn instrumenttype(mut self, instrumenttype: &str) -> Self {
+        self.instrumenttype = Some(instrumenttype.to_string());
+        self
+    }
+
+    /// Only contracts expiring on this date, e.g. "30DEC25"
+    pub fn expiry(mut self, expiry: &str) -> Self {
+        self.expiry = Some(expiry.to_string());
+        self
+    }
+
+    /// At most `limit` results
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the first `offset` results
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
 }
 
 /// Search response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchResponse {
     pub status: String,
     pub data: Option<Vec<SymbolData>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// One page of search results
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchResultPage {
+    pub results: Vec<SymbolData>,
+    /// Position of the first result in the full result set
+    pub offset: u32,
+    /// Requested page size
+    pub limit: Option<u32>,
+    /// Size of the full result set, when known
+    pub total: Option<u64>,
+}
+
+impl SearchResultPage {
+    /// Build a page from a search response
+    ///
+    /// Servers that ignore `limit`/`offset` return every match; the page is
+    /// then cut from the full list and `total` is its length.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::{SearchFilters, SearchResponse, SearchResultPage};
+    ///
+    /// let response: SearchResponse = serde_json::from_str(r#"{"status": "success", "data": [
+    ///     {"symbol": "A"}, {"symbol": "B"}, {"symbol": "C"}, {"symbol": "D"}, {"symbol": "E"}
+    /// ]}"#).unwrap();
+    /// let page = SearchResultPage::from_response(response, &SearchFilters::default().limit(2).offset(2));
+    /// assert_eq!(page.results.iter().map(|s| s.symbol.as_deref().unwrap()).collect::<Vec<_>>(), ["C", "D"]);
+    /// assert_eq!(page.total, Some(5));
+    /// assert_eq!(page.next_offset(), Some(4));
+    /// ```
+    pub fn from_response(response: SearchResponse, filters: &SearchFilters) -> Self {
+        let offset = filters.offset.unwrap_or(0);
+        let mut results = response.data.unwrap_or_default();
+        let mut total = ["total", "count"]
+            .iter()
+            .find_map(|key| response.extra.get(*key)?.as_u64());
+        if let Some(limit) = filters.limit {
+            if results.len() > limit as usize {
+                total = Some(results.len() as u64);
+                results = results.into_iter().skip(offset as usize).take(limit as usize).collect();
+            }
+        }
+        Self { results, offset, limit: filters.limit, total }
+    }
+
+    /// Whether more results follow this page
+    pub fn has_more(&self) -> bool {
+        match (self.total, self.limit) {
+            (Some(total), _) => (self.offset as u64 + self.results.len() as u64) < total,
+            (None, Some(limit)) => self.results.len() as u32 >= limit && limit > 0,
+            (None, None) => false,
+        }
+    }
+
+    /// Offset of the next page, if there is one
+    pub fn next_offset(&self) -> Option<u32> {
+        self.has_more().then(|| self.offset + self.results.len() as u32)
+    }
 }
 
 /// Option symbol request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct OptionSymbolRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub underlying: String,
     pub exchange: String,
     pub expiry_date: String,
@@ -648,43 +1126,56 @@ pub struct OptionSymbolRequest {
 }
 
 /// Option symbol response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionSymbolResponse {
     pub status: String,
     pub symbol: Option<String>,
     pub exchange: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub lotsize: Option<i32>,
-    pub tick_size: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub tick_size: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub freeze_qty: Option<i32>,
-    pub underlying_ltp: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub underlying_ltp: Option<Price>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Synthetic future request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct SyntheticFutureRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub underlying: String,
     pub exchange: String,
     pub expiry_date: String,
 }
 
 /// Synthetic future response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SyntheticFutureResponse {
     pub status: String,
-    pub atm_strike: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub atm_strike: Option<Price>,
     pub expiry: Option<String>,
-    pub synthetic_future_price: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub synthetic_future_price: Option<Price>,
     pub underlying: Option<String>,
-    pub underlying_ltp: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub underlying_ltp: Option<Price>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Option Greeks request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct OptionGreeksRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub symbol: String,
     pub exchange: String,
     pub interest_rate: f64,
@@ -693,64 +1184,87 @@ pub struct OptionGreeksRequest {
 }
 
 /// Greeks data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GreeksData {
+    #[serde(default, deserialize_with = "de::option_number")]
     pub delta: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub gamma: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub theta: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub vega: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub rho: Option<f64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Option Greeks response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionGreeksResponse {
     pub status: String,
     pub symbol: Option<String>,
     pub exchange: Option<String>,
     pub underlying: Option<String>,
     pub option_type: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub strike: Option<f64>,
     pub expiry_date: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub days_to_expiry: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub spot_price: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub option_price: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub implied_volatility: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub interest_rate: Option<f64>,
     pub greeks: Option<GreeksData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Expiry request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct ExpiryRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub symbol: String,
     pub exchange: String,
     pub instrumenttype: String,
 }
 
 /// Expiry response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExpiryResponse {
     pub status: String,
     pub data: Option<Vec<String>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Instruments request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct InstrumentsRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub exchange: String,
 }
 
 /// Instruments response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InstrumentsResponse {
     pub status: String,
     pub data: Option<Vec<SymbolData>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 // ============================================================================
@@ -758,31 +1272,42 @@ pub struct InstrumentsResponse {
 // ============================================================================
 
 /// Funds request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct FundsRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
 }
 
 /// Funds data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FundsData {
+    #[serde(default, deserialize_with = "de::option_string")]
     pub availablecash: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub collateral: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub m2mrealized: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub m2munrealized: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub utiliseddebits: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Funds response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FundsResponse {
     pub status: String,
     pub data: Option<FundsData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Margin position
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct MarginPosition {
     pub symbol: String,
     pub exchange: String,
@@ -813,230 +1338,320 @@ impl MarginPosition {
 }
 
 /// Margin request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct MarginRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub positions: Vec<MarginPosition>,
 }
 
 /// Margin data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MarginData {
+    #[serde(default, deserialize_with = "de::option_number")]
     pub total_margin_required: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub span_margin: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub exposure_margin: Option<f64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Margin response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MarginResponse {
     pub status: String,
     pub data: Option<MarginData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Orderbook request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct OrderbookRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
 }
 
 /// Order in orderbook
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderbookOrder {
     pub action: Option<String>,
     pub symbol: Option<String>,
     pub exchange: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub orderid: Option<String>,
     pub product: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub quantity: Option<String>,
-    pub price: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub price: Option<Price>,
     pub pricetype: Option<String>,
     pub order_status: Option<String>,
-    pub trigger_price: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub trigger_price: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub timestamp: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Orderbook statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderbookStatistics {
+    #[serde(default, deserialize_with = "de::option_number")]
     pub total_buy_orders: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub total_sell_orders: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub total_completed_orders: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub total_open_orders: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub total_rejected_orders: Option<f64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Orderbook data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderbookData {
     pub orders: Option<Vec<OrderbookOrder>>,
     pub statistics: Option<OrderbookStatistics>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Orderbook response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderbookResponse {
     pub status: String,
     pub data: Option<OrderbookData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Tradebook request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct TradebookRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
 }
 
 /// Trade in tradebook
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TradebookTrade {
     pub action: Option<String>,
     pub symbol: Option<String>,
     pub exchange: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub orderid: Option<String>,
     pub product: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub quantity: Option<f64>,
-    pub average_price: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub average_price: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub timestamp: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub trade_value: Option<f64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Tradebook response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TradebookResponse {
     pub status: String,
     pub data: Option<Vec<TradebookTrade>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Positionbook request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct PositionbookRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
 }
 
 /// Position in positionbook
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PositionbookPosition {
     pub symbol: Option<String>,
     pub exchange: Option<String>,
     pub product: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub quantity: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub average_price: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub ltp: Option<String>,
+    #[serde(default, deserialize_with = "de::option_string")]
     pub pnl: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Positionbook response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PositionbookResponse {
     pub status: String,
     pub data: Option<Vec<PositionbookPosition>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Holdings request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct HoldingsRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
 }
 
 /// Holding item
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HoldingItem {
     pub symbol: Option<String>,
     pub exchange: Option<String>,
     pub product: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub quantity: Option<i32>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub pnl: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub pnlpercent: Option<f64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Holdings statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HoldingsStatistics {
+    #[serde(default, deserialize_with = "de::option_number")]
     pub totalholdingvalue: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub totalinvvalue: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub totalprofitandloss: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub totalpnlpercentage: Option<f64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Holdings data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HoldingsData {
     pub holdings: Option<Vec<HoldingItem>>,
     pub statistics: Option<HoldingsStatistics>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Holdings response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HoldingsResponse {
     pub status: String,
     pub data: Option<HoldingsData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Holidays request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct HolidaysRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub year: i32,
 }
 
 /// Open exchange timing
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OpenExchangeTiming {
     pub exchange: String,
+    #[serde(deserialize_with = "de::number")]
     pub start_time: i64,
+    #[serde(deserialize_with = "de::number")]
     pub end_time: i64,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Holiday item
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HolidayItem {
     pub date: String,
     pub description: String,
     pub holiday_type: String,
     pub closed_exchanges: Vec<String>,
     pub open_exchanges: Vec<OpenExchangeTiming>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Holidays response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HolidaysResponse {
     pub status: String,
     pub data: Option<Vec<HolidayItem>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Timings request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct TimingsRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub date: String,
 }
 
 /// Exchange timing
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExchangeTiming {
     pub exchange: String,
+    #[serde(deserialize_with = "de::number")]
     pub start_time: i64,
+    #[serde(deserialize_with = "de::number")]
     pub end_time: i64,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Timings response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimingsResponse {
     pub status: String,
     pub data: Option<Vec<ExchangeTiming>>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Telegram request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct TelegramRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub username: String,
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1044,10 +1659,114 @@ pub struct TelegramRequest {
 }
 
 /// Telegram response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TelegramResponse {
     pub status: String,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Ping request
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct PingRequest {
+    pub apikey: ApiKey,
+}
+
+/// Ping data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PingData {
+    pub broker: Option<String>,
+    pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Ping response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PingResponse {
+    pub status: String,
+    pub data: Option<PingData>,
+    pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Ping response with the measured round-trip time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PingReport {
+    pub response: PingResponse,
+    pub latency: std::time::Duration,
+}
+
+impl PingReport {
+    /// Whether the server answered successfully
+    pub fn is_ok(&self) -> bool {
+        self.response.status == "success"
+    }
+}
+
+/// Server version request
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct VersionRequest {
+    pub apikey: ApiKey,
+}
+
+/// Server version data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionData {
+    pub version: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl VersionData {
+    /// Whether the server version is at least `minimum` (dotted numeric, e.g. "1.0.20")
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::VersionData;
+    /// let data = VersionData { version: Some("1.0.21".to_string()), extra: Default::default() };
+    /// assert!(data.at_least("1.0.9"));
+    /// assert!(!data.at_least("1.1"));
+    /// ```
+    pub fn at_least(&self, minimum: &str) -> bool {
+        let parse = |v: &str| -> Vec<u64> {
+            v.trim_start_matches('v')
+                .split('.')
+                .map(|part| {
+                    part.chars()
+                        .take_while(|c| c.is_ascii_digit())
+                        .collect::<String>()
+                        .parse()
+                        .unwrap_or(0)
+                })
+                .collect()
+        };
+        let Some(version) = &self.version else {
+            return false;
+        };
+        let (mut have, mut want) = (parse(version), parse(minimum));
+        let len = have.len().max(want.len());
+        have.resize(len, 0);
+        want.resize(len, 0);
+        have >= want
+    }
+}
+
+/// Server version response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionResponse {
+    pub status: String,
+    pub data: Option<VersionData>,
+    pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 // ============================================================================
@@ -1055,49 +1774,63 @@ pub struct TelegramResponse {
 // ============================================================================
 
 /// Analyzer status request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct AnalyzerStatusRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
 }
 
 /// Analyzer status data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnalyzerStatusData {
     pub analyze_mode: Option<bool>,
     pub mode: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub total_logs: Option<i32>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Analyzer status response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnalyzerStatusResponse {
     pub status: String,
     pub data: Option<AnalyzerStatusData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Analyzer toggle request
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct AnalyzerToggleRequest {
-    pub apikey: String,
+    pub apikey: ApiKey,
     pub mode: bool,
 }
 
 /// Analyzer toggle data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnalyzerToggleData {
     pub analyze_mode: Option<bool>,
     pub mode: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub total_logs: Option<i32>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Analyzer toggle response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnalyzerToggleResponse {
     pub status: String,
     pub data: Option<AnalyzerToggleData>,
     pub message: Option<String>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 // ============================================================================
@@ -1105,7 +1838,7 @@ pub struct AnalyzerToggleResponse {
 // ============================================================================
 
 /// WebSocket instrument for subscription
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct WsInstrument {
     pub exchange: String,
     pub symbol: String,
@@ -1117,7 +1850,11 @@ impl WsInstrument {
     /// # Example
     /// ```rust
     /// use openalgo::WsInstrument;
+    /// use std::collections::HashSet;
+    ///
     /// let instrument = WsInstrument::new("NSE", "RELIANCE");
+    /// let unique: HashSet<_> = [instrument.clone(), instrument].into_iter().collect();
+    /// assert_eq!(unique.len(), 1);
     /// ```
     pub fn new(exchange: &str, symbol: &str) -> Self {
         Self {
@@ -1128,14 +1865,14 @@ impl WsInstrument {
 }
 
 /// WebSocket authentication message
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct WsAuthMessage {
     pub action: String,
-    pub api_key: String,
+    pub api_key: ApiKey,
 }
 
 /// WebSocket subscribe/unsubscribe message
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct WsSubscribeMessage {
     pub action: String,
     pub mode: String,
@@ -1143,49 +1880,329 @@ pub struct WsSubscribeMessage {
 }
 
 /// WebSocket LTP data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WsLtpData {
     pub exchange: Option<String>,
     pub symbol: Option<String>,
-    pub ltp: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub ltp: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub timestamp: Option<i64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// WebSocket Quote data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WsQuoteData {
     pub exchange: Option<String>,
     pub symbol: Option<String>,
-    pub ltp: Option<f64>,
-    pub open: Option<f64>,
-    pub high: Option<f64>,
-    pub low: Option<f64>,
-    pub close: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub ltp: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub open: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub high: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub low: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub close: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub volume: Option<i64>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub timestamp: Option<i64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// WebSocket Depth data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WsDepthData {
     pub exchange: Option<String>,
     pub symbol: Option<String>,
-    pub ltp: Option<f64>,
-    pub open: Option<f64>,
-    pub high: Option<f64>,
-    pub low: Option<f64>,
-    pub close: Option<f64>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub ltp: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub open: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub high: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub low: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
+    pub close: Option<Price>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub volume: Option<i64>,
     pub bids: Option<Vec<DepthLevel>>,
     pub asks: Option<Vec<DepthLevel>>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub timestamp: Option<i64>,
+    /// Any additional fields returned by the server
+    #[serde(flatten, deserialize_with = "de::extra")]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// WebSocket market data message
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WsMarketDataMessage {
     #[serde(rename = "type")]
     pub msg_type: Option<String>,
+    #[serde(default, deserialize_with = "de::option_number")]
     pub mode: Option<i32>,
     pub data: Option<serde_json::Value>,
 }
+
+// ============================================================================
+// Timestamps
+// ============================================================================
+
+impl HistoryCandle {
+    /// Bar start in IST
+    ///
+    /// # Example
+    /// ```rust
+    /// # use openalgo::HistoryCandle;
+    /// let candle: HistoryCandle = serde_json::from_str(
+    ///     r#"{"timestamp": 1733111100, "open": 1, "high": 1, "low": 1, "close": 1, "volume": 0}"#,
+    /// ).unwrap();
+    /// assert_eq!(candle.datetime().unwrap().to_string(), "2024-12-02 09:15:00 +05:30");
+    /// ```
+    pub fn datetime(&self) -> Option<DateTime<FixedOffset>> {
+        ist_datetime(self.timestamp)
+    }
+}
+
+impl OrderStatusData {
+    /// Order time in IST, parsed from the broker's timestamp
+    pub fn datetime(&self) -> Option<DateTime<FixedOffset>> {
+        parse_timestamp(self.timestamp.as_deref()?)
+    }
+
+    /// Order time as Unix seconds
+    pub fn epoch(&self) -> Option<i64> {
+        self.datetime().map(|t| t.timestamp())
+    }
+}
+
+impl OrderbookOrder {
+    /// Order time in IST, parsed from the broker's timestamp
+    pub fn datetime(&self) -> Option<DateTime<FixedOffset>> {
+        parse_timestamp(self.timestamp.as_deref()?)
+    }
+
+    /// Order time as Unix seconds
+    pub fn epoch(&self) -> Option<i64> {
+        self.datetime().map(|t| t.timestamp())
+    }
+}
+
+impl TradebookTrade {
+    /// Trade time in IST, parsed from the broker's timestamp
+    pub fn datetime(&self) -> Option<DateTime<FixedOffset>> {
+        parse_timestamp(self.timestamp.as_deref()?)
+    }
+
+    /// Trade time as Unix seconds
+    pub fn epoch(&self) -> Option<i64> {
+        self.datetime().map(|t| t.timestamp())
+    }
+}
+
+impl WsLtpData {
+    /// Tick time in IST
+    pub fn datetime(&self) -> Option<DateTime<FixedOffset>> {
+        ist_datetime(self.timestamp?)
+    }
+
+    /// Tick time as Unix seconds, whether the server sent seconds or milliseconds
+    pub fn epoch(&self) -> Option<i64> {
+        self.timestamp.map(epoch_secs)
+    }
+}
+
+impl WsQuoteData {
+    /// Tick time in IST
+    pub fn datetime(&self) -> Option<DateTime<FixedOffset>> {
+        ist_datetime(self.timestamp?)
+    }
+
+    /// Tick time as Unix seconds, whether the server sent seconds or milliseconds
+    pub fn epoch(&self) -> Option<i64> {
+        self.timestamp.map(epoch_secs)
+    }
+}
+
+impl WsDepthData {
+    /// Tick time in IST
+    pub fn datetime(&self) -> Option<DateTime<FixedOffset>> {
+        ist_datetime(self.timestamp?)
+    }
+
+    /// Tick time as Unix seconds, whether the server sent seconds or milliseconds
+    pub fn epoch(&self) -> Option<i64> {
+        self.timestamp.map(epoch_secs)
+    }
+}
+
+// ============================================================================
+// Conversions
+// ============================================================================
+
+fn missing(item: &str, field: &str) -> OpenAlgoError {
+    OpenAlgoError::ValidationError(ValidationError::MissingField {
+        item: item.to_string(),
+        field: field.to_string(),
+    })
+}
+
+impl TryFrom<&OrderbookOrder> for ModifyOrderRequest {
+    type Error = OpenAlgoError;
+
+    /// Modify request that repeats the order as it stands
+    ///
+    /// `apikey` and `strategy` are left empty; change the fields you want to
+    /// modify and send it with `OrderAPI::modify_order_request`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::{ModifyOrderRequest, OrderbookOrder};
+    ///
+    /// let order: OrderbookOrder = serde_json::from_str(r#"{
+    ///     "orderid": "2403", "symbol": "RELIANCE", "exchange": "NSE", "action": "BUY",
+    ///     "product": "MIS", "pricetype": "LIMIT", "quantity": "1", "price": 2500.0
+    /// }"#).unwrap();
+    /// let mut modify = ModifyOrderRequest::try_from(&order).unwrap();
+    /// modify.price = "2510".to_string();
+    /// assert_eq!(modify.orderid, "2403");
+    ///
+    /// let incomplete: OrderbookOrder = serde_json::from_str(r#"{"symbol": "RELIANCE"}"#).unwrap();
+    /// let error = ModifyOrderRequest::try_from(&incomplete).unwrap_err();
+    /// assert!(matches!(error, openalgo::client::OpenAlgoError::ValidationError(_)));
+    /// ```
+    fn try_from(order: &OrderbookOrder) -> Result<Self, Self::Error> {
+        let field = |value: &Option<String>, name: &str| value.clone().ok_or_else(|| missing("Order", name));
+        Ok(Self {
+            apikey: ApiKey::default(),
+            orderid: field(&order.orderid, "orderid")?,
+            strategy: String::new(),
+            symbol: field(&order.symbol, "symbol")?,
+            action: field(&order.action, "action")?,
+            exchange: field(&order.exchange, "exchange")?,
+            pricetype: field(&order.pricetype, "pricetype")?,
+            product: field(&order.product, "product")?,
+            quantity: field(&order.quantity, "quantity")?,
+            price: order.price.unwrap_or_default().to_string(),
+            disclosed_quantity: None,
+            trigger_price: order.trigger_price.filter(|p| *p != Price::default()).map(|p| p.to_string()),
+            validity: None,
+        })
+    }
+}
+
+impl TryFrom<&PositionbookPosition> for PlaceOrderRequest {
+    type Error = OpenAlgoError;
+
+    /// Market order that squares off the position
+    ///
+    /// `apikey` and `strategy` are left empty; send it with
+    /// `OrderAPI::place_order_request`. Fails for a flat position.
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::{PlaceOrderRequest, PositionbookPosition};
+    ///
+    /// let position: PositionbookPosition = serde_json::from_str(
+    ///     r#"{"symbol": "RELIANCE", "exchange": "NSE", "product": "MIS", "quantity": "-50"}"#,
+    /// ).unwrap();
+    /// let exit = PlaceOrderRequest::try_from(&position).unwrap();
+    /// assert_eq!((exit.action.as_str(), exit.quantity.as_str()), ("BUY", "50"));
+    /// ```
+    fn try_from(position: &PositionbookPosition) -> Result<Self, Self::Error> {
+        let field = |value: &Option<String>, name: &str| value.clone().ok_or_else(|| missing("Position", name));
+        let quantity = field(&position.quantity, "quantity")?;
+        let quantity: i64 = quantity
+            .trim()
+            .parse()
+            .map_err(|_| OpenAlgoError::ApiError(format!("Invalid position quantity: {}", quantity).into()))?;
+        if quantity == 0 {
+            return Err(OpenAlgoError::ApiError("Position is already flat".into()));
+        }
+        Ok(Self {
+            apikey: ApiKey::default(),
+            strategy: String::new(),
+            symbol: field(&position.symbol, "symbol")?,
+            action: if quantity > 0 { "SELL" } else { "BUY" }.to_string(),
+            exchange: field(&position.exchange, "exchange")?,
+            pricetype: "MARKET".to_string(),
+            product: field(&position.product, "product")?,
+            quantity: quantity.unsigned_abs().to_string(),
+            price: None,
+            trigger_price: None,
+            disclosed_quantity: None,
+            validity: None,
+            amo: false,
+        })
+    }
+}
+
+impl TryFrom<&SymbolData> for WsInstrument {
+    type Error = OpenAlgoError;
+
+    fn try_from(symbol: &SymbolData) -> Result<Self, Self::Error> {
+        match (&symbol.exchange, &symbol.symbol) {
+            (Some(exchange), Some(name)) => Ok(Self::new(exchange, name)),
+            (None, _) => Err(missing("Symbol", "exchange")),
+            (_, None) => Err(missing("Symbol", "symbol")),
+        }
+    }
+}
+
+impl TryFrom<&SymbolData> for MultiQuotesSymbol {
+    type Error = OpenAlgoError;
+
+    fn try_from(symbol: &SymbolData) -> Result<Self, Self::Error> {
+        WsInstrument::try_from(symbol).map(Self::from)
+    }
+}
+
+/// `(exchange, symbol)`, the argument order of `WsInstrument::new`
+impl From<(&str, &str)> for WsInstrument {
+    fn from((exchange, symbol): (&str, &str)) -> Self {
+        Self::new(exchange, symbol)
+    }
+}
+
+/// `(exchange, symbol)`, the same order as for `WsInstrument`
+///
+/// # Example
+/// ```rust
+/// use openalgo::{MultiQuotesSymbol, WsInstrument};
+///
+/// let symbol = MultiQuotesSymbol::from(("NSE", "RELIANCE"));
+/// assert_eq!(symbol, MultiQuotesSymbol::new("RELIANCE", "NSE"));
+/// assert_eq!(WsInstrument::from(symbol), WsInstrument::from(("NSE", "RELIANCE")));
+/// ```
+impl From<(&str, &str)> for MultiQuotesSymbol {
+    fn from((exchange, symbol): (&str, &str)) -> Self {
+        Self::new(symbol, exchange)
+    }
+}
+
+impl From<WsInstrument> for MultiQuotesSymbol {
+    fn from(instrument: WsInstrument) -> Self {
+        Self {
+            symbol: instrument.symbol,
+            exchange: instrument.exchange,
+        }
+    }
+}
+
+impl From<MultiQuotesSymbol> for WsInstrument {
+    fn from(symbol: MultiQuotesSymbol) -> Self {
+        Self {
+            exchange: symbol.exchange,
+            symbol: symbol.symbol,
+        }
+    }
+}
@@ -1,12 +1,15 @@
 //! Utilities API module for OpenAlgo.
 //!
-//! This module contains utility functions like holidays, timings, and telegram messaging.
+//! This module contains utility functions like holidays, timings, telegram messaging,
+//! and connectivity checks.
 
 use crate::client::{OpenAlgoClient, OpenAlgoError};
 use crate::types::*;
 use std::sync::Arc;
+use web_time::Instant;
 
 /// Utilities API client
+#[derive(Clone)]
 pub struct UtilitiesAPI {
     client: Arc<OpenAlgoClient>,
 }
@@ -17,6 +20,29 @@ impl UtilitiesAPI {
         Self { client }
     }
 
+    /// Check connectivity and API key, measuring the round trip
+    pub async fn ping(&self) -> Result<PingReport, OpenAlgoError> {
+        let request = PingRequest {
+            apikey: self.client.api_key(),
+        };
+
+        let started = Instant::now();
+        let response: PingResponse = self.client.post("ping", &request).await?;
+        Ok(PingReport {
+            response,
+            latency: started.elapsed(),
+        })
+    }
+
+    /// Get the OpenAlgo server version
+    pub async fn server_version(&self) -> Result<VersionResponse, OpenAlgoError> {
+        let request = VersionRequest {
+            apikey: self.client.api_key(),
+        };
+
+        self.client.post("version", &request).await
+    }
+
     /// Get market holidays
     ///
     /// # Arguments
@@ -27,7 +53,7 @@ impl UtilitiesAPI {
         year: i32,
     ) -> Result<HolidaysResponse, OpenAlgoError> {
         let request = HolidaysRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             year,
         };
 
@@ -44,7 +70,7 @@ impl UtilitiesAPI {
         date: &str,
     ) -> Result<TimingsResponse, OpenAlgoError> {
         let request = TimingsRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             date: date.to_string(),
         };
 
@@ -79,7 +105,7 @@ impl UtilitiesAPI {
         priority: i32,
     ) -> Result<TelegramResponse, OpenAlgoError> {
         let request = TelegramRequest {
-            apikey: self.client.api_key.clone(),
+            apikey: self.client.api_key(),
             username: username.to_string(),
             message: message.to_string(),
             priority: Some(priority),
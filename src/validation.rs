@@ -0,0 +1,135 @@
+//! Tick-size and lot-size checks for order prices and quantities.
+//!
+//! Brokers reject prices off the tick grid and F&O quantities that are not
+//! whole lots. `OpenAlgoBuilder::validate_orders` runs these checks against
+//! the symbol's cached `tick_size` and `lotsize` before order calls; the
+//! error suggests the nearest valid values on either side.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::price::from_f64;
+//! use openalgo::validation::{check_lot, check_tick, ValidationError};
+//!
+//! assert!(check_tick(from_f64(2501.15), from_f64(0.05)).is_ok());
+//! match check_tick(from_f64(2501.17), from_f64(0.05)) {
+//!     Err(ValidationError::InvalidTick { below, above, .. }) => {
+//!         assert_eq!(below.to_string(), "2501.15");
+//!         assert_eq!(above.to_string(), "2501.2");
+//!     }
+//!     other => panic!("{:?}", other),
+//! }
+//! assert_eq!(
+//!     check_lot(100, 75),
+//!     Err(ValidationError::InvalidLot { quantity: 100, lot_size: 75, below: 75, above: 150 })
+//! );
+//! ```
+
+use crate::price::{ceil_to_tick, floor_to_tick, Price};
+use crate::types::{SymbolData, Validity};
+use thiserror::Error;
+
+/// Why an order, or a value to be turned into one, is invalid
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ValidationError {
+    #[error("price {price} is not a multiple of the tick size {tick_size} (nearest valid: {below} or {above})")]
+    InvalidTick {
+        price: Price,
+        tick_size: Price,
+        below: Price,
+        above: Price,
+    },
+
+    #[error("quantity {quantity} is not a multiple of the lot size {lot_size} (nearest valid: {below} or {above})")]
+    InvalidLot {
+        quantity: i64,
+        lot_size: i64,
+        /// Largest whole-lot quantity below, at least one lot
+        below: i64,
+        above: i64,
+    },
+
+    #[error("CNC sell of {quantity} exceeds the {sellable} shares held")]
+    InsufficientHoldings { quantity: i64, sellable: i64 },
+
+    #[error("{validity} validity is not allowed for {pricetype} orders")]
+    InvalidValidity { validity: Validity, pricetype: String },
+
+    /// A conversion such as `ModifyOrderRequest::try_from(&order)` lacked a field it needs
+    #[error("{item} has no {field}")]
+    MissingField { item: String, field: String },
+}
+
+/// Check that a price lies on the tick grid
+///
+/// A zero or negative tick size, or a zero price (market orders), passes.
+pub fn check_tick(price: Price, tick_size: Price) -> Result<(), ValidationError> {
+    let zero = Price::default();
+    if tick_size <= zero || price == zero {
+        return Ok(());
+    }
+    let below = floor_to_tick(price, tick_size);
+    let above = ceil_to_tick(price, tick_size);
+    if below == above {
+        return Ok(());
+    }
+    Err(ValidationError::InvalidTick {
+        price,
+        tick_size,
+        below,
+        above,
+    })
+}
+
+/// Check that a quantity is a whole number of lots
+///
+/// A lot size of zero or one, or a quantity of zero, passes.
+pub fn check_lot(quantity: i64, lot_size: i64) -> Result<(), ValidationError> {
+    if lot_size <= 1 || quantity == 0 || quantity % lot_size == 0 {
+        return Ok(());
+    }
+    let below = (quantity.abs() / lot_size * lot_size).max(lot_size);
+    Err(ValidationError::InvalidLot {
+        quantity,
+        lot_size,
+        below,
+        above: (quantity.abs() / lot_size + 1) * lot_size,
+    })
+}
+
+/// Check an order validity against its price type
+///
+/// IOC applies to MARKET and LIMIT orders only; DAY and no validity always pass.
+pub fn check_validity(validity: Option<Validity>, pricetype: &str) -> Result<(), ValidationError> {
+    let pricetype = pricetype.trim().to_ascii_uppercase();
+    match validity {
+        Some(Validity::Ioc) if pricetype != "MARKET" && pricetype != "LIMIT" => Err(ValidationError::InvalidValidity {
+            validity: Validity::Ioc,
+            pricetype,
+        }),
+        _ => Ok(()),
+    }
+}
+
+impl SymbolData {
+    /// Check an order's quantity against the lot size and its prices against the tick size
+    ///
+    /// # Example
+    /// ```rust
+    /// # use openalgo::SymbolData;
+    /// use openalgo::price::from_f64;
+    ///
+    /// let nifty: SymbolData = serde_json::from_str(r#"{"symbol": "NIFTY30DEC25FUT", "lotsize": 75, "tick_size": 0.1}"#).unwrap();
+    /// assert!(nifty.validate_order(150, &[from_f64(24150.5)]).is_ok());
+    /// assert!(nifty.validate_order(100, &[]).is_err());
+    /// assert!(nifty.validate_order(75, &[from_f64(24150.55)]).is_err());
+    /// ```
+    pub fn validate_order(&self, quantity: i64, prices: &[Price]) -> Result<(), ValidationError> {
+        check_lot(quantity, self.lotsize.map(i64::from).unwrap_or(1))?;
+        if let Some(tick_size) = self.tick_size {
+            for price in prices {
+                check_tick(*price, tick_size)?;
+            }
+        }
+        Ok(())
+    }
+}
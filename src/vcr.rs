@@ -0,0 +1,381 @@
+//! Record-and-replay of REST traffic for offline tests.
+//!
+//! `Vcr` is a transport that runs in one of three modes:
+//!
+//! - `VcrMode::Record` forwards requests to a real transport. It appends each
+//!   request and response to a cassette file.
+//! - `VcrMode::Replay` answers from the cassette without touching the network.
+//! - `VcrMode::Auto` replays when the cassette exists and records otherwise.
+//!
+//! Record once against a live server, commit the cassette, and CI runs the
+//! same strategy code offline.
+//!
+//! Cassettes never contain the API key. `apikey` is removed from request
+//! bodies and queries, and any occurrence of the key in a response is
+//! replaced with `[REDACTED]`. Headers are not recorded. Other fields can be
+//! scrubbed with `Vcr::scrub`. Enabled by the `test-utils` feature.
+//!
+//! Requests are matched by method, endpoint and scrubbed body. Identical
+//! requests get the recorded responses in recording order.
+//!
+//! # Example
+//! ```rust
+//! use openalgo::mock::MockOpenAlgo;
+//! use openalgo::vcr::{Vcr, VcrMode};
+//! use openalgo::OpenAlgo;
+//! use std::sync::Arc;
+//!
+//! let cassette = std::env::temp_dir().join("openalgo-vcr-doc.json");
+//! # let _ = std::fs::remove_file(&cassette);
+//! tokio_test::block_on(async {
+//!     // Record, here from a mock standing in for the live server
+//!     let live = MockOpenAlgo::new();
+//!     live.set_price("SBIN", 801.5);
+//!     let recorder = Vcr::new(&cassette, VcrMode::Record, Some(live.transport())).unwrap();
+//!     let client = OpenAlgo::builder("secret-key").transport(Arc::new(recorder)).build();
+//!     client.quotes("SBIN", "NSE").await.unwrap();
+//!
+//!     // Replay offline
+//!     let client = Vcr::replay_client(&cassette).unwrap();
+//!     let quote = client.quotes("SBIN", "NSE").await.unwrap();
+//!     assert_eq!(quote.data.unwrap().ltp.map(openalgo::price::to_f64), Some(801.5));
+//! });
+//! assert!(!std::fs::read_to_string(&cassette).unwrap().contains("secret-key"));
+//! # std::fs::remove_file(&cassette).unwrap();
+//! ```
+
+use crate::client::{ApiErrorDetails, OpenAlgoError};
+use crate::middleware::{Method, RequestParts};
+use crate::transport::{Transport, TransportFuture};
+use crate::OpenAlgo;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Whether a `Vcr` records or replays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VcrMode {
+    /// Forward to the real transport and write every interaction to the cassette
+    Record,
+    /// Answer from the cassette only
+    Replay,
+    /// Replay if the cassette exists, otherwise record
+    Auto,
+}
+
+impl VcrMode {
+    /// Mode from the `OPENALGO_VCR` environment variable
+    ///
+    /// Accepts `record`, `replay` or `auto`; anything else, or no value,
+    /// gives `Auto`.
+    pub fn from_env() -> Self {
+        match std::env::var("OPENALGO_VCR").as_deref().map(str::to_ascii_lowercase).as_deref() {
+            Ok("record") => VcrMode::Record,
+            Ok("replay") => VcrMode::Replay,
+            _ => VcrMode::Auto,
+        }
+    }
+}
+
+/// One recorded request and its response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// "GET" or "POST"
+    pub method: String,
+    /// Endpoint such as "placeorder"
+    pub endpoint: String,
+    /// Request body, or the query parameters of a GET, without secrets
+    pub request: Value,
+    /// HTTP status of an error response (500 if the transport gave none);
+    /// absent for successful ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
+    /// Response body, as JSON when it parses and as a string otherwise
+    pub response: Value,
+}
+
+/// Recorded interactions, saved as a pretty-printed JSON file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Read a cassette file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Write the cassette, creating parent directories
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        std::fs::write(path, text + "\n")
+    }
+}
+
+struct VcrState {
+    cassette: Cassette,
+    /// Which interactions have been replayed
+    used: Vec<bool>,
+}
+
+/// Transport recording to or replaying from a cassette file
+pub struct Vcr {
+    path: PathBuf,
+    recording: bool,
+    inner: Option<Arc<dyn Transport>>,
+    scrub: Vec<String>,
+    allow_repeats: bool,
+    state: Mutex<VcrState>,
+}
+
+impl std::fmt::Debug for Vcr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Vcr")
+            .field("path", &self.path)
+            .field("recording", &self.recording)
+            .field("interactions", &self.state.lock().unwrap().cassette.interactions.len())
+            .finish()
+    }
+}
+
+impl Vcr {
+    /// Open a cassette in `mode`
+    ///
+    /// Recording needs the transport that reaches the live server, usually
+    /// `ReqwestTransport::default()`, and starts a new cassette. Replaying
+    /// needs the cassette file to exist.
+    pub fn new(path: impl Into<PathBuf>, mode: VcrMode, inner: Option<Arc<dyn Transport>>) -> io::Result<Self> {
+        let path = path.into();
+        let recording = match mode {
+            VcrMode::Record => true,
+            VcrMode::Replay => false,
+            VcrMode::Auto => !path.exists(),
+        };
+        if recording && inner.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("recording {} needs a transport to the live server", path.display()),
+            ));
+        }
+        let cassette = if recording {
+            Cassette::default()
+        } else {
+            Cassette::load(&path)?
+        };
+        let used = vec![false; cassette.interactions.len()];
+        Ok(Self {
+            path,
+            recording,
+            inner,
+            scrub: Vec::new(),
+            allow_repeats: false,
+            state: Mutex::new(VcrState { cassette, used }),
+        })
+    }
+
+    /// Client answering from a cassette, for offline tests
+    pub fn replay_client(path: impl Into<PathBuf>) -> io::Result<OpenAlgo> {
+        let vcr = Self::new(path, VcrMode::Replay, None)?;
+        Ok(OpenAlgo::builder("replay").transport(Arc::new(vcr)).build())
+    }
+
+    /// Also remove this field from recorded requests and response objects
+    pub fn scrub(mut self, field: &str) -> Self {
+        self.scrub.push(field.to_string());
+        self
+    }
+
+    /// Serve the last matching response again once all matches are used
+    ///
+    /// Useful for polling loops whose call count varies between runs. By
+    /// default an extra request is an error.
+    pub fn allow_repeats(mut self, allow: bool) -> Self {
+        self.allow_repeats = allow;
+        self
+    }
+
+    /// Whether this cassette is being recorded rather than replayed
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Interactions recorded or loaded so far
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.state.lock().unwrap().cassette.interactions.clone()
+    }
+
+    /// Request payload with secrets and scrubbed fields removed
+    fn scrubbed_request(&self, parts: &RequestParts) -> Value {
+        let mut request = match parts.method {
+            Method::Post => parts
+                .body
+                .as_deref()
+                .map(|body| serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string())))
+                .unwrap_or(Value::Null),
+            Method::Get => Value::Object(
+                parts
+                    .query
+                    .iter()
+                    .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+                    .collect(),
+            ),
+        };
+        if let Some(fields) = request.as_object_mut() {
+            fields.remove("apikey");
+        }
+        self.remove_fields(&mut request);
+        request
+    }
+
+    fn remove_fields(&self, value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                for field in &self.scrub {
+                    fields.remove(field);
+                }
+                fields.values_mut().for_each(|v| self.remove_fields(v));
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| self.remove_fields(v)),
+            _ => {}
+        }
+    }
+
+    fn record(&self, parts: &RequestParts, result: &Result<String, OpenAlgoError>) {
+        let api_key = api_key_of(parts);
+        let redact = |text: &str| match &api_key {
+            Some(key) => text.replace(key.as_str(), "[REDACTED]"),
+            None => text.to_string(),
+        };
+        let (http_status, body) = match result {
+            Ok(body) => (None, redact(body)),
+            Err(error) => match error.api_details() {
+                Some(details) => (
+                    Some(details.http_status.unwrap_or(500)),
+                    redact(details.body.as_deref().unwrap_or(&details.message)),
+                ),
+                // Transport failures never reached the server; nothing to replay
+                None => return,
+            },
+        };
+        let mut response = serde_json::from_str(&body).unwrap_or(Value::String(body));
+        self.remove_fields(&mut response);
+        let interaction = Interaction {
+            method: parts.method.as_str().to_string(),
+            endpoint: parts.endpoint.clone(),
+            request: self.scrubbed_request(parts),
+            http_status,
+            response,
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.cassette.interactions.push(interaction);
+        state.used.push(true);
+        if let Err(e) = state.cassette.save(&self.path) {
+            log::warn!("vcr: could not save {}: {}", self.path.display(), e);
+        }
+    }
+
+    fn replay(&self, parts: &RequestParts) -> Result<String, OpenAlgoError> {
+        let method = parts.method.as_str();
+        let request = self.scrubbed_request(parts);
+        let mut state = self.state.lock().unwrap();
+        let matches: Vec<usize> = state
+            .cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| i.method == method && i.endpoint == parts.endpoint && i.request == request)
+            .map(|(index, _)| index)
+            .collect();
+        let index = match matches.iter().find(|&&i| !state.used[i]) {
+            Some(&index) => index,
+            None if self.allow_repeats && !matches.is_empty() => matches[matches.len() - 1],
+            None => {
+                return Err(OpenAlgoError::ApiError(
+                    format!(
+                        "no recorded response for {} {} {} in {}",
+                        method,
+                        parts.endpoint,
+                        request,
+                        self.path.display()
+                    )
+                    .into(),
+                ))
+            }
+        };
+        state.used[index] = true;
+        let interaction = &state.cassette.interactions[index];
+        let body = match &interaction.response {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        match interaction.http_status {
+            Some(status) => Err(OpenAlgoError::ApiError(Box::new(ApiErrorDetails::from_body(Some(status), &body)))),
+            None => Ok(body),
+        }
+    }
+}
+
+impl Transport for Vcr {
+    fn post_json<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let parts = parts_for(Method::Post, url, Some(body), Vec::new());
+            self.send(&parts).await
+        })
+    }
+
+    fn get_json<'a>(&'a self, url: &'a str, query: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let query = query.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            let parts = parts_for(Method::Get, url, None, query);
+            self.send(&parts).await
+        })
+    }
+
+    fn send<'a>(&'a self, request: &'a RequestParts) -> TransportFuture<'a> {
+        Box::pin(async move {
+            match (&self.inner, self.recording) {
+                (Some(inner), true) => {
+                    let result = inner.send(request).await;
+                    self.record(request, &result);
+                    result
+                }
+                _ => self.replay(request),
+            }
+        })
+    }
+}
+
+fn parts_for(method: Method, url: &str, body: Option<String>, query: Vec<(String, String)>) -> RequestParts {
+    let path = url.split("/api/").nth(1).unwrap_or(url);
+    let endpoint = path.split_once('/').map_or(path, |(_, rest)| rest);
+    RequestParts {
+        method,
+        endpoint: endpoint.trim_end_matches('/').to_string(),
+        url: url.to_string(),
+        headers: Vec::new(),
+        body,
+        query,
+    }
+}
+
+/// API key sent with a request, to redact it from the response
+fn api_key_of(parts: &RequestParts) -> Option<String> {
+    let key = match parts.method {
+        Method::Post => parts
+            .body
+            .as_deref()
+            .and_then(|body| serde_json::from_str::<Value>(body).ok())
+            .and_then(|body| body.get("apikey")?.as_str().map(str::to_string)),
+        Method::Get => parts.query.iter().find(|(k, _)| k == "apikey").map(|(_, v)| v.clone()),
+    };
+    key.filter(|k| !k.is_empty())
+}
@@ -0,0 +1,468 @@
+//! Volatility API module for OpenAlgo.
+//!
+//! Builds implied volatility surfaces and ATM term structures from option
+//! chains, computing IVs locally with Black-Scholes. The same local Greeks
+//! drive delta-based strike selection (`find_strike_by_delta`).
+
+use crate::client::{OpenAlgoClient, OpenAlgoError};
+use crate::data::DataAPI;
+use crate::options::{
+    black_scholes_delta, black_scholes_gamma, black_scholes_theta, black_scholes_vega, implied_volatility,
+    time_to_expiry, OptionType,
+};
+use crate::price;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use web_time::Instant;
+
+/// Default time an option chain IV slice is cached for
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Cache key: (underlying, exchange, expiry date, bits of the interest rate the IVs were solved at)
+type SliceKey = (String, String, String, u64);
+
+/// Implied volatility at a single strike
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IvPoint {
+    pub strike: f64,
+    pub call_iv: Option<f64>,
+    pub put_iv: Option<f64>,
+}
+
+impl IvPoint {
+    /// Out-of-the-money IV for this strike (put below spot, call at or above)
+    pub fn otm_iv(&self, spot: f64) -> Option<f64> {
+        if self.strike < spot {
+            self.put_iv.or(self.call_iv)
+        } else {
+            self.call_iv.or(self.put_iv)
+        }
+    }
+}
+
+/// Implied volatility across strikes for one expiry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IvSlice {
+    pub expiry_date: String,
+    pub time_to_expiry: f64,
+    pub underlying_ltp: f64,
+    pub atm_strike: Option<f64>,
+    pub atm_iv: Option<f64>,
+    pub points: Vec<IvPoint>,
+}
+
+impl IvSlice {
+    /// IV at a strike (out-of-the-money side)
+    pub fn iv(&self, strike: f64) -> Option<f64> {
+        self.points
+            .iter()
+            .find(|p| (p.strike - strike).abs() < 1e-9)
+            .and_then(|p| p.otm_iv(self.underlying_ltp))
+    }
+}
+
+/// Point on the ATM volatility term structure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TermStructurePoint {
+    pub expiry_date: String,
+    pub time_to_expiry: f64,
+    pub atm_strike: Option<f64>,
+    pub atm_iv: Option<f64>,
+}
+
+/// Strike x expiry implied volatility surface
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IvSurface {
+    pub underlying: String,
+    pub exchange: String,
+    pub slices: Vec<IvSlice>,
+}
+
+impl IvSurface {
+    /// IV at a strike for an expiry
+    pub fn iv(&self, strike: f64, expiry_date: &str) -> Option<f64> {
+        self.slice(expiry_date).and_then(|s| s.iv(strike))
+    }
+
+    /// Slice for an expiry
+    pub fn slice(&self, expiry_date: &str) -> Option<&IvSlice> {
+        self.slices
+            .iter()
+            .find(|s| s.expiry_date.eq_ignore_ascii_case(expiry_date))
+    }
+
+    /// Strikes present in any slice, sorted ascending
+    pub fn strikes(&self) -> Vec<f64> {
+        let mut strikes: Vec<f64> = self
+            .slices
+            .iter()
+            .flat_map(|s| s.points.iter().map(|p| p.strike))
+            .collect();
+        strikes.sort_by(|a, b| a.total_cmp(b));
+        strikes.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+        strikes
+    }
+
+    /// ATM term structure ordered by time to expiry
+    pub fn term_structure(&self) -> Vec<TermStructurePoint> {
+        let mut points: Vec<TermStructurePoint> = self
+            .slices
+            .iter()
+            .map(|s| TermStructurePoint {
+                expiry_date: s.expiry_date.clone(),
+                time_to_expiry: s.time_to_expiry,
+                atm_strike: s.atm_strike,
+                atm_iv: s.atm_iv,
+            })
+            .collect();
+        points.sort_by(|a, b| a.time_to_expiry.total_cmp(&b.time_to_expiry));
+        points
+    }
+}
+
+/// Locally computed Greeks of one chain option
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrikeGreeks {
+    pub strike: f64,
+    pub option_type: OptionType,
+    pub symbol: Option<String>,
+    /// Premium the IV was solved from (bid/ask mid, else LTP)
+    pub premium: f64,
+    pub iv: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    /// Per calendar day
+    pub theta: f64,
+    /// Per 1% change in volatility
+    pub vega: f64,
+}
+
+/// Greeks of every priced option in a chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainGreeks {
+    pub expiry_date: String,
+    pub time_to_expiry: f64,
+    pub underlying_ltp: f64,
+    pub options: Vec<StrikeGreeks>,
+}
+
+impl ChainGreeks {
+    /// Option of a type whose delta is closest to `delta`; see [`find_strike_by_delta`]
+    pub fn by_delta(&self, delta: f64, option_type: OptionType) -> Option<&StrikeGreeks> {
+        find_strike_by_delta(self, delta, option_type)
+    }
+}
+
+/// Option of a type whose delta is closest to `delta`
+///
+/// The sign of `delta` is ignored, so 0.25 picks the 25-delta put as well
+/// as the 25-delta call.
+///
+/// # Example
+/// ```rust
+/// use openalgo::options::OptionType;
+/// use openalgo::volatility::{find_strike_by_delta, ChainGreeks, StrikeGreeks};
+///
+/// let put = |strike: f64, delta: f64| StrikeGreeks {
+///     strike,
+///     option_type: OptionType::PE,
+///     symbol: None,
+///     premium: 0.0,
+///     iv: 0.15,
+///     delta,
+///     gamma: 0.0,
+///     theta: 0.0,
+///     vega: 0.0,
+/// };
+/// let chain = ChainGreeks {
+///     expiry_date: "26DEC24".into(),
+///     time_to_expiry: 0.05,
+///     underlying_ltp: 24000.0,
+///     options: vec![put(23500.0, -0.18), put(23700.0, -0.27), put(23900.0, -0.41)],
+/// };
+/// assert_eq!(find_strike_by_delta(&chain, 0.25, OptionType::PE).unwrap().strike, 23700.0);
+/// assert!(find_strike_by_delta(&chain, 0.25, OptionType::CE).is_none());
+/// ```
+pub fn find_strike_by_delta(chain: &ChainGreeks, delta: f64, option_type: OptionType) -> Option<&StrikeGreeks> {
+    let target = delta.abs();
+    chain
+        .options
+        .iter()
+        .filter(|o| o.option_type == option_type)
+        .min_by(|a, b| (a.delta.abs() - target).abs().total_cmp(&(b.delta.abs() - target).abs()))
+}
+
+/// Compute Greeks for every option in a chain that has a price and a solvable IV
+///
+/// `interest_rate` is in percent (e.g. 6.5).
+pub fn build_chain_greeks(
+    chain: &OptionChainResponse,
+    expiry_date: &str,
+    interest_rate: f64,
+) -> Result<ChainGreeks, OpenAlgoError> {
+    let slice = build_iv_slice(chain, expiry_date, interest_rate)?;
+    let (spot, t, rate) = (slice.underlying_ltp, slice.time_to_expiry, interest_rate / 100.0);
+
+    let mut options = Vec::new();
+    for s in chain.chain.iter().flatten() {
+        let strike = price::to_f64(s.strike);
+        let point = slice.points.iter().find(|p| (p.strike - strike).abs() < 1e-9);
+        for (data, option_type) in [(&s.ce, OptionType::CE), (&s.pe, OptionType::PE)] {
+            let Some(data) = data else { continue };
+            let iv = point.and_then(|p| match option_type {
+                OptionType::CE => p.call_iv,
+                OptionType::PE => p.put_iv,
+            });
+            let (Some(iv), Some(premium)) = (iv, option_price(data)) else { continue };
+            options.push(StrikeGreeks {
+                strike,
+                option_type,
+                symbol: data.symbol.clone(),
+                premium,
+                iv,
+                delta: black_scholes_delta(spot, strike, t, rate, iv, option_type),
+                gamma: black_scholes_gamma(spot, strike, t, rate, iv),
+                theta: black_scholes_theta(spot, strike, t, rate, iv, option_type),
+                vega: black_scholes_vega(spot, strike, t, rate, iv) / 100.0,
+            });
+        }
+    }
+
+    Ok(ChainGreeks {
+        expiry_date: slice.expiry_date,
+        time_to_expiry: t,
+        underlying_ltp: spot,
+        options,
+    })
+}
+
+/// Volatility API client
+///
+/// Clones share the IV slice cache.
+#[derive(Clone)]
+pub struct VolatilityAPI {
+    data: DataAPI,
+    cache: Arc<Mutex<HashMap<SliceKey, (Instant, IvSlice)>>>,
+    cache_ttl: Arc<Mutex<Duration>>,
+}
+
+impl VolatilityAPI {
+    /// Create a new Volatility API client
+    pub fn new(client: Arc<OpenAlgoClient>) -> Self {
+        Self {
+            data: DataAPI::new(client),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            cache_ttl: Arc::new(Mutex::new(DEFAULT_CACHE_TTL)),
+        }
+    }
+
+    /// Set how long computed IV slices are reused before refetching the chain
+    ///
+    /// Slices are cached per interest rate, so a call with another rate
+    /// solves the IVs afresh.
+    pub fn set_cache_ttl(&self, ttl: Duration) {
+        *self.cache_ttl.lock().unwrap() = ttl;
+    }
+
+    /// Drop all cached IV slices
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    /// Get the IV slice for a single expiry
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - Underlying symbol (e.g. "NIFTY")
+    /// * `exchange` - Exchange of the underlying (e.g. "NSE_INDEX")
+    /// * `expiry_date` - Expiry date (e.g. "26DEC24")
+    /// * `interest_rate` - Risk-free rate in percent (e.g. 6.5)
+    pub async fn iv_slice(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_date: &str,
+        interest_rate: f64,
+    ) -> Result<IvSlice, OpenAlgoError> {
+        let key = (
+            underlying.to_string(),
+            exchange.to_string(),
+            expiry_date.to_string(),
+            interest_rate.to_bits(),
+        );
+        let ttl = *self.cache_ttl.lock().unwrap();
+        if let Some((fetched_at, slice)) = self.cache.lock().unwrap().get(&key) {
+            if fetched_at.elapsed() < ttl {
+                return Ok(slice.clone());
+            }
+        }
+
+        let chain = self.data.option_chain(underlying, exchange, expiry_date).await?;
+        let slice = build_iv_slice(&chain, expiry_date, interest_rate)?;
+
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(key, (Instant::now(), slice.clone()));
+        Ok(slice)
+    }
+
+    /// Build an IV surface across several expiries
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - Underlying symbol (e.g. "NIFTY")
+    /// * `exchange` - Exchange of the underlying (e.g. "NSE_INDEX")
+    /// * `expiry_dates` - Expiry dates to include
+    /// * `interest_rate` - Risk-free rate in percent (e.g. 6.5)
+    pub async fn iv_surface(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_dates: &[&str],
+        interest_rate: f64,
+    ) -> Result<IvSurface, OpenAlgoError> {
+        let mut slices = Vec::with_capacity(expiry_dates.len());
+        for expiry_date in expiry_dates {
+            slices.push(
+                self.iv_slice(underlying, exchange, expiry_date, interest_rate)
+                    .await?,
+            );
+        }
+        slices.sort_by(|a, b| a.time_to_expiry.total_cmp(&b.time_to_expiry));
+
+        Ok(IvSurface {
+            underlying: underlying.to_string(),
+            exchange: exchange.to_string(),
+            slices,
+        })
+    }
+
+    /// Compute local Greeks for every option in a chain
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - Underlying symbol (e.g. "NIFTY")
+    /// * `exchange` - Exchange of the underlying (e.g. "NSE_INDEX")
+    /// * `expiry_date` - Expiry date (e.g. "26DEC24")
+    /// * `interest_rate` - Risk-free rate in percent (e.g. 6.5)
+    pub async fn chain_greeks(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_date: &str,
+        interest_rate: f64,
+    ) -> Result<ChainGreeks, OpenAlgoError> {
+        let chain = self.data.option_chain(underlying, exchange, expiry_date).await?;
+        build_chain_greeks(&chain, expiry_date, interest_rate)
+    }
+
+    /// Option whose delta is closest to `delta` (sign ignored), e.g. 0.25 for a 25-delta put
+    pub async fn strike_by_delta(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_date: &str,
+        delta: f64,
+        option_type: OptionType,
+        interest_rate: f64,
+    ) -> Result<StrikeGreeks, OpenAlgoError> {
+        let greeks = self.chain_greeks(underlying, exchange, expiry_date, interest_rate).await?;
+        find_strike_by_delta(&greeks, delta, option_type).cloned().ok_or_else(|| {
+            OpenAlgoError::ApiError(
+                format!("No priced {} options in the {} {} chain", option_type.as_str(), underlying, expiry_date).into(),
+            )
+        })
+    }
+
+    /// Get the ATM IV term structure across several expiries
+    pub async fn term_structure(
+        &self,
+        underlying: &str,
+        exchange: &str,
+        expiry_dates: &[&str],
+        interest_rate: f64,
+    ) -> Result<Vec<TermStructurePoint>, OpenAlgoError> {
+        Ok(self
+            .iv_surface(underlying, exchange, expiry_dates, interest_rate)
+            .await?
+            .term_structure())
+    }
+}
+
+/// Option premium used for IV: bid/ask mid when both sides are quoted, else LTP
+fn option_price(data: &OptionStrikeData) -> Option<f64> {
+    let (bid, ask, ltp) = (data.bid.map(price::to_f64), data.ask.map(price::to_f64), data.ltp.map(price::to_f64));
+    match (bid, ask) {
+        (Some(bid), Some(ask)) if bid > 0.0 && ask > 0.0 => Some((bid + ask) / 2.0),
+        _ => ltp.filter(|ltp| *ltp > 0.0),
+    }
+}
+
+/// Compute the IV slice for an option chain response
+pub fn build_iv_slice(
+    chain: &OptionChainResponse,
+    expiry_date: &str,
+    interest_rate: f64,
+) -> Result<IvSlice, OpenAlgoError> {
+    let strikes = chain.chain.as_ref().ok_or_else(|| {
+        OpenAlgoError::ApiError(
+            chain
+                .message
+                .clone()
+                .unwrap_or_else(|| "Option chain has no data".to_string())
+                .into(),
+        )
+    })?;
+    let spot = chain
+        .underlying_ltp
+        .map(price::to_f64)
+        .ok_or_else(|| OpenAlgoError::ApiError("Option chain has no underlying LTP".into()))?;
+    let expiry = chain.expiry_date.as_deref().unwrap_or(expiry_date);
+    let t = time_to_expiry(expiry)
+        .ok_or_else(|| OpenAlgoError::ApiError(format!("Invalid expiry date: {}", expiry).into()))?;
+    let rate = interest_rate / 100.0;
+
+    let iv_for = |data: &Option<OptionStrikeData>, strike: f64, option_type: OptionType| {
+        data.as_ref()
+            .and_then(option_price)
+            .and_then(|price| implied_volatility(price, spot, strike, t, rate, option_type))
+    };
+
+    let points: Vec<IvPoint> = strikes
+        .iter()
+        .map(|s| {
+            let strike = price::to_f64(s.strike);
+            IvPoint {
+                strike,
+                call_iv: iv_for(&s.ce, strike, OptionType::CE),
+                put_iv: iv_for(&s.pe, strike, OptionType::PE),
+            }
+        })
+        .collect();
+
+    let atm_strike = chain.atm_strike.map(price::to_f64).or_else(|| {
+        points
+            .iter()
+            .min_by(|a, b| (a.strike - spot).abs().total_cmp(&(b.strike - spot).abs()))
+            .map(|p| p.strike)
+    });
+    let atm_iv = atm_strike
+        .and_then(|atm| points.iter().find(|p| (p.strike - atm).abs() < 1e-9))
+        .and_then(|p| match (p.call_iv, p.put_iv) {
+            (Some(c), Some(p)) => Some((c + p) / 2.0),
+            (c, p) => c.or(p),
+        });
+
+    Ok(IvSlice {
+        expiry_date: expiry.to_string(),
+        time_to_expiry: t,
+        underlying_ltp: spot,
+        atm_strike,
+        atm_iv,
+        points,
+    })
+}
@@ -0,0 +1,186 @@
+//! Watchlist module for OpenAlgo.
+//!
+//! Groups instruments for bulk quotes, scanning and WebSocket subscriptions,
+//! and persists them to disk as JSON.
+
+use crate::client::OpenAlgoError;
+use crate::data::DataAPI;
+use crate::types::*;
+use crate::websocket::WsSubscriber;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Named group of instruments
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Watchlist {
+    pub name: String,
+    pub instruments: Vec<WsInstrument>,
+}
+
+/// Summary statistics for a watchlist's quotes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatchlistSummary {
+    pub total: usize,
+    pub advancers: usize,
+    pub decliners: usize,
+    pub unchanged: usize,
+    pub average_change_percent: Option<f64>,
+    /// Best performer as (symbol, change percent)
+    pub top_gainer: Option<(String, f64)>,
+    /// Worst performer as (symbol, change percent)
+    pub top_loser: Option<(String, f64)>,
+}
+
+impl Watchlist {
+    /// Create an empty watchlist
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::Watchlist;
+    /// let mut watchlist = Watchlist::new("Banks");
+    /// watchlist.add("NSE", "HDFCBANK");
+    /// watchlist.add("NSE", "ICICIBANK");
+    /// assert!(watchlist.contains("NSE", "HDFCBANK"));
+    /// ```
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            instruments: Vec::new(),
+        }
+    }
+
+    /// Add an instrument (ignored if already present)
+    pub fn add(&mut self, exchange: &str, symbol: &str) {
+        if !self.contains(exchange, symbol) {
+            self.instruments.push(WsInstrument::new(exchange, symbol));
+        }
+    }
+
+    /// Remove an instrument, returning true if it was present
+    pub fn remove(&mut self, exchange: &str, symbol: &str) -> bool {
+        let before = self.instruments.len();
+        self.instruments
+            .retain(|i| !(i.exchange == exchange && i.symbol == symbol));
+        self.instruments.len() != before
+    }
+
+    /// Whether the watchlist contains an instrument
+    pub fn contains(&self, exchange: &str, symbol: &str) -> bool {
+        self.instruments
+            .iter()
+            .any(|i| i.exchange == exchange && i.symbol == symbol)
+    }
+
+    /// Number of instruments
+    pub fn len(&self) -> usize {
+        self.instruments.len()
+    }
+
+    /// Whether the watchlist is empty
+    pub fn is_empty(&self) -> bool {
+        self.instruments.is_empty()
+    }
+
+    /// Load a watchlist from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, OpenAlgoError> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save the watchlist to a JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), OpenAlgoError> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Subscribe all instruments to LTP updates
+    pub async fn subscribe_ltp(&self, subscriber: &WsSubscriber) -> Result<(), OpenAlgoError> {
+        subscriber.subscribe_ltp(self.instruments.clone()).await
+    }
+
+    /// Subscribe all instruments to Quote updates
+    pub async fn subscribe_quote(&self, subscriber: &WsSubscriber) -> Result<(), OpenAlgoError> {
+        subscriber.subscribe_quote(self.instruments.clone()).await
+    }
+
+    /// Subscribe all instruments to Depth updates
+    pub async fn subscribe_depth(&self, subscriber: &WsSubscriber) -> Result<(), OpenAlgoError> {
+        subscriber.subscribe_depth(self.instruments.clone()).await
+    }
+
+    /// Fetch quotes for all instruments in one multi-quotes call
+    pub async fn quotes(&self, data: &DataAPI) -> Result<MultiQuotesResponse, OpenAlgoError> {
+        let symbols: Vec<(&str, &str)> = self
+            .instruments
+            .iter()
+            .map(|i| (i.symbol.as_str(), i.exchange.as_str()))
+            .collect();
+
+        data.multi_quotes(&symbols).await
+    }
+
+    /// Fetch quotes and keep the instruments matching a predicate
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo, watchlist: openalgo::Watchlist) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// // Stocks up more than 2% on the day
+    /// let movers = watchlist
+    ///     .scan(&client.data, |r| r.data.as_ref().and_then(|q| q.change_percent()).unwrap_or(0.0) > 2.0)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn scan<F>(&self, data: &DataAPI, predicate: F) -> Result<Vec<MultiQuotesResult>, OpenAlgoError>
+    where
+        F: Fn(&MultiQuotesResult) -> bool,
+    {
+        let response = self.quotes(data).await?;
+        Ok(response
+            .results
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| predicate(r))
+            .collect())
+    }
+
+    /// Fetch quotes and compute advance/decline and change statistics
+    pub async fn summary(&self, data: &DataAPI) -> Result<WatchlistSummary, OpenAlgoError> {
+        let response = self.quotes(data).await?;
+        Ok(summarize(&response.results.unwrap_or_default()))
+    }
+}
+
+/// Compute summary statistics from multi-quotes results
+pub fn summarize(results: &[MultiQuotesResult]) -> WatchlistSummary {
+    let changes: Vec<(String, f64)> = results
+        .iter()
+        .filter_map(|r| {
+            let change = r.data.as_ref()?.change_percent()?;
+            Some((r.symbol.clone(), change))
+        })
+        .collect();
+
+    let mut summary = WatchlistSummary {
+        total: results.len(),
+        ..Default::default()
+    };
+    for (_, change) in &changes {
+        if *change > 0.0 {
+            summary.advancers += 1;
+        } else if *change < 0.0 {
+            summary.decliners += 1;
+        } else {
+            summary.unchanged += 1;
+        }
+    }
+
+    if !changes.is_empty() {
+        summary.average_change_percent =
+            Some(changes.iter().map(|(_, c)| c).sum::<f64>() / changes.len() as f64);
+    }
+    summary.top_gainer = changes.iter().max_by(|a, b| a.1.total_cmp(&b.1)).cloned();
+    summary.top_loser = changes.iter().min_by(|a, b| a.1.total_cmp(&b.1)).cloned();
+    summary
+}
@@ -1,14 +1,59 @@
 //! WebSocket module for OpenAlgo real-time data.
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::candles::CandleAggregator;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::clock::{ClockSkewReport, ClockSync};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::client::OpenAlgoClient;
 use crate::client::OpenAlgoError;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::data::DataAPI;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::metrics::{WsMetrics, WsStats};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::proxy::WsProxy;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::reconnect::{quote_events, snapshot_events, ReconnectPolicy};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::registry::{ConnectionRegistries, SubscriptionRegistry};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::secret::SharedApiKey;
 use crate::types::*;
+use serde::Serialize;
+#[cfg(not(target_arch = "wasm32"))]
+use futures_util::stream::{SplitSink, SplitStream};
+#[cfg(not(target_arch = "wasm32"))]
 use futures_util::{SinkExt, StreamExt};
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::VecDeque;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{Arc, Mutex};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::net::TcpStream;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::sync::Notify;
+#[cfg(not(target_arch = "wasm32"))]
+use tracing::Instrument;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio_tungstenite::{
+    tungstenite::Message, MaybeTlsStream, WebSocketStream,
+};
+#[cfg(not(target_arch = "wasm32"))]
 use url::Url;
 
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+mod web;
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub use web::OpenAlgoWebSocket;
+
 /// WebSocket data types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum WsData {
     Ltp(WsLtpData),
     Quote(WsQuoteData),
@@ -16,23 +61,348 @@ pub enum WsData {
     Connected,
     Disconnected,
     Error(String),
+    /// Server message that is not market data, such as auth or subscription replies
+    Unknown {
+        msg_type: Option<String>,
+        json: serde_json::Value,
+    },
+    /// Message that could not be parsed as JSON (only with `WsConfig::raw_messages`)
+    Raw(String),
+    /// Server confirmed a subscribe or unsubscribe for these instruments
+    SubscriptionAck {
+        action: String,
+        instruments: Vec<WsInstrument>,
+    },
+    /// Server rejected a subscribe or unsubscribe for these instruments
+    SubscriptionFailed {
+        action: String,
+        instruments: Vec<WsInstrument>,
+        message: String,
+    },
+    /// Connection restored and subscriptions replayed; REST snapshots follow
+    Reconnected { attempt: u32 },
+    /// Socket still down; quotes now come from REST polling until `Reconnected`
+    Degraded,
+    /// Completed candle built from live ticks (see `WsConfig::candles`)
+    Candle {
+        exchange: String,
+        symbol: String,
+        interval: String,
+        candle: HistoryCandle,
+    },
+    /// Error reported by the server
+    ServerError {
+        code: Option<String>,
+        message: String,
+    },
+}
+
+impl WsData {
+    /// Whether this is a tick (`Ltp`, `Quote` or `Depth`), the only events a backpressure policy may drop
+    pub fn is_market_data(&self) -> bool {
+        matches!(self, WsData::Ltp(_) | WsData::Quote(_) | WsData::Depth(_))
+    }
+
+    /// Symbol of a market data event
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            WsData::Ltp(d) => d.symbol.as_deref(),
+            WsData::Quote(d) => d.symbol.as_deref(),
+            WsData::Depth(d) => d.symbol.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Exchange of a market data event
+    pub fn exchange(&self) -> Option<&str> {
+        match self {
+            WsData::Ltp(d) => d.exchange.as_deref(),
+            WsData::Quote(d) => d.exchange.as_deref(),
+            WsData::Depth(d) => d.exchange.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// What to do with market data when the consumer falls behind
+///
+/// Only ticks are dropped. Connection and subscription events, errors and
+/// candles are always delivered, waiting for the consumer if they must.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackpressurePolicy {
+    /// Wait for the consumer; the socket is not read while the channel is full
+    #[default]
+    Block,
+    /// Discard the oldest buffered tick to make room
+    DropOldest,
+    /// Discard the incoming tick
+    DropNewest,
+}
+
+/// WebSocket connection settings
+///
+/// The client does not offer `permessage-deflate`: the underlying tungstenite
+/// stack cannot decode compressed frames, so servers always send uncompressed
+/// data. To cut bandwidth on WAN links, prefer LTP/Quote over Depth where
+/// possible and use `conflation::conflate` to thin out updates.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct WsConfig {
+    /// Capacity of the command channel
+    pub command_capacity: usize,
+    /// Capacity of the data channel
+    pub data_capacity: usize,
+    pub backpressure: BackpressurePolicy,
+    /// Deliver unparseable messages as `WsData::Raw` instead of dropping them
+    pub raw_messages: bool,
+    /// Proxy used to reach the server
+    pub proxy: Option<WsProxy>,
+    pub tls: WsTlsConfig,
+    /// Reconnect automatically when the socket drops
+    pub reconnect: Option<ReconnectPolicy>,
+    /// Intervals to build live candles for, such as "1m" or "5m"
+    pub candle_intervals: Vec<String>,
+    /// Shift tick timestamps onto the local clock using the measured skew
+    pub adjust_timestamps: bool,
+}
+
+/// TLS settings for `wss://` connections
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Default)]
+pub struct WsTlsConfig {
+    /// Extra trusted root certificates in PEM format
+    pub root_certificates: Vec<Vec<u8>>,
+    /// Skip certificate verification (testing only)
+    pub accept_invalid_certs: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl WsTlsConfig {
+    /// Trust an additional root certificate given in PEM format
+    pub fn add_root_certificate_pem(mut self, pem: &[u8]) -> Self {
+        self.root_certificates.push(pem.to_vec());
+        self
+    }
+
+    /// Trust an additional root certificate read from a PEM file
+    pub fn load_root_certificate(self, path: impl AsRef<std::path::Path>) -> Result<Self, OpenAlgoError> {
+        let pem = std::fs::read(path)?;
+        Ok(self.add_root_certificate_pem(&pem))
+    }
+
+    /// Skip certificate verification (testing only)
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    #[cfg_attr(not(any(feature = "rustls", feature = "native-tls")), allow(dead_code))]
+    pub(crate) fn is_default(&self) -> bool {
+        self.root_certificates.is_empty() && !self.accept_invalid_certs
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for WsConfig {
+    fn default() -> Self {
+        Self {
+            command_capacity: 32,
+            data_capacity: 128,
+            backpressure: BackpressurePolicy::Block,
+            raw_messages: false,
+            proxy: None,
+            tls: WsTlsConfig::default(),
+            reconnect: None,
+            candle_intervals: Vec::new(),
+            adjust_timestamps: false,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl WsConfig {
+    /// Capacity of the command channel
+    pub fn command_capacity(mut self, capacity: usize) -> Self {
+        self.command_capacity = capacity.max(1);
+        self
+    }
+
+    /// Capacity of the data channel
+    pub fn data_capacity(mut self, capacity: usize) -> Self {
+        self.data_capacity = capacity.max(1);
+        self
+    }
+
+    /// Policy for when the data channel is full
+    pub fn backpressure(mut self, policy: BackpressurePolicy) -> Self {
+        self.backpressure = policy;
+        self
+    }
+
+    /// Deliver unparseable messages as `WsData::Raw`
+    pub fn raw_messages(mut self, enabled: bool) -> Self {
+        self.raw_messages = enabled;
+        self
+    }
+
+    /// Connect through an HTTP or SOCKS5 proxy
+    pub fn proxy(mut self, proxy: WsProxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// TLS settings for `wss://` connections
+    pub fn tls(mut self, tls: WsTlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Reconnect automatically, resubscribing every instrument
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect = Some(policy);
+        self
+    }
+
+    /// Emit `WsData::Candle` events built from ticks for these intervals
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::WsConfig;
+    /// let config = WsConfig::default().candles(&["1m", "5m"]);
+    /// ```
+    pub fn candles(mut self, intervals: &[&str]) -> Self {
+        self.candle_intervals = intervals.iter().map(|i| i.to_string()).collect();
+        self
+    }
+
+    /// Shift tick timestamps onto the local clock using the measured skew
+    pub fn adjust_timestamps(mut self, enabled: bool) -> Self {
+        self.adjust_timestamps = enabled;
+        self
+    }
 }
 
 /// OpenAlgo WebSocket client for real-time market data
+///
+/// Clones share metrics and the subscription view, so both cover every
+/// connection opened through them.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone)]
 pub struct OpenAlgoWebSocket {
-    api_key: String,
+    api_key: Arc<SharedApiKey>,
     ws_url: String,
+    config: WsConfig,
+    metrics: Arc<WsMetrics>,
+    registries: Arc<Mutex<ConnectionRegistries>>,
+    clock: Arc<Mutex<ClockSync>>,
+    rest_client: Option<Arc<OpenAlgoClient>>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl OpenAlgoWebSocket {
     /// Create a new WebSocket client
     pub fn new(api_key: &str, ws_url: &str) -> Self {
+        Self::with_shared_key(Arc::new(SharedApiKey::new(api_key)), ws_url)
+    }
+
+    /// WebSocket client following the API key of a REST client
+    pub(crate) fn with_shared_key(api_key: Arc<SharedApiKey>, ws_url: &str) -> Self {
         Self {
-            api_key: api_key.to_string(),
+            api_key,
             ws_url: ws_url.to_string(),
+            config: WsConfig::default(),
+            metrics: Arc::new(WsMetrics::default()),
+            registries: Arc::new(Mutex::new(ConnectionRegistries::default())),
+            clock: Arc::new(Mutex::new(ClockSync::new())),
+            rest_client: None,
         }
     }
 
+    /// Use custom connection settings
+    ///
+    /// # Example
+    /// ```rust
+    /// use openalgo::websocket::{BackpressurePolicy, OpenAlgoWebSocket, WsConfig};
+    /// let ws = OpenAlgoWebSocket::new("your-api-key", "ws://127.0.0.1:8765").with_config(
+    ///     WsConfig::default()
+    ///         .data_capacity(1024)
+    ///         .backpressure(BackpressurePolicy::DropOldest),
+    /// );
+    /// ```
+    pub fn with_config(mut self, config: WsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Replace the API key; live connections reconnect and resubscribe with it
+    ///
+    /// Clones, and the REST client this WebSocket was created from, share the
+    /// key and see the change.
+    pub fn set_api_key(&self, api_key: &str) {
+        self.api_key.set(api_key);
+    }
+
+    /// REST client used for snapshots after a reconnect
+    pub fn with_rest_client(mut self, client: Arc<OpenAlgoClient>) -> Self {
+        self.rest_client = Some(client);
+        self
+    }
+
+    /// Current connection settings
+    pub fn ws_config(&self) -> &WsConfig {
+        &self.config
+    }
+
+    /// Whether any connection opened through this client currently has a live socket
+    pub fn is_connected(&self) -> bool {
+        self.metrics.open() > 0
+    }
+
+    /// Market data messages discarded by the backpressure policy
+    pub fn dropped_messages(&self) -> u64 {
+        self.metrics.dropped()
+    }
+
+    /// Snapshot of message counts, rates, parse failures and tick latency
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// let ws = client.websocket();
+    /// let (cmd_tx, mut data_rx) = ws.connect().await?;
+    /// // ...
+    /// let stats = ws.stats();
+    /// println!("LTP/s: {:.1}, avg latency: {:?} ms", stats.ltp_per_sec, stats.avg_latency_ms);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stats(&self) -> WsStats {
+        self.metrics.snapshot()
+    }
+
+    /// Skew between the local clock and server tick timestamps
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # fn example(ws: openalgo::OpenAlgoWebSocket) {
+    /// let report = ws.clock_skew();
+    /// if report.is_skewed(500) {
+    ///     eprintln!("Local clock is off by {:?} ms", report.skew_ms());
+    /// }
+    /// # }
+    /// ```
+    pub fn clock_skew(&self) -> ClockSkewReport {
+        self.clock.lock().unwrap().report()
+    }
+
+    /// Snapshot of the instruments subscribed and their modes
+    ///
+    /// Covers every open connection; an instrument stays listed while any
+    /// of them is subscribed to it.
+    pub fn subscriptions(&self) -> SubscriptionRegistry {
+        self.registries.lock().unwrap().merged()
+    }
+
     /// Connect to WebSocket server and return channels for communication
     ///
     /// Returns a tuple of (sender for commands, receiver for data)
@@ -45,17 +415,58 @@ impl OpenAlgoWebSocket {
         ),
         OpenAlgoError,
     > {
+        let rotations = self.api_key.rotations();
+        let (write, read) = self.open_socket().await?;
+
+        // Create channels
+        let (cmd_tx, cmd_rx) = mpsc::channel::<WsCommand>(self.config.command_capacity.max(1));
+        let (sink, data_rx) = DataSink::new(&self.config, Arc::clone(&self.metrics));
+        self.metrics.record_connect();
+        tracing::info!(url = %self.ws_url, "WebSocket connected");
+
+        // Send connected notification
+        sink.send(WsData::Connected).await;
+
+        let connection = Connection {
+            id: self.registries.lock().unwrap().open(),
+            ws: self.clone(),
+            sink,
+            registry: SubscriptionRegistry::new(),
+            candles: CandleAggregator::new(&self.config.candle_intervals),
+            commands_open: true,
+            rotations,
+        };
+        let span = tracing::info_span!("openalgo.ws", url = %self.ws_url);
+        tokio::spawn(connection.run(write, read, cmd_rx).instrument(span));
+
+        Ok((cmd_tx, data_rx))
+    }
+
+    /// Open and authenticate a socket
+    async fn open_socket(&self) -> Result<(WsWrite, WsRead), OpenAlgoError> {
         let url = Url::parse(&self.ws_url)?;
-        let (ws_stream, _) = connect_async(url)
-            .await
-            .map_err(|e| OpenAlgoError::WebSocketError(e.to_string()))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| OpenAlgoError::WebSocketError(format!("WebSocket URL has no host: {}", url)))?
+            .to_string();
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| OpenAlgoError::WebSocketError(format!("WebSocket URL has no port: {}", url)))?;
+
+        let stream = match &self.config.proxy {
+            Some(proxy) => proxy.connect(&host, port).await?,
+            None => TcpStream::connect((host.as_str(), port)).await?,
+        };
+        stream.set_nodelay(true)?;
+
+        let ws_stream = crate::tls::handshake(&url, stream, &self.config.tls).await?;
 
-        let (mut write, mut read) = ws_stream.split();
+        let (mut write, read) = ws_stream.split();
 
         // Send authentication message
         let auth_msg = WsAuthMessage {
             action: "authenticate".to_string(),
-            api_key: self.api_key.clone(),
+            api_key: self.api_key.get(),
         };
         let auth_json = serde_json::to_string(&auth_msg)?;
         write
@@ -63,78 +474,463 @@ impl OpenAlgoWebSocket {
             .await
             .map_err(|e| OpenAlgoError::WebSocketError(e.to_string()))?;
 
-        // Create channels
-        let (cmd_tx, mut cmd_rx) = mpsc::channel::<WsCommand>(32);
-        let (data_tx, data_rx) = mpsc::channel::<WsData>(128);
+        Ok((write, read))
+    }
+}
 
-        // Spawn reader task
-        let data_tx_clone = data_tx.clone();
-        tokio::spawn(async move {
-            while let Some(msg) = read.next().await {
-                match msg {
-                    Ok(Message::Text(text)) => {
-                        if let Ok(market_data) = serde_json::from_str::<WsMarketDataMessage>(&text)
-                        {
-                            let ws_data = parse_market_data(market_data);
-                            let _ = data_tx_clone.send(ws_data).await;
+#[cfg(not(target_arch = "wasm32"))]
+type WsWrite = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+#[cfg(not(target_arch = "wasm32"))]
+type WsRead = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+/// Why a socket session ended
+#[cfg(not(target_arch = "wasm32"))]
+enum SessionEnd {
+    /// Disconnect requested or the consumer went away
+    Closed,
+    /// Socket lost, with the error if it was not a clean close
+    Lost(Option<String>),
+    /// API key rotated; reconnect with the new one
+    Rekeyed,
+}
+
+/// Task driving one logical connection across reconnects
+#[cfg(not(target_arch = "wasm32"))]
+struct Connection {
+    /// Key of this connection in the client's subscription view
+    id: u64,
+    ws: OpenAlgoWebSocket,
+    sink: DataSink,
+    /// Subscriptions of this connection, replayed after a reconnect
+    registry: SubscriptionRegistry,
+    candles: CandleAggregator,
+    commands_open: bool,
+    /// Changes when the API key is rotated
+    rotations: tokio::sync::watch::Receiver<u64>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Connection {
+    async fn run(mut self, mut write: WsWrite, mut read: WsRead, mut cmd_rx: mpsc::Receiver<WsCommand>) {
+        loop {
+            let end = self.session(&mut write, &mut read, &mut cmd_rx).await;
+            self.ws.metrics.record_disconnect();
+            let lost = match end {
+                SessionEnd::Closed => break,
+                SessionEnd::Rekeyed => {
+                    let _ = write.close().await;
+                    match self.ws.open_socket().await {
+                        Ok((w, r)) => {
+                            write = w;
+                            read = r;
+                            self.ws.metrics.record_connect();
+                            tracing::info!(subscriptions = self.registry.len(), "WebSocket reconnected with the new API key");
+                            let snapshot = self.ws.config.reconnect.as_ref().is_some_and(|p| p.snapshot);
+                            if !self.restore(&mut write, 1, snapshot).await {
+                                break;
+                            }
+                            continue;
+                        }
+                        Err(e) => {
+                            tracing::warn!(error = %e, "WebSocket reconnect with the new API key failed");
+                            WsData::Error(e.to_string())
                         }
                     }
-                    Ok(Message::Close(_)) => {
-                        let _ = data_tx_clone.send(WsData::Disconnected).await;
-                        break;
-                    }
-                    Err(e) => {
-                        let _ = data_tx_clone.send(WsData::Error(e.to_string())).await;
-                        break;
-                    }
-                    _ => {}
                 }
+                SessionEnd::Lost(None) => {
+                    tracing::warn!("WebSocket closed by server");
+                    WsData::Disconnected
+                }
+                SessionEnd::Lost(Some(e)) => {
+                    tracing::warn!(error = %e, "WebSocket connection lost");
+                    WsData::Error(e)
+                }
+            };
+            if !self.sink.send(lost).await {
+                break;
             }
-        });
 
-        // Spawn writer task
-        tokio::spawn(async move {
-            while let Some(cmd) = cmd_rx.recv().await {
-                let msg = match cmd {
-                    WsCommand::SubscribeLtp(instruments) => {
-                        create_subscribe_message("subscribe", "ltp", instruments)
+            let Some(policy) = self.ws.config.reconnect.clone() else {
+                break;
+            };
+            match self.reconnect(&policy, &mut cmd_rx).await {
+                Some((w, r)) => {
+                    write = w;
+                    read = r;
+                }
+                None => break,
+            }
+        }
+        tracing::info!("WebSocket closed");
+        self.sink.close();
+    }
+
+    /// Relay commands and messages until the socket closes
+    async fn session(
+        &mut self,
+        write: &mut WsWrite,
+        read: &mut WsRead,
+        cmd_rx: &mut mpsc::Receiver<WsCommand>,
+    ) -> SessionEnd {
+        let raw_messages = self.ws.config.raw_messages;
+        let building_candles = !self.candles.is_empty();
+        let mut candle_flush = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            tokio::select! {
+                _ = candle_flush.tick(), if building_candles => {
+                    // Allow a second for late ticks before closing a bar
+                    let events = self.candles.flush(unix_secs() - 1);
+                    if !self.send_all(events).await {
+                        return SessionEnd::Closed;
                     }
-                    WsCommand::UnsubscribeLtp(instruments) => {
-                        create_subscribe_message("unsubscribe", "ltp", instruments)
+                }
+                Ok(()) = self.rotations.changed() => return SessionEnd::Rekeyed,
+                cmd = cmd_rx.recv(), if self.commands_open => match cmd {
+                    Some(WsCommand::Disconnect) => {
+                        self.apply(&WsCommand::Disconnect);
+                        let _ = write.close().await;
+                        return SessionEnd::Closed;
                     }
-                    WsCommand::SubscribeQuote(instruments) => {
-                        create_subscribe_message("subscribe", "quote", instruments)
+                    Some(cmd) => {
+                        self.apply(&cmd);
+                        if let Some(json) = command_message(cmd) {
+                            let _ = write.send(Message::Text(json)).await;
+                        }
                     }
-                    WsCommand::UnsubscribeQuote(instruments) => {
-                        create_subscribe_message("unsubscribe", "quote", instruments)
+                    None => self.commands_open = false,
+                },
+                msg = read.next() => match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        let events = match serde_json::from_str::<serde_json::Value>(&text) {
+                            Ok(json) => parse_message(json),
+                            Err(_) => {
+                                self.ws.metrics.record_parse_failure();
+                                if !raw_messages {
+                                    continue;
+                                }
+                                vec![WsData::Raw(text)]
+                            }
+                        };
+                        for mut ws_data in events {
+                            self.ws.metrics.record(&ws_data);
+                            {
+                                let mut clock = self.ws.clock.lock().unwrap();
+                                clock.record_event(&ws_data);
+                                if self.ws.config.adjust_timestamps {
+                                    clock.adjust(&mut ws_data);
+                                }
+                            }
+                            let candles = if building_candles {
+                                self.candles.on_tick(&ws_data, unix_secs())
+                            } else {
+                                Vec::new()
+                            };
+                            if !self.sink.send(ws_data).await || !self.send_all(candles).await {
+                                return SessionEnd::Closed;
+                            }
+                        }
                     }
-                    WsCommand::SubscribeDepth(instruments) => {
-                        create_subscribe_message("subscribe", "depth", instruments)
+                    Some(Ok(Message::Binary(bytes))) if raw_messages => {
+                        let text = String::from_utf8_lossy(&bytes).into_owned();
+                        if !self.sink.send(WsData::Raw(text)).await {
+                            return SessionEnd::Closed;
+                        }
                     }
-                    WsCommand::UnsubscribeDepth(instruments) => {
-                        create_subscribe_message("unsubscribe", "depth", instruments)
+                    Some(Ok(Message::Close(_))) | None => return SessionEnd::Lost(None),
+                    Some(Err(e)) => return SessionEnd::Lost(Some(e.to_string())),
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+    }
+
+    /// Reconnect with backoff, resubscribe and emit snapshots
+    async fn reconnect(
+        &mut self,
+        policy: &ReconnectPolicy,
+        cmd_rx: &mut mpsc::Receiver<WsCommand>,
+    ) -> Option<(WsWrite, WsRead)> {
+        let mut attempt = 0;
+        // Start of REST polling, once the fallback deadline has passed
+        let mut next_poll = policy
+            .rest_fallback
+            .as_ref()
+            .filter(|_| self.ws.rest_client.is_some())
+            .map(|fallback| tokio::time::Instant::now() + fallback.after);
+        let mut degraded = false;
+
+        loop {
+            attempt += 1;
+            if policy.max_attempts.is_some_and(|max| attempt > max) {
+                let message = format!("WebSocket reconnect failed after {} attempts", attempt - 1);
+                tracing::error!("{}", message);
+                self.sink.send(WsData::Error(message)).await;
+                return None;
+            }
+
+            // Keep taking commands while waiting so a disconnect is honoured
+            let sleep = tokio::time::sleep(policy.delay(attempt));
+            tokio::pin!(sleep);
+            loop {
+                let poll_at = next_poll.unwrap_or_else(tokio::time::Instant::now);
+                tokio::select! {
+                    _ = &mut sleep => break,
+                    _ = tokio::time::sleep_until(poll_at), if next_poll.is_some() => {
+                        if !degraded {
+                            degraded = true;
+                            tracing::warn!("WebSocket still down, polling REST quotes");
+                            if !self.sink.send(WsData::Degraded).await {
+                                return None;
+                            }
+                        }
+                        if !self.poll_quotes().await {
+                            return None;
+                        }
+                        let interval = policy.rest_fallback.as_ref().map_or(Duration::from_secs(1), |f| f.interval);
+                        next_poll = Some(tokio::time::Instant::now() + interval);
                     }
-                    WsCommand::Disconnect => {
-                        let _ = write.close().await;
-                        break;
+                    cmd = cmd_rx.recv(), if self.commands_open => match cmd {
+                        Some(WsCommand::Disconnect) => {
+                            self.apply(&WsCommand::Disconnect);
+                            return None;
+                        }
+                        Some(cmd) => self.apply(&cmd),
+                        None => self.commands_open = false,
+                    },
+                }
+            }
+
+            let (mut write, read) = match self.ws.open_socket().await {
+                Ok(socket) => socket,
+                Err(e) => {
+                    tracing::warn!(attempt, error = %e, "WebSocket reconnect attempt failed");
+                    continue;
+                }
+            };
+            self.ws.metrics.record_connect();
+            tracing::info!(attempt, subscriptions = self.registry.len(), "WebSocket reconnected");
+            if !self.restore(&mut write, attempt, policy.snapshot).await {
+                return None;
+            }
+            return Some((write, read));
+        }
+    }
+
+    /// Replay subscriptions on a new socket, announce it and send REST snapshots
+    ///
+    /// Returns false once the consumer has gone away.
+    async fn restore(&mut self, write: &mut WsWrite, attempt: u32, snapshot: bool) -> bool {
+        for cmd in self.registry.subscribe_commands() {
+            if let Some(json) = command_message(cmd) {
+                let _ = write.send(Message::Text(json)).await;
+            }
+        }
+        if !self.sink.send(WsData::Reconnected { attempt }).await {
+            return false;
+        }
+        if snapshot {
+            if let Some(client) = &self.ws.rest_client {
+                let data = DataAPI::new(Arc::clone(client));
+                for event in snapshot_events(&data, &self.registry).await {
+                    if !self.sink.send(event).await {
+                        return false;
                     }
-                };
+                }
+            }
+        }
+        true
+    }
+
+    /// Deliver events in order; returns false once the consumer has gone away
+    async fn send_all(&self, events: Vec<WsData>) -> bool {
+        for event in events {
+            if !self.sink.send(event).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Poll REST quotes for subscribed instruments while the socket is down
+    async fn poll_quotes(&mut self) -> bool {
+        let Some(client) = &self.ws.rest_client else {
+            return true;
+        };
+        let data = DataAPI::new(Arc::clone(client));
+        for event in quote_events(&data, &self.registry).await {
+            let candles = self.candles.on_tick(&event, unix_secs());
+            if !self.sink.send(event).await || !self.send_all(candles).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Track a command in this connection's and the client's registry
+    fn apply(&mut self, cmd: &WsCommand) {
+        self.registry.apply(cmd);
+        self.ws.registries.lock().unwrap().apply(self.id, cmd);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.ws.registries.lock().unwrap().close(self.id);
+    }
+}
+
+/// Server message for a subscribe/unsubscribe command
+#[cfg_attr(all(target_arch = "wasm32", not(feature = "wasm")), allow(dead_code))]
+fn command_message(cmd: WsCommand) -> Option<String> {
+    match cmd {
+        WsCommand::SubscribeLtp(instruments) => {
+            create_subscribe_message("subscribe", "ltp", instruments)
+        }
+        WsCommand::UnsubscribeLtp(instruments) => {
+            create_subscribe_message("unsubscribe", "ltp", instruments)
+        }
+        WsCommand::SubscribeQuote(instruments) => {
+            create_subscribe_message("subscribe", "quote", instruments)
+        }
+        WsCommand::UnsubscribeQuote(instruments) => {
+            create_subscribe_message("unsubscribe", "quote", instruments)
+        }
+        WsCommand::SubscribeDepth(instruments) => {
+            create_subscribe_message("subscribe", "depth", instruments)
+        }
+        WsCommand::UnsubscribeDepth(instruments) => {
+            create_subscribe_message("unsubscribe", "depth", instruments)
+        }
+        WsCommand::Disconnect => None,
+    }
+}
+
+/// Delivers data to the consumer according to the backpressure policy
+#[cfg(not(target_arch = "wasm32"))]
+struct DataSink {
+    tx: mpsc::Sender<WsData>,
+    policy: BackpressurePolicy,
+    queue: Arc<DropQueue>,
+    metrics: Arc<WsMetrics>,
+}
+
+/// Bounded queue that evicts its oldest entry, drained by a forwarder task
+#[cfg(not(target_arch = "wasm32"))]
+struct DropQueue {
+    items: Mutex<(VecDeque<WsData>, bool)>,
+    capacity: usize,
+    notify: Notify,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl DataSink {
+    fn new(config: &WsConfig, metrics: Arc<WsMetrics>) -> (Self, mpsc::Receiver<WsData>) {
+        let capacity = config.data_capacity.max(1);
+        let queue = Arc::new(DropQueue {
+            items: Mutex::new((VecDeque::new(), false)),
+            capacity,
+            notify: Notify::new(),
+        });
 
-                if let Some(json) = msg {
-                    let _ = write.send(Message::Text(json)).await;
+        if config.backpressure != BackpressurePolicy::DropOldest {
+            let (tx, rx) = mpsc::channel::<WsData>(capacity);
+            let sink = Self { tx, policy: config.backpressure, queue, metrics };
+            return (sink, rx);
+        }
+
+        // Messages wait in the evicting queue; the channel only holds the next one
+        let (tx, rx) = mpsc::channel::<WsData>(1);
+        let forward_tx = tx.clone();
+        let forward_queue = Arc::clone(&queue);
+        tokio::spawn(async move {
+            loop {
+                let (next, closed) = {
+                    let mut items = forward_queue.items.lock().unwrap();
+                    (items.0.pop_front(), items.1)
+                };
+                match next {
+                    Some(data) => {
+                        if forward_tx.send(data).await.is_err() {
+                            return;
+                        }
+                    }
+                    None if closed => return,
+                    None => forward_queue.notify.notified().await,
                 }
             }
         });
 
-        // Send connected notification
-        let _ = data_tx.send(WsData::Connected).await;
+        let sink = Self { tx, policy: config.backpressure, queue, metrics };
+        (sink, rx)
+    }
 
-        Ok((cmd_tx, data_rx))
+    /// Deliver a message; returns false once the consumer has gone away
+    async fn send(&self, data: WsData) -> bool {
+        match self.policy {
+            BackpressurePolicy::Block => self.tx.send(data).await.is_ok(),
+            BackpressurePolicy::DropNewest if !data.is_market_data() => self.tx.send(data).await.is_ok(),
+            BackpressurePolicy::DropNewest => match self.tx.try_send(data) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    self.metrics.record_dropped();
+                    true
+                }
+                Err(TrySendError::Closed(_)) => false,
+            },
+            BackpressurePolicy::DropOldest => {
+                {
+                    let mut items = self.queue.items.lock().unwrap();
+                    if items.0.len() >= self.queue.capacity {
+                        // Events other than ticks stay queued even beyond the capacity
+                        match items.0.iter().position(WsData::is_market_data) {
+                            Some(oldest) => {
+                                items.0.remove(oldest);
+                                self.metrics.record_dropped();
+                            }
+                            None if data.is_market_data() => {
+                                self.metrics.record_dropped();
+                                return !self.tx.is_closed();
+                            }
+                            None => {}
+                        }
+                    }
+                    items.0.push_back(data);
+                }
+                self.queue.notify.notify_one();
+                !self.tx.is_closed()
+            }
+        }
+    }
+
+    /// Let the forwarder finish once the queue is drained
+    fn close(&self) {
+        self.queue.items.lock().unwrap().1 = true;
+        self.queue.notify.notify_one();
+    }
+}
+
+/// WebSocket subscription mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WsMode {
+    Ltp,
+    Quote,
+    Depth,
+}
+
+impl WsMode {
+    /// Mode name used in subscribe messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WsMode::Ltp => "ltp",
+            WsMode::Quote => "quote",
+            WsMode::Depth => "depth",
+        }
     }
 }
 
 /// WebSocket commands
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum WsCommand {
     SubscribeLtp(Vec<WsInstrument>),
     UnsubscribeLtp(Vec<WsInstrument>),
@@ -158,6 +954,93 @@ fn create_subscribe_message(
     serde_json::to_string(&msg).ok()
 }
 
+/// Parse a JSON message into market data, subscription replies or `Unknown`
+#[cfg_attr(all(target_arch = "wasm32", not(feature = "wasm")), allow(dead_code))]
+fn parse_message(json: serde_json::Value) -> Vec<WsData> {
+    let msg_type = json.get("type").and_then(|t| t.as_str()).map(str::to_string);
+    match msg_type.as_deref() {
+        Some("subscribe") | Some("unsubscribe") => return parse_subscription_reply(&json),
+        Some("error") => {
+            let code = json.get("code").and_then(|c| c.as_str()).map(str::to_string);
+            let message = json_str(&json, "message").unwrap_or_else(|| json.to_string());
+            return vec![WsData::ServerError { code, message }];
+        }
+        _ => {}
+    }
+
+    let mode = json
+        .get("mode")
+        .and_then(|m| m.as_i64().or_else(|| m.as_str()?.trim().parse().ok()));
+    if !matches!(mode, Some(1..=3)) {
+        return vec![WsData::Unknown { msg_type, json }];
+    }
+
+    match serde_json::from_value::<WsMarketDataMessage>(json) {
+        Ok(msg) => vec![parse_market_data(msg)],
+        Err(e) => vec![WsData::Error(format!("Failed to parse market data: {}", e))],
+    }
+}
+
+fn json_str(json: &serde_json::Value, key: &str) -> Option<String> {
+    json.get(key).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+/// Split a subscribe/unsubscribe reply into acknowledged and failed instruments
+///
+/// Failed instruments are grouped by their error message.
+fn parse_subscription_reply(json: &serde_json::Value) -> Vec<WsData> {
+    let action = json_str(json, "type").unwrap_or_default();
+    let status = json_str(json, "status").unwrap_or_default();
+    let message = json_str(json, "message").unwrap_or_default();
+
+    let mut acknowledged = Vec::new();
+    let mut failed: Vec<(String, Vec<WsInstrument>)> = Vec::new();
+    let entries = json
+        .get("subscriptions")
+        .or_else(|| json.get("symbols"))
+        .and_then(|v| v.as_array());
+
+    for entry in entries.into_iter().flatten() {
+        let instrument = WsInstrument::new(
+            &json_str(entry, "exchange").unwrap_or_default(),
+            &json_str(entry, "symbol").unwrap_or_default(),
+        );
+        match json_str(entry, "status").as_deref() {
+            Some("success") | None => acknowledged.push(instrument),
+            Some(_) => {
+                let reason = json_str(entry, "message").unwrap_or_else(|| message.clone());
+                match failed.iter_mut().find(|(m, _)| *m == reason) {
+                    Some((_, instruments)) => instruments.push(instrument),
+                    None => failed.push((reason, vec![instrument])),
+                }
+            }
+        }
+    }
+
+    let mut events = Vec::new();
+    if !acknowledged.is_empty() || (status == "success" && failed.is_empty()) {
+        events.push(WsData::SubscriptionAck {
+            action: action.clone(),
+            instruments: acknowledged,
+        });
+    }
+    for (reason, instruments) in failed {
+        events.push(WsData::SubscriptionFailed {
+            action: action.clone(),
+            instruments,
+            message: reason,
+        });
+    }
+    if events.is_empty() {
+        events.push(WsData::SubscriptionFailed {
+            action,
+            instruments: Vec::new(),
+            message,
+        });
+    }
+    events
+}
+
 fn parse_market_data(msg: WsMarketDataMessage) -> WsData {
     let mode = msg.mode.unwrap_or(0);
 
@@ -193,6 +1076,26 @@ fn parse_market_data(msg: WsMarketDataMessage) -> WsData {
     }
 }
 
+impl WsCommand {
+    /// Subscribe command for a mode
+    pub fn subscribe(mode: WsMode, instruments: Vec<WsInstrument>) -> Self {
+        match mode {
+            WsMode::Ltp => WsCommand::SubscribeLtp(instruments),
+            WsMode::Quote => WsCommand::SubscribeQuote(instruments),
+            WsMode::Depth => WsCommand::SubscribeDepth(instruments),
+        }
+    }
+
+    /// Unsubscribe command for a mode
+    pub fn unsubscribe(mode: WsMode, instruments: Vec<WsInstrument>) -> Self {
+        match mode {
+            WsMode::Ltp => WsCommand::UnsubscribeLtp(instruments),
+            WsMode::Quote => WsCommand::UnsubscribeQuote(instruments),
+            WsMode::Depth => WsCommand::UnsubscribeDepth(instruments),
+        }
+    }
+}
+
 /// Helper struct for easy WebSocket subscriptions
 pub struct WsSubscriber {
     cmd_tx: mpsc::Sender<WsCommand>,
@@ -252,6 +1155,53 @@ impl WsSubscriber {
             .map_err(|e| OpenAlgoError::WebSocketError(e.to_string()))
     }
 
+    /// Subscribe to updates in the given mode
+    pub async fn subscribe(&self, mode: WsMode, instruments: Vec<WsInstrument>) -> Result<(), OpenAlgoError> {
+        self.cmd_tx
+            .send(WsCommand::subscribe(mode, instruments))
+            .await
+            .map_err(|e| OpenAlgoError::WebSocketError(e.to_string()))
+    }
+
+    /// Unsubscribe from updates in the given mode
+    pub async fn unsubscribe(&self, mode: WsMode, instruments: Vec<WsInstrument>) -> Result<(), OpenAlgoError> {
+        self.cmd_tx
+            .send(WsCommand::unsubscribe(mode, instruments))
+            .await
+            .map_err(|e| OpenAlgoError::WebSocketError(e.to_string()))
+    }
+
+    /// Subscribe instruments in different modes in one call
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example(subscriber: openalgo::WsSubscriber) -> Result<(), openalgo::client::OpenAlgoError> {
+    /// use openalgo::{WsInstrument, WsMode};
+    /// subscriber
+    ///     .subscribe_mixed(vec![
+    ///         (WsInstrument::new("NSE", "RELIANCE"), WsMode::Depth),
+    ///         (WsInstrument::new("NSE", "TCS"), WsMode::Ltp),
+    ///         (WsInstrument::new("NSE", "INFY"), WsMode::Ltp),
+    ///     ])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe_mixed(&self, subscriptions: Vec<(WsInstrument, WsMode)>) -> Result<(), OpenAlgoError> {
+        for (mode, instruments) in group_by_mode(subscriptions) {
+            self.subscribe(mode, instruments).await?;
+        }
+        Ok(())
+    }
+
+    /// Unsubscribe instruments from different modes in one call
+    pub async fn unsubscribe_mixed(&self, subscriptions: Vec<(WsInstrument, WsMode)>) -> Result<(), OpenAlgoError> {
+        for (mode, instruments) in group_by_mode(subscriptions) {
+            self.unsubscribe(mode, instruments).await?;
+        }
+        Ok(())
+    }
+
     /// Disconnect from WebSocket
     pub async fn disconnect(&self) -> Result<(), OpenAlgoError> {
         self.cmd_tx
@@ -260,3 +1210,23 @@ impl WsSubscriber {
             .map_err(|e| OpenAlgoError::WebSocketError(e.to_string()))
     }
 }
+
+/// Group (instrument, mode) pairs by mode, keeping first-seen order
+fn group_by_mode(subscriptions: Vec<(WsInstrument, WsMode)>) -> Vec<(WsMode, Vec<WsInstrument>)> {
+    let mut groups: Vec<(WsMode, Vec<WsInstrument>)> = Vec::new();
+    for (instrument, mode) in subscriptions {
+        match groups.iter_mut().find(|(m, _)| *m == mode) {
+            Some((_, instruments)) => instruments.push(instrument),
+            None => groups.push((mode, vec![instrument])),
+        }
+    }
+    groups
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
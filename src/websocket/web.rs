@@ -0,0 +1,256 @@
+//! Browser WebSocket client for `wasm32` builds.
+//!
+//! Same channel API as the native client, backed by the browser's
+//! `WebSocket` through `web-sys`. Proxy, TLS, reconnect and candle settings
+//! do not apply here: the browser owns the socket.
+
+use super::{command_message, parse_message, WsCommand, WsData};
+use crate::client::OpenAlgoError;
+use crate::registry::{ConnectionRegistries, SubscriptionRegistry};
+use crate::secret::SharedApiKey;
+use crate::types::WsAuthMessage;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{CloseEvent, Event, MessageEvent, WebSocket};
+
+/// OpenAlgo WebSocket client for real-time market data in the browser
+///
+/// Messages that arrive while the data channel is full are dropped.
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example(client: openalgo::OpenAlgo) -> Result<(), openalgo::client::OpenAlgoError> {
+/// # fn render(_: openalgo::WsLtpData) {}
+/// use openalgo::{WsData, WsInstrument, WsSubscriber};
+/// let (cmd_tx, mut data_rx) = client.websocket().connect().await?;
+/// WsSubscriber::new(cmd_tx)
+///     .subscribe_ltp(vec![WsInstrument::new("NSE", "RELIANCE")])
+///     .await?;
+/// while let Some(data) = data_rx.recv().await {
+///     if let WsData::Ltp(tick) = data {
+///         render(tick);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct OpenAlgoWebSocket {
+    api_key: Arc<SharedApiKey>,
+    ws_url: String,
+    data_capacity: usize,
+    open: Arc<AtomicUsize>,
+    registries: Arc<Mutex<ConnectionRegistries>>,
+}
+
+/// Socket callbacks, kept alive for as long as the connection is in use
+struct Handlers {
+    _open: Closure<dyn FnMut(Event)>,
+    _error: Closure<dyn FnMut(Event)>,
+    message: Closure<dyn FnMut(MessageEvent)>,
+    close: Closure<dyn FnMut(CloseEvent)>,
+}
+
+/// End-of-session bookkeeping shared by the close handler and the command task
+#[derive(Clone)]
+struct Session {
+    closed: Rc<Cell<bool>>,
+    open: Arc<AtomicUsize>,
+    data_tx: mpsc::Sender<WsData>,
+}
+
+impl Session {
+    /// Mark the socket closed and notify the consumer, once
+    fn close(&self) {
+        if !self.closed.replace(true) {
+            self.open.fetch_sub(1, Ordering::Relaxed);
+            let _ = self.data_tx.try_send(WsData::Disconnected);
+        }
+    }
+}
+
+impl OpenAlgoWebSocket {
+    /// Create a new WebSocket client
+    pub fn new(api_key: &str, ws_url: &str) -> Self {
+        Self::with_shared_key(Arc::new(SharedApiKey::new(api_key)), ws_url)
+    }
+
+    /// WebSocket client following the API key of a REST client
+    pub(crate) fn with_shared_key(api_key: Arc<SharedApiKey>, ws_url: &str) -> Self {
+        Self {
+            api_key,
+            ws_url: ws_url.to_string(),
+            data_capacity: 128,
+            open: Arc::new(AtomicUsize::new(0)),
+            registries: Arc::new(Mutex::new(ConnectionRegistries::default())),
+        }
+    }
+
+    /// Replace the API key used by connections opened from now on
+    ///
+    /// Open browser sockets keep their session; reconnect them to switch.
+    pub fn set_api_key(&self, api_key: &str) {
+        self.api_key.set(api_key);
+    }
+
+    /// Capacity of the data channel
+    pub fn data_capacity(mut self, capacity: usize) -> Self {
+        self.data_capacity = capacity.max(1);
+        self
+    }
+
+    /// Whether any connection opened through this client currently has a live socket
+    pub fn is_connected(&self) -> bool {
+        self.open.load(Ordering::Relaxed) > 0
+    }
+
+    /// Snapshot of the instruments subscribed and their modes
+    ///
+    /// Covers every open connection; an instrument stays listed while any
+    /// of them is subscribed to it.
+    pub fn subscriptions(&self) -> SubscriptionRegistry {
+        self.registries.lock().unwrap().merged()
+    }
+
+    /// Connect to WebSocket server and return channels for communication
+    ///
+    /// Returns a tuple of (sender for commands, receiver for data)
+    pub async fn connect(
+        &self,
+    ) -> Result<
+        (
+            mpsc::Sender<WsCommand>,
+            mpsc::Receiver<WsData>,
+        ),
+        OpenAlgoError,
+    > {
+        let socket = WebSocket::new(&self.ws_url).map_err(js_error)?;
+        let (data_tx, data_rx) = mpsc::channel::<WsData>(self.data_capacity);
+        let (opened_tx, opened_rx) = oneshot::channel::<Result<(), OpenAlgoError>>();
+        let opened_tx = Rc::new(RefCell::new(Some(opened_tx)));
+
+        let on_open = {
+            let opened_tx = Rc::clone(&opened_tx);
+            Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+                if let Some(tx) = opened_tx.borrow_mut().take() {
+                    let _ = tx.send(Ok(()));
+                }
+            })
+        };
+        let on_error = {
+            let data_tx = data_tx.clone();
+            Closure::<dyn FnMut(Event)>::new(move |_: Event| match opened_tx.borrow_mut().take() {
+                Some(tx) => {
+                    let _ = tx.send(Err(OpenAlgoError::WebSocketError("Connection failed".to_string())));
+                }
+                None => {
+                    let _ = data_tx.try_send(WsData::Error("WebSocket error".to_string()));
+                }
+            })
+        };
+        let on_message = {
+            let data_tx = data_tx.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                let Some(text) = event.data().as_string() else {
+                    return;
+                };
+                let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) else {
+                    return;
+                };
+                for data in parse_message(json) {
+                    let _ = data_tx.try_send(data);
+                }
+            })
+        };
+        let session = Session {
+            closed: Rc::new(Cell::new(false)),
+            open: Arc::clone(&self.open),
+            data_tx: data_tx.clone(),
+        };
+        let on_close = {
+            let session = session.clone();
+            Closure::<dyn FnMut(CloseEvent)>::new(move |_: CloseEvent| session.close())
+        };
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        let handlers = Handlers {
+            _open: on_open,
+            _error: on_error,
+            message: on_message,
+            close: on_close,
+        };
+
+        let opened = match opened_rx.await {
+            Ok(result) => result,
+            Err(_) => Err(OpenAlgoError::WebSocketError("Connection closed".to_string())),
+        };
+        if let Err(e) = opened {
+            socket.set_onopen(None);
+            socket.set_onerror(None);
+            let _ = socket.close();
+            return Err(e);
+        }
+        socket.set_onmessage(Some(handlers.message.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(handlers.close.as_ref().unchecked_ref()));
+        self.open.fetch_add(1, Ordering::Relaxed);
+
+        // Send authentication message
+        let auth_msg = WsAuthMessage {
+            action: "authenticate".to_string(),
+            api_key: self.api_key.get(),
+        };
+        socket
+            .send_with_str(&serde_json::to_string(&auth_msg)?)
+            .map_err(js_error)?;
+        tracing::info!(url = %self.ws_url, "WebSocket connected");
+        let _ = data_tx.try_send(WsData::Connected);
+
+        let (cmd_tx, cmd_rx) = mpsc::channel::<WsCommand>(32);
+        let registries = Arc::clone(&self.registries);
+        let id = registries.lock().unwrap().open();
+        wasm_bindgen_futures::spawn_local(run_commands(socket, handlers, session, cmd_rx, registries, id));
+
+        Ok((cmd_tx, data_rx))
+    }
+}
+
+/// Forward commands to the socket until disconnect, then release it
+async fn run_commands(
+    socket: WebSocket,
+    handlers: Handlers,
+    session: Session,
+    mut cmd_rx: mpsc::Receiver<WsCommand>,
+    registries: Arc<Mutex<ConnectionRegistries>>,
+    id: u64,
+) {
+    while let Some(cmd) = cmd_rx.recv().await {
+        let disconnect = matches!(cmd, WsCommand::Disconnect);
+        registries.lock().unwrap().apply(id, &cmd);
+        if let Some(json) = command_message(cmd) {
+            let _ = socket.send_with_str(&json);
+        }
+        if disconnect {
+            break;
+        }
+    }
+
+    // The handlers are released before the browser reports the close
+    registries.lock().unwrap().close(id);
+    session.close();
+    socket.set_onopen(None);
+    socket.set_onerror(None);
+    socket.set_onmessage(None);
+    socket.set_onclose(None);
+    let _ = socket.close();
+    drop(handlers);
+    tracing::info!("WebSocket closed");
+}
+
+fn js_error(e: wasm_bindgen::JsValue) -> OpenAlgoError {
+    OpenAlgoError::WebSocketError(format!("{:?}", e))
+}
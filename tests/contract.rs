@@ -0,0 +1,98 @@
+//! Contract tests against recorded server responses.
+//!
+//! Every file in `tests/fixtures` is a response body returned by an OpenAlgo
+//! server, named after its endpoint (`/` becomes `_`, a `-2` suffix marks a
+//! second example). Each must deserialize into the SDK's response type and
+//! survive a serialize/deserialize round trip, and fields the SDK does not
+//! model yet fail the test.
+
+use openalgo::types::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::fs;
+use std::path::PathBuf;
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures")
+}
+
+fn check<T: DeserializeOwned + Serialize + PartialEq + Debug>(name: &str) -> T {
+    let path = fixtures_dir().join(format!("{}.json", name));
+    let text = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    let response: T = openalgo::de::from_str_strict(&text).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    let again: T = serde_json::from_value(serde_json::to_value(&response).unwrap())
+        .unwrap_or_else(|e| panic!("{}: round trip: {}", path.display(), e));
+    assert_eq!(again, response, "{}: round trip changed the response", path.display());
+    response
+}
+
+macro_rules! contracts {
+    ($($test:ident: $fixture:literal => $ty:ty),* $(,)?) => {
+        const FIXTURES: &[&str] = &[$($fixture),*];
+
+        $(
+            #[test]
+            fn $test() {
+                let response: $ty = check($fixture);
+                assert_eq!(response.status, "success");
+            }
+        )*
+    };
+}
+
+contracts! {
+    placeorder: "placeorder" => OrderResponse,
+    placesmartorder: "placesmartorder" => OrderResponse,
+    optionsorder: "optionsorder" => OptionsOrderResponse,
+    optionsorder_2: "optionsorder-2" => OptionsOrderResponse,
+    optionsmultiorder: "optionsmultiorder" => OptionsMultiOrderResponse,
+    basketorder: "basketorder" => BasketOrderResponse,
+    splitorder: "splitorder" => SplitOrderResponse,
+    modifyorder: "modifyorder" => OrderResponse,
+    cancelorder: "cancelorder" => OrderResponse,
+    cancelallorder: "cancelallorder" => CancelAllOrderResponse,
+    closeposition: "closeposition" => StatusResponse,
+    orderstatus: "orderstatus" => OrderStatusResponse,
+    openposition: "openposition" => OpenPositionResponse,
+    quotes: "quotes" => QuotesResponse,
+    multiquotes: "multiquotes" => MultiQuotesResponse,
+    depth: "depth" => DepthResponse,
+    history: "history" => HistoryResponse,
+    intervals: "intervals" => IntervalsResponse,
+    optionchain: "optionchain" => OptionChainResponse,
+    symbol: "symbol" => SymbolResponse,
+    search: "search" => SearchResponse,
+    optionsymbol: "optionsymbol" => OptionSymbolResponse,
+    optionsymbol_2: "optionsymbol-2" => OptionSymbolResponse,
+    optionsymbol_3: "optionsymbol-3" => OptionSymbolResponse,
+    syntheticfuture: "syntheticfuture" => SyntheticFutureResponse,
+    optiongreeks: "optiongreeks" => OptionGreeksResponse,
+    expiry: "expiry" => ExpiryResponse,
+    instruments: "instruments" => InstrumentsResponse,
+    telegram_notify: "telegram_notify" => TelegramResponse,
+    funds: "funds" => FundsResponse,
+    margin: "margin" => MarginResponse,
+    orderbook: "orderbook" => OrderbookResponse,
+    tradebook: "tradebook" => TradebookResponse,
+    positionbook: "positionbook" => PositionbookResponse,
+    holdings: "holdings" => HoldingsResponse,
+    market_holidays: "market_holidays" => HolidaysResponse,
+    market_timings: "market_timings" => TimingsResponse,
+    analyzer: "analyzer" => AnalyzerStatusResponse,
+    analyzer_toggle: "analyzer_toggle" => AnalyzerToggleResponse,
+}
+
+#[test]
+fn every_fixture_has_a_contract() {
+    let files: BTreeSet<String> = fs::read_dir(fixtures_dir())
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.unwrap().path();
+            (path.extension()? == "json").then(|| path.file_stem()?.to_str().map(str::to_string))?
+        })
+        .collect();
+    let covered: BTreeSet<String> = FIXTURES.iter().map(|f| f.to_string()).collect();
+    assert_eq!(files, covered, "fixtures without a contract test, or contract tests without a fixture");
+}
@@ -0,0 +1,47 @@
+//! HTTP requests against a running `ControlServer`.
+
+use openalgo::control::{ControlServer, StrategyStatus};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Send a raw request and return the status code of the response
+async fn status(address: SocketAddr, request: &str) -> u16 {
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response.split_whitespace().nth(1).unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn refuses_foreign_origins_and_wrong_tokens() {
+    let server = ControlServer::new().auth_token("secret").allow_origin("http://localhost:3000");
+    let strategy = server.register("Momentum");
+    let (address, _handle) = server.start("127.0.0.1:0").await.unwrap();
+
+    let pause = "POST /strategies/Momentum/pause HTTP/1.1\r\nHost: x\r\n";
+    let from_page = format!("{}Authorization: Bearer secret\r\nOrigin: https://evil.example\r\n\r\n", pause);
+    assert_eq!(status(address, &from_page).await, 403);
+    assert_eq!(status(address, &format!("{}Authorization: Bearer secreT\r\n\r\n", pause)).await, 401);
+    assert_eq!(status(address, &format!("{}\r\n", pause)).await, 401);
+    assert_eq!(strategy.status(), StrategyStatus::Running);
+
+    let allowed = format!("{}Authorization: Bearer secret\r\nOrigin: http://localhost:3000\r\n\r\n", pause);
+    assert_eq!(status(address, &allowed).await, 200);
+    assert_eq!(strategy.status(), StrategyStatus::Paused);
+}
+
+#[tokio::test(start_paused = true)]
+async fn drops_clients_that_never_finish_their_request() {
+    let server = ControlServer::new();
+    let (address, _handle) = server.start("127.0.0.1:0").await.unwrap();
+
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    stream.write_all(b"GET /strategies HTTP/1.1\r\nHost: x\r\n").await.unwrap();
+    let mut response = Vec::new();
+    let read = tokio::time::timeout(Duration::from_secs(60), stream.read_to_end(&mut response)).await;
+    assert!(read.is_ok(), "connection still open");
+    assert!(response.is_empty());
+}
@@ -0,0 +1,878 @@
+//! End-to-end tests against `TestServer`: retries, error mapping, timeouts,
+//! fixtures and WebSocket reconnects over real loopback connections.
+
+use openalgo::circuit::{CircuitEvent, LossCircuit};
+use openalgo::health::{CancelOnDisconnect, HealthConfig, HealthState, WatchdogEvent};
+use openalgo::reconnect::ReconnectPolicy;
+use openalgo::retry::{OrderRecovery, RetryPolicy};
+use openalgo::supervisor::{CrashAction, RestartPolicy, Supervisor, SupervisorEvent};
+use openalgo::test_server::{Recorder, Reply, TestServer};
+use openalgo::transport::ReqwestTransport;
+use openalgo::{BackpressurePolicy, PlaceOrderRequest, WsConfig, WsCommand, WsData, WsInstrument, WsMode};
+use serde_json::json;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+const WAIT: Duration = Duration::from_secs(5);
+
+fn fast_retry(attempts: u32) -> RetryPolicy {
+    RetryPolicy::new(attempts).backoff(Duration::from_millis(1), Duration::from_millis(1))
+}
+
+/// Next message that is not an auth or subscription reply
+async fn next_data(rx: &mut mpsc::Receiver<WsData>) -> WsData {
+    loop {
+        let data = tokio::time::timeout(WAIT, rx.recv()).await.expect("timed out").expect("channel closed");
+        if !matches!(data, WsData::Unknown { .. } | WsData::SubscriptionAck { .. }) {
+            return data;
+        }
+    }
+}
+
+/// Next event of a `CancelOnDisconnect` watchdog
+async fn next_watchdog_event(rx: &mut mpsc::Receiver<WatchdogEvent>) -> WatchdogEvent {
+    tokio::time::timeout(WAIT, rx.recv()).await.expect("timed out").expect("watchdog stopped")
+}
+
+#[tokio::test]
+async fn retries_gateway_errors_until_success() {
+    let server = TestServer::start().await;
+    server.respond_once("funds", Reply::error(503, "unavailable"));
+    server.respond_once("funds", Reply::error(502, "bad gateway"));
+    server.respond("funds", json!({"status": "success", "data": {"availablecash": "1000.00"}}));
+
+    let client = server.builder().retry(fast_retry(3)).build();
+    let funds = client.funds().await.unwrap();
+    assert_eq!(funds.status, "success");
+    assert_eq!(server.requests("funds").len(), 3);
+}
+
+#[tokio::test]
+async fn orders_are_not_retried() {
+    let server = TestServer::start().await;
+    server.respond("placeorder", Reply::error(503, "unavailable"));
+
+    let client = server.builder().retry(fast_retry(3)).build();
+    let result = client.place_order("test", "SBIN", "BUY", "NSE", "MARKET", "MIS", "1").await;
+    assert!(result.is_err());
+    let sent = server.requests("placeorder");
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0]["apikey"], "test-api-key");
+    assert_eq!(sent[0]["symbol"], "SBIN");
+}
+
+#[tokio::test]
+async fn error_bodies_become_api_errors() {
+    let server = TestServer::start().await;
+    server.respond("quotes", Reply::error(400, "Invalid symbol"));
+
+    let error = server.client().quotes("NOPE", "NSE").await.unwrap_err();
+    let details = error.api_details().expect("API error");
+    assert_eq!(details.http_status, Some(400));
+    assert_eq!(details.message, "Invalid symbol");
+}
+
+#[tokio::test]
+async fn unconfigured_endpoints_return_404() {
+    let server = TestServer::start().await;
+    let error = server.client().holdings().await.unwrap_err();
+    assert_eq!(error.api_details().and_then(|d| d.http_status), Some(404));
+}
+
+#[tokio::test]
+async fn strict_clients_reject_unknown_fields() {
+    let server = TestServer::start().await;
+    server.respond("funds", json!({"status": "success", "data": {"availablecash": "1000.00", "collateral_v2": "0"}}));
+
+    let funds = server.client().funds().await.unwrap();
+    assert!(funds.data.unwrap().extra.contains_key("collateral_v2"));
+    let error = server.builder().strict(true).build().funds().await.unwrap_err();
+    assert!(error.to_string().contains("collateral_v2"), "{}", error);
+}
+
+#[tokio::test]
+async fn splits_orders_at_the_freeze_quantity() {
+    let server = TestServer::start().await;
+    server.respond(
+        "symbol",
+        json!({"status": "success", "data": {"symbol": "NIFTY30DEC25FUT", "exchange": "NFO", "lotsize": 75, "freeze_qty": 1800}}),
+    );
+    server.respond("placeorder", json!({"status": "success", "orderid": "1"}));
+
+    let client = server.client();
+    assert_eq!(client.split_by_freeze_qty("NIFTY30DEC25FUT", "NFO", 3900).await.unwrap(), vec![1800, 1800, 300]);
+    assert!(client.split_by_freeze_qty("NIFTY30DEC25FUT", "NFO", 100).await.is_err());
+
+    let results = client
+        .place_sliced_order("test", "NIFTY30DEC25FUT", "BUY", "NFO", "MARKET", "NRML", 3900)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    let sent: Vec<_> = server.requests("placeorder").iter().map(|r| r["quantity"].clone()).collect();
+    assert_eq!(sent, vec![json!("1800"), json!("1800"), json!("300")]);
+    assert_eq!(server.requests("symbol").len(), 1);
+}
+
+#[tokio::test]
+async fn validates_tick_and_lot_before_sending() {
+    use openalgo::client::OpenAlgoError;
+    use openalgo::validation::ValidationError;
+
+    let server = TestServer::start().await;
+    server.respond(
+        "symbol",
+        json!({"status": "success", "data": {"symbol": "NIFTY30DEC25FUT", "exchange": "NFO", "lotsize": 75, "tick_size": 0.05}}),
+    );
+    server.respond("placeorder", json!({"status": "success", "orderid": "1"}));
+    let client = server.builder().validate_orders(true).build();
+
+    let error = client
+        .place_limit_order("test", "NIFTY30DEC25FUT", "BUY", "NFO", "NRML", "75", "24150.03")
+        .await
+        .unwrap_err();
+    match error.inner() {
+        OpenAlgoError::ValidationError(ValidationError::InvalidTick { below, above, .. }) => {
+            assert_eq!(below.to_string(), "24150");
+            assert_eq!(above.to_string(), "24150.05");
+        }
+        other => panic!("expected a tick error, got {:?}", other),
+    }
+    let error = client
+        .place_order("test", "NIFTY30DEC25FUT", "BUY", "NFO", "MARKET", "NRML", "100")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.inner(),
+        OpenAlgoError::ValidationError(ValidationError::InvalidLot { below: 75, above: 150, .. })
+    ));
+    assert!(server.requests("placeorder").is_empty());
+
+    client
+        .place_limit_order("test", "NIFTY30DEC25FUT", "BUY", "NFO", "NRML", "150", "24150.05")
+        .await
+        .unwrap();
+    assert_eq!(server.requests("placeorder").len(), 1);
+    assert_eq!(server.requests("symbol").len(), 1);
+}
+
+#[tokio::test]
+async fn guards_cnc_sells_against_holdings() {
+    use openalgo::client::OpenAlgoError;
+    use openalgo::validation::ValidationError;
+
+    let server = TestServer::start().await;
+    server.respond(
+        "holdings",
+        json!({"status": "success", "data": {"holdings": [{"symbol": "TCS", "exchange": "NSE", "product": "CNC", "quantity": 10}]}}),
+    );
+    server.respond(
+        "positionbook",
+        json!({"status": "success", "data": [{"symbol": "TCS", "exchange": "NSE", "product": "CNC", "quantity": "-4"}]}),
+    );
+    server.respond("placeorder", json!({"status": "success", "orderid": "1"}));
+    let client = server.builder().guard_cnc_sells(true).build();
+
+    let error = client.place_order("test", "TCS", "SELL", "NSE", "MARKET", "CNC", "7").await.unwrap_err();
+    assert!(matches!(
+        error.inner(),
+        OpenAlgoError::ValidationError(ValidationError::InsufficientHoldings { quantity: 7, sellable: 6 })
+    ));
+    assert!(server.requests("placeorder").is_empty());
+
+    client.place_order("test", "TCS", "SELL", "NSE", "MARKET", "CNC", "6").await.unwrap();
+    client.place_order("test", "TCS", "SELL", "NSE", "MARKET", "MIS", "50").await.unwrap();
+    assert_eq!(server.requests("placeorder").len(), 2);
+    assert_eq!(server.requests("holdings").len(), 2);
+}
+
+#[tokio::test]
+async fn smart_orders_skip_when_cached_position_matches() {
+    let server = TestServer::start().await;
+    server.respond(
+        "positionbook",
+        json!({"status": "success", "data": [{"symbol": "SBIN", "exchange": "NSE", "product": "MIS", "quantity": "10"}]}),
+    );
+    server.respond("placesmartorder", json!({"status": "success", "orderid": "1"}));
+    let client = server.builder().smart_order_position_cache(Duration::from_secs(60)).build();
+
+    let changes = client.refresh_positions().await.unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!((changes[0].previous, changes[0].quantity), (0, 10));
+    assert_eq!(client.cached_position("SBIN", "NSE", "MIS", WAIT), Some(10));
+    assert_eq!(client.cached_position("INFY", "NSE", "MIS", WAIT), Some(0));
+
+    let skipped = client.place_smart_order("test", "SBIN", "BUY", "NSE", "MARKET", "MIS", "10", "10").await.unwrap();
+    assert_eq!(skipped.orderid, None);
+    assert!(server.requests("placesmartorder").is_empty());
+
+    client.place_smart_order("test", "SBIN", "BUY", "NSE", "MARKET", "MIS", "5", "15").await.unwrap();
+    assert_eq!(server.requests("placesmartorder").len(), 1);
+    // The order made the cached position stale
+    assert_eq!(client.cached_position("SBIN", "NSE", "MIS", WAIT), None);
+    assert!(client.refresh_positions().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn open_position_quantity_is_typed_and_cached() {
+    let server = TestServer::start().await;
+    server.respond("openposition", json!({"status": "success", "quantity": "-25"}));
+    let client = server.client();
+    assert_eq!(client.open_position_quantity("test", "SBIN", "NSE", "MIS").await.unwrap(), -25);
+    assert_eq!(client.cached_position("SBIN", "NSE", "MIS", WAIT), Some(-25));
+}
+
+#[tokio::test]
+async fn sends_ioc_validity_and_rejects_it_for_stop_orders() {
+    use openalgo::{PlaceOrderRequest, Validity};
+
+    let server = TestServer::start().await;
+    server.respond("placeorder", json!({"status": "success", "orderid": "1"}));
+    let client = server.client();
+
+    let sweep = PlaceOrderRequest::new("test", "SBIN", "BUY", "NSE", "LIMIT", "MIS", "500")
+        .price("801")
+        .validity(Validity::Ioc);
+    client.place_order_request(sweep).await.unwrap();
+    let stop = PlaceOrderRequest::new("test", "SBIN", "SELL", "NSE", "SL-M", "MIS", "500")
+        .trigger_price("790")
+        .validity(Validity::Ioc);
+    assert!(client.place_order_request(stop).await.is_err());
+
+    let sent = server.requests("placeorder");
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0]["validity"], "IOC");
+    assert_eq!(sent[0]["price"], "801");
+}
+
+#[tokio::test]
+async fn amo_flag_and_queued_orders() {
+    use openalgo::calendar::MarketCalendar;
+    use openalgo::scheduler::OrderQueue;
+    use openalgo::PlaceOrderRequest;
+    use std::time::Duration;
+
+    let server = TestServer::start().await;
+    server.respond("placeorder", json!({"status": "success", "orderid": "1"}));
+    let client = server.client();
+
+    client.place_amo_order("test", "SBIN", "BUY", "NSE", "LIMIT", "CNC", "1", Some("790")).await.unwrap();
+    assert_eq!(server.requests("placeorder")[0]["amo"], true);
+
+    let path = std::env::temp_dir().join(format!("openalgo-queue-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let queue = OrderQueue::new(MarketCalendar::nse()).persist(&path).unwrap();
+    queue
+        .submit(PlaceOrderRequest::new("test", "SBIN", "BUY", "NSE", "MARKET", "MIS", "1").amo(true))
+        .unwrap();
+
+    // Reloaded after a restart, and placed 5 minutes before the 09:15 open
+    let queue = OrderQueue::new(MarketCalendar::nse())
+        .pre_open(Duration::from_secs(5 * 60))
+        .persist(&path)
+        .unwrap();
+    assert_eq!(queue.pending().len(), 1);
+    // Sunday 2024-12-01 10:00 IST, then Monday 2024-12-02 09:05 and 09:12 IST
+    assert!(queue.place_due(&client, 1_733_027_400).await.is_empty());
+    assert!(queue.place_due(&client, 1_733_110_500).await.is_empty());
+    let placed = queue.place_due(&client, 1_733_110_920).await;
+    assert_eq!(placed.len(), 1);
+    assert!(placed[0].result.is_ok());
+    assert!(queue.pending().is_empty());
+    assert!(OrderQueue::new(MarketCalendar::nse()).persist(&path).unwrap().pending().is_empty());
+    std::fs::remove_file(&path).unwrap();
+
+    let sent = server.requests("placeorder");
+    assert_eq!(sent.len(), 2);
+    assert!(sent[1].get("amo").is_none());
+    assert_eq!(sent[1]["apikey"], sent[0]["apikey"]);
+}
+
+#[tokio::test]
+async fn warm_up_fetches_enough_history_and_keeps_the_latest_bars() {
+    let server = TestServer::start().await;
+    // 2024-12-02 09:15 to 10:00 IST, long complete by now
+    let candles: Vec<_> = (0..10)
+        .map(|i| json!({"timestamp": 1_733_111_100 + i * 300, "open": 800 + i, "high": 801 + i, "low": 799 + i, "close": 800 + i, "volume": 1000}))
+        .collect();
+    server.respond("history", json!({"status": "success", "data": candles}));
+    let client = server.client();
+
+    let mut series = client.warm_up("SBIN", "NSE", "5m", 4).await.unwrap();
+    let sent = &server.requests("history")[0];
+    assert_eq!(sent["interval"], "5m");
+    assert!(sent["start_date"].as_str().unwrap() < sent["end_date"].as_str().unwrap());
+    let timestamps: Vec<i64> = series.candles().iter().map(|c| c.timestamp).collect();
+    assert_eq!(timestamps, [1_733_112_900, 1_733_113_200, 1_733_113_500, 1_733_113_800]);
+    assert!(series.current().is_none());
+
+    // Live bars continue after the history
+    let now = 1_733_113_800 + 3_600;
+    assert!(series.update(now, openalgo::price::from_f64(812.0), None).is_none());
+    assert!(series.flush(now + 300).is_some());
+    assert_eq!(series.candles().len(), 5);
+
+    assert!(client.warm_up("SBIN", "NSE", "D", 4).await.is_err());
+}
+
+#[tokio::test]
+async fn basis_report_keeps_expiries_that_could_be_priced() {
+    let server = TestServer::start().await;
+    server.respond("expiry", json!({"status": "success", "data": ["25-DEC-25", "29-JAN-26"]}));
+    server.respond_once("syntheticfuture", Reply::error(500, "no option chain"));
+    server.respond("syntheticfuture", json!({"status": "success", "synthetic_future_price": 26000.0, "underlying_ltp": 25950.0}));
+    server.respond("quotes", json!({"status": "success", "data": {"ltp": 26130.0}}));
+
+    let report = server.client().basis_report("NIFTY", "NSE_INDEX").await.unwrap();
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].expiry_date, "29JAN26");
+    assert_eq!(report.entries[0].basis(), Some(130.0));
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.errors[0].starts_with("25DEC25: "), "{}", report.errors[0]);
+}
+
+#[tokio::test]
+async fn shadow_orders_compare_live_fills_with_paper() {
+    use openalgo::shadow::ShadowExecutor;
+
+    let server = TestServer::start().await;
+    server.respond("quotes", json!({"status": "success", "data": {"ltp": 800.0, "bid": 799.9, "ask": 800.1}}));
+    server.respond(
+        "depth",
+        json!({"status": "success", "data": {
+            "bids": [{"price": 799.9, "quantity": 500}],
+            "asks": [{"price": 800.1, "quantity": 60}, {"price": 800.2, "quantity": 100}],
+        }}),
+    );
+    server.respond("placeorder", json!({"status": "success", "orderid": "7"}));
+    server.respond(
+        "orderstatus",
+        json!({"status": "success", "data": {"orderid": "7", "order_status": "complete", "average_price": 800.3}}),
+    );
+    let client = server.client();
+
+    let shadow = ShadowExecutor::new();
+    shadow
+        .place_order(&client, "S", "SBIN", "BUY", "NSE", "MARKET", "MIS", "100", &[("signal", "orb")])
+        .await
+        .unwrap();
+    let paper = shadow.paper_fill("7").unwrap();
+    assert_eq!(paper.quantity, 100);
+    // 60 at 800.1 and 40 at 800.2
+    assert!((paper.average_price.unwrap() - 800.14).abs() < 1e-9);
+    assert_eq!(shadow.journal().get("7").unwrap().tag("signal"), Some("orb"));
+
+    shadow.refresh(&client).await;
+    let report = shadow.report();
+    assert_eq!(report.orders[0].live_price, Some(800.3));
+    assert!((report.total.divergence_cost - 16.0).abs() < 1e-6);
+    assert_eq!(report.total.fill_mismatches, 0);
+    assert!(report.to_csv().lines().nth(1).unwrap().starts_with("S,SBIN,1,1,1,0,"));
+}
+
+#[tokio::test]
+async fn order_recovery_checks_the_orderbook_before_retrying() {
+    let server = TestServer::start().await;
+    let recovery = OrderRecovery::new(2).settle(Duration::ZERO);
+    let request = PlaceOrderRequest::new("S", "SBIN", "BUY", "NSE", "MARKET", "MIS", "10");
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let sbin = |orderid: &str, timestamp: &str| {
+        json!({"orderid": orderid, "symbol": "SBIN", "exchange": "NSE", "action": "BUY", "product": "MIS",
+               "pricetype": "MARKET", "quantity": "10", "order_status": "open", "timestamp": timestamp})
+    };
+    let earlier = sbin("70", &now.to_string());
+
+    // The order reached the broker although the request failed: no second order
+    server.respond("placeorder", Reply::error(503, "gateway timeout"));
+    server.respond_once("orderbook", json!({"status": "success", "data": {"orders": [earlier.clone()]}}));
+    server.respond("orderbook", json!({"status": "success", "data": {"orders": [earlier.clone(), sbin("77", &now.to_string())]}}));
+    let response = server.client().place_order_recovering(request.clone(), &recovery).await.unwrap();
+    assert_eq!(response.orderid.as_deref(), Some("77"));
+    assert_eq!(response.message, None);
+    assert_eq!(server.requests("placeorder").len(), 1);
+
+    // Only the identical order from before the first attempt: this one is resent
+    server.reset();
+    server.respond_once("placeorder", Reply::error(503, "gateway timeout"));
+    server.respond("placeorder", json!({"status": "success", "orderid": "78"}));
+    server.respond("orderbook", json!({"status": "success", "data": {"orders": [earlier.clone()]}}));
+    let response = server.client().place_order_recovering(request.clone(), &recovery).await.unwrap();
+    assert_eq!(response.orderid.as_deref(), Some("78"));
+    assert_eq!(server.requests("placeorder").len(), 2);
+
+    // A new order whose timestamp cannot be read is not taken as ours
+    server.reset();
+    server.respond_once("placeorder", Reply::error(503, "gateway timeout"));
+    server.respond("placeorder", json!({"status": "success", "orderid": "80"}));
+    server.respond_once("orderbook", json!({"status": "success", "data": {"orders": []}}));
+    server.respond("orderbook", json!({"status": "success", "data": {"orders": [sbin("79", "sometime today")]}}));
+    let response = server.client().place_order_recovering(request.clone(), &recovery).await.unwrap();
+    assert_eq!(response.orderid.as_deref(), Some("80"));
+    assert_eq!(server.requests("placeorder").len(), 2);
+
+    // Without the orderbook before the first attempt the order is not retried
+    server.reset();
+    server.respond("placeorder", Reply::error(503, "gateway timeout"));
+    server.respond("orderbook", Reply::error(500, "down"));
+    assert!(server.client().place_order_recovering(request.clone(), &recovery).await.is_err());
+    assert_eq!(server.requests("placeorder").len(), 1);
+
+    // Rejections are not retried
+    server.reset();
+    server.respond("placeorder", Reply::error(400, "insufficient funds"));
+    assert!(server.client().place_order_recovering(request, &recovery).await.is_err());
+    assert_eq!(server.requests("placeorder").len(), 1);
+    assert_eq!(server.requests("orderbook").len(), 1);
+}
+
+#[tokio::test]
+async fn loss_circuit_prices_unmarked_positions_and_trips_when_blind() {
+    let server = TestServer::start().await;
+    let client = server.client();
+    let trade = json!({"action": "BUY", "symbol": "SBIN", "exchange": "NSE", "quantity": 100, "average_price": 800.0});
+    server.respond("tradebook", json!({"status": "success", "data": [trade]}));
+    server.respond("positionbook", json!({"status": "success", "data": [{"symbol": "SBIN", "exchange": "NSE"}]}));
+    server.respond("quotes", json!({"status": "success", "data": {"ltp": 790.0}}));
+    let pnl = openalgo::circuit::current_pnl(&client).await.unwrap();
+    assert_eq!(pnl.total, -1000.0);
+    assert!(pnl.unpriced.is_empty());
+
+    server.respond("tradebook", Reply::error(500, "down"));
+    server.respond("cancelallorder", json!({"status": "success"}));
+    server.respond("closeposition", json!({"status": "success"}));
+    let (mut events, _handle) = LossCircuit::new(50_000.0)
+        .interval(Duration::from_millis(10))
+        .max_failed_polls(2)
+        .start(&client);
+    let first = tokio::time::timeout(WAIT, events.recv()).await.unwrap().unwrap();
+    assert!(matches!(first, CircuitEvent::PollFailed { failures: 1, .. }));
+    let second = tokio::time::timeout(WAIT, events.recv()).await.unwrap().unwrap();
+    assert!(matches!(second, CircuitEvent::Tripped { .. }));
+    assert!(client.trading_halt().unwrap().reason.contains("could not be read"));
+    assert_eq!(server.requests("cancelallorder").len(), 1);
+}
+
+#[tokio::test]
+async fn loss_circuit_trips_when_nobody_reads_its_events() {
+    let server = TestServer::start().await;
+    let trade = json!({"action": "BUY", "symbol": "SBIN", "exchange": "NSE", "quantity": 100, "average_price": 800.0});
+    server.respond("tradebook", json!({"status": "success", "data": [trade]}));
+    server.respond("positionbook", json!({"status": "success", "data": [{"symbol": "SBIN", "exchange": "NSE", "ltp": "800"}]}));
+    server.respond("cancelallorder", json!({"status": "success"}));
+    server.respond("closeposition", json!({"status": "success"}));
+    let circuit = LossCircuit::new(5_000.0).interval(Duration::from_millis(1));
+
+    // A receiver that is kept but never read fills up with P&L events
+    let unread_client = server.client();
+    let (_unread, unread) = circuit.start(&unread_client);
+    // A dropped receiver
+    let dropped_client = server.client();
+    let (events, dropped) = circuit.start(&dropped_client);
+    drop(events);
+
+    let filled = async {
+        while server.requests("tradebook").len() < 80 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    };
+    tokio::time::timeout(WAIT, filled).await.unwrap();
+    server.respond("positionbook", json!({"status": "success", "data": [{"symbol": "SBIN", "exchange": "NSE", "ltp": "700"}]}));
+
+    tokio::time::timeout(WAIT, unread).await.unwrap().unwrap();
+    tokio::time::timeout(WAIT, dropped).await.unwrap().unwrap();
+    assert!(unread_client.trading_halt().is_some());
+    assert!(dropped_client.trading_halt().is_some());
+    assert_eq!(server.requests("cancelallorder").len(), 2);
+}
+
+#[tokio::test]
+async fn cancels_orders_once_the_connection_returns_and_after_a_panic() {
+    let server = TestServer::start().await;
+    server.respond("ping", Reply::error(503, "unavailable"));
+    server.respond("cancelallorder", json!({"status": "success", "canceled_orders": ["11", "12"]}));
+    let health = HealthConfig::default().interval(Duration::from_millis(100)).failures_before_down(2);
+    let (mut events, watchdog) = CancelOnDisconnect::new(&["Momentum"]).health(health).start(&server.client());
+
+    // Armed while down, cancelled only once the API answers again
+    loop {
+        if let WatchdogEvent::Armed { reason } = next_watchdog_event(&mut events).await {
+            assert!(reason.starts_with("connection down"));
+            break;
+        }
+    }
+    assert!(server.requests("cancelallorder").is_empty());
+    server.respond("ping", json!({"status": "success", "data": {"message": "pong"}}));
+    loop {
+        if let WatchdogEvent::Cancelled { canceled_orders, errors, .. } = next_watchdog_event(&mut events).await {
+            assert_eq!((canceled_orders.len(), errors.len()), (2, 0));
+            break;
+        }
+    }
+    assert_eq!(server.requests("cancelallorder")[0]["strategy"], "Momentum");
+
+    let task = watchdog.watch(tokio::spawn(async { panic!("strategy bug") }));
+    assert!(task.await.unwrap().unwrap_err().is_panic());
+    loop {
+        if let WatchdogEvent::Cancelled { reason, .. } = next_watchdog_event(&mut events).await {
+            assert!(reason.contains("panicked"));
+            break;
+        }
+    }
+    assert_eq!(server.requests("cancelallorder").len(), 2);
+}
+
+#[tokio::test]
+async fn cancels_once_a_slow_probe_gets_through() {
+    let server = TestServer::start().await;
+    server.respond("ping", Reply::error(503, "unavailable"));
+    server.respond("cancelallorder", json!({"status": "success", "canceled_orders": ["11"]}));
+    let health = HealthConfig::default()
+        .interval(Duration::from_millis(100))
+        .slow_threshold(Duration::from_millis(10))
+        .failures_before_down(100);
+    let (mut events, watchdog) = CancelOnDisconnect::new(&["Momentum"]).health(health).start(&server.client());
+    loop {
+        if let WatchdogEvent::Health(event) = next_watchdog_event(&mut events).await {
+            assert_eq!(event.state, HealthState::Degraded);
+            break;
+        }
+    }
+    watchdog.trigger("feed lost");
+
+    // Slow but successful: still `Degraded`, so no health event, yet the API is reachable
+    server.respond("ping", Reply::json(json!({"status": "success", "data": {"message": "pong"}})).delay(Duration::from_millis(50)));
+    loop {
+        match next_watchdog_event(&mut events).await {
+            WatchdogEvent::Cancelled { reason, .. } => {
+                assert_eq!(reason, "feed lost");
+                break;
+            }
+            WatchdogEvent::Health(event) => panic!("unexpected health change {:?}", event),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn watchdog_idles_once_its_handle_is_dropped() {
+    let (done_tx, done_rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .start_paused(true)
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let server = TestServer::start().await;
+            server.respond("ping", json!({"status": "success", "data": {"message": "pong"}}));
+            let (_events, watchdog) = CancelOnDisconnect::new(&["Momentum"]).start(&server.client());
+            drop(watchdog);
+            // The paused clock only moves on while every task waits, so a spinning watchdog never lets this end
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+        let _ = done_tx.send(());
+    });
+    assert!(
+        done_rx.recv_timeout(Duration::from_secs(10)).is_ok(),
+        "watchdog kept running after its handle was dropped"
+    );
+}
+
+#[tokio::test]
+async fn supervisor_keeps_restarting_when_its_events_are_not_read() {
+    let server = TestServer::start().await;
+    let runs = Arc::new(AtomicU32::new(0));
+    let strategy_runs = Arc::clone(&runs);
+    // Each crash queues two events, so the buffer of 32 fills after 16 runs
+    let (_unread, handle) = Supervisor::new("Momentum")
+        .restart(RestartPolicy::limited(40).backoff(Duration::from_millis(1), Duration::from_millis(1)))
+        .start(&server.client(), move || {
+            strategy_runs.fetch_add(1, Ordering::SeqCst);
+            async { Err("feed lost") }
+        });
+    tokio::time::timeout(WAIT, handle).await.expect("supervisor stalled").unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 41);
+}
+
+#[tokio::test]
+async fn supervisor_flattens_and_restarts_crashed_strategies() {
+    let server = TestServer::start().await;
+    server.respond("cancelallorder", json!({"status": "success", "canceled_orders": []}));
+    server.respond("closeposition", json!({"status": "success"}));
+    server.respond("telegram/send", Reply::error(500, "bot offline"));
+
+    // Panics on the first two runs, then finishes
+    let runs = Arc::new(AtomicU32::new(0));
+    let strategy_runs = Arc::clone(&runs);
+    let (mut events, handle) = Supervisor::new("Momentum")
+        .restart(RestartPolicy::limited(3).backoff(Duration::from_millis(1), Duration::from_millis(1)))
+        .on_crash(CrashAction::Flatten)
+        .notify_telegram("trader")
+        .start(&server.client(), move || {
+            let run = strategy_runs.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if run < 2 {
+                    panic!("bad tick {}", run);
+                }
+                Ok::<(), String>(())
+            }
+        });
+    tokio::time::timeout(WAIT, handle).await.expect("timed out").unwrap();
+
+    let mut received = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        received.push(event);
+    }
+    assert_eq!(received.len(), 5);
+    match &received[0] {
+        SupervisorEvent::Crashed { reason, errors, restart_in, .. } => {
+            assert_eq!(reason, "panicked: bad tick 0");
+            assert!(errors[0].starts_with("telegram"));
+            assert_eq!(*restart_in, Some(Duration::from_millis(1)));
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(matches!(&received[3], SupervisorEvent::Restarted { restarts: 2, .. }));
+    assert!(matches!(&received[4], SupervisorEvent::Finished { .. }));
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+    assert_eq!(server.requests("cancelallorder").len(), 2);
+    assert_eq!(server.requests("closeposition")[0]["strategy"], "Momentum");
+
+    // Without restarts a returned error stops the strategy for good
+    let (mut events, handle) = Supervisor::new("Momentum").start(&server.client(), || async { Err("feed lost") });
+    tokio::time::timeout(WAIT, handle).await.expect("timed out").unwrap();
+    assert!(matches!(
+        events.try_recv().unwrap(),
+        SupervisorEvent::Crashed { restart_in: None, reason, .. } if reason == "returned an error: feed lost"
+    ));
+    assert!(events.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn slow_replies_hit_the_client_timeout() {
+    let server = TestServer::start().await;
+    server.respond("funds", Reply::json(json!({"status": "success"})).delay(Duration::from_secs(2)));
+
+    let http = reqwest::Client::builder().timeout(Duration::from_millis(100)).build().unwrap();
+    let client = server.builder().http_client(http).build();
+    assert!(client.funds().await.is_err());
+}
+
+#[tokio::test]
+async fn serves_fixtures_and_records_them() {
+    let server = TestServer::start().await;
+    let loaded = server.load_fixtures(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures")).unwrap();
+    assert!(loaded > 0);
+    assert!(server.client().funds().await.is_ok());
+
+    let dir = std::env::temp_dir().join(format!("openalgo-recorder-{}", std::process::id()));
+    let recorder = Recorder::new(Arc::new(ReqwestTransport::default()), &dir);
+    let client = server.builder().transport(Arc::new(recorder)).build();
+    client.funds().await.unwrap();
+    let saved = std::fs::read_to_string(dir.join("funds.json")).unwrap();
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&saved).unwrap()["status"], "success");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn decodes_gzip_responses() {
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use wiremock::matchers::{header_regex, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let server = TestServer::start().await;
+    let body = json!({"status": "success", "data": {"ltp": 801.5}}).to_string();
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(body.as_bytes()).unwrap();
+    Mock::given(path("/api/v1/quotes"))
+        .and(header_regex("accept-encoding", "gzip"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-encoding", "gzip")
+                .insert_header("content-type", "application/json")
+                .set_body_bytes(encoder.finish().unwrap()),
+        )
+        .mount(server.http())
+        .await;
+    server.respond("quotes", Reply::error(406, "gzip not accepted"));
+
+    let quote = server.client().quotes("SBIN", "NSE").await.unwrap();
+    assert_eq!(quote.data.unwrap().ltp.map(openalgo::price::to_f64), Some(801.5));
+
+    let plain = server.builder().gzip(false).build();
+    assert!(plain.quotes("SBIN", "NSE").await.is_err());
+}
+
+#[tokio::test]
+async fn iv_slices_are_cached_per_interest_rate() {
+    let server = TestServer::start().await;
+    let in_30_days = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64 + 30 * 86_400;
+    let expiry = chrono::DateTime::from_timestamp(in_30_days, 0).unwrap().format("%d%b%y").to_string().to_uppercase();
+    server.respond(
+        "optionchain",
+        json!({
+            "status": "success",
+            "underlying": "NIFTY",
+            "underlying_ltp": 24000.0,
+            "expiry_date": expiry,
+            "atm_strike": 24000.0,
+            "chain": [{"strike": 24000.0, "ce": {"ltp": 420.0}, "pe": {"ltp": 330.0}}]
+        }),
+    );
+    let client = server.client();
+
+    let low = client.volatility.iv_slice("NIFTY", "NSE_INDEX", &expiry, 6.5).await.unwrap();
+    let cached = client.volatility.iv_slice("NIFTY", "NSE_INDEX", &expiry, 6.5).await.unwrap();
+    let high = client.volatility.iv_slice("NIFTY", "NSE_INDEX", &expiry, 10.0).await.unwrap();
+    assert_eq!(low, cached);
+    assert_eq!(server.requests("optionchain").len(), 2);
+    let (low_iv, high_iv) = (low.points[0].call_iv.unwrap(), high.points[0].call_iv.unwrap());
+    assert!(high_iv < low_iv, "{} vs {}", high_iv, low_iv);
+}
+
+#[tokio::test]
+async fn streams_ltp_and_resubscribes_after_reconnect() {
+    let server = TestServer::start().await;
+    let policy = ReconnectPolicy::default().backoff(Duration::from_millis(10), Duration::from_millis(10));
+    let mut config = WsConfig::default().reconnect(policy);
+    config.reconnect.as_mut().unwrap().snapshot = false;
+    let ws = server.client().websocket().with_config(config);
+
+    let (tx, mut rx) = ws.connect().await.unwrap();
+    assert!(matches!(next_data(&mut rx).await, WsData::Connected));
+    let sbin = WsInstrument {
+        exchange: "NSE".to_string(),
+        symbol: "SBIN".to_string(),
+    };
+    tx.send(WsCommand::SubscribeLtp(vec![sbin])).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    server.push_ltp("SBIN", "NSE", 801.5);
+    match next_data(&mut rx).await {
+        WsData::Ltp(tick) => assert_eq!(tick.ltp.map(openalgo::price::to_f64), Some(801.5)),
+        other => panic!("expected a tick, got {:?}", other),
+    }
+
+    server.drop_connections();
+    assert!(server.wait_for_ws_connections(2, WAIT).await);
+    loop {
+        match next_data(&mut rx).await {
+            WsData::Reconnected { .. } => break,
+            WsData::Disconnected | WsData::Error(_) => continue,
+            other => panic!("expected a reconnect, got {:?}", other),
+        }
+    }
+    let resubscribed = async {
+        while server.ws_messages().iter().filter(|m| m["action"] == "subscribe").count() < 2 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    };
+    tokio::time::timeout(WAIT, resubscribed).await.expect("no resubscribe after reconnect");
+
+    server.push_ltp("SBIN", "NSE", 802.0);
+    match next_data(&mut rx).await {
+        WsData::Ltp(tick) => assert_eq!(tick.ltp.map(openalgo::price::to_f64), Some(802.0)),
+        other => panic!("expected a tick, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn backpressure_drops_ticks_but_not_disconnects() {
+    for policy in [BackpressurePolicy::DropNewest, BackpressurePolicy::DropOldest] {
+        let server = TestServer::start().await;
+        let ws = server.client().websocket().with_config(WsConfig::default().data_capacity(2).backpressure(policy));
+        let (tx, mut rx) = ws.connect().await.unwrap();
+        let sbin = WsInstrument {
+            exchange: "NSE".to_string(),
+            symbol: "SBIN".to_string(),
+        };
+        tx.send(WsCommand::SubscribeLtp(vec![sbin])).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        for i in 0..20 {
+            server.push_ltp("SBIN", "NSE", 800.0 + i as f64);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        server.drop_connections();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let disconnected = async {
+            while let Some(data) = rx.recv().await {
+                if data == WsData::Disconnected {
+                    return true;
+                }
+            }
+            false
+        };
+        assert!(tokio::time::timeout(WAIT, disconnected).await.unwrap(), "{:?} lost the disconnect", policy);
+        assert!(ws.dropped_messages() > 0);
+    }
+}
+
+#[tokio::test]
+async fn subscriptions_cover_every_open_connection() {
+    let server = TestServer::start().await;
+    let ws = server.client().websocket();
+    let (first, _first_rx) = ws.connect().await.unwrap();
+    let (second, _second_rx) = ws.clone().connect().await.unwrap();
+    let sbin = WsInstrument::new("NSE", "SBIN");
+    let tcs = WsInstrument::new("NSE", "TCS");
+    first.send(WsCommand::SubscribeLtp(vec![sbin.clone(), tcs])).await.unwrap();
+    second.send(WsCommand::SubscribeLtp(vec![sbin.clone()])).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(ws.subscriptions().len(), 2);
+
+    first.send(WsCommand::UnsubscribeLtp(vec![sbin])).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(ws.subscriptions().modes("NSE", "SBIN"), vec![WsMode::Ltp]);
+
+    first.send(WsCommand::Disconnect).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let subscriptions = ws.subscriptions();
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions.modes("NSE", "SBIN"), vec![WsMode::Ltp]);
+
+    second.send(WsCommand::Disconnect).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(ws.subscriptions().is_empty());
+}
+
+#[tokio::test]
+async fn reports_rejected_authentication() {
+    let server = TestServer::start().await;
+    server.reject_auth(true);
+    let (_tx, mut rx) = server.client().websocket().connect().await.unwrap();
+    assert!(matches!(next_data(&mut rx).await, WsData::Connected));
+    match next_data(&mut rx).await {
+        WsData::ServerError { code, .. } => assert_eq!(code.as_deref(), Some("AUTHENTICATION_ERROR")),
+        other => panic!("expected a server error, got {:?}", other),
+    }
+}
+
+#[cfg(feature = "test-utils")]
+#[tokio::test]
+async fn reconnects_through_chaos_proxy() {
+    use openalgo::chaos::{Chaos, ChaosProxy};
+
+    let server = TestServer::start().await;
+    let chaos = Chaos::new();
+    let proxy = ChaosProxy::start(server.ws_url(), &chaos).await.unwrap();
+    let policy = ReconnectPolicy::default().backoff(Duration::from_millis(10), Duration::from_millis(10));
+    let mut config = WsConfig::default().reconnect(policy);
+    config.reconnect.as_mut().unwrap().snapshot = false;
+    let ws = server.builder().ws_url(&proxy.url()).build().websocket().with_config(config);
+
+    let (_tx, mut rx) = ws.connect().await.unwrap();
+    assert!(matches!(next_data(&mut rx).await, WsData::Connected));
+    proxy.drop_connections();
+    assert!(server.wait_for_ws_connections(2, WAIT).await);
+    loop {
+        match next_data(&mut rx).await {
+            WsData::Reconnected { .. } => break,
+            WsData::Disconnected | WsData::Error(_) => continue,
+            other => panic!("expected a reconnect, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Recording cassettes from `MockOpenAlgo` and replaying them offline.
+
+use openalgo::mock::MockOpenAlgo;
+use openalgo::vcr::{Cassette, Vcr, VcrMode};
+use openalgo::OpenAlgo;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn cassette(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("openalgo-vcr-{}-{}.json", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn replays_responses_and_errors_in_order() {
+    let path = cassette("order");
+    let live = MockOpenAlgo::new();
+    live.respond_once("funds", json!({"status": "success", "data": {"availablecash": "100.00"}}));
+    live.respond_once("funds", json!({"status": "success", "data": {"availablecash": "90.00"}}));
+    live.fail_once("holdings", "Broker session expired");
+
+    let vcr = Vcr::new(&path, VcrMode::Record, Some(live.transport())).unwrap();
+    assert!(vcr.is_recording());
+    let client = OpenAlgo::builder("live-key").transport(Arc::new(vcr)).build();
+    client.funds().await.unwrap();
+    client.funds().await.unwrap();
+    assert!(client.holdings().await.is_err());
+
+    let client = Vcr::replay_client(&path).unwrap();
+    let first = client.funds().await.unwrap().data.unwrap();
+    let second = client.funds().await.unwrap().data.unwrap();
+    assert_ne!(first.availablecash, second.availablecash);
+    // Only two funds calls were recorded
+    assert!(client.funds().await.is_err());
+    let error = client.holdings().await.unwrap_err();
+    assert_eq!(error.api_details().unwrap().message, "Broker session expired");
+    assert_eq!(Cassette::load(&path).unwrap().interactions.len(), 3);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn scrubs_fields_and_repeats_when_allowed() {
+    let path = cassette("scrub");
+    let live = MockOpenAlgo::new();
+    live.respond("quotes", json!({"status": "success", "data": {"ltp": 10.0, "client_id": "AB1234"}}));
+
+    // `client_id` is not a quote field, so the recording client must keep unknown fields in `extra`
+    let vcr = Vcr::new(&path, VcrMode::Auto, Some(live.transport())).unwrap().scrub("client_id");
+    let client = OpenAlgo::builder("live-key").transport(Arc::new(vcr)).strict(false).build();
+    client.quotes("SBIN", "NSE").await.unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(!text.contains("AB1234"));
+    assert!(!text.contains("live-key"));
+
+    let vcr = Vcr::new(&path, VcrMode::Auto, None).unwrap().allow_repeats(true);
+    assert!(!vcr.is_recording());
+    let client = OpenAlgo::builder("other-key").transport(Arc::new(vcr)).strict(true).build();
+    for _ in 0..3 {
+        let quote = client.quotes("SBIN", "NSE").await.unwrap().data.unwrap();
+        assert!(quote.extra.is_empty());
+    }
+    assert!(client.quotes("INFY", "NSE").await.is_err());
+    std::fs::remove_file(&path).unwrap();
+}
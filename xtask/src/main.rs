@@ -0,0 +1,377 @@
+//! Development tasks for the openalgo crate.
+//!
+//! ```text
+//! cargo xtask openapi check <spec.json>
+//! cargo xtask openapi generate <spec.json> [endpoint ...]
+//! cargo xtask openapi endpoints
+//! ```
+//!
+//! `check` compares the request and response schemas of an OpenAlgo
+//! OpenAPI/Swagger document with the SDK's structs. It reports endpoints
+//! the SDK does not call, request fields missing on either side, required
+//! fields the SDK treats as optional, and response fields that would only
+//! land in `extra`. It exits non-zero when it finds drift, so it can run in
+//! CI against a server's `/api/v1/swagger.json`.
+//!
+//! `generate` prints request and response structs in the style of
+//! `src/types.rs` for the given endpoints, or for every endpoint the SDK does
+//! not call yet. `endpoints` lists what the SDK calls, as `check` sees it.
+
+mod sdk;
+mod spec;
+
+use sdk::{Endpoint, Field};
+use spec::{Kind, Schema, Spec};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage:
+    cargo xtask openapi check <spec.json>
+    cargo xtask openapi generate <spec.json> [endpoint ...]
+    cargo xtask openapi endpoints";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["openapi", "check", spec] => check(spec),
+        ["openapi", "generate", spec, endpoints @ ..] => generate(spec, endpoints),
+        ["openapi", "endpoints"] => list_endpoints(),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// `src/` of the openalgo crate
+fn sdk_src() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("src")
+}
+
+struct Sdk {
+    structs: BTreeMap<String, Vec<Field>>,
+    endpoints: BTreeMap<String, Endpoint>,
+}
+
+fn load_sdk() -> Result<Sdk, String> {
+    let src = sdk_src();
+    Ok(Sdk {
+        structs: sdk::load_structs(&src)?,
+        endpoints: sdk::load_endpoints(&src)?,
+    })
+}
+
+fn load_spec(path: &str) -> Result<Spec, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    Spec::parse(&text).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn list_endpoints() -> Result<ExitCode, String> {
+    let sdk = load_sdk()?;
+    for (path, endpoint) in &sdk.endpoints {
+        println!(
+            "{:<20} {:<28} {:<28} {}",
+            path,
+            endpoint.request.as_deref().unwrap_or("-"),
+            endpoint.response.as_deref().unwrap_or("-"),
+            endpoint.function,
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[derive(Default)]
+struct Findings {
+    /// Drift that fails the check
+    problems: Vec<String>,
+    /// Differences worth knowing about that do not fail it
+    notes: Vec<String>,
+}
+
+fn check(spec_path: &str) -> Result<ExitCode, String> {
+    let spec = load_spec(spec_path)?;
+    let sdk = load_sdk()?;
+    let mut findings = Findings::default();
+
+    for (path, operation) in &spec.operations {
+        let Some(endpoint) = sdk.endpoints.get(path) else {
+            findings.problems.push(format!("{}: not implemented by the SDK", path));
+            continue;
+        };
+        if let (Some(schema), Some(request)) = (&operation.request, &endpoint.request) {
+            compare_request(path, schema, request, &sdk, &mut findings);
+        }
+        if let (Some(schema), Some(response)) = (&operation.response, &endpoint.response) {
+            compare_response(&spec, path, schema, response, &sdk, &mut findings, 0);
+        }
+    }
+    for path in sdk.endpoints.keys().filter(|p| !spec.operations.contains_key(*p)) {
+        findings.notes.push(format!("{}: called by the SDK but not in the spec", path));
+    }
+
+    for note in &findings.notes {
+        println!("note: {}", note);
+    }
+    for problem in &findings.problems {
+        println!("drift: {}", problem);
+    }
+    println!(
+        "{} endpoints in the spec, {} called by the SDK: {} drift, {} notes",
+        spec.operations.len(),
+        sdk.endpoints.len(),
+        findings.problems.len(),
+        findings.notes.len(),
+    );
+    Ok(if findings.problems.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn compare_request(path: &str, schema: &Schema, request: &str, sdk: &Sdk, findings: &mut Findings) {
+    let Some(fields) = sdk.structs.get(request) else {
+        findings.notes.push(format!("{}: request type {} not found in types.rs", path, request));
+        return;
+    };
+    let sent: BTreeMap<&str, &Field> = fields.iter().map(|f| (f.name.as_str(), f)).collect();
+    for name in schema.properties.keys() {
+        match sent.get(name.as_str()) {
+            None => findings.problems.push(format!("{}: request field `{}` is not sent by {}", path, name, request)),
+            Some(field) if field.optional && schema.required.contains(name) => findings.problems.push(format!(
+                "{}: request field `{}` is required by the server but optional in {}",
+                path, name, request
+            )),
+            Some(_) => {}
+        }
+    }
+    for name in sent.keys().filter(|n| !schema.properties.contains_key(**n)) {
+        findings.problems.push(format!("{}: {} sends `{}`, which the spec does not list", path, request, name));
+    }
+}
+
+fn compare_response(
+    spec: &Spec,
+    context: &str,
+    schema: &Schema,
+    response: &str,
+    sdk: &Sdk,
+    findings: &mut Findings,
+    depth: usize,
+) {
+    let Some(fields) = sdk.structs.get(response) else {
+        findings.notes.push(format!("{}: response type {} not found in types.rs", context, response));
+        return;
+    };
+    let modeled: BTreeMap<&str, &Field> = fields.iter().map(|f| (f.name.as_str(), f)).collect();
+    for (name, property) in &schema.properties {
+        let Some(field) = modeled.get(name.as_str()) else {
+            findings.problems.push(format!(
+                "{}: response field `{}` is not modeled by {} (it lands in `extra`)",
+                context, name, response
+            ));
+            continue;
+        };
+        let nested = spec.object(property).zip(field.inner_type().filter(|t| sdk.structs.contains_key(t)));
+        if let (Some((schema, inner)), true) = (nested, depth < 8) {
+            compare_response(spec, &format!("{}.{}", context, name), &schema, &inner, sdk, findings, depth + 1);
+        }
+    }
+    for name in modeled.keys().filter(|n| !schema.properties.contains_key(**n)) {
+        findings.notes.push(format!("{}: {} models `{}`, which the spec does not list", context, response, name));
+    }
+}
+
+fn generate(spec_path: &str, endpoints: &[&str]) -> Result<ExitCode, String> {
+    let spec = load_spec(spec_path)?;
+    let sdk = load_sdk()?;
+    let selected: Vec<&str> = if endpoints.is_empty() {
+        spec.operations
+            .keys()
+            .filter(|p| !sdk.endpoints.contains_key(*p))
+            .map(String::as_str)
+            .collect()
+    } else {
+        for endpoint in endpoints {
+            if !spec.operations.contains_key(*endpoint) {
+                return Err(format!("{}: not in the spec", endpoint));
+            }
+        }
+        endpoints.to_vec()
+    };
+
+    let mut generator = Generator {
+        spec: &spec,
+        existing: sdk.structs.keys().cloned().collect(),
+        out: String::new(),
+    };
+    for path in selected {
+        let operation = &spec.operations[path];
+        let base = type_name(path);
+        let title = operation.summary.clone().unwrap_or_else(|| base.clone());
+        if let Some(schema) = &operation.request {
+            generator.request(&format!("{}Request", base), &title, schema);
+        }
+        if let Some(schema) = &operation.response {
+            generator.response(&format!("{}Response", base), &format!("{} response", title), schema);
+        }
+    }
+    print!("{}", generator.out);
+    Ok(ExitCode::SUCCESS)
+}
+
+struct Generator<'a> {
+    spec: &'a Spec,
+    /// Struct names taken, by the SDK or by earlier output
+    existing: BTreeSet<String>,
+    out: String,
+}
+
+impl Generator<'_> {
+    fn request(&mut self, name: &str, title: &str, schema: &Schema) {
+        if !self.existing.insert(name.to_string()) {
+            return;
+        }
+        let mut body = String::new();
+        let mut exact = true;
+        for (property, value) in schema.ordered() {
+            let (field, rename) = field_name(property);
+            let ty = match (property.as_str(), self.spec.kind(value)) {
+                ("apikey", _) => "ApiKey".to_string(),
+                (_, kind) => {
+                    exact &= !matches!(kind, Kind::Number);
+                    scalar(&kind)
+                }
+            };
+            if let Some(rename) = rename {
+                let _ = writeln!(body, "    #[serde(rename = \"{}\")]", rename);
+            }
+            if schema.required.contains(property) || property == "apikey" {
+                let _ = writeln!(body, "    pub {}: {},", field, ty);
+            } else {
+                let _ = writeln!(body, "    #[serde(skip_serializing_if = \"Option::is_none\")]");
+                let _ = writeln!(body, "    pub {}: Option<{}>,", field, ty);
+            }
+        }
+        let derives = if exact { "Debug, Clone, PartialEq, Eq, Hash, Serialize" } else { "Debug, Clone, PartialEq, Serialize" };
+        let _ = writeln!(self.out, "/// {} request", title);
+        let _ = writeln!(self.out, "#[derive({})]", derives);
+        let _ = writeln!(self.out, "pub struct {} {{\n{}}}\n", name, body);
+    }
+
+    fn response(&mut self, name: &str, title: &str, schema: &Schema) {
+        if !self.existing.insert(name.to_string()) {
+            return;
+        }
+        let mut body = String::new();
+        let mut nested = Vec::new();
+        let stem = name.strip_suffix("Response").unwrap_or(name);
+        for (property, value) in schema.ordered() {
+            let (field, rename) = field_name(property);
+            if let Some(rename) = rename {
+                let _ = writeln!(body, "    #[serde(rename = \"{}\")]", rename);
+            }
+            if property == "status" {
+                let _ = writeln!(body, "    pub status: String,");
+                continue;
+            }
+            let kind = self.spec.kind(value);
+            let ty = match self.spec.object(value) {
+                Some(object) => {
+                    let inner = object.name.clone().unwrap_or_else(|| format!("{}{}", stem, type_name(property)));
+                    nested.push((inner.clone(), object));
+                    match kind {
+                        Kind::Array(_) => format!("Vec<{}>", inner),
+                        _ => inner,
+                    }
+                }
+                None => match &kind {
+                    Kind::Number if is_price(property) => "Price".to_string(),
+                    Kind::Array(item) => format!("Vec<{}>", scalar(item)),
+                    other => scalar(other),
+                },
+            };
+            match kind {
+                Kind::String => {
+                    let _ = writeln!(body, "    #[serde(default, deserialize_with = \"de::option_string\")]");
+                }
+                Kind::Integer | Kind::Number => {
+                    let _ = writeln!(body, "    #[serde(default, deserialize_with = \"de::option_number\")]");
+                }
+                _ => {}
+            }
+            let _ = writeln!(body, "    pub {}: Option<{}>,", field, ty);
+        }
+        let _ = writeln!(self.out, "/// {}", title);
+        let _ = writeln!(self.out, "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]");
+        let _ = writeln!(self.out, "#[cfg_attr(feature = \"strict\", serde(deny_unknown_fields))]");
+        let _ = write!(self.out, "pub struct {} {{\n{}", name, body);
+        let _ = writeln!(self.out, "    /// Any additional fields returned by the server");
+        let _ = writeln!(self.out, "    #[cfg_attr(not(feature = \"strict\"), serde(flatten))]");
+        let _ = writeln!(self.out, "    #[cfg_attr(feature = \"strict\", serde(skip))]");
+        let _ = writeln!(self.out, "    pub extra: HashMap<String, serde_json::Value>,\n}}\n");
+        for (inner, object) in nested {
+            self.response(&inner, &type_title(&inner), &object);
+        }
+    }
+}
+
+fn scalar(kind: &Kind) -> String {
+    match kind {
+        Kind::String => "String".into(),
+        Kind::Integer => "i64".into(),
+        Kind::Number => "f64".into(),
+        Kind::Boolean => "bool".into(),
+        Kind::Array(item) => format!("Vec<{}>", scalar(item)),
+        Kind::Object | Kind::Any => "serde_json::Value".into(),
+    }
+}
+
+fn is_price(name: &str) -> bool {
+    matches!(name, "ltp" | "open" | "high" | "low" | "close" | "bid" | "ask" | "prev_close" | "tick_size")
+        || name.ends_with("price")
+}
+
+/// Rust field name for a JSON property, and the rename it needs
+fn field_name(property: &str) -> (String, Option<String>) {
+    let mut field: String = property
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if field.starts_with(|c: char| c.is_ascii_digit()) || syn::parse_str::<syn::Ident>(&field).is_err() {
+        field.push('_');
+        if field.starts_with(|c: char| c.is_ascii_digit()) {
+            field.insert(0, '_');
+        }
+    }
+    let rename = (field != property).then(|| property.to_string());
+    (field, rename)
+}
+
+/// `market/holidays` -> `MarketHolidays`, `option_type` -> `OptionType`
+fn type_name(path: &str) -> String {
+    path.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+/// `OptionChainStrike` -> `Option chain strike`
+fn type_title(name: &str) -> String {
+    let mut title = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            title.push(' ');
+            title.push(c.to_ascii_lowercase());
+        } else {
+            title.push(c);
+        }
+    }
+    title
+}
@@ -0,0 +1,236 @@
+//! What the SDK sends and expects, read from its source with `syn`.
+//!
+//! Structs come from `src/types.rs`. Endpoints come from every function in
+//! `src/` that builds a `...Request` struct and posts it: the string literal
+//! passed to `post`/`post_custom` is the endpoint, the struct literal is the
+//! request type, and the `...Response` in the return type (or a `let`
+//! annotation) is the response type.
+
+use quote::ToTokens;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use syn::visit::{self, Visit};
+
+/// A struct field as it appears on the wire
+#[derive(Debug, Clone)]
+pub struct Field {
+    /// Serialized name
+    pub name: String,
+    /// Rust type, e.g. `Option<Vec<HistoryCandle>>`
+    pub ty: syn::Type,
+    pub optional: bool,
+}
+
+impl Field {
+    /// Innermost type name, unwrapping `Option`, `Vec` and `Box`
+    pub fn inner_type(&self) -> Option<String> {
+        let mut ty = &self.ty;
+        loop {
+            let syn::Type::Path(path) = ty else { return None };
+            let segment = path.path.segments.last()?;
+            let name = segment.ident.to_string();
+            match (&segment.arguments, name.as_str()) {
+                (syn::PathArguments::AngleBracketed(args), "Option" | "Vec" | "Box") => match args.args.first() {
+                    Some(syn::GenericArgument::Type(inner)) => ty = inner,
+                    _ => return Some(name),
+                },
+                _ => return Some(name),
+            }
+        }
+    }
+}
+
+/// Fields of every struct in `types.rs`, without flattened `extra` maps
+pub fn load_structs(src: &Path) -> Result<BTreeMap<String, Vec<Field>>, String> {
+    let path = src.join("types.rs");
+    let file = parse(&path)?;
+    let mut structs = BTreeMap::new();
+    for item in file.items {
+        let syn::Item::Struct(item) = item else { continue };
+        let syn::Fields::Named(named) = item.fields else { continue };
+        let fields = named
+            .named
+            .iter()
+            .filter_map(|field| {
+                let mut name = field.ident.as_ref()?.to_string();
+                for attr in &field.attrs {
+                    let tokens = attr.to_token_stream().to_string();
+                    if tokens.contains("flatten") || is_skipped(attr) {
+                        return None;
+                    }
+                    if let Some(rename) = serde_rename(attr) {
+                        name = rename;
+                    }
+                }
+                let optional = matches!(
+                    &field.ty,
+                    syn::Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "Option")
+                );
+                Some(Field {
+                    name,
+                    ty: field.ty.clone(),
+                    optional,
+                })
+            })
+            .collect();
+        structs.insert(item.ident.to_string(), fields);
+    }
+    Ok(structs)
+}
+
+fn is_skipped(attr: &syn::Attribute) -> bool {
+    let mut skipped = false;
+    if attr.path().is_ident("serde") {
+        let _ = attr.parse_nested_meta(|meta| {
+            skipped |= meta.path.is_ident("skip");
+            if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+            Ok(())
+        });
+    }
+    skipped
+}
+
+fn serde_rename(attr: &syn::Attribute) -> Option<String> {
+    let mut rename = None;
+    if attr.path().is_ident("serde") {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+            Ok(())
+        });
+    }
+    rename
+}
+
+/// An endpoint the SDK calls
+#[derive(Debug, Clone, Default)]
+pub struct Endpoint {
+    /// Function that calls it
+    pub function: String,
+    pub request: Option<String>,
+    pub response: Option<String>,
+}
+
+/// Endpoints called from the source files in `src`, keyed by path
+pub fn load_endpoints(src: &Path) -> Result<BTreeMap<String, Endpoint>, String> {
+    let mut endpoints: BTreeMap<String, Endpoint> = BTreeMap::new();
+    let mut files: Vec<_> = fs::read_dir(src)
+        .map_err(|e| format!("{}: {}", src.display(), e))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|x| x == "rs"))
+        .collect();
+    files.sort();
+    for path in files {
+        let file = parse(&path)?;
+        let mut finder = Finder::default();
+        finder.visit_file(&file);
+        for (endpoint, found) in finder.found {
+            let entry = endpoints.entry(endpoint).or_insert_with(|| found.clone());
+            if entry.request.is_none() {
+                entry.request = found.request;
+            }
+            if entry.response.is_none() {
+                entry.response = found.response;
+            }
+        }
+    }
+    Ok(endpoints)
+}
+
+fn parse(path: &Path) -> Result<syn::File, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    syn::parse_file(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[derive(Default)]
+struct Finder {
+    found: Vec<(String, Endpoint)>,
+}
+
+impl Finder {
+    fn function(&mut self, name: &syn::Ident, output: &syn::ReturnType, body: &syn::Block) {
+        let mut calls = Calls::default();
+        calls.visit_block(body);
+        let [endpoint] = calls.endpoints.as_slice() else { return };
+        let response = match output {
+            syn::ReturnType::Type(_, ty) => response_type(ty),
+            syn::ReturnType::Default => None,
+        }
+        .or(calls.annotated_response);
+        self.found.push((
+            endpoint.clone(),
+            Endpoint {
+                function: name.to_string(),
+                request: calls.requests.first().cloned(),
+                response,
+            },
+        ));
+    }
+}
+
+impl<'ast> Visit<'ast> for Finder {
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        self.function(&item.sig.ident, &item.sig.output, &item.block);
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        self.function(&item.sig.ident, &item.sig.output, &item.block);
+    }
+}
+
+/// First `...Response` type named inside `ty`
+fn response_type(ty: &syn::Type) -> Option<String> {
+    let tokens = ty.to_token_stream().to_string();
+    tokens
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .find(|word| word.ends_with("Response"))
+        .map(str::to_string)
+}
+
+#[derive(Default)]
+struct Calls {
+    endpoints: Vec<String>,
+    requests: Vec<String>,
+    annotated_response: Option<String>,
+}
+
+impl<'ast> Visit<'ast> for Calls {
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        if call.method == "post" || call.method == "post_custom" {
+            if let Some(syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(endpoint), .. })) = call.args.first() {
+                if !self.endpoints.contains(&endpoint.value()) {
+                    self.endpoints.push(endpoint.value());
+                }
+            }
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_struct(&mut self, expr: &'ast syn::ExprStruct) {
+        if let Some(last) = expr.path.segments.last() {
+            let name = last.ident.to_string();
+            if name.ends_with("Request") {
+                self.requests.push(name);
+            }
+        }
+        visit::visit_expr_struct(self, expr);
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if let syn::Pat::Type(typed) = &local.pat {
+            if self.annotated_response.is_none() {
+                self.annotated_response = response_type(&typed.ty);
+            }
+        }
+        visit::visit_local(self, local);
+    }
+
+    /// Nested functions are found on their own
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+}
@@ -0,0 +1,172 @@
+//! Endpoints and schemas from an OpenAPI 3 or Swagger 2 document.
+//!
+//! Flask-RESTX, which the OpenAlgo server uses for its REST API, serves a
+//! Swagger 2 document at `/api/v1/swagger.json`; OpenAPI 3 documents work
+//! too. Only POST operations are read, since every OpenAlgo endpoint is one.
+
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Object schema with its `$ref`s and `allOf` resolved
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    /// Name of the definition it came from, if any
+    pub name: Option<String>,
+    pub properties: Map<String, Value>,
+    pub required: BTreeSet<String>,
+}
+
+/// A POST operation
+#[derive(Debug, Clone, Default)]
+pub struct Operation {
+    pub summary: Option<String>,
+    pub request: Option<Schema>,
+    pub response: Option<Schema>,
+}
+
+/// A parsed API document
+pub struct Spec {
+    root: Value,
+    /// Operations keyed by endpoint path relative to `/api/v1/`
+    pub operations: BTreeMap<String, Operation>,
+}
+
+impl Spec {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let root: Value = serde_json::from_str(text).map_err(|e| format!("not a JSON document: {}", e))?;
+        if root.get("swagger").is_none() && root.get("openapi").is_none() {
+            return Err("neither a Swagger 2 nor an OpenAPI 3 document".into());
+        }
+        let mut spec = Self {
+            root,
+            operations: BTreeMap::new(),
+        };
+        let paths = spec.root.get("paths").and_then(Value::as_object).cloned().unwrap_or_default();
+        for (path, item) in paths {
+            let Some(post) = item.get("post") else { continue };
+            let operation = Operation {
+                summary: post.get("summary").and_then(Value::as_str).map(str::to_string),
+                request: spec.request_schema(post, &item),
+                response: spec.response_schema(post),
+            };
+            spec.operations.insert(endpoint_name(&path), operation);
+        }
+        Ok(spec)
+    }
+
+    fn request_schema(&self, post: &Value, item: &Value) -> Option<Schema> {
+        if let Some(schema) = post.pointer("/requestBody/content/application~1json/schema") {
+            return Some(self.resolve(schema));
+        }
+        let parameters = post
+            .get("parameters")
+            .and_then(Value::as_array)
+            .into_iter()
+            .chain(item.get("parameters").and_then(Value::as_array))
+            .flatten();
+        parameters
+            .into_iter()
+            .find(|p| p.get("in").and_then(Value::as_str) == Some("body"))
+            .and_then(|p| p.get("schema"))
+            .map(|schema| self.resolve(schema))
+    }
+
+    fn response_schema(&self, post: &Value) -> Option<Schema> {
+        let ok = post.pointer("/responses/200")?;
+        let schema = ok.pointer("/content/application~1json/schema").or_else(|| ok.get("schema"))?;
+        Some(self.resolve(schema))
+    }
+
+    /// The definition a `$ref` points at
+    fn lookup(&self, reference: &str) -> Option<&Value> {
+        self.root.pointer(reference.strip_prefix('#')?)
+    }
+
+    /// Resolve `$ref` and `allOf` into one object schema
+    pub fn resolve(&self, schema: &Value) -> Schema {
+        let mut out = Schema::default();
+        self.merge(schema, &mut out, 0);
+        out
+    }
+
+    fn merge(&self, schema: &Value, out: &mut Schema, depth: usize) {
+        if depth > 16 {
+            return;
+        }
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            if out.name.is_none() {
+                out.name = reference.rsplit('/').next().map(str::to_string);
+            }
+            if let Some(target) = self.lookup(reference) {
+                self.merge(target, out, depth + 1);
+            }
+            return;
+        }
+        for part in schema.get("allOf").and_then(Value::as_array).into_iter().flatten() {
+            self.merge(part, out, depth + 1);
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                out.properties.insert(name.clone(), property.clone());
+            }
+        }
+        for name in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+            if let Some(name) = name.as_str() {
+                out.required.insert(name.to_string());
+            }
+        }
+    }
+
+    /// Object schema of a property, looking through arrays; `None` for scalars
+    pub fn object(&self, property: &Value) -> Option<Schema> {
+        let mut property = property;
+        while let Some(items) = property.get("items") {
+            property = items;
+        }
+        let schema = self.resolve(property);
+        (!schema.properties.is_empty()).then_some(schema)
+    }
+
+    /// JSON type of a property after resolving `$ref`
+    pub fn kind(&self, property: &Value) -> Kind {
+        if let Some(target) = property.get("$ref").and_then(Value::as_str).and_then(|r| self.lookup(r)) {
+            return self.kind(target);
+        }
+        match property.get("type").and_then(Value::as_str) {
+            Some("string") => Kind::String,
+            Some("integer") => Kind::Integer,
+            Some("number") => Kind::Number,
+            Some("boolean") => Kind::Boolean,
+            Some("array") => Kind::Array(Box::new(property.get("items").map_or(Kind::Any, |i| self.kind(i)))),
+            _ if property.get("properties").is_some() || property.get("allOf").is_some() => Kind::Object,
+            _ => Kind::Any,
+        }
+    }
+}
+
+impl Schema {
+    /// Properties with `apikey` and `status` first, then by name
+    pub fn ordered(&self) -> Vec<(&String, &Value)> {
+        let mut properties: Vec<_> = self.properties.iter().collect();
+        properties.sort_by_key(|(name, _)| !matches!(name.as_str(), "apikey" | "status"));
+        properties
+    }
+}
+
+/// JSON type of a schema property
+#[derive(Debug, Clone, PartialEq)]
+pub enum Kind {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Array(Box<Kind>),
+    Object,
+    Any,
+}
+
+/// `/api/v1/market/holidays/` -> `market/holidays`
+fn endpoint_name(path: &str) -> String {
+    let path = path.trim_matches('/');
+    path.strip_prefix("api/v1/").unwrap_or(path).to_string()
+}